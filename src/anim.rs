@@ -0,0 +1,657 @@
+use std::{collections::HashMap, io::{ErrorKind, Read, Seek, SeekFrom}, sync::Arc};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{log_debug, math::{Matrix4x4, Quaternion, Vector2, Vector3, Vector4}};
+
+#[cfg(feature = "mesh")]
+use crate::mesh::{DBSkeleton, DBSkelNode};
+
+pub use crate::math::Lerp;
+
+const DBA_VER: u32 = 1;
+
+/// Binding id of bone translation channels (the channel id is the bone index)
+pub const BINDING_TRANSLATION: u32 = 0;
+
+/// Binding id of bone rotation channels (the channel id is the bone index)
+pub const BINDING_ROTATION: u32 = 1;
+
+/// Binding id of bone scale channels (the channel id is the bone index)
+pub const BINDING_SCALE: u32 = 2;
+
+/// How an animation curve is sampled past its last keyframe
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnimationCurveLoopMode {
+    /// Hold the value of the last keyframe
+    Clamp,
+    /// Wrap back around to the start of the curve
+    Repeat,
+}
+
+/// Represents a keyframe of animation
+#[derive(Clone, Copy)]
+pub struct AnimationKeyframe<T> where T : Clone + Copy {
+    pub value: T,
+    pub time: f32,
+}
+
+/// Represents a collection of animation keyframes which can be sampled at a given point in time
+pub struct AnimationCurve<T> where T : Clone + Copy + Lerp<T> {
+    keyframes: Vec<AnimationKeyframe<T>>,
+    duration: f32,
+}
+
+impl<T> AnimationCurve<T> where T : Clone + Copy + Lerp<T> {
+    /// Construct a new empty animation curve
+    pub fn new() -> AnimationCurve<T> {
+        return AnimationCurve { keyframes: Vec::new(), duration: 0.0 };
+    }
+
+    /// Insert a keyframe into this curve, keeping keyframes sorted by time
+    pub fn insert_keyframe(&mut self, value: T, time: f32) {
+        let idx = self.keyframes.partition_point(|x| x.time <= time);
+        self.keyframes.insert(idx, AnimationKeyframe { value: value, time: time });
+        self.duration = self.duration.max(time);
+    }
+
+    /// Get the keyframes of this curve, sorted by time
+    pub fn keyframes(&self) -> &[AnimationKeyframe<T>] {
+        return &self.keyframes;
+    }
+
+    /// Get the duration of this animation curve
+    pub fn duration(&self) -> f32 {
+        return self.duration;
+    }
+
+    /// Sample this animation curve at the given point in time (or None if the curve has no keyframes)
+    pub fn sample(&self, time: f32, loop_mode: AnimationCurveLoopMode) -> Option<T> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+
+        if self.keyframes.len() == 1 || time < first.time {
+            return Some(first.value);
+        }
+
+        let mut sample_time = time;
+
+        if sample_time >= self.duration {
+            match loop_mode {
+                AnimationCurveLoopMode::Clamp => {
+                    return Some(last.value);
+                },
+                AnimationCurveLoopMode::Repeat => {
+                    if self.duration <= 0.0 {
+                        return Some(last.value);
+                    }
+                    sample_time = sample_time.rem_euclid(self.duration);
+                    if sample_time < first.time {
+                        return Some(first.value);
+                    }
+                }
+            };
+        }
+
+        let idx = self.keyframes.partition_point(|x| x.time <= sample_time);
+        if idx == 0 {
+            return Some(first.value);
+        }
+        if idx >= self.keyframes.len() {
+            return Some(last.value);
+        }
+
+        let lhs = &self.keyframes[idx - 1];
+        let rhs = &self.keyframes[idx];
+        let lerp_t = (sample_time - lhs.time) / (rhs.time - lhs.time);
+        return Some(T::lerp(lhs.value, rhs.value, lerp_t));
+    }
+}
+
+impl<T> Default for AnimationCurve<T> where T : Clone + Copy + Lerp<T> {
+    fn default() -> Self {
+        return AnimationCurve::new();
+    }
+}
+
+/// Identifies a single channel of an animation clip (for example, the rotation channel of a particular bone)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ChannelKey {
+    pub channel_id: u32,
+    pub binding_id: u32,
+}
+
+impl ChannelKey {
+    pub const fn new(channel_id: u32, binding_id: u32) -> ChannelKey {
+        return ChannelKey { channel_id: channel_id, binding_id: binding_id };
+    }
+}
+
+/// Represents an animation clip loaded from a DBA file
+pub struct DBAnimationClip {
+    pub channels_f32: HashMap<ChannelKey, AnimationCurve<f32>>,
+    pub channels_vec2: HashMap<ChannelKey, AnimationCurve<Vector2>>,
+    pub channels_vec3: HashMap<ChannelKey, AnimationCurve<Vector3>>,
+    pub channels_vec4: HashMap<ChannelKey, AnimationCurve<Vector4>>,
+    pub channels_quat: HashMap<ChannelKey, AnimationCurve<Quaternion>>,
+    duration: f32,
+}
+
+/// Enumeration of errors which can result from parsing a DBA animation file
+#[derive(Debug)]
+pub enum DBAnimationError {
+    ParseError,
+    VersionError,
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for DBAnimationError {
+    fn from(value: std::io::Error) -> Self {
+        return DBAnimationError::IOError(value);
+    }
+}
+
+fn read_channel<R, T, F>(reader: &mut R, read_value: F) -> Result<(ChannelKey, AnimationCurve<T>),std::io::Error>
+    where R : Read,
+    T : Clone + Copy + Lerp<T>,
+    F : Fn(&mut R) -> Result<T,std::io::Error>
+{
+    let channel_id = reader.read_u32::<LittleEndian>()?;
+    let binding_id = reader.read_u32::<LittleEndian>()?;
+    let key_cnt = reader.read_u32::<LittleEndian>()? as usize;
+
+    let mut anim_curve: AnimationCurve<T> = AnimationCurve::new();
+
+    for _ in 0..key_cnt {
+        let time = reader.read_f32::<LittleEndian>()?;
+        let val = read_value(reader)?;
+        anim_curve.insert_keyframe(val, time);
+    }
+
+    return Ok((ChannelKey::new(channel_id, binding_id), anim_curve));
+}
+
+impl DBAnimationClip {
+    /// Load an animation clip from the given stream
+    pub fn new<R>(reader: &mut R) -> Result<DBAnimationClip,DBAnimationError> where R : Read + Seek {
+        // read header
+        let mut id: [u8;4] = [0;4];
+        reader.read_exact(&mut id)?;
+
+        if &id != b"DBA\0" {
+            return Err(DBAnimationError::ParseError);
+        }
+
+        let ver = reader.read_u32::<LittleEndian>()?;
+        if ver != DBA_VER {
+            return Err(DBAnimationError::VersionError);
+        }
+
+        let mut clip = DBAnimationClip {
+            channels_f32: HashMap::new(),
+            channels_vec2: HashMap::new(),
+            channels_vec3: HashMap::new(),
+            channels_vec4: HashMap::new(),
+            channels_quat: HashMap::new(),
+            duration: 0.0,
+        };
+
+        // scan chunks
+        loop {
+            let mut chunk_id: [u8;4] = [0;4];
+            match reader.read_exact(&mut chunk_id) {
+                Ok(_) => {
+                },
+                Err(e) => {
+                    // EOF, no more chunks in stream
+                    if e.kind() == ErrorKind::UnexpectedEof {
+                        break;
+                    }
+                    return Err(DBAnimationError::IOError(e));
+                }
+            };
+
+            let chunk_size = reader.read_u32::<LittleEndian>()?;
+
+            let duration = match &chunk_id {
+                b"F32\0" => {
+                    let (key, curve) = read_channel(reader, |r| r.read_f32::<LittleEndian>())?;
+                    let duration = curve.duration();
+                    clip.channels_f32.insert(key, curve);
+                    duration
+                },
+                b"VEC2" => {
+                    let (key, curve) = read_channel(reader, |r| {
+                        return Ok(Vector2::new(r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?));
+                    })?;
+                    let duration = curve.duration();
+                    clip.channels_vec2.insert(key, curve);
+                    duration
+                },
+                b"VEC3" => {
+                    let (key, curve) = read_channel(reader, |r| {
+                        return Ok(Vector3::new(r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?));
+                    })?;
+                    let duration = curve.duration();
+                    clip.channels_vec3.insert(key, curve);
+                    duration
+                },
+                b"VEC4" => {
+                    let (key, curve) = read_channel(reader, |r| {
+                        return Ok(Vector4::new(r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?));
+                    })?;
+                    let duration = curve.duration();
+                    clip.channels_vec4.insert(key, curve);
+                    duration
+                },
+                b"QUAT" => {
+                    let (key, curve) = read_channel(reader, |r| {
+                        return Ok(Quaternion::new(r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?, r.read_f32::<LittleEndian>()?));
+                    })?;
+                    let duration = curve.duration();
+                    clip.channels_quat.insert(key, curve);
+                    duration
+                },
+                _ => {
+                    // unknown chunk ID, skip
+                    reader.seek(SeekFrom::Current(chunk_size as i64))?;
+                    0.0
+                }
+            };
+
+            clip.duration = clip.duration.max(duration);
+        }
+
+        log_debug!("Animation clip loaded (duration: {}s, channels: {})", clip.duration, clip.channel_count());
+
+        return Ok(clip);
+    }
+
+    /// Get the total duration of this animation clip
+    pub fn duration(&self) -> f32 {
+        return self.duration;
+    }
+
+    /// Recompute the duration of this clip from its channels, after channels have been added or removed
+    pub fn recalculate_duration(&mut self) {
+        self.duration = self.channels_f32.values().map(|x| x.duration())
+            .chain(self.channels_vec2.values().map(|x| x.duration()))
+            .chain(self.channels_vec3.values().map(|x| x.duration()))
+            .chain(self.channels_vec4.values().map(|x| x.duration()))
+            .chain(self.channels_quat.values().map(|x| x.duration()))
+            .fold(0.0, f32::max);
+    }
+
+    /// Get the total number of channels in this animation clip
+    pub fn channel_count(&self) -> usize {
+        return self.channels_f32.len() + self.channels_vec2.len() + self.channels_vec3.len() + self.channels_vec4.len() + self.channels_quat.len();
+    }
+
+    /// Get the f32 animation channel with the given channel & binding id, or none
+    pub fn get_channel_f32(&self, channel_id: u32, binding_id: u32) -> Option<&AnimationCurve<f32>> {
+        return self.channels_f32.get(&ChannelKey::new(channel_id, binding_id));
+    }
+
+    /// Get the Vector2 animation channel with the given channel & binding id, or none
+    pub fn get_channel_vec2(&self, channel_id: u32, binding_id: u32) -> Option<&AnimationCurve<Vector2>> {
+        return self.channels_vec2.get(&ChannelKey::new(channel_id, binding_id));
+    }
+
+    /// Get the Vector3 animation channel with the given channel & binding id, or none
+    pub fn get_channel_vec3(&self, channel_id: u32, binding_id: u32) -> Option<&AnimationCurve<Vector3>> {
+        return self.channels_vec3.get(&ChannelKey::new(channel_id, binding_id));
+    }
+
+    /// Get the Vector4 animation channel with the given channel & binding id, or none
+    pub fn get_channel_vec4(&self, channel_id: u32, binding_id: u32) -> Option<&AnimationCurve<Vector4>> {
+        return self.channels_vec4.get(&ChannelKey::new(channel_id, binding_id));
+    }
+
+    /// Get the Quaternion animation channel with the given channel & binding id, or none
+    pub fn get_channel_quat(&self, channel_id: u32, binding_id: u32) -> Option<&AnimationCurve<Quaternion>> {
+        return self.channels_quat.get(&ChannelKey::new(channel_id, binding_id));
+    }
+}
+
+impl Default for DBAnimationClip {
+    /// Construct a new clip with no channels
+    fn default() -> Self {
+        return DBAnimationClip {
+            channels_f32: HashMap::new(),
+            channels_vec2: HashMap::new(),
+            channels_vec3: HashMap::new(),
+            channels_vec4: HashMap::new(),
+            channels_quat: HashMap::new(),
+            duration: 0.0,
+        };
+    }
+}
+
+/// The local translation, rotation & scale of a single bone, relative to its rest pose
+#[derive(Clone, Copy)]
+pub struct BonePose {
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+    pub scale: Vector3,
+}
+
+impl BonePose {
+    pub const IDENTITY: BonePose = BonePose { translation: Vector3::zero(), rotation: Quaternion::identity(), scale: Vector3::new(1.0, 1.0, 1.0) };
+
+    /// Compute the transform matrix of this pose
+    pub fn to_matrix(&self) -> Matrix4x4 {
+        let mut mat = Matrix4x4::identity();
+        Matrix4x4::load_simd(&Matrix4x4::scale(self.scale));
+        Matrix4x4::mul_simd(&Matrix4x4::rotation(self.rotation));
+        Matrix4x4::mul_simd(&Matrix4x4::translation(self.translation));
+        Matrix4x4::store_simd(&mut mat);
+        return mat;
+    }
+}
+
+impl Lerp<BonePose> for BonePose {
+    /// Blend between two poses, spherically interpolating rotation
+    fn lerp(lhs: BonePose, rhs: BonePose, time: f32) -> BonePose {
+        return BonePose {
+            translation: Vector3::lerp(lhs.translation, rhs.translation, time),
+            rotation: Quaternion::lerp(lhs.rotation, rhs.rotation, time),
+            scale: Vector3::lerp(lhs.scale, rhs.scale, time),
+        };
+    }
+}
+
+impl DBAnimationClip {
+    /// Sample the translation, rotation & scale channels of the given bone. Missing channels use the identity pose
+    pub fn sample_bone(&self, bone: u32, time: f32, loop_mode: AnimationCurveLoopMode) -> BonePose {
+        let mut pose = BonePose::IDENTITY;
+        if let Some(v) = self.get_channel_vec3(bone, BINDING_TRANSLATION).and_then(|x| x.sample(time, loop_mode)) {
+            pose.translation = v;
+        }
+        if let Some(v) = self.get_channel_quat(bone, BINDING_ROTATION).and_then(|x| x.sample(time, loop_mode)) {
+            pose.rotation = v;
+        }
+        if let Some(v) = self.get_channel_vec3(bone, BINDING_SCALE).and_then(|x| x.sample(time, loop_mode)) {
+            pose.scale = v;
+        }
+        return pose;
+    }
+}
+
+struct MixerLayer {
+    clip: Arc<DBAnimationClip>,
+    time: f32,
+    speed: f32,
+    weight: f32,
+    // target weight & weight change per second
+    fade: Option<(f32, f32)>,
+}
+
+/// Plays several animation clips at once, blending them together with per-clip weights <br/>
+/// Rotations are blended by normalized linear interpolation, with translations & scales blended linearly
+pub struct AnimationMixer {
+    layers: Vec<MixerLayer>,
+    pub loop_mode: AnimationCurveLoopMode,
+}
+
+impl AnimationMixer {
+    /// Construct a new mixer with no clips playing
+    pub fn new() -> AnimationMixer {
+        return AnimationMixer {
+            layers: Vec::new(),
+            loop_mode: AnimationCurveLoopMode::Repeat,
+        };
+    }
+
+    /// Stop every clip and play the given clip at full weight
+    pub fn play(&mut self, clip: Arc<DBAnimationClip>) {
+        self.layers.clear();
+        self.add(clip, 1.0);
+    }
+
+    /// Add a clip with the given weight, returning its layer index
+    pub fn add(&mut self, clip: Arc<DBAnimationClip>, weight: f32) -> usize {
+        self.layers.push(MixerLayer { clip: clip, time: 0.0, speed: 1.0, weight: weight, fade: None });
+        return self.layers.len() - 1;
+    }
+
+    /// Fade in the given clip over the given duration in seconds, while fading out every other clip <br/>
+    /// Clips are removed once they have faded out completely
+    pub fn crossfade(&mut self, clip: Arc<DBAnimationClip>, duration: f32) {
+        if duration <= 0.0 {
+            self.play(clip);
+            return;
+        }
+
+        for layer in &mut self.layers {
+            layer.fade = Some((0.0, layer.weight / duration));
+        }
+
+        let idx = self.add(clip, 0.0);
+        self.layers[idx].fade = Some((1.0, 1.0 / duration));
+    }
+
+    /// Remove every clip
+    pub fn clear(&mut self) {
+        self.layers.clear();
+    }
+
+    /// Get the number of clips currently playing
+    pub fn len(&self) -> usize {
+        return self.layers.len();
+    }
+
+    /// Gets whether no clips are playing
+    pub fn is_empty(&self) -> bool {
+        return self.layers.is_empty();
+    }
+
+    /// Set the weight of the given layer, cancelling any fade in progress
+    pub fn set_weight(&mut self, layer: usize, weight: f32) {
+        if let Some(l) = self.layers.get_mut(layer) {
+            l.weight = weight;
+            l.fade = None;
+        }
+    }
+
+    /// Get the weight of the given layer
+    pub fn weight(&self, layer: usize) -> f32 {
+        return self.layers.get(layer).map(|x| x.weight).unwrap_or(0.0);
+    }
+
+    /// Set the playback speed of the given layer
+    pub fn set_speed(&mut self, layer: usize, speed: f32) {
+        if let Some(l) = self.layers.get_mut(layer) {
+            l.speed = speed;
+        }
+    }
+
+    /// Set the playback time of the given layer in seconds
+    pub fn set_time(&mut self, layer: usize, time: f32) {
+        if let Some(l) = self.layers.get_mut(layer) {
+            l.time = time;
+        }
+    }
+
+    /// Advance every clip & fade by the given time in seconds
+    pub fn update(&mut self, delta: f32) {
+        for layer in &mut self.layers {
+            layer.time += delta * layer.speed;
+
+            if let Some((target, rate)) = layer.fade {
+                if layer.weight < target {
+                    layer.weight = (layer.weight + rate * delta).min(target);
+                } else {
+                    layer.weight = (layer.weight - rate * delta).max(target);
+                }
+
+                if layer.weight == target {
+                    layer.fade = None;
+                }
+            }
+        }
+
+        // drop layers which have finished fading out
+        self.layers.retain(|x| x.weight > 0.0 || x.fade.is_some());
+    }
+
+    /// Sample the blended pose of the given bone
+    pub fn sample_bone(&self, bone: u32) -> BonePose {
+        let total: f32 = self.layers.iter().map(|x| x.weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return BonePose::IDENTITY;
+        }
+
+        let mut translation = Vector3::zero();
+        let mut scale = Vector3::zero();
+        let mut rotation = Quaternion::new(0.0, 0.0, 0.0, 0.0);
+        let mut first_rotation: Option<Quaternion> = None;
+
+        for layer in &self.layers {
+            let w = layer.weight.max(0.0) / total;
+            if w == 0.0 {
+                continue;
+            }
+
+            let pose = layer.clip.sample_bone(bone, layer.time, self.loop_mode);
+            translation += pose.translation * w;
+            scale += pose.scale * w;
+
+            // keep every rotation in the same hemisphere so they don't cancel out
+            let r = match first_rotation {
+                Some(first) => {
+                    let dot = first.x * pose.rotation.x + first.y * pose.rotation.y + first.z * pose.rotation.z + first.w * pose.rotation.w;
+                    if dot < 0.0 { -pose.rotation } else { pose.rotation }
+                },
+                None => {
+                    first_rotation = Some(pose.rotation);
+                    pose.rotation
+                }
+            };
+            rotation += r * w;
+        }
+
+        return BonePose { translation: translation, rotation: rotation.normalized(), scale: scale };
+    }
+
+    /// Sample the blended pose of every bone in the given skeleton, writing each bone's skinning matrix into the palette (indexed by bone index)
+    #[cfg(feature = "mesh")]
+    pub fn sample_skeleton(&self, skeleton: &DBSkeleton, palette: &mut [Matrix4x4]) {
+        for root in &skeleton.nodes {
+            self.sample_node(root, Matrix4x4::identity(), palette, None);
+        }
+    }
+
+    /// Sample the blended pose of every bone in the given skeleton, storing both skinning matrices & bone transforms so that bones can be queried afterwards
+    #[cfg(feature = "mesh")]
+    pub fn sample_pose(&self, skeleton: &DBSkeleton, pose: &mut SkeletonPose) {
+        let count = skeleton.bone_count as usize;
+        pose.palette.resize(count, Matrix4x4::identity());
+        pose.bone_transforms.resize(count, Matrix4x4::identity());
+
+        for root in &skeleton.nodes {
+            self.sample_node(root, Matrix4x4::identity(), &mut pose.palette, Some(&mut pose.bone_transforms));
+        }
+    }
+
+    #[cfg(feature = "mesh")]
+    fn sample_node(&self, node: &DBSkelNode, parent_mat: Matrix4x4, palette: &mut [Matrix4x4], mut bone_transforms: Option<&mut [Matrix4x4]>) {
+        let pose = self.sample_bone(node.bone_index as u32);
+
+        // animation transform relative to rest pose, then rest pose, then accumulated parent transform
+        let mut bone_to_object = Matrix4x4::identity();
+        Matrix4x4::load_simd(&pose.to_matrix());
+        Matrix4x4::mul_simd(&node.local_rest_pose);
+        Matrix4x4::mul_simd(&parent_mat);
+        Matrix4x4::store_simd(&mut bone_to_object);
+
+        let mut skin_mat = Matrix4x4::identity();
+        Matrix4x4::load_simd(&node.inv_bind_pose);
+        Matrix4x4::mul_simd(&bone_to_object);
+        Matrix4x4::store_simd(&mut skin_mat);
+
+        if let Some(slot) = palette.get_mut(node.bone_index as usize) {
+            *slot = skin_mat;
+        }
+
+        if let Some(slot) = bone_transforms.as_deref_mut().and_then(|x| x.get_mut(node.bone_index as usize)) {
+            *slot = bone_to_object;
+        }
+
+        for child in &node.children {
+            self.sample_node(child, bone_to_object, palette, bone_transforms.as_deref_mut());
+        }
+    }
+}
+
+impl Default for AnimationMixer {
+    fn default() -> Self {
+        return AnimationMixer::new();
+    }
+}
+
+/// The sampled pose of a skeleton: skinning matrices for drawing, plus the object-space transform of every bone
+#[cfg(feature = "mesh")]
+pub struct SkeletonPose {
+    /// Skinning matrix of each bone (inverse bind pose followed by bone transform), indexed by bone index
+    pub palette: Vec<Matrix4x4>,
+    /// Object-space transform of each bone, indexed by bone index
+    pub bone_transforms: Vec<Matrix4x4>,
+}
+
+#[cfg(feature = "mesh")]
+impl SkeletonPose {
+    /// Construct a new pose for the given skeleton, with every bone at the origin
+    pub fn new(skeleton: &DBSkeleton) -> SkeletonPose {
+        let count = skeleton.bone_count as usize;
+        return SkeletonPose {
+            palette: vec![Matrix4x4::identity();count],
+            bone_transforms: vec![Matrix4x4::identity();count],
+        };
+    }
+
+    /// Get the object-space transform of the bone with the given name
+    pub fn get_bone_transform(&self, skeleton: &DBSkeleton, name: &str) -> Option<Matrix4x4> {
+        let bone = skeleton.bone_index(name)?;
+        return self.bone_transforms.get(bone as usize).copied();
+    }
+
+    /// Get the world-space transform of the bone with the given name, given the model's world transform
+    pub fn get_bone_world_transform(&self, skeleton: &DBSkeleton, name: &str, model: &Matrix4x4) -> Option<Matrix4x4> {
+        let bone_mat = self.get_bone_transform(skeleton, name)?;
+
+        let mut world = Matrix4x4::identity();
+        Matrix4x4::load_simd(&bone_mat);
+        Matrix4x4::mul_simd(model);
+        Matrix4x4::store_simd(&mut world);
+        return Some(world);
+    }
+}
+
+/// Parents an object (such as a weapon in a hand, or a hat on a head) to a bone of an animated skeleton
+#[cfg(feature = "mesh")]
+#[derive(Clone, Copy)]
+pub struct Attachment {
+    pub bone_index: u8,
+    /// Transform of the attached object relative to the bone
+    pub offset: Matrix4x4,
+}
+
+#[cfg(feature = "mesh")]
+impl Attachment {
+    /// Construct an attachment to the bone with the given name, or None if the skeleton has no such bone
+    pub fn new(skeleton: &DBSkeleton, bone_name: &str, offset: Matrix4x4) -> Option<Attachment> {
+        return Some(Attachment { bone_index: skeleton.bone_index(bone_name)?, offset: offset });
+    }
+
+    /// Compute the world transform of the attached object from the sampled pose & the model's world transform
+    pub fn transform(&self, pose: &SkeletonPose, model: &Matrix4x4) -> Matrix4x4 {
+        let bone_mat = pose.bone_transforms.get(self.bone_index as usize).copied().unwrap_or(Matrix4x4::identity());
+
+        let mut world = Matrix4x4::identity();
+        Matrix4x4::load_simd(&self.offset);
+        Matrix4x4::mul_simd(&bone_mat);
+        Matrix4x4::mul_simd(model);
+        Matrix4x4::store_simd(&mut world);
+        return world;
+    }
+}
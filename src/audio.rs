@@ -1,6 +1,18 @@
 use std::{convert::TryInto};
 
-use crate::db_internal::{audio_alloc, audio_allocCompressed, audio_free, audio_getUsage, audio_queueSetParam_i, audio_queueSetParam_f, audio_queueStartVoice, audio_queueStopVoice, audio_getVoiceState, audio_getTime, audio_setReverbParams, audio_initSynth, audio_playMidi, audio_setMidiReverb, audio_setMidiVolume};
+use crate::{easing::{Easing, Tween}, math::Lerp};
+
+use crate::db_internal::{audio_alloc, audio_allocCompressed, audio_free, audio_getUsage, audio_queueSetParam_i, audio_queueSetParam_f, audio_queueStartVoice, audio_queueStopVoice, audio_getVoiceState, audio_getTime, audio_setReverbParams, audio_initSynth, audio_playMidi, audio_setMidiReverb, audio_setMidiVolume, audio_stopMidi, audio_setMidiPaused, audio_getMidiPosition, audio_setMidiTempo};
+
+pub mod bank;
+pub mod budget;
+pub mod stream;
+
+#[cfg(feature = "qoa")]
+pub mod qoa;
+
+#[cfg(feature = "vorbis")]
+pub mod vorbis;
 
 pub const VOICE_COUNT: usize = 32;
 
@@ -9,6 +21,8 @@ pub const VOICE_COUNT: usize = 32;
 pub struct AudioSample {
     pub handle: i32,
     pub samplerate: i32,
+    /// Length of the sample in frames
+    pub length: i32,
 }
 
 #[repr(C)]
@@ -38,9 +52,12 @@ impl AudioSample {
             return Err(());
         }
 
+        budget::record_alloc(handle, pcm_data.len());
+
         return Ok(AudioSample {
             handle: handle,
-            samplerate: samplerate
+            samplerate: samplerate,
+            length: pcm_data.len().try_into().unwrap(),
         });
     }
 
@@ -51,9 +68,12 @@ impl AudioSample {
             return Err(());
         }
 
+        budget::record_alloc(handle, pcm_data.len() * 2);
+
         return Ok(AudioSample {
             handle: handle,
-            samplerate: samplerate
+            samplerate: samplerate,
+            length: pcm_data.len().try_into().unwrap(),
         });
     }
 
@@ -64,19 +84,120 @@ impl AudioSample {
             return Err(());
         }
 
+        budget::record_alloc(handle, adpcm_data.len());
+
+        // each block starts with a 4-byte header containing the first sample, followed by two samples per byte
+        let block_frames = |block_len: usize| if block_len > 4 { (block_len - 4) * 2 + 1 } else { 0 };
+        let chunk_len: usize = chunk_size.max(1).try_into().unwrap();
+        let length = (adpcm_data.len() / chunk_len) * block_frames(chunk_len) + block_frames(adpcm_data.len() % chunk_len);
+
         return Ok(AudioSample {
             handle: handle,
-            samplerate: samplerate
+            samplerate: samplerate,
+            length: length.try_into().unwrap(),
         });
     }
 }
 
 impl Drop for AudioSample {
     fn drop(&mut self) {
+        budget::record_free(self.handle);
         unsafe { audio_free(self.handle); }
     }
 }
 
+/// Owns a hardware voice slot, scheduling parameter changes on it and stopping it when dropped
+pub struct Voice {
+    slot: i32,
+}
+
+impl Voice {
+    /// Take ownership of the given voice slot
+    pub fn new(slot: i32) -> Voice {
+        assert!(slot >= 0 && slot < VOICE_COUNT.try_into().unwrap(), "Tried to create invalid voice handle");
+        return Voice { slot: slot };
+    }
+
+    /// Get the voice slot owned by this voice
+    pub fn slot(&self) -> i32 {
+        return self.slot;
+    }
+
+    /// Schedule the sample data & samplerate to play on this voice
+    pub fn set_sample(&self, sample: &AudioSample, time: f64) {
+        queue_set_voice_param_i(self.slot, AudioVoiceParam::SampleData, sample.handle, time);
+        queue_set_voice_param_i(self.slot, AudioVoiceParam::Samplerate, sample.samplerate, time);
+    }
+
+    /// Schedule a change to the samplerate the current sample is played back at
+    pub fn set_samplerate(&self, samplerate: i32, time: f64) {
+        queue_set_voice_param_i(self.slot, AudioVoiceParam::Samplerate, samplerate, time);
+    }
+
+    /// Schedule a change to the looping settings of the voice (loop start & end are in sample frames, a loop end of 0 loops the entire sample)
+    pub fn set_looping(&self, enabled: bool, loop_start: i32, loop_end: i32, time: f64) {
+        queue_set_voice_param_i(self.slot, AudioVoiceParam::LoopEnabled, if enabled { 1 } else { 0 }, time);
+        queue_set_voice_param_i(self.slot, AudioVoiceParam::LoopStart, loop_start, time);
+        queue_set_voice_param_i(self.slot, AudioVoiceParam::LoopEnd, loop_end, time);
+    }
+
+    /// Schedule a change to whether the voice is routed through the reverb unit
+    pub fn set_reverb(&self, enabled: bool, time: f64) {
+        queue_set_voice_param_i(self.slot, AudioVoiceParam::Reverb, if enabled { 1 } else { 0 }, time);
+    }
+
+    /// Schedule a change to the volume of the voice
+    pub fn set_volume(&self, volume: f32, time: f64) {
+        queue_set_voice_param_f(self.slot, AudioVoiceParam::Volume, volume, time);
+    }
+
+    /// Schedule a change to the pitch of the voice
+    pub fn set_pitch(&self, pitch: f32, time: f64) {
+        queue_set_voice_param_f(self.slot, AudioVoiceParam::Pitch, pitch, time);
+    }
+
+    /// Schedule a change to the detune of the voice
+    pub fn set_detune(&self, detune: f32, time: f64) {
+        queue_set_voice_param_f(self.slot, AudioVoiceParam::Detune, detune, time);
+    }
+
+    /// Schedule a change to the pan of the voice (-1.0 is left, 1.0 is right)
+    pub fn set_pan(&self, pan: f32, time: f64) {
+        queue_set_voice_param_f(self.slot, AudioVoiceParam::Pan, pan, time);
+    }
+
+    /// Schedule a change to the fade in duration applied when the voice starts
+    pub fn set_fade_in(&self, duration: f32, time: f64) {
+        queue_set_voice_param_f(self.slot, AudioVoiceParam::FadeInDuration, duration, time);
+    }
+
+    /// Schedule a change to the fade out duration applied when the voice stops
+    pub fn set_fade_out(&self, duration: f32, time: f64) {
+        queue_set_voice_param_f(self.slot, AudioVoiceParam::FadeOutDuration, duration, time);
+    }
+
+    /// Schedule the voice to start playing
+    pub fn start(&self, time: f64) {
+        queue_start_voice(self.slot, time);
+    }
+
+    /// Schedule the voice to stop playing
+    pub fn stop(&self, time: f64) {
+        queue_stop_voice(self.slot, time);
+    }
+
+    /// Gets whether the voice is currently playing
+    pub fn is_playing(&self) -> bool {
+        return get_voice_state(self.slot);
+    }
+}
+
+impl Drop for Voice {
+    fn drop(&mut self) {
+        queue_stop_voice(self.slot, get_time());
+    }
+}
+
 /// Get the current sample memory usage in bytes
 pub fn get_usage() -> i32 {
     unsafe { return audio_getUsage(); }
@@ -122,6 +243,97 @@ pub fn set_reverb(room_size: f32, damping: f32, width: f32, wet: f32, dry: f32)
     unsafe { audio_setReverbParams(room_size, damping, width, wet, dry); }
 }
 
+/// Parameters for the reverb unit
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReverbParams {
+    pub room_size: f32,
+    pub damping: f32,
+    pub width: f32,
+    pub wet: f32,
+    pub dry: f32,
+}
+
+impl Lerp<ReverbParams> for ReverbParams {
+    fn lerp(a: ReverbParams, b: ReverbParams, t: f32) -> ReverbParams {
+        return ReverbParams {
+            room_size: f32::lerp(a.room_size, b.room_size, t),
+            damping: f32::lerp(a.damping, b.damping, t),
+            width: f32::lerp(a.width, b.width, t),
+            wet: f32::lerp(a.wet, b.wet, t),
+            dry: f32::lerp(a.dry, b.dry, t),
+        };
+    }
+}
+
+/// Tuned reverb settings for common environments
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReverbPreset {
+    Off,
+    Room,
+    Hall,
+    Cave,
+    Arena,
+}
+
+impl ReverbPreset {
+    /// Get the reverb unit parameters for this preset
+    pub fn params(self) -> ReverbParams {
+        return match self {
+            ReverbPreset::Off => { ReverbParams { room_size: 0.0, damping: 0.0, width: 1.0, wet: 0.0, dry: 1.0 } }
+            ReverbPreset::Room => { ReverbParams { room_size: 0.35, damping: 0.6, width: 1.0, wet: 0.15, dry: 1.0 } }
+            ReverbPreset::Hall => { ReverbParams { room_size: 0.8, damping: 0.4, width: 1.0, wet: 0.3, dry: 0.85 } }
+            ReverbPreset::Cave => { ReverbParams { room_size: 0.9, damping: 0.2, width: 1.0, wet: 0.45, dry: 0.75 } }
+            ReverbPreset::Arena => { ReverbParams { room_size: 0.95, damping: 0.35, width: 1.0, wet: 0.35, dry: 0.8 } }
+        };
+    }
+}
+
+/// Set the current reverb unit parameters from a parameter set
+pub fn set_reverb_params(params: &ReverbParams) {
+    set_reverb(params.room_size, params.damping, params.width, params.wet, params.dry);
+}
+
+/// Helper which smoothly sweeps the reverb unit between parameter sets over time
+pub struct ReverbFader {
+    tween: Tween<ReverbParams>,
+}
+
+impl ReverbFader {
+    /// Construct a new fader, immediately applying the given initial parameters
+    pub fn new(initial: ReverbParams) -> ReverbFader {
+        set_reverb_params(&initial);
+        return ReverbFader { tween: Tween::new(initial, initial, 0.0, Easing::Linear) };
+    }
+
+    /// Get the reverb parameters currently applied by the fader
+    pub fn current(&self) -> ReverbParams {
+        return self.tween.value();
+    }
+
+    /// Start sweeping from the current parameters to the given parameters over the given duration in seconds
+    pub fn transition_to(&mut self, target: ReverbParams, duration: f32) {
+        self.tween = Tween::new(self.current(), target, duration, Easing::QuadInOut);
+        if duration <= 0.0 {
+            set_reverb_params(&target);
+        }
+    }
+
+    /// Start sweeping from the current parameters to the given preset over the given duration in seconds
+    pub fn transition_to_preset(&mut self, preset: ReverbPreset, duration: f32) {
+        self.transition_to(preset.params(), duration);
+    }
+
+    /// Advance the transition by the given delta time in seconds, updating the reverb unit
+    pub fn update(&mut self, delta: f32) {
+        if self.tween.is_finished() {
+            return;
+        }
+
+        let params = self.tween.update(delta);
+        set_reverb_params(&params);
+    }
+}
+
 /// Initialize the MIDI synth using the given soundfont data
 pub fn init_synth(sf2_data: &[u8]) -> Result<(),()> {
     unsafe {
@@ -154,4 +366,64 @@ pub fn set_midi_reverb(enabled: bool) {
 /// Set the volume of MIDI playback
 pub fn set_midi_volume(volume: f32) {
     unsafe { audio_setMidiVolume(volume); }
-}
\ No newline at end of file
+}
+
+/// Stop MIDI playback
+pub fn stop_midi() {
+    unsafe { audio_stopMidi(); }
+}
+
+/// Pause MIDI playback, keeping the current playback position
+pub fn pause_midi() {
+    unsafe { audio_setMidiPaused(true); }
+}
+
+/// Resume paused MIDI playback
+pub fn resume_midi() {
+    unsafe { audio_setMidiPaused(false); }
+}
+
+/// Get the current MIDI playback position in seconds
+pub fn get_midi_position() -> f64 {
+    unsafe { return audio_getMidiPosition(); }
+}
+
+/// Set the MIDI playback tempo scale (1.0 is the tempo of the MIDI file, 2.0 is twice as fast)
+pub fn set_midi_tempo(scale: f32) {
+    unsafe { audio_setMidiTempo(scale); }
+}
+
+/// Helper which fades MIDI playback volume down to silence over time and then stops playback
+pub struct MidiFadeOut {
+    volume: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl MidiFadeOut {
+    /// Start fading out from the given MIDI volume over the given duration in seconds
+    pub fn new(volume: f32, duration: f32) -> MidiFadeOut {
+        return MidiFadeOut { volume: volume, duration: duration, elapsed: 0.0 };
+    }
+
+    /// Advance the fade by the given delta time in seconds, stopping MIDI playback & restoring the original volume once the fade is finished
+    pub fn update(&mut self, delta: f32) {
+        if self.is_finished() {
+            return;
+        }
+
+        self.elapsed += delta;
+
+        if self.is_finished() {
+            stop_midi();
+            set_midi_volume(self.volume);
+        } else {
+            set_midi_volume(self.volume * (1.0 - (self.elapsed / self.duration)));
+        }
+    }
+
+    /// Gets whether the fade has finished and MIDI playback has been stopped
+    pub fn is_finished(&self) -> bool {
+        return self.elapsed >= self.duration;
+    }
+}
@@ -0,0 +1,177 @@
+use std::{collections::HashMap, io::{ErrorKind, Read}, sync::Arc};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::audio::{AudioSample, budget};
+
+const DBSB_VER: u32 = 1;
+
+/// Encoding of sample data stored in a sound bank
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundBankFormat {
+    PCM8,
+    PCM16,
+    ADPCM,
+}
+
+/// A single named sample loaded from a sound bank
+pub struct SoundBankEntry {
+    pub sample: Arc<AudioSample>,
+    /// Loop start position in sample frames
+    pub loop_start: i32,
+    /// Loop end position in sample frames (0 loops the entire sample)
+    pub loop_end: i32,
+}
+
+/// A collection of named audio samples loaded from a single DBSB sound bank file <br/>
+/// The file starts with a "DBSB" identifier and u32 version, followed by chunks of a 4-byte id and u32 size. <br/>
+/// Each "SMPL" chunk contains a null-terminated 32-byte name, u8 format (0 = signed 8-bit PCM, 1 = signed 16-bit PCM, 2 = IMA ADPCM), 3 bytes padding,
+/// u32 samplerate, u32 ADPCM block size, i32 loop start, i32 loop end, and then the sample data. Unknown chunks are skipped
+pub struct SoundBank {
+    entries: HashMap<String, SoundBankEntry>,
+}
+
+/// Enumeration of errors which can result from loading a sound bank
+#[derive(Debug)]
+pub enum SoundBankError {
+    ParseError,
+    VersionError,
+    AllocError,
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for SoundBankError {
+    fn from(value: std::io::Error) -> Self {
+        return SoundBankError::IOError(value);
+    }
+}
+
+fn str_from_null_terminated(s: &[u8]) -> Result<&str,SoundBankError> {
+    let len = s.iter().position(|x| *x == 0).unwrap_or(s.len());
+    return match std::str::from_utf8(&s[..len]) {
+        Ok(v) => { Ok(v) },
+        Err(_) => { Err(SoundBankError::ParseError) }
+    };
+}
+
+impl SoundBank {
+    /// Load a sound bank from the given stream, uploading every sample it contains
+    pub fn new<R>(reader: &mut R) -> Result<SoundBank,SoundBankError> where R : Read {
+        let mut id: [u8;4] = [0;4];
+        reader.read_exact(&mut id)?;
+
+        if &id != b"DBSB" {
+            return Err(SoundBankError::ParseError);
+        }
+
+        let ver = reader.read_u32::<LittleEndian>()?;
+        if ver != DBSB_VER {
+            return Err(SoundBankError::VersionError);
+        }
+
+        let mut bank = SoundBank {
+            entries: HashMap::new(),
+        };
+
+        // scan chunks
+        loop {
+            let mut chunk_id: [u8;4] = [0;4];
+            match reader.read_exact(&mut chunk_id) {
+                Ok(_) => {
+                },
+                Err(e) => {
+                    // EOF, no more chunks in stream
+                    if e.kind() == ErrorKind::UnexpectedEof {
+                        break;
+                    }
+                    return Err(SoundBankError::IOError(e));
+                }
+            };
+
+            let chunk_size = reader.read_u32::<LittleEndian>()? as usize;
+            let mut chunk_data: Vec<u8> = vec![0;chunk_size];
+            reader.read_exact(&mut chunk_data)?;
+
+            if &chunk_id == b"SMPL" {
+                let (name, entry) = SoundBank::read_sample(chunk_data.as_slice())?;
+                bank.entries.insert(name, entry);
+            }
+        }
+
+        return Ok(bank);
+    }
+
+    fn read_sample(mut chunk: &[u8]) -> Result<(String, SoundBankEntry),SoundBankError> {
+        let mut name: [u8;32] = [0;32];
+        chunk.read_exact(&mut name)?;
+        let name = str_from_null_terminated(&name)?.to_string();
+
+        let format = match chunk.read_u8()? {
+            0 => { SoundBankFormat::PCM8 },
+            1 => { SoundBankFormat::PCM16 },
+            2 => { SoundBankFormat::ADPCM },
+            _ => { return Err(SoundBankError::ParseError); }
+        };
+
+        let mut padding: [u8;3] = [0;3];
+        chunk.read_exact(&mut padding)?;
+
+        let samplerate = chunk.read_u32::<LittleEndian>()? as i32;
+        let block_size = chunk.read_u32::<LittleEndian>()? as i32;
+        let loop_start = chunk.read_i32::<LittleEndian>()?;
+        let loop_end = chunk.read_i32::<LittleEndian>()?;
+
+        // remaining chunk data is the sample data
+        let sample = budget::with_label(name.as_str(), || {
+            return match format {
+                SoundBankFormat::PCM8 => {
+                    let pcm8: Vec<i8> = chunk.iter().map(|x| *x as i8).collect();
+                    AudioSample::create_s8(pcm8.as_slice(), samplerate)
+                },
+                SoundBankFormat::PCM16 => {
+                    let pcm16: Vec<i16> = chunk.chunks_exact(2).map(|x| i16::from_le_bytes([x[0], x[1]])).collect();
+                    AudioSample::create_s16(pcm16.as_slice(), samplerate)
+                },
+                SoundBankFormat::ADPCM => {
+                    AudioSample::create_adpcm(chunk, block_size, samplerate)
+                }
+            };
+        });
+
+        let sample = match sample {
+            Ok(v) => { v },
+            Err(_) => { return Err(SoundBankError::AllocError); }
+        };
+
+        return Ok((name, SoundBankEntry {
+            sample: Arc::new(sample),
+            loop_start: loop_start,
+            loop_end: loop_end,
+        }));
+    }
+
+    /// Get the sample with the given name
+    pub fn get(&self, name: &str) -> Option<&Arc<AudioSample>> {
+        return self.entries.get(name).map(|x| &x.sample);
+    }
+
+    /// Get the sample & loop points with the given name
+    pub fn entry(&self, name: &str) -> Option<&SoundBankEntry> {
+        return self.entries.get(name);
+    }
+
+    /// Get an iterator over the names of every sample in the bank
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        return self.entries.keys().map(|x| x.as_str());
+    }
+
+    /// Get the number of samples in the bank
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    /// Gets whether the bank contains no samples
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+}
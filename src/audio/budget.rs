@@ -0,0 +1,73 @@
+use std::sync::Mutex;
+
+use crate::{audio::{AudioSample, get_usage}, budget::{self, BudgetTracker}, db::log};
+
+static TRACKER: Mutex<BudgetTracker> = Mutex::new(BudgetTracker::new());
+
+/// Summary of audio memory usage
+#[derive(Clone, Copy, Debug)]
+pub struct BudgetReport {
+    /// Bytes of sample data allocated through AudioSample
+    pub tracked_bytes: usize,
+    /// Bytes of sample memory in use as reported by the audio hardware
+    pub hardware_bytes: usize,
+    /// Number of live audio samples
+    pub sample_count: usize,
+    /// The configured budget in bytes, if any
+    pub budget: Option<usize>,
+}
+
+/// Set the audio memory budget in bytes (or None to disable budget checks) <br/>
+/// When an allocation exceeds the budget, a warning and the largest samples are logged. If panic_in_debug is set, debug builds will also panic
+pub fn set_budget(budget: Option<usize>, panic_in_debug: bool) {
+    TRACKER.lock().unwrap().set_budget(budget, panic_in_debug);
+}
+
+/// Attach a debug label to the given sample, used when reporting usage
+pub fn set_label(sample: &AudioSample, label: &str) {
+    TRACKER.lock().unwrap().set_label(sample.handle, label);
+}
+
+/// Run the given function, labelling every sample allocated inside of it with the given debug label (for example, a whole load_wav call)
+pub fn with_label<T, F>(label: &str, f: F) -> T where F : FnOnce() -> T {
+    return budget::with_label(&TRACKER, label, f);
+}
+
+/// Get a summary of current audio memory usage
+pub fn report() -> BudgetReport {
+    let tracker = TRACKER.lock().unwrap();
+    return BudgetReport {
+        tracked_bytes: tracker.tracked_bytes(),
+        hardware_bytes: get_usage().max(0) as usize,
+        sample_count: tracker.count(),
+        budget: tracker.budget(),
+    };
+}
+
+/// Get the labels & sizes in bytes of the largest live samples, largest first
+pub fn largest(count: usize) -> Vec<(String, usize)> {
+    return TRACKER.lock().unwrap().largest(count);
+}
+
+/// Log current usage along with the given number of largest samples
+pub fn dump(count: usize) {
+    let r = report();
+    match r.budget {
+        Some(budget) => {
+            log(format!("Audio memory: {} bytes in use ({} tracked across {} samples), budget {} bytes", r.hardware_bytes, r.tracked_bytes, r.sample_count, budget).as_str());
+        }
+        None => {
+            log(format!("Audio memory: {} bytes in use ({} tracked across {} samples)", r.hardware_bytes, r.tracked_bytes, r.sample_count).as_str());
+        }
+    }
+
+    budget::log_largest(largest(count));
+}
+
+pub(crate) fn record_alloc(handle: i32, bytes: usize) {
+    budget::record_alloc(&TRACKER, handle, bytes, get_usage().max(0) as usize, "audio", dump);
+}
+
+pub(crate) fn record_free(handle: i32) {
+    TRACKER.lock().unwrap().record_free(handle);
+}
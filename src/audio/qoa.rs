@@ -0,0 +1,186 @@
+use std::{convert::TryInto, io::Read};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::audio::AudioSample;
+
+const QOA_MAGIC: [u8;4] = *b"qoaf";
+const QOA_SLICE_LEN: usize = 20;
+const QOA_LMS_LEN: usize = 4;
+
+const SCALEFACTOR_TAB: [i32;16] = [1, 7, 21, 45, 84, 138, 211, 304, 421, 562, 731, 928, 1157, 1419, 1715, 2048];
+const DEQUANT_STEPS: [f32;8] = [0.75, -0.75, 2.5, -2.5, 4.5, -4.5, 7.0, -7.0];
+
+#[derive(Clone, Copy)]
+struct QoaLms {
+    history: [i32;QOA_LMS_LEN],
+    weights: [i32;QOA_LMS_LEN],
+}
+
+impl QoaLms {
+    fn predict(&self) -> i32 {
+        let mut prediction = 0;
+        for i in 0..QOA_LMS_LEN {
+            prediction += self.weights[i] * self.history[i];
+        }
+        return prediction >> 13;
+    }
+
+    fn update(&mut self, sample: i32, residual: i32) {
+        let delta = residual >> 4;
+        for i in 0..QOA_LMS_LEN {
+            self.weights[i] += if self.history[i] < 0 { -delta } else { delta };
+        }
+
+        self.history.copy_within(1.., 0);
+        self.history[QOA_LMS_LEN - 1] = sample;
+    }
+}
+
+/// Incremental decoder for QOA ("Quite OK Audio") encoded audio
+pub struct QoaDecoder<R> where R : Read {
+    reader: R,
+    total_samples: u32,
+    decoded_samples: u32,
+    samplerate: u32,
+    dequant: [[i32;8];16],
+}
+
+impl<R> QoaDecoder<R> where R : Read {
+    /// Construct a new decoder, reading the file header from the given stream
+    pub fn new(mut reader: R) -> Result<QoaDecoder<R>,()> {
+        let mut magic: [u8;4] = [0;4];
+        if reader.read_exact(&mut magic).is_err() || magic != QOA_MAGIC {
+            return Err(());
+        }
+
+        let total_samples = match reader.read_u32::<BigEndian>() {
+            Ok(v) => { v },
+            Err(_) => { return Err(()); }
+        };
+
+        let mut dequant: [[i32;8];16] = [[0;8];16];
+        for (s, row) in dequant.iter_mut().enumerate() {
+            for (q, v) in row.iter_mut().enumerate() {
+                *v = (SCALEFACTOR_TAB[s] as f32 * DEQUANT_STEPS[q]).round() as i32;
+            }
+        }
+
+        return Ok(QoaDecoder {
+            reader: reader,
+            total_samples: total_samples,
+            decoded_samples: 0,
+            samplerate: 0,
+            dequant: dequant,
+        });
+    }
+
+    /// Get the total number of samples in the stream (0 if the file was encoded in streaming mode and the length is unknown)
+    pub fn total_samples(&self) -> u32 {
+        return self.total_samples;
+    }
+
+    /// Get the samplerate of the most recently decoded frame (0 if no frames have been decoded yet)
+    pub fn samplerate(&self) -> u32 {
+        return self.samplerate;
+    }
+
+    /// Gets whether the decoder has reached the end of the stream
+    pub fn is_finished(&self) -> bool {
+        return self.total_samples > 0 && self.decoded_samples >= self.total_samples;
+    }
+
+    /// Decode the next frame, appending mono signed 16-bit samples to the output buffer <br/>
+    /// Returns the number of samples decoded, or 0 at the end of the stream. Only mono streams are supported.
+    pub fn decode_frame(&mut self, output: &mut Vec<i16>) -> Result<usize,()> {
+        if self.is_finished() {
+            return Ok(0);
+        }
+
+        let frame_header = match self.reader.read_u64::<BigEndian>() {
+            Ok(v) => { v },
+            Err(e) => {
+                // files encoded in streaming mode simply end after the last frame
+                if self.total_samples == 0 && e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    return Ok(0);
+                }
+                return Err(());
+            }
+        };
+
+        let channels = (frame_header >> 56) & 0xFF;
+        let samplerate = ((frame_header >> 32) & 0xFFFFFF) as u32;
+        let frame_samples = ((frame_header >> 16) & 0xFFFF) as usize;
+
+        if channels != 1 || samplerate == 0 {
+            return Err(());
+        }
+
+        self.samplerate = samplerate;
+
+        let mut lms = QoaLms { history: [0;QOA_LMS_LEN], weights: [0;QOA_LMS_LEN] };
+        let mut history = match self.reader.read_u64::<BigEndian>() {
+            Ok(v) => { v },
+            Err(_) => { return Err(()); }
+        };
+        let mut weights = match self.reader.read_u64::<BigEndian>() {
+            Ok(v) => { v },
+            Err(_) => { return Err(()); }
+        };
+
+        for i in 0..QOA_LMS_LEN {
+            lms.history[i] = (history >> 48) as i16 as i32;
+            history <<= 16;
+            lms.weights[i] = (weights >> 48) as i16 as i32;
+            weights <<= 16;
+        }
+
+        output.reserve(frame_samples);
+
+        let mut sample_index = 0;
+        while sample_index < frame_samples {
+            let mut slice = match self.reader.read_u64::<BigEndian>() {
+                Ok(v) => { v },
+                Err(_) => { return Err(()); }
+            };
+
+            let scalefactor = ((slice >> 60) & 0xF) as usize;
+            slice <<= 4;
+
+            let slice_end = (sample_index + QOA_SLICE_LEN).min(frame_samples);
+            for _ in sample_index..slice_end {
+                let predicted = lms.predict();
+                let quantized = ((slice >> 61) & 0x7) as usize;
+                let dequantized = self.dequant[scalefactor][quantized];
+                let reconstructed = (predicted + dequantized).clamp(-32768, 32767);
+                output.push(reconstructed as i16);
+                slice <<= 3;
+                lms.update(reconstructed, dequantized);
+            }
+
+            sample_index = slice_end;
+        }
+
+        self.decoded_samples += TryInto::<u32>::try_into(frame_samples).unwrap();
+
+        return Ok(frame_samples);
+    }
+
+    /// Decode all remaining frames into a buffer of mono signed 16-bit samples
+    pub fn decode_all(&mut self) -> Result<Vec<i16>,()> {
+        // the header's sample count isn't trusted until the frames are actually decoded, so only reserve up front for a modest length
+        let remaining: usize = self.total_samples.saturating_sub(self.decoded_samples).try_into().map_err(|_| ())?;
+        let mut output: Vec<i16> = Vec::with_capacity(remaining.min(1 << 20));
+        while self.decode_frame(&mut output)? > 0 {
+        }
+        return Ok(output);
+    }
+}
+
+/// Load a QOA file, returning an audio sample handle (only mono files are supported)
+pub fn load_qoa<R: Read>(reader: R) -> Result<AudioSample,()> {
+    let mut decoder = QoaDecoder::new(reader)?;
+    let pcm16 = decoder.decode_all()?;
+    let samplerate = decoder.samplerate().try_into().map_err(|_| ())?;
+    return AudioSample::create_s16(pcm16.as_slice(), samplerate);
+}
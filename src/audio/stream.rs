@@ -0,0 +1,232 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use crate::audio::{AudioSample, Voice, get_time};
+
+/// Default amount of time in seconds that audio is scheduled ahead of the audio clock
+pub const DEFAULT_LOOKAHEAD: f64 = 0.25;
+
+// chunks of dropped streams which may still be referenced by queued voice commands, kept alive until they would have finished playing
+static RETIRED: Mutex<Vec<StreamChunk>> = Mutex::new(Vec::new());
+
+struct StreamChunk {
+    start: f64,
+    end: f64,
+    _samples: Vec<AudioSample>,
+}
+
+struct PendingChunk {
+    start: f64,
+    end: f64,
+    channels: Vec<Vec<i16>>,
+}
+
+/// Plays a continuous stream of PCM audio by scheduling consecutive chunks on a set of reserved hardware voices <br/>
+/// Mono streams use one voice, stereo streams use two voices panned hard left & right. The voices are stopped when the stream is dropped,
+/// though the sample data of chunks already queued is kept alive until they would have finished playing
+pub struct AudioStream {
+    voices: Vec<Voice>,
+    samplerate: i32,
+    volume: f32,
+    reverb: bool,
+    lookahead: f64,
+    next_time: f64,
+    pending: Option<PendingChunk>,
+    scheduled: VecDeque<StreamChunk>,
+}
+
+impl AudioStream {
+    /// Construct a new stream which plays audio on the given voice slots (one slot for mono, or left & right slots for stereo)
+    pub fn new(slots: &[i32], samplerate: i32) -> AudioStream {
+        assert!(slots.len() == 1 || slots.len() == 2, "Audio streams must be either mono or stereo");
+
+        return AudioStream {
+            voices: slots.iter().map(|slot| Voice::new(*slot)).collect(),
+            samplerate: samplerate,
+            volume: 1.0,
+            reverb: false,
+            lookahead: DEFAULT_LOOKAHEAD,
+            next_time: -1.0,
+            pending: None,
+            scheduled: VecDeque::new(),
+        };
+    }
+
+    /// Get the number of channels in the stream
+    pub fn channels(&self) -> usize {
+        return self.voices.len();
+    }
+
+    /// Get the samplerate of the stream
+    pub fn samplerate(&self) -> i32 {
+        return self.samplerate;
+    }
+
+    /// Set the volume of the stream (applies to chunks which have not been scheduled yet)
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+    }
+
+    /// Set whether the stream is routed through the reverb unit (applies to chunks which have not been scheduled yet)
+    pub fn set_reverb(&mut self, reverb: bool) {
+        self.reverb = reverb;
+    }
+
+    /// Set the amount of time in seconds that audio is scheduled ahead of the audio clock
+    pub fn set_lookahead(&mut self, lookahead: f64) {
+        self.lookahead = lookahead;
+    }
+
+    /// Get the amount of audio in seconds which has been submitted but has not finished playing yet
+    pub fn buffered_time(&self) -> f64 {
+        return (self.next_time - get_time()).max(0.0);
+    }
+
+    /// Gets whether the stream wants another chunk of audio to avoid running dry
+    pub fn needs_data(&self) -> bool {
+        let now = get_time();
+        return match &self.pending {
+            Some(p) => { p.start - now < self.lookahead }
+            None => { self.next_time - now < self.lookahead }
+        };
+    }
+
+    /// Gets whether any submitted audio is still waiting to play or currently playing
+    pub fn is_playing(&self) -> bool {
+        return self.pending.is_some() || self.next_time > get_time();
+    }
+
+    /// Submit a chunk of interleaved signed 16-bit PCM audio to be played after any previously submitted audio
+    pub fn submit(&mut self, samples: &[i16]) -> Result<(),()> {
+        let channels = self.voices.len();
+        let frames = samples.len() / channels;
+        if frames == 0 {
+            return Ok(());
+        }
+
+        let now = get_time();
+
+        // drop any chunks which have finished playing
+        while let Some(chunk) = self.scheduled.front() {
+            if chunk.end >= now {
+                break;
+            }
+            self.scheduled.pop_front();
+        }
+        RETIRED.lock().unwrap().retain(|x| x.end >= now);
+
+        // if playback fell behind the audio clock (or this is the first chunk), restart the schedule
+        let late = match &self.pending {
+            Some(p) => { p.start < now }
+            None => { self.next_time < now }
+        };
+
+        if late {
+            let restart = now + self.lookahead;
+            match &mut self.pending {
+                Some(p) => {
+                    let offset = restart - p.start;
+                    p.start += offset;
+                    p.end += offset;
+                    self.next_time += offset;
+                }
+                None => {
+                    self.next_time = restart;
+                }
+            }
+        }
+
+        let mut data: Vec<Vec<i16>> = vec![Vec::with_capacity(frames + 1); channels];
+        for frame in samples.chunks_exact(channels) {
+            for (ch, v) in frame.iter().enumerate() {
+                data[ch].push(*v);
+            }
+        }
+
+        // chunks are held back until the next one arrives, so that the first sample of the next chunk can be appended to the end of the previous one
+        // this is because DreamBox's 2-tap sampling doesn't take the next queued sample into account at the end of a buffer,
+        // which would otherwise cause a single sample of aliasing between every buffer
+        if let Some(mut prev) = self.pending.take() {
+            for (ch, buf) in prev.channels.iter_mut().enumerate() {
+                buf.push(data[ch][0]);
+            }
+            self.schedule(prev)?;
+        }
+
+        let start = self.next_time;
+        self.next_time += frames as f64 / self.samplerate as f64;
+        self.pending = Some(PendingChunk { start: start, end: self.next_time, channels: data });
+
+        return Ok(());
+    }
+
+    /// Schedule any held back audio, used once the final chunk of a stream has been submitted
+    pub fn flush(&mut self) -> Result<(),()> {
+        if let Some(prev) = self.pending.take() {
+            self.schedule(prev)?;
+        }
+        return Ok(());
+    }
+
+    /// Stop the stream and discard any audio which has not been scheduled yet
+    pub fn stop(&mut self) {
+        let now = get_time();
+        for voice in &self.voices {
+            voice.stop(now);
+
+            // chunks which were already scheduled ahead cannot be cancelled, so stop the voice again right as they start
+            for chunk in &self.scheduled {
+                if chunk.start > now {
+                    voice.stop(chunk.start);
+                }
+            }
+        }
+
+        self.pending = None;
+        self.next_time = -1.0;
+    }
+
+    fn schedule(&mut self, chunk: PendingChunk) -> Result<(),()> {
+        let t = chunk.start;
+
+        // every channel is allocated before any voice is queued, so a failed allocation can't leave one voice of a stereo pair playing a freed sample
+        let mut samples: Vec<AudioSample> = Vec::with_capacity(chunk.channels.len());
+        for data in &chunk.channels {
+            samples.push(AudioSample::create_s16(data.as_slice(), self.samplerate)?);
+        }
+
+        for (ch, sample) in samples.iter().enumerate() {
+            let voice = &self.voices[ch];
+            let pan = if self.voices.len() == 1 { 0.0 } else if ch == 0 { -1.0 } else { 1.0 };
+
+            voice.set_sample(sample, t);
+            voice.set_looping(false, 0, 0, t);
+            voice.set_reverb(self.reverb, t);
+            voice.set_volume(self.volume, t);
+            voice.set_pitch(1.0, t);
+            voice.set_detune(0.0, t);
+            voice.set_pan(pan, t);
+            voice.set_fade_in(0.0, t);
+            voice.set_fade_out(0.0, t);
+
+            voice.stop(t);
+            voice.start(t);
+        }
+
+        // sample data must stay alive until it has finished playing
+        self.scheduled.push_back(StreamChunk { start: t, end: chunk.end, _samples: samples });
+
+        return Ok(());
+    }
+}
+
+impl Drop for AudioStream {
+    fn drop(&mut self) {
+        self.stop();
+
+        // the stop commands only take effect at their queued times, so sample data can't be freed until then
+        let now = get_time();
+        let mut retired = RETIRED.lock().unwrap();
+        retired.retain(|x| x.end >= now);
+        retired.extend(self.scheduled.drain(..).filter(|x| x.end >= now));
+    }
+}
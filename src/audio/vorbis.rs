@@ -0,0 +1,188 @@
+use std::{convert::TryInto, io::{Read, Seek}};
+
+use lewton::inside_ogg::OggStreamReader;
+
+use crate::audio::stream::AudioStream;
+
+/// Streams Ogg Vorbis music from a file, decoding it in chunks and playing it through an AudioStream <br/>
+/// Loop points are read from LOOPSTART and LOOPLENGTH (or LOOPEND) comments, given in sample frames
+pub struct VorbisStream<R> where R : Read + Seek {
+    reader: OggStreamReader<R>,
+    stream: AudioStream,
+    position: Option<u64>,
+    seek_target: Option<u64>,
+    loop_start: u64,
+    loop_end: Option<u64>,
+    finished: bool,
+    pub looping: bool,
+}
+
+impl<R> VorbisStream<R> where R : Read + Seek {
+    /// Open an Ogg Vorbis stream which plays on the given voice slots (one slot for mono files, or left & right slots for stereo files)
+    pub fn new(reader: R, slots: &[i32]) -> Result<VorbisStream<R>,()> {
+        let reader = match OggStreamReader::new(reader) {
+            Ok(v) => { v },
+            Err(_) => { return Err(()); }
+        };
+
+        let channels: usize = reader.ident_hdr.audio_channels.into();
+        if channels != slots.len() {
+            return Err(());
+        }
+
+        let samplerate: i32 = match reader.ident_hdr.audio_sample_rate.try_into() {
+            Ok(v) => { v },
+            Err(_) => { return Err(()); }
+        };
+
+        let mut loop_start: Option<u64> = None;
+        let mut loop_length: Option<u64> = None;
+        let mut loop_end: Option<u64> = None;
+
+        for (key, value) in &reader.comment_hdr.comment_list {
+            let value = value.trim().parse::<u64>().ok();
+            match key.to_ascii_uppercase().as_str() {
+                "LOOPSTART" => { loop_start = value; }
+                "LOOPLENGTH" => { loop_length = value; }
+                "LOOPEND" => { loop_end = value; }
+                _ => {}
+            };
+        }
+
+        let loop_start_frame = loop_start.unwrap_or(0);
+        let loop_end_frame = match loop_length {
+            Some(len) => { Some(loop_start_frame + len) }
+            None => { loop_end }
+        }.filter(|end| *end > loop_start_frame);
+
+        return Ok(VorbisStream {
+            reader: reader,
+            stream: AudioStream::new(slots, samplerate),
+            position: Some(0),
+            seek_target: None,
+            loop_start: loop_start_frame,
+            loop_end: loop_end_frame,
+            finished: false,
+            looping: loop_start.is_some() || loop_end_frame.is_some(),
+        });
+    }
+
+    /// Get the loop start position in sample frames
+    pub fn loop_start(&self) -> u64 {
+        return self.loop_start;
+    }
+
+    /// Get the loop end position in sample frames (None if the stream loops at the end of the file)
+    pub fn loop_end(&self) -> Option<u64> {
+        return self.loop_end;
+    }
+
+    /// Set the loop region in sample frames (a loop end of None loops at the end of the file)
+    pub fn set_loop_points(&mut self, start: u64, end: Option<u64>) {
+        self.loop_start = start;
+        self.loop_end = end.filter(|e| *e > start);
+    }
+
+    /// Get the underlying audio stream
+    pub fn stream(&mut self) -> &mut AudioStream {
+        return &mut self.stream;
+    }
+
+    /// Gets whether the stream has reached the end of the file and finished playing
+    pub fn is_finished(&self) -> bool {
+        return self.finished && !self.stream.is_playing();
+    }
+
+    /// Decode & submit more audio as needed (should be called once per frame)
+    pub fn update(&mut self) -> Result<(),()> {
+        while !self.finished && self.stream.needs_data() {
+            self.decode_next()?;
+        }
+        return Ok(());
+    }
+
+    /// Stop playback
+    pub fn stop(&mut self) {
+        self.stream.stop();
+        self.finished = true;
+    }
+
+    fn seek_to_loop(&mut self) -> Result<(),()> {
+        if self.reader.seek_absgp_pg(self.loop_start).is_err() {
+            return Err(());
+        }
+
+        // seeking only has page granularity, so the exact position isn't known until the next page boundary
+        // decoded audio before the loop start is discarded
+        self.position = None;
+        self.seek_target = Some(self.loop_start);
+        return Ok(());
+    }
+
+    fn decode_next(&mut self) -> Result<(),()> {
+        let channels = self.stream.channels();
+        let mut packet = match self.reader.read_dec_packet_itl() {
+            Ok(Some(v)) => { v },
+            Ok(None) => {
+                // if the end of the file was reached before the loop start, there's nothing left to loop
+                if self.looping && self.seek_target.is_none() {
+                    return self.seek_to_loop();
+                }
+
+                self.finished = true;
+                return self.stream.flush();
+            },
+            Err(_) => { return Err(()); }
+        };
+
+        let frames = (packet.len() / channels) as u64;
+
+        // figure out where this packet starts
+        let start = match self.reader.get_last_absgp() {
+            Some(end) => { end.saturating_sub(frames) }
+            None => {
+                match self.position {
+                    Some(p) => { p },
+                    None => {
+                        // position unknown after a seek, discard
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        let mut first = 0;
+        if let Some(target) = self.seek_target {
+            if start + frames <= target {
+                self.position = Some(start + frames);
+                return Ok(());
+            }
+            first = target.saturating_sub(start);
+            self.seek_target = None;
+        }
+
+        let mut last = frames;
+        let mut reached_loop_end = false;
+        if self.looping {
+            if let Some(end) = self.loop_end {
+                if start + frames >= end {
+                    last = end.saturating_sub(start).max(first);
+                    reached_loop_end = true;
+                }
+            }
+        }
+
+        self.position = Some(start + frames);
+
+        let last_sample: usize = (last as usize) * channels;
+        let first_sample: usize = (first as usize) * channels;
+        packet.truncate(last_sample);
+        self.stream.submit(&packet[first_sample..])?;
+
+        if reached_loop_end {
+            return self.seek_to_loop();
+        }
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+
+use crate::db::log;
+
+/// Number of allocations listed when a budget is exceeded
+const DUMP_COUNT: usize = 8;
+
+struct AllocRecord {
+    handle: i32,
+    label: String,
+    bytes: usize,
+}
+
+/// Tracks labelled allocations against an optional memory budget (shared by the audio & texture budgets)
+pub(crate) struct BudgetTracker {
+    records: Vec<AllocRecord>,
+    budget: Option<usize>,
+    panic_in_debug: bool,
+    current_label: Option<String>,
+}
+
+impl BudgetTracker {
+    pub const fn new() -> BudgetTracker {
+        return BudgetTracker {
+            records: Vec::new(),
+            budget: None,
+            panic_in_debug: false,
+            current_label: None,
+        };
+    }
+
+    pub fn set_budget(&mut self, budget: Option<usize>, panic_in_debug: bool) {
+        self.budget = budget;
+        self.panic_in_debug = panic_in_debug;
+    }
+
+    pub fn budget(&self) -> Option<usize> {
+        return self.budget;
+    }
+
+    pub fn set_label(&mut self, handle: i32, label: &str) {
+        if let Some(record) = self.records.iter_mut().find(|x| x.handle == handle) {
+            record.label = label.to_string();
+        }
+    }
+
+    // get the label of the given allocation (empty if it is unlabeled or untracked)
+    pub fn label(&self, handle: i32) -> String {
+        return match self.records.iter().find(|x| x.handle == handle) {
+            Some(v) => { v.label.clone() },
+            None => { String::new() }
+        };
+    }
+
+    pub fn tracked_bytes(&self) -> usize {
+        return self.records.iter().map(|x| x.bytes).sum();
+    }
+
+    pub fn count(&self) -> usize {
+        return self.records.len();
+    }
+
+    pub fn largest(&self, count: usize) -> Vec<(String, usize)> {
+        let mut list: Vec<(String, usize)> = self.records.iter().map(|x| (x.label.clone(), x.bytes)).collect();
+        list.sort_by_key(|x| std::cmp::Reverse(x.1));
+        list.truncate(count);
+        return list;
+    }
+
+    pub fn record_free(&mut self, handle: i32) {
+        self.records.retain(|x| x.handle != handle);
+    }
+}
+
+// run the given function with every allocation recorded by the tracker inside of it labelled with the given label
+pub(crate) fn with_label<T, F>(tracker: &Mutex<BudgetTracker>, label: &str, f: F) -> T where F : FnOnce() -> T {
+    let prev = tracker.lock().unwrap().current_label.replace(label.to_string());
+    let result = f();
+    tracker.lock().unwrap().current_label = prev;
+    return result;
+}
+
+// log each of the given labels & sizes, as returned by largest
+pub(crate) fn log_largest(list: Vec<(String, usize)>) {
+    for (label, bytes) in list {
+        let label = if label.is_empty() { "<unlabeled>" } else { label.as_str() };
+        log(format!("    {}: {} bytes", label, bytes).as_str());
+    }
+}
+
+// record a new allocation, then warn (dumping the largest allocations) & optionally panic if hardware usage is now over budget
+pub(crate) fn record_alloc(tracker: &Mutex<BudgetTracker>, handle: i32, bytes: usize, usage: usize, kind: &str, dump: fn(usize)) {
+    let (budget, panic_in_debug) = {
+        let mut state = tracker.lock().unwrap();
+        let label = state.current_label.clone().unwrap_or_default();
+        state.records.push(AllocRecord { handle: handle, label: label, bytes: bytes });
+        (state.budget, state.panic_in_debug)
+    };
+
+    if let Some(budget) = budget {
+        if usage > budget {
+            crate::log_warn!("{} memory budget exceeded ({} / {} bytes)", kind, usage, budget);
+            dump(DUMP_COUNT);
+
+            if panic_in_debug && cfg!(debug_assertions) {
+                panic!("{} memory budget exceeded ({} / {} bytes)", kind, usage, budget);
+            }
+        }
+    }
+}
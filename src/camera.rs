@@ -0,0 +1,160 @@
+use crate::{clock::FrameTimer, easing::Easing, math::{Matrix4x4, Vector3, curve::TimedSpline}};
+
+/// A point in time on a camera rail which fires an event as playback passes over it
+#[derive(Clone, Copy)]
+pub struct RailEvent {
+    pub time: f32,
+    /// User-defined identifier reported when the event fires
+    pub id: u32,
+}
+
+/// Drives a camera along a path for intros & cutscenes <br/>
+/// The camera position & the point it looks at each follow their own timed Catmull-Rom spline, so both can be keyed independently
+pub struct CameraRail {
+    /// Spline followed by the camera's position
+    pub position: TimedSpline<Vector3>,
+    /// Spline followed by the point the camera looks at
+    pub target: TimedSpline<Vector3>,
+    /// World up direction used to orient the camera
+    pub up: Vector3,
+    /// Whether playback wraps back to the start after reaching the end
+    pub looping: bool,
+    events: Vec<RailEvent>,
+    fired: Vec<u32>,
+    time: f32,
+    started: bool,
+}
+
+impl CameraRail {
+    /// Construct a new rail with no keys
+    pub fn new() -> CameraRail {
+        return CameraRail {
+            position: TimedSpline::new(),
+            target: TimedSpline::new(),
+            up: Vector3::unit_y(),
+            looping: false,
+            events: Vec::new(),
+            fired: Vec::new(),
+            time: 0.0,
+            started: false,
+        };
+    }
+
+    /// Insert a key into both splines at once, placing the camera at the given position looking at the given target
+    pub fn insert_key(&mut self, time: f32, position: Vector3, target: Vector3, easing: Easing) {
+        self.position.insert_key(time, position, easing);
+        self.target.insert_key(time, target, easing);
+    }
+
+    /// Add an event which fires when playback passes the given time
+    pub fn add_event(&mut self, time: f32, id: u32) {
+        let idx = self.events.partition_point(|e| e.time <= time);
+        self.events.insert(idx, RailEvent { time: time, id: id });
+    }
+
+    /// Get the events on the rail, ordered by time
+    pub fn events(&self) -> &[RailEvent] {
+        return &self.events;
+    }
+
+    /// Get the length of the rail in seconds (the time of the last key or event)
+    pub fn duration(&self) -> f32 {
+        let events = match self.events.last() {
+            Some(v) => { v.time },
+            None => { 0.0 }
+        };
+
+        return self.position.duration().max(self.target.duration()).max(events);
+    }
+
+    /// Get the current playback time in seconds
+    pub fn time(&self) -> f32 {
+        return self.time;
+    }
+
+    /// Jump to the given playback time without firing any events (events exactly at that time don't fire on the next update either)
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.clamp(0.0, self.duration());
+        self.started = true;
+    }
+
+    /// Rewind to the start so that playback begins again, including events at time 0
+    pub fn rewind(&mut self) {
+        self.time = 0.0;
+        self.started = false;
+    }
+
+    /// Gets whether playback has reached the end of a non-looping rail
+    pub fn is_finished(&self) -> bool {
+        return !self.looping && self.time >= self.duration();
+    }
+
+    /// Advance playback by the given delta time in seconds, returning the IDs of any events passed over in order
+    pub fn update(&mut self, delta: f32) -> &[u32] {
+        self.fired.clear();
+
+        let duration = self.duration();
+        let start = self.time;
+        let mut end = start + delta.max(0.0);
+
+        // events exactly at the starting time only fire on the first update after playback begins
+        let mut inclusive = !self.started;
+        self.started = true;
+
+        if self.looping && duration > 0.0 {
+            // fire events across every wrap of the rail
+            let mut from = start;
+            while end > duration {
+                self.fire_events(from, duration, inclusive);
+                end -= duration;
+                from = 0.0;
+                inclusive = true;
+            }
+            self.fire_events(from, end, inclusive);
+        } else {
+            end = end.min(duration);
+            self.fire_events(start, end, inclusive);
+        }
+
+        self.time = end;
+        return &self.fired;
+    }
+
+    /// Advance playback by the frame timer's most recent delta time, returning the IDs of any events passed over in order
+    pub fn update_with_timer(&mut self, timer: &FrameTimer) -> &[u32] {
+        return self.update(timer.delta());
+    }
+
+    /// Get the camera position at the current playback time
+    pub fn eye(&self) -> Vector3 {
+        return self.position.evaluate(self.time).unwrap_or(Vector3::zero());
+    }
+
+    /// Get the point the camera looks at at the current playback time
+    pub fn look_target(&self) -> Vector3 {
+        return match self.target.evaluate(self.time) {
+            Some(v) => { v },
+            None => { self.eye() - Vector3::unit_z() }
+        };
+    }
+
+    /// Get the camera's view matrix at the current playback time
+    pub fn view_matrix(&self) -> Matrix4x4 {
+        return Matrix4x4::look_at(self.eye(), self.look_target(), self.up);
+    }
+
+    // fire events in the range (from, to], or [from, to] if inclusive
+    fn fire_events(&mut self, from: f32, to: f32, inclusive: bool) {
+        for e in &self.events {
+            if (e.time > from || (inclusive && e.time >= from)) && e.time <= to {
+                self.fired.push(e.id);
+            }
+        }
+    }
+}
+
+impl Default for CameraRail {
+    fn default() -> Self {
+        return CameraRail::new();
+    }
+}
@@ -1,9 +1,18 @@
 use core::fmt::Display;
 
-use crate::db_internal::{clock_getTimestamp, clock_timestampToDatetime};
+use std::sync::Mutex;
+
+use crate::{audio, db_internal::{clock_getTimestamp, clock_getHighResTime, clock_timestampToDatetime}, vdp};
+
+/// Nominal time in seconds between vsync ticks
+pub const NOMINAL_FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Default upper limit on a single frame's delta time in seconds
+pub const DEFAULT_MAX_DELTA: f32 = 0.25;
 
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DateTime {
     pub year: u16,
     pub month: u8,
@@ -34,4 +43,188 @@ pub fn get_time() -> DateTime {
         clock_timestampToDatetime(ts, &mut dt);
         return dt;
     }
-}
\ No newline at end of file
+}
+
+/// Get a high resolution monotonic timer value in seconds, for measuring short durations such as profiling
+pub fn get_high_res_time() -> f64 {
+    unsafe { return clock_getHighResTime(); }
+}
+
+/// Tracks the time between frames using the audio clock <br/>
+/// Call tick once per vsync tick, then use delta to advance game logic instead of assuming a fixed 1/60th of a second
+pub struct FrameTimer {
+    last: Option<f64>,
+    delta: f32,
+    total: f64,
+    frames: u64,
+    /// Upper limit on a single frame's delta time in seconds, so that long stalls (such as loading) don't cause huge simulation steps
+    pub max_delta: f32,
+}
+
+impl FrameTimer {
+    /// Construct a new frame timer
+    pub const fn new() -> FrameTimer {
+        return FrameTimer {
+            last: None,
+            delta: NOMINAL_FRAME_TIME,
+            total: 0.0,
+            frames: 0,
+            max_delta: DEFAULT_MAX_DELTA,
+        };
+    }
+
+    /// Advance the timer using the current audio clock, returning the new delta time in seconds (should be called once per frame)
+    pub fn tick(&mut self) -> f32 {
+        return self.tick_with(audio::get_time());
+    }
+
+    /// Advance the timer using the given timestamp in seconds, returning the new delta time in seconds <br/>
+    /// The first tick, and any tick where the clock did not advance, uses the nominal frame time
+    pub fn tick_with(&mut self, now: f64) -> f32 {
+        let delta = match self.last {
+            Some(last) if now > last => { ((now - last) as f32).min(self.max_delta) },
+            _ => { NOMINAL_FRAME_TIME }
+        };
+
+        self.last = Some(now);
+        self.delta = delta;
+        self.total += delta as f64;
+        self.frames += 1;
+
+        return delta;
+    }
+
+    /// Get the time in seconds between the two most recent ticks
+    pub fn delta(&self) -> f32 {
+        return self.delta;
+    }
+
+    /// Get the total time in seconds accumulated across every tick
+    pub fn total(&self) -> f64 {
+        return self.total;
+    }
+
+    /// Get the number of ticks since the timer was created or reset
+    pub fn frame_count(&self) -> u64 {
+        return self.frames;
+    }
+
+    /// Get the frame rate implied by the most recent delta time
+    pub fn fps(&self) -> f32 {
+        return 1.0 / self.delta;
+    }
+
+    /// Reset the timer's total time & frame count. The next tick uses the nominal frame time
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.delta = NOMINAL_FRAME_TIME;
+        self.total = 0.0;
+        self.frames = 0;
+    }
+}
+
+impl Default for FrameTimer {
+    fn default() -> Self {
+        return FrameTimer::new();
+    }
+}
+
+/// Default maximum number of fixed updates run in a single frame
+pub const DEFAULT_MAX_STEPS: u32 = 5;
+
+/// Runs game logic at a fixed rate independent of the display rate <br/>
+/// Each tick accumulates real time, runs as many fixed-size update steps as fit, and then renders with an interpolation alpha
+/// (from 0 to 1) describing how far the current time is between the last two updates
+pub struct GameLoop {
+    timer: FrameTimer,
+    accumulator: f32,
+    step: f32,
+    /// Maximum number of updates run in a single frame. Time beyond this is dropped to avoid a spiral of ever-longer frames
+    pub max_steps: u32,
+}
+
+impl GameLoop {
+    /// Construct a new game loop which updates at the given rate in Hz
+    pub fn new(rate: f32) -> GameLoop {
+        return GameLoop {
+            timer: FrameTimer::new(),
+            accumulator: 0.0,
+            step: 1.0 / rate,
+            max_steps: DEFAULT_MAX_STEPS,
+        };
+    }
+
+    /// Get the fixed update step in seconds
+    pub fn step(&self) -> f32 {
+        return self.step;
+    }
+
+    /// Get the interpolation alpha between the last two updates
+    pub fn alpha(&self) -> f32 {
+        return self.accumulator / self.step;
+    }
+
+    /// Get the underlying frame timer
+    pub fn timer(&self) -> &FrameTimer {
+        return &self.timer;
+    }
+
+    // accumulate this frame's time & return the number of updates to run
+    fn advance(&mut self) -> u32 {
+        self.accumulator += self.timer.tick();
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        if self.accumulator >= self.step {
+            self.accumulator %= self.step;
+        }
+
+        return steps;
+    }
+
+    /// Advance the loop by one frame, calling update for each fixed step and then render with the interpolation alpha (should be called once per vsync tick)
+    pub fn tick<U, R>(&mut self, mut update: U, mut render: R) where U : FnMut(f32), R : FnMut(f32) {
+        let steps = self.advance();
+        for _ in 0..steps {
+            update(self.step);
+        }
+        render(self.alpha());
+    }
+
+    /// Advance the loop by one frame using the given game's update & render functions
+    pub fn tick_game(&mut self, game: &mut dyn Game) {
+        let steps = self.advance();
+        for _ in 0..steps {
+            game.update(self.step);
+        }
+        game.render(self.alpha());
+    }
+}
+
+/// A game driven by a GameLoop
+pub trait Game {
+    /// Advance game logic by the given fixed time step in seconds
+    fn update(&mut self, delta: f32);
+
+    /// Draw the game, given the interpolation alpha between the last two updates
+    fn render(&mut self, alpha: f32);
+}
+
+static RUNNING_GAME: Mutex<Option<(GameLoop, Box<dyn Game + Send>)>> = Mutex::new(None);
+
+fn game_vsync_handler() {
+    if let Some((game_loop, game)) = RUNNING_GAME.lock().unwrap().as_mut() {
+        game_loop.tick_game(game.as_mut());
+    }
+}
+
+/// Install a vsync handler which runs the given game's updates at the given rate in Hz, replacing any previous vsync handler or running game <br/>
+/// This must not be called from inside the game's own update or render functions
+pub fn run_game<G>(game: G, rate: f32) where G : Game + Send + 'static {
+    *RUNNING_GAME.lock().unwrap() = Some((GameLoop::new(rate), Box::new(game)));
+    vdp::set_vsync_handler(Some(game_vsync_handler));
+}
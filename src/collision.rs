@@ -0,0 +1,760 @@
+use crate::math::{Vector3, bounds::Aabb};
+
+pub mod controller;
+
+const EPSILON: f32 = 1e-6;
+
+/// A sphere
+#[derive(Clone, Copy)]
+pub struct Sphere {
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub const fn new(center: Vector3, radius: f32) -> Sphere {
+        return Sphere { center: center, radius: radius };
+    }
+}
+
+/// A capsule (the set of points within radius of the line segment from a to b)
+#[derive(Clone, Copy)]
+pub struct Capsule {
+    pub a: Vector3,
+    pub b: Vector3,
+    pub radius: f32,
+}
+
+impl Capsule {
+    pub const fn new(a: Vector3, b: Vector3, radius: f32) -> Capsule {
+        return Capsule { a: a, b: b, radius: radius };
+    }
+}
+
+/// A triangle (collides from both sides)
+#[derive(Clone, Copy)]
+pub struct Triangle {
+    pub a: Vector3,
+    pub b: Vector3,
+    pub c: Vector3,
+}
+
+impl Triangle {
+    pub const fn new(a: Vector3, b: Vector3, c: Vector3) -> Triangle {
+        return Triangle { a: a, b: b, c: c };
+    }
+
+    /// Get the unit normal of the triangle (counter-clockwise winding faces towards the normal)
+    pub fn normal(&self) -> Vector3 {
+        return Vector3::cross(&(self.b - self.a), &(self.c - self.a)).normalized();
+    }
+}
+
+/// Result of an overlap test between two shapes
+#[derive(Clone, Copy)]
+pub struct Contact {
+    /// Unit direction to move the first shape to separate it from the second
+    pub normal: Vector3,
+    /// Distance the first shape must move along the normal to separate the shapes
+    pub depth: f32,
+}
+
+/// Result of a swept or ray test
+#[derive(Clone, Copy)]
+pub struct Hit {
+    /// Fraction of the movement (or ray length) at which contact first occurs (0.0 .. 1.0). Shapes which already overlap hit at 0.0
+    pub time: f32,
+    /// Unit surface normal of the shape which was hit at the contact point, facing the moving shape
+    pub normal: Vector3,
+}
+
+fn earliest(a: Option<Hit>, b: Option<Hit>) -> Option<Hit> {
+    return match (a, b) {
+        (Some(x), Some(y)) => { if y.time < x.time { Some(y) } else { Some(x) } },
+        (x, None) => { x },
+        (None, y) => { y }
+    };
+}
+
+/// Get the point on the line segment from a to b closest to the given point
+pub fn closest_point_segment(point: Vector3, a: Vector3, b: Vector3) -> Vector3 {
+    let ab = b - a;
+    let len_sq = ab.length_sq();
+    if len_sq <= EPSILON {
+        return a;
+    }
+
+    let t = (Vector3::dot(&(point - a), &ab) / len_sq).clamp(0.0, 1.0);
+    return a + (ab * t);
+}
+
+/// Get the closest pair of points between the line segments p1-q1 and p2-q2
+pub fn closest_points_segments(p1: Vector3, q1: Vector3, p2: Vector3, q2: Vector3) -> (Vector3, Vector3) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.length_sq();
+    let e = d2.length_sq();
+    let f = Vector3::dot(&d2, &r);
+
+    if a <= EPSILON && e <= EPSILON {
+        return (p1, p2);
+    }
+
+    let (s, t) = if a <= EPSILON {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = Vector3::dot(&d1, &r);
+        if e <= EPSILON {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = Vector3::dot(&d1, &d2);
+            let denom = a * e - b * b;
+
+            // parallel segments have no unique closest pair, so pick any point on the first
+            let s = if denom != 0.0 { ((b * f - c * e) / denom).clamp(0.0, 1.0) } else { 0.0 };
+            let t = (b * s + f) / e;
+
+            if t < 0.0 {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else if t > 1.0 {
+                (((b - c) / a).clamp(0.0, 1.0), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+
+    return (p1 + (d1 * s), p2 + (d2 * t));
+}
+
+/// Get the point on (or in) the given triangle closest to the given point
+pub fn closest_point_triangle(point: Vector3, tri: &Triangle) -> Vector3 {
+    let (a, b, c) = (tri.a, tri.b, tri.c);
+    let ab = b - a;
+    let ac = c - a;
+
+    let ap = point - a;
+    let d1 = Vector3::dot(&ab, &ap);
+    let d2 = Vector3::dot(&ac, &ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = point - b;
+    let d3 = Vector3::dot(&ab, &bp);
+    let d4 = Vector3::dot(&ac, &bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + (ab * (d1 / (d1 - d3)));
+    }
+
+    let cp = point - c;
+    let d5 = Vector3::dot(&ab, &cp);
+    let d6 = Vector3::dot(&ac, &cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + (ac * (d2 / (d2 - d6)));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + ((c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6))));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    return a + (ab * (vb * denom)) + (ac * (vc * denom));
+}
+
+/// Get the point on (or in) the given box closest to the given point
+pub fn closest_point_aabb(point: Vector3, aabb: &Aabb) -> Vector3 {
+    return Vector3::new(
+        point.x.clamp(aabb.min.x, aabb.max.x),
+        point.y.clamp(aabb.min.y, aabb.max.y),
+        point.z.clamp(aabb.min.z, aabb.max.z));
+}
+
+// contact between a sphere & a point on another shape, given a fallback normal for when the point is at the sphere's center
+fn sphere_point_contact(center: Vector3, radius: f32, point: Vector3, fallback: Vector3) -> Option<Contact> {
+    let d = center - point;
+    let dist_sq = d.length_sq();
+    if dist_sq > radius * radius {
+        return None;
+    }
+
+    let dist = dist_sq.sqrt();
+    if dist <= EPSILON {
+        return Some(Contact { normal: fallback, depth: radius });
+    }
+
+    return Some(Contact { normal: d / dist, depth: radius - dist });
+}
+
+/// Test two boxes for overlap, separating along the axis of least penetration
+pub fn aabb_aabb(a: &Aabb, b: &Aabb) -> Option<Contact> {
+    let mut best: Option<Contact> = None;
+    let (ca, cb) = (a.center(), b.center());
+
+    for axis in 0..3 {
+        let overlap = a.max[axis].min(b.max[axis]) - a.min[axis].max(b.min[axis]);
+        if overlap < 0.0 {
+            return None;
+        }
+
+        if best.is_none_or(|x| overlap < x.depth) {
+            let mut normal = Vector3::zero();
+            normal[axis] = if ca[axis] < cb[axis] { -1.0 } else { 1.0 };
+            best = Some(Contact { normal: normal, depth: overlap });
+        }
+    }
+
+    return best;
+}
+
+/// Test two spheres for overlap
+pub fn sphere_sphere(a: &Sphere, b: &Sphere) -> Option<Contact> {
+    return sphere_point_contact(a.center, a.radius + b.radius, b.center, Vector3::unit_y());
+}
+
+/// Test a sphere & a box for overlap
+pub fn sphere_aabb(a: &Sphere, b: &Aabb) -> Option<Contact> {
+    let point = closest_point_aabb(a.center, b);
+    if !b.contains_point(a.center) {
+        return sphere_point_contact(a.center, a.radius, point, Vector3::unit_y());
+    }
+
+    // center is inside the box, so push out through the nearest face
+    let mut best = Contact { normal: Vector3::unit_y(), depth: f32::MAX };
+    for axis in 0..3 {
+        let to_min = a.center[axis] - b.min[axis];
+        let to_max = b.max[axis] - a.center[axis];
+        let (dist, sign) = if to_min < to_max { (to_min, -1.0) } else { (to_max, 1.0) };
+
+        if dist < best.depth {
+            let mut normal = Vector3::zero();
+            normal[axis] = sign;
+            best = Contact { normal: normal, depth: dist };
+        }
+    }
+
+    best.depth += a.radius;
+    return Some(best);
+}
+
+/// Test a sphere & a capsule for overlap
+pub fn sphere_capsule(a: &Sphere, b: &Capsule) -> Option<Contact> {
+    let point = closest_point_segment(a.center, b.a, b.b);
+    return sphere_point_contact(a.center, a.radius + b.radius, point, Vector3::unit_y());
+}
+
+/// Test a sphere & a triangle for overlap
+pub fn sphere_triangle(a: &Sphere, b: &Triangle) -> Option<Contact> {
+    let point = closest_point_triangle(a.center, b);
+    return sphere_point_contact(a.center, a.radius, point, b.normal());
+}
+
+/// Test two capsules for overlap
+pub fn capsule_capsule(a: &Capsule, b: &Capsule) -> Option<Contact> {
+    let (pa, pb) = closest_points_segments(a.a, a.b, b.a, b.b);
+    return sphere_point_contact(pa, a.radius + b.radius, pb, Vector3::unit_y());
+}
+
+/// Test a capsule & a triangle for overlap
+pub fn capsule_triangle(a: &Capsule, b: &Triangle) -> Option<Contact> {
+    let normal = b.normal();
+
+    // the segment passing through the triangle is pushed out towards whichever side its start point is on
+    if let Some(hit) = ray_triangle(a.a, a.b - a.a, b) {
+        if hit.time <= 1.0 {
+            let start_dist = Vector3::dot(&(a.a - b.a), &normal);
+            let end_dist = Vector3::dot(&(a.b - b.a), &normal);
+            let (n, depth) = if start_dist >= 0.0 { (normal, -end_dist) } else { (-normal, end_dist) };
+            return Some(Contact { normal: n, depth: depth + a.radius });
+        }
+    }
+
+    // otherwise the closest pair of points involves a segment end point or a triangle edge
+    let mut best = (a.a, closest_point_triangle(a.a, b));
+    let mut best_dist = (best.0 - best.1).length_sq();
+
+    let candidates = [
+        (a.b, closest_point_triangle(a.b, b)),
+        closest_points_segments(a.a, a.b, b.a, b.b),
+        closest_points_segments(a.a, a.b, b.b, b.c),
+        closest_points_segments(a.a, a.b, b.c, b.a),
+    ];
+
+    for pair in candidates {
+        let dist = (pair.0 - pair.1).length_sq();
+        if dist < best_dist {
+            best = pair;
+            best_dist = dist;
+        }
+    }
+
+    return sphere_point_contact(best.0, a.radius, best.1, normal);
+}
+
+/// Test a capsule & a box for overlap
+pub fn capsule_aabb(a: &Capsule, b: &Aabb) -> Option<Contact> {
+    // distance from the box is convex along the segment, so search for the closest point on the segment
+    let dist_sq = |t: f32| {
+        let p = a.a + ((a.b - a.a) * t);
+        return (p - closest_point_aabb(p, b)).length_sq();
+    };
+
+    let mut lo = 0.0;
+    let mut hi = 1.0;
+    for _ in 0..24 {
+        let m1 = lo + (hi - lo) / 3.0;
+        let m2 = hi - (hi - lo) / 3.0;
+        if dist_sq(m1) <= dist_sq(m2) {
+            hi = m2;
+        } else {
+            lo = m1;
+        }
+    }
+
+    // when the segment passes through the box, use the point deepest inside it
+    let t = (lo + hi) * 0.5;
+    let point = if dist_sq(t) > 0.0 {
+        a.a + ((a.b - a.a) * t)
+    } else {
+        let center = b.center();
+        closest_point_segment(center, a.a, a.b)
+    };
+
+    return sphere_aabb(&Sphere::new(point, a.radius), b);
+}
+
+/// Test a box & a triangle for overlap using the separating axis test, separating along the axis of least penetration
+pub fn aabb_triangle(a: &Aabb, b: &Triangle) -> Option<Contact> {
+    let center = a.center();
+    let extents = a.extents();
+    let verts = [b.a, b.b, b.c];
+    let edges = [b.b - b.a, b.c - b.b, b.a - b.c];
+
+    let mut axes: Vec<Vector3> = vec![Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z(), Vector3::cross(&edges[0], &edges[1])];
+    for edge in &edges {
+        for box_axis in [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()] {
+            axes.push(Vector3::cross(&box_axis, edge));
+        }
+    }
+
+    let tri_center = (b.a + b.b + b.c) / 3.0;
+    let mut best: Option<Contact> = None;
+
+    for axis in axes {
+        let len = axis.length();
+        if len <= EPSILON {
+            continue;
+        }
+        let axis = axis / len;
+
+        let box_radius = extents.x * axis.x.abs() + extents.y * axis.y.abs() + extents.z * axis.z.abs();
+        let box_pos = Vector3::dot(&center, &axis);
+
+        let tri_proj = verts.map(|v| Vector3::dot(&v, &axis));
+        let tri_min = tri_proj[0].min(tri_proj[1]).min(tri_proj[2]);
+        let tri_max = tri_proj[0].max(tri_proj[1]).max(tri_proj[2]);
+
+        let overlap = (box_pos + box_radius - tri_min).min(tri_max - (box_pos - box_radius));
+        if overlap < 0.0 {
+            return None;
+        }
+
+        if best.is_none_or(|x| overlap < x.depth) {
+            let normal = if Vector3::dot(&(center - tri_center), &axis) < 0.0 { -axis } else { axis };
+            best = Some(Contact { normal: normal, depth: overlap });
+        }
+    }
+
+    return best;
+}
+
+/// Intersect a ray from origin along dir (hit times are in units of dir) with a sphere
+pub fn ray_sphere(origin: Vector3, dir: Vector3, sphere: &Sphere) -> Option<Hit> {
+    let m = origin - sphere.center;
+    let c = m.length_sq() - sphere.radius * sphere.radius;
+    if c <= 0.0 {
+        return Some(Hit { time: 0.0, normal: if m.length_sq() > EPSILON { m.normalized() } else { -dir.normalized() } });
+    }
+
+    let a = dir.length_sq();
+    let b = Vector3::dot(&m, &dir);
+    if a <= EPSILON || b > 0.0 {
+        return None;
+    }
+
+    let disc = b * b - a * c;
+    if disc < 0.0 {
+        return None;
+    }
+
+    let t = (-b - disc.sqrt()) / a;
+    if t > 1.0 {
+        return None;
+    }
+
+    return Some(Hit { time: t, normal: ((origin + (dir * t)) - sphere.center).normalized() });
+}
+
+/// Intersect a ray from origin along dir (hit times are in units of dir) with a box
+pub fn ray_aabb(origin: Vector3, dir: Vector3, aabb: &Aabb) -> Option<Hit> {
+    let mut t_min: f32 = 0.0;
+    let mut t_max: f32 = 1.0;
+    let mut normal = -dir.normalized();
+
+    for axis in 0..3 {
+        if dir[axis].abs() <= EPSILON {
+            if origin[axis] < aabb.min[axis] || origin[axis] > aabb.max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let inv = 1.0 / dir[axis];
+        let mut t1 = (aabb.min[axis] - origin[axis]) * inv;
+        let mut t2 = (aabb.max[axis] - origin[axis]) * inv;
+        let mut sign = -1.0;
+        if t1 > t2 {
+            std::mem::swap(&mut t1, &mut t2);
+            sign = 1.0;
+        }
+
+        if t1 > t_min {
+            t_min = t1;
+            normal = Vector3::zero();
+            normal[axis] = sign;
+        }
+        t_max = t_max.min(t2);
+
+        if t_min > t_max {
+            return None;
+        }
+    }
+
+    return Some(Hit { time: t_min, normal: normal });
+}
+
+/// Intersect a ray from origin along dir (hit times are in units of dir) with either side of a triangle
+pub fn ray_triangle(origin: Vector3, dir: Vector3, tri: &Triangle) -> Option<Hit> {
+    let ab = tri.b - tri.a;
+    let ac = tri.c - tri.a;
+
+    let p = Vector3::cross(&dir, &ac);
+    let det = Vector3::dot(&ab, &p);
+    if det.abs() <= EPSILON {
+        return None;
+    }
+
+    let inv_det = 1.0 / det;
+    let s = origin - tri.a;
+    let u = Vector3::dot(&s, &p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = Vector3::cross(&s, &ab);
+    let v = Vector3::dot(&dir, &q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = Vector3::dot(&ac, &q) * inv_det;
+    if !(0.0..=1.0).contains(&t) {
+        return None;
+    }
+
+    let normal = tri.normal();
+    return Some(Hit { time: t, normal: if Vector3::dot(&normal, &dir) > 0.0 { -normal } else { normal } });
+}
+
+/// Intersect a ray from origin along dir (hit times are in units of dir) with a capsule
+pub fn ray_capsule(origin: Vector3, dir: Vector3, capsule: &Capsule) -> Option<Hit> {
+    let axis = capsule.b - capsule.a;
+    let axis_len = axis.length();
+
+    let mut result = earliest(
+        ray_sphere(origin, dir, &Sphere::new(capsule.a, capsule.radius)),
+        ray_sphere(origin, dir, &Sphere::new(capsule.b, capsule.radius)));
+
+    if axis_len <= EPSILON {
+        return result;
+    }
+
+    // infinite cylinder around the axis, limited to the segment between the end caps
+    let u = axis / axis_len;
+    let m = origin - capsule.a;
+    let m_perp = m - (u * Vector3::dot(&m, &u));
+    let d_perp = dir - (u * Vector3::dot(&dir, &u));
+
+    let a = d_perp.length_sq();
+    let b = Vector3::dot(&m_perp, &d_perp);
+    let c = m_perp.length_sq() - capsule.radius * capsule.radius;
+
+    if a > EPSILON {
+        let disc = b * b - a * c;
+        if disc >= 0.0 {
+            let t = ((-b - disc.sqrt()) / a).max(0.0);
+            let along = Vector3::dot(&(m + (dir * t)), &u);
+            if t <= 1.0 && (0.0..=axis_len).contains(&along) && (c > 0.0 || t == 0.0) {
+                let point = origin + (dir * t);
+                let normal = point - (capsule.a + (u * along));
+                let normal = if normal.length_sq() > EPSILON { normal.normalized() } else { -dir.normalized() };
+                result = earliest(result, Some(Hit { time: t, normal: normal }));
+            }
+        }
+    }
+
+    return result;
+}
+
+/// Sweep a sphere along the given movement against another sphere, returning the first point of contact
+pub fn sweep_sphere_sphere(a: &Sphere, velocity: Vector3, b: &Sphere) -> Option<Hit> {
+    return ray_sphere(a.center, velocity, &Sphere::new(b.center, a.radius + b.radius));
+}
+
+/// Sweep a sphere along the given movement against a capsule, returning the first point of contact
+pub fn sweep_sphere_capsule(a: &Sphere, velocity: Vector3, b: &Capsule) -> Option<Hit> {
+    return ray_capsule(a.center, velocity, &Capsule::new(b.a, b.b, a.radius + b.radius));
+}
+
+/// Sweep a sphere along the given movement against a box, returning the first point of contact
+pub fn sweep_sphere_aabb(a: &Sphere, velocity: Vector3, b: &Aabb) -> Option<Hit> {
+    if let Some(c) = sphere_aabb(a, b) {
+        return Some(Hit { time: 0.0, normal: c.normal });
+    }
+
+    let r = Vector3::new(a.radius, a.radius, a.radius);
+    let hit = ray_aabb(a.center, velocity, &Aabb::new(b.min - r, b.max + r))?;
+
+    // hits on the flat faces of the expanded box are exact, but the edges & corners are rounded
+    let point = a.center + (velocity * hit.time);
+    let outside = (0..3).filter(|i| point[*i] < b.min[*i] || point[*i] > b.max[*i]).count();
+    if outside <= 1 {
+        return Some(hit);
+    }
+
+    let mut result: Option<Hit> = None;
+    for i in 0..8 {
+        let corner = Vector3::new(
+            if i & 1 == 0 { b.min.x } else { b.max.x },
+            if i & 2 == 0 { b.min.y } else { b.max.y },
+            if i & 4 == 0 { b.min.z } else { b.max.z });
+
+        // the three edges leading from this corner towards larger coordinates
+        for axis in 0..3 {
+            if i & (1 << axis) != 0 {
+                continue;
+            }
+            let mut end = corner;
+            end[axis] = b.max[axis];
+            result = earliest(result, ray_capsule(a.center, velocity, &Capsule::new(corner, end, a.radius)));
+        }
+    }
+
+    return result;
+}
+
+/// Sweep a sphere along the given movement against a triangle, returning the first point of contact
+pub fn sweep_sphere_triangle(a: &Sphere, velocity: Vector3, b: &Triangle) -> Option<Hit> {
+    if let Some(c) = sphere_triangle(a, b) {
+        return Some(Hit { time: 0.0, normal: c.normal });
+    }
+
+    // face: offset the triangle towards the sphere by its radius
+    let normal = b.normal();
+    let side = if Vector3::dot(&(a.center - b.a), &normal) >= 0.0 { normal } else { -normal };
+    let offset = side * a.radius;
+    let face = Triangle::new(b.a + offset, b.b + offset, b.c + offset);
+
+    let mut result = ray_triangle(a.center, velocity, &face).map(|h| Hit { time: h.time, normal: side });
+
+    // edges & vertices
+    for (p, q) in [(b.a, b.b), (b.b, b.c), (b.c, b.a)] {
+        result = earliest(result, ray_capsule(a.center, velocity, &Capsule::new(p, q, a.radius)));
+    }
+
+    return result;
+}
+
+/// Sweep a box along the given movement against another box, returning the first point of contact
+pub fn sweep_aabb_aabb(a: &Aabb, velocity: Vector3, b: &Aabb) -> Option<Hit> {
+    if let Some(c) = aabb_aabb(a, b) {
+        return Some(Hit { time: 0.0, normal: c.normal });
+    }
+
+    let e = a.extents();
+    return ray_aabb(a.center(), velocity, &Aabb::new(b.min - e, b.max + e));
+}
+
+/// Sweep a box along the given movement against a sphere, returning the first point of contact
+pub fn sweep_aabb_sphere(a: &Aabb, velocity: Vector3, b: &Sphere) -> Option<Hit> {
+    // the sphere moving the opposite way hits the box at the same time, with the normal flipped to face the moving box
+    return sweep_sphere_aabb(b, -velocity, a).map(|h| Hit { time: h.time, normal: -h.normal });
+}
+
+/// Sweep a capsule along the given movement against a sphere, returning the first point of contact
+pub fn sweep_capsule_sphere(a: &Capsule, velocity: Vector3, b: &Sphere) -> Option<Hit> {
+    return sweep_sphere_capsule(b, -velocity, a).map(|h| Hit { time: h.time, normal: -h.normal });
+}
+
+/// Sweep a capsule along the given movement against another capsule, returning the first point of contact
+pub fn sweep_capsule_capsule(a: &Capsule, velocity: Vector3, b: &Capsule) -> Option<Hit> {
+    if let Some(c) = capsule_capsule(a, b) {
+        return Some(Hit { time: 0.0, normal: c.normal });
+    }
+
+    let radius = a.radius + b.radius;
+
+    // contact involving an end point of either segment
+    let mut result = earliest(
+        ray_capsule(a.a, velocity, &Capsule::new(b.a, b.b, radius)),
+        ray_capsule(a.b, velocity, &Capsule::new(b.a, b.b, radius)));
+    result = earliest(result, ray_capsule(b.a, -velocity, &Capsule::new(a.a, a.b, radius)));
+    result = earliest(result, ray_capsule(b.b, -velocity, &Capsule::new(a.a, a.b, radius)));
+
+    // contact between the interiors of both segments, which happens along their common perpendicular
+    let cross = Vector3::cross(&(a.b - a.a), &(b.b - b.a));
+    if cross.length_sq() > EPSILON {
+        let n = cross.normalized();
+        let dist = Vector3::dot(&(a.a - b.a), &n);
+        let speed = Vector3::dot(&velocity, &n);
+        if speed.abs() > EPSILON {
+            let t = ((if dist > 0.0 { radius } else { -radius }) - dist) / speed;
+            if (0.0..=1.0).contains(&t) && result.is_none_or(|x| t < x.time) {
+                let offset = velocity * t;
+                let (pa, pb) = closest_points_segments(a.a + offset, a.b + offset, b.a, b.b);
+                if (pa - pb).length() <= radius + 1e-4 {
+                    result = Some(Hit { time: t, normal: Vector3::zero() });
+                }
+            }
+        }
+    }
+
+    // the normal points from the closest point on the other segment towards the moving one at the time of contact
+    return result.map(|h| {
+        let offset = velocity * h.time;
+        let (pa, pb) = closest_points_segments(a.a + offset, a.b + offset, b.a, b.b);
+        let normal = pa - pb;
+        return Hit { time: h.time, normal: if normal.length_sq() > EPSILON { normal.normalized() } else { -velocity.normalized() } };
+    });
+}
+
+/// Sweep a capsule along the given movement against a triangle, returning the first point of contact
+pub fn sweep_capsule_triangle(a: &Capsule, velocity: Vector3, b: &Triangle) -> Option<Hit> {
+    if let Some(c) = capsule_triangle(a, b) {
+        return Some(Hit { time: 0.0, normal: c.normal });
+    }
+
+    // a capsule first touches the triangle's face with one of its end caps, or one of its edges or vertices with its side
+    let mut result = earliest(
+        sweep_sphere_triangle(&Sphere::new(a.a, a.radius), velocity, b),
+        sweep_sphere_triangle(&Sphere::new(a.b, a.radius), velocity, b));
+
+    for (p, q) in [(b.a, b.b), (b.b, b.c), (b.c, b.a)] {
+        result = earliest(result, sweep_capsule_capsule(a, velocity, &Capsule::new(p, q, 0.0)));
+    }
+
+    return result;
+}
+
+/// Sweep a capsule along the given movement against a box, returning the first point of contact
+pub fn sweep_capsule_aabb(a: &Capsule, velocity: Vector3, b: &Aabb) -> Option<Hit> {
+    if let Some(c) = capsule_aabb(a, b) {
+        return Some(Hit { time: 0.0, normal: c.normal });
+    }
+
+    // the capsule starts outside of the box, so it first touches one of the box's faces
+    let corner = |i: usize| Vector3::new(
+        if i & 1 == 0 { b.min.x } else { b.max.x },
+        if i & 2 == 0 { b.min.y } else { b.max.y },
+        if i & 4 == 0 { b.min.z } else { b.max.z });
+    let faces = [[0, 2, 6, 4], [1, 3, 7, 5], [0, 1, 5, 4], [2, 3, 7, 6], [0, 1, 3, 2], [4, 5, 7, 6]];
+
+    let mut result: Option<Hit> = None;
+    for f in faces {
+        result = earliest(result, sweep_capsule_triangle(a, velocity, &Triangle::new(corner(f[0]), corner(f[1]), corner(f[2]))));
+        result = earliest(result, sweep_capsule_triangle(a, velocity, &Triangle::new(corner(f[0]), corner(f[2]), corner(f[3]))));
+    }
+
+    return result;
+}
+
+/// Sweep a box along the given movement against a capsule, returning the first point of contact
+pub fn sweep_aabb_capsule(a: &Aabb, velocity: Vector3, b: &Capsule) -> Option<Hit> {
+    return sweep_capsule_aabb(b, -velocity, a).map(|h| Hit { time: h.time, normal: -h.normal });
+}
+
+/// Sweep a box along the given movement against a triangle using the separating axis test, returning the first point of contact
+pub fn sweep_aabb_triangle(a: &Aabb, velocity: Vector3, b: &Triangle) -> Option<Hit> {
+    if let Some(c) = aabb_triangle(a, b) {
+        return Some(Hit { time: 0.0, normal: c.normal });
+    }
+
+    let center = a.center();
+    let extents = a.extents();
+    let verts = [b.a, b.b, b.c];
+    let edges = [b.b - b.a, b.c - b.b, b.a - b.c];
+
+    let mut axes: Vec<Vector3> = vec![Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z(), Vector3::cross(&edges[0], &edges[1])];
+    for edge in &edges {
+        for box_axis in [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()] {
+            axes.push(Vector3::cross(&box_axis, edge));
+        }
+    }
+
+    // the shapes touch once they overlap on every axis, so contact happens at the latest time they start overlapping on any one axis
+    let mut t_enter: f32 = 0.0;
+    let mut t_exit: f32 = 1.0;
+    let mut normal = -velocity.normalized();
+
+    for axis in axes {
+        let len = axis.length();
+        if len <= EPSILON {
+            continue;
+        }
+        let axis = axis / len;
+
+        let box_radius = extents.x * axis.x.abs() + extents.y * axis.y.abs() + extents.z * axis.z.abs();
+        let box_pos = Vector3::dot(&center, &axis);
+
+        let tri_proj = verts.map(|v| Vector3::dot(&v, &axis));
+        let tri_min = tri_proj[0].min(tri_proj[1]).min(tri_proj[2]);
+        let tri_max = tri_proj[0].max(tri_proj[1]).max(tri_proj[2]);
+
+        // the projections overlap while lo <= speed * t <= hi
+        let lo = tri_min - (box_pos + box_radius);
+        let hi = tri_max - (box_pos - box_radius);
+        let speed = Vector3::dot(&velocity, &axis);
+
+        if speed.abs() <= EPSILON {
+            if lo > 0.0 || hi < 0.0 {
+                return None;
+            }
+            continue;
+        }
+
+        let (enter, exit, side) = if speed > 0.0 { (lo / speed, hi / speed, -axis) } else { (hi / speed, lo / speed, axis) };
+        if enter > t_enter {
+            t_enter = enter;
+            normal = side;
+        }
+        t_exit = t_exit.min(exit);
+
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+
+    return Some(Hit { time: t_enter, normal: normal });
+}
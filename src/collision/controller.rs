@@ -0,0 +1,224 @@
+use crate::{collision::{self, Capsule, Contact, Sphere, Triangle}, math::{Vector3, bounds::Aabb}};
+
+/// Maximum number of times overlaps are resolved after each movement step
+const MAX_RESOLVE_ITERATIONS: usize = 4;
+
+/// Cosine of the steepest slope treated as ground (about 85 degrees), since walkable ground is pushed out along Y by depth / normal.y
+const MIN_GROUND_COS: f32 = 0.087;
+
+/// A static shape which a character controller collides with
+#[derive(Clone, Copy)]
+pub enum Collider {
+    Aabb(Aabb),
+    Sphere(Sphere),
+    Capsule(Capsule),
+    Triangle(Triangle),
+}
+
+/// The shape of a character controller, positioned by its bottom center
+#[derive(Clone, Copy)]
+pub enum ControllerShape {
+    /// An upright capsule with the given radius & total height (including both end caps)
+    Capsule { radius: f32, height: f32 },
+    /// A box with the given half size along each axis
+    Aabb { half_extents: Vector3 },
+}
+
+struct SlideResult {
+    position: Vector3,
+    ground_normal: Option<Vector3>,
+    hit_wall: bool,
+}
+
+/// A kinematic character which moves through a set of colliders by sliding along them, with step climbing, ground detection, and slope limits <br/>
+/// Up is always +Y
+pub struct CharacterController {
+    /// Bottom center of the character
+    pub position: Vector3,
+    pub shape: ControllerShape,
+    /// Maximum height of ledges (such as stairs) the character climbs automatically while grounded. Also the maximum distance the character snaps down to stay grounded
+    pub step_height: f32,
+    /// Steepest slope in radians which counts as ground (limited to about 85 degrees). Steeper surfaces are treated as walls
+    pub max_slope: f32,
+    /// Distance below the character checked for ground
+    pub probe_distance: f32,
+    ground_normal: Option<Vector3>,
+}
+
+impl CharacterController {
+    /// Construct a new controller at the given position, with no step height & a 45 degree slope limit
+    pub fn new(shape: ControllerShape, position: Vector3) -> CharacterController {
+        return CharacterController {
+            position: position,
+            shape: shape,
+            step_height: 0.0,
+            max_slope: 45.0_f32.to_radians(),
+            probe_distance: 0.05,
+            ground_normal: None,
+        };
+    }
+
+    /// Gets whether the character was standing on ground at the end of the last move
+    pub fn is_grounded(&self) -> bool {
+        return self.ground_normal.is_some();
+    }
+
+    /// Get the normal of the ground the character was standing on at the end of the last move
+    pub fn ground_normal(&self) -> Option<Vector3> {
+        return self.ground_normal;
+    }
+
+    // largest distance moved in a single step, so that thin colliders aren't skipped over
+    fn max_step(&self) -> f32 {
+        return match self.shape {
+            ControllerShape::Capsule { radius, .. } => { radius * 0.5 },
+            ControllerShape::Aabb { half_extents } => { half_extents.x.min(half_extents.y).min(half_extents.z) * 0.5 }
+        }.max(0.001);
+    }
+
+    fn contact(&self, position: Vector3, collider: &Collider) -> Option<Contact> {
+        return match self.shape {
+            ControllerShape::Capsule { radius, height } => {
+                let a = position + Vector3::new(0.0, radius, 0.0);
+                let b = position + Vector3::new(0.0, (height - radius).max(radius), 0.0);
+                let capsule = Capsule::new(a, b, radius);
+
+                match collider {
+                    Collider::Aabb(v) => { collision::capsule_aabb(&capsule, v) },
+                    Collider::Sphere(v) => { collision::sphere_capsule(v, &capsule).map(|c| Contact { normal: -c.normal, depth: c.depth }) },
+                    Collider::Capsule(v) => { collision::capsule_capsule(&capsule, v) },
+                    Collider::Triangle(v) => { collision::capsule_triangle(&capsule, v) }
+                }
+            },
+            ControllerShape::Aabb { half_extents } => {
+                let aabb = Aabb::from_center_extents(position + Vector3::new(0.0, half_extents.y, 0.0), half_extents);
+
+                match collider {
+                    Collider::Aabb(v) => { collision::aabb_aabb(&aabb, v) },
+                    Collider::Sphere(v) => { collision::sphere_aabb(v, &aabb).map(|c| Contact { normal: -c.normal, depth: c.depth }) },
+                    Collider::Capsule(v) => { collision::capsule_aabb(v, &aabb).map(|c| Contact { normal: -c.normal, depth: c.depth }) },
+                    Collider::Triangle(v) => { collision::aabb_triangle(&aabb, v) }
+                }
+            }
+        };
+    }
+
+    // cosine of max_slope, limited so ground normals never get close to horizontal
+    fn slope_cos(&self) -> f32 {
+        return self.max_slope.cos().max(MIN_GROUND_COS);
+    }
+
+    fn is_walkable(&self, normal: Vector3) -> bool {
+        return normal.y >= self.slope_cos();
+    }
+
+    // push the character out of every collider, clipping the remaining motion against each surface
+    fn resolve(&self, result: &mut SlideResult, remaining: &mut Vector3, colliders: &[Collider]) {
+        for _ in 0..MAX_RESOLVE_ITERATIONS {
+            let mut resolved = true;
+
+            for collider in colliders {
+                let contact = match self.contact(result.position, collider) {
+                    Some(v) if v.depth > 0.0 => { v },
+                    _ => { continue; }
+                };
+                resolved = false;
+
+                let n = contact.normal;
+                let dir = if self.is_walkable(n) {
+                    // push straight up out of walkable ground, so the character doesn't slide down slopes
+                    result.ground_normal = Some(n);
+                    result.position.y += contact.depth / n.y;
+                    Vector3::unit_y()
+                } else if n.y > 0.0 {
+                    // steep slopes push sideways only, so they can't be climbed
+                    result.hit_wall = true;
+                    let flat = Vector3::new(n.x, 0.0, n.z);
+                    let len = flat.length().max(0.001);
+                    result.position += (flat / len) * (contact.depth / len);
+                    flat / len
+                } else {
+                    if n.y > -self.slope_cos() {
+                        result.hit_wall = true;
+                    }
+                    result.position += n * contact.depth;
+                    n
+                };
+
+                let into = Vector3::dot(remaining, &dir);
+                if into < 0.0 {
+                    *remaining -= dir * into;
+                }
+            }
+
+            if resolved {
+                break;
+            }
+        }
+    }
+
+    fn slide(&self, start: Vector3, motion: Vector3, colliders: &[Collider]) -> SlideResult {
+        let mut result = SlideResult { position: start, ground_normal: None, hit_wall: false };
+        let mut remaining = motion;
+        let max_step = self.max_step();
+
+        // step along the motion, which may be redirected by each collision
+        while remaining.length_sq() > 1e-10 {
+            let len = remaining.length();
+            let step = if len > max_step { remaining * (max_step / len) } else { remaining };
+
+            result.position += step;
+            remaining -= step;
+
+            self.resolve(&mut result, &mut remaining, colliders);
+        }
+
+        // resolve once more for motion which was too small to step
+        self.resolve(&mut result, &mut remaining, colliders);
+        return result;
+    }
+
+    fn probe_ground(&self, position: Vector3, colliders: &[Collider]) -> Option<Vector3> {
+        let probe = position - Vector3::new(0.0, self.probe_distance, 0.0);
+        return colliders.iter()
+            .filter_map(|x| self.contact(probe, x))
+            .map(|x| x.normal)
+            .find(|x| self.is_walkable(*x));
+    }
+
+    /// Move the character by the given motion, sliding along any colliders in the way, then update ground state <br/>
+    /// Returns the distance actually moved
+    pub fn move_and_slide(&mut self, motion: Vector3, colliders: &[Collider]) -> Vector3 {
+        let start = self.position;
+        let was_grounded = self.is_grounded();
+        let mut result = self.slide(start, motion, colliders);
+
+        // if blocked while walking, try stepping up over the obstacle
+        let horizontal = Vector3::new(motion.x, 0.0, motion.z);
+        if result.hit_wall && was_grounded && self.step_height > 0.0 && horizontal.length_sq() > 0.0 {
+            let raised = self.slide(start, Vector3::new(0.0, self.step_height, 0.0), colliders);
+            let moved = self.slide(raised.position, horizontal, colliders);
+            let lowered = self.slide(moved.position, Vector3::new(0.0, -(raised.position.y - start.y) + motion.y.min(0.0), 0.0), colliders);
+
+            let flat_dist = |p: Vector3| Vector3::new(p.x - start.x, 0.0, p.z - start.z).length_sq();
+            if lowered.ground_normal.is_some() && flat_dist(lowered.position) > flat_dist(result.position) + 1e-6 {
+                result = lowered;
+            }
+        }
+
+        let mut ground = result.ground_normal.or_else(|| self.probe_ground(result.position, colliders));
+
+        // stay attached to the ground when walking down slopes & stairs
+        if ground.is_none() && was_grounded && motion.y <= 0.0 && self.step_height > 0.0 {
+            let snapped = self.slide(result.position, Vector3::new(0.0, -self.step_height, 0.0), colliders);
+            if snapped.ground_normal.is_some() {
+                result = snapped;
+                ground = result.ground_normal;
+            }
+        }
+
+        self.position = result.position;
+        self.ground_normal = ground;
+        return self.position - start;
+    }
+}
@@ -0,0 +1,525 @@
+use crate::{math::Vector2, tilemap::{Properties, PropertyValue, TileLayer, TileMap}};
+
+const EPSILON: f32 = 1e-3;
+
+/// A 2D axis-aligned box in pixels (Y points down, as in tile maps)
+#[derive(Clone, Copy)]
+pub struct Rect2D {
+    pub min: Vector2,
+    pub max: Vector2,
+}
+
+impl Rect2D {
+    pub const fn new(min: Vector2, max: Vector2) -> Rect2D {
+        return Rect2D { min: min, max: max };
+    }
+
+    /// Construct a box from its top left corner & size
+    pub fn from_position_size(position: Vector2, size: Vector2) -> Rect2D {
+        return Rect2D { min: position, max: position + size };
+    }
+
+    pub fn center(&self) -> Vector2 {
+        return (self.min + self.max) * 0.5;
+    }
+
+    pub fn size(&self) -> Vector2 {
+        return self.max - self.min;
+    }
+
+    pub fn intersects(&self, other: &Rect2D) -> bool {
+        return self.min.x < other.max.x && self.max.x > other.min.x
+            && self.min.y < other.max.y && self.max.y > other.min.y;
+    }
+}
+
+/// The collision shape of a single tile
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TileShape {
+    Empty,
+    /// Blocks movement from every direction
+    Solid,
+    /// Only blocks movement downwards onto the top of the tile (such as a platform which can be jumped through from below)
+    OneWay,
+    /// A floor which is solid below a line between the given heights at the tile's left & right edges, as fractions of the tile's height measured from its bottom (0.0 .. 1.0) <br/>
+    /// Slopes only act as floors - bodies walk up & down them, but they never block horizontal movement or movement upwards
+    Slope { left: f32, right: f32 },
+}
+
+impl TileShape {
+    /// Get the shape of a tile from its custom properties <br/>
+    /// The "collision" property may be "none", "solid", "one_way", or "slope" (with float properties "slope_left" & "slope_right"). Tiles without the property are solid
+    pub fn from_properties(properties: Option<&Properties>) -> TileShape {
+        let props = match properties {
+            Some(v) => { v },
+            None => { return TileShape::Solid; }
+        };
+
+        let float = |name: &str| match props.get(name) {
+            Some(PropertyValue::Float(v)) => { *v },
+            Some(PropertyValue::Int(v)) => { *v as f32 },
+            _ => { 0.0 }
+        };
+
+        return match props.get("collision") {
+            Some(PropertyValue::String(v)) if v == "none" => { TileShape::Empty },
+            Some(PropertyValue::String(v)) if v == "one_way" => { TileShape::OneWay },
+            Some(PropertyValue::String(v)) if v == "slope" => {
+                TileShape::Slope { left: float("slope_left").clamp(0.0, 1.0), right: float("slope_right").clamp(0.0, 1.0) }
+            },
+            _ => { TileShape::Solid }
+        };
+    }
+}
+
+/// Result of a raycast against a collision layer
+#[derive(Clone, Copy)]
+pub struct TileHit {
+    /// Fraction of the ray length at which the ray hits a tile (0.0 .. 1.0). Rays starting inside a tile hit at 0.0
+    pub time: f32,
+    /// Unit surface normal of the tile at the hit point, facing the ray
+    pub normal: Vector2,
+    /// Coordinates of the tile which was hit
+    pub tile_x: i32,
+    pub tile_y: i32,
+}
+
+/// A grid of tile collision shapes, built from a tile layer
+pub struct CollisionLayer {
+    pub width: u32,
+    pub height: u32,
+    pub tile_width: f32,
+    pub tile_height: f32,
+    /// Offset of the layer in pixels
+    pub offset: Vector2,
+    /// Row-major tile shapes
+    pub shapes: Vec<TileShape>,
+}
+
+impl CollisionLayer {
+    /// Construct a new empty collision layer (panics if width * height tiles can't be addressed)
+    pub fn new(width: u32, height: u32, tile_width: f32, tile_height: f32) -> CollisionLayer {
+        let count = (width as usize).checked_mul(height as usize).expect("Collision layer is too large");
+        return CollisionLayer {
+            width: width,
+            height: height,
+            tile_width: tile_width,
+            tile_height: tile_height,
+            offset: Vector2::zero(),
+            shapes: vec![TileShape::Empty;count],
+        };
+    }
+
+    /// Build a collision layer from a tile layer, using the given function to get the shape of each non-empty tile (including flip flags)
+    pub fn from_layer<F>(map: &TileMap, layer: &TileLayer, mut shape_fn: F) -> CollisionLayer where F : FnMut(u32) -> TileShape {
+        let mut collision = CollisionLayer::new(layer.width, layer.height, map.tile_width as f32, map.tile_height as f32);
+        collision.offset = layer.offset;
+
+        for (shape, tile) in collision.shapes.iter_mut().zip(&layer.tiles) {
+            if *tile != 0 {
+                *shape = shape_fn(*tile);
+            }
+        }
+
+        return collision;
+    }
+
+    /// Build a collision layer from a tile layer, using each tile's custom properties to get its shape (see TileShape::from_properties)
+    pub fn from_properties(map: &TileMap, layer: &TileLayer) -> CollisionLayer {
+        return CollisionLayer::from_layer(map, layer, |tile| TileShape::from_properties(map.tile_properties(tile)));
+    }
+
+    /// Get the shape of the tile at the given coordinates (empty if out of bounds)
+    pub fn get(&self, x: i32, y: i32) -> TileShape {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return TileShape::Empty;
+        }
+        return self.shapes[(y as usize * self.width as usize) + x as usize];
+    }
+
+    /// Set the shape of the tile at the given coordinates (ignored if out of bounds)
+    pub fn set(&mut self, x: i32, y: i32, shape: TileShape) {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return;
+        }
+        self.shapes[(y as usize * self.width as usize) + x as usize] = shape;
+    }
+
+    /// Get the box covered by the tile at the given coordinates
+    pub fn tile_rect(&self, x: i32, y: i32) -> Rect2D {
+        let min = self.offset + Vector2::new(x as f32 * self.tile_width, y as f32 * self.tile_height);
+        return Rect2D::new(min, min + Vector2::new(self.tile_width, self.tile_height));
+    }
+
+    // range of tile columns covered by the given horizontal span (excluding tiles only touched at the edges)
+    fn columns(&self, min: f32, max: f32) -> (i32, i32) {
+        return (((min - self.offset.x) / self.tile_width).floor() as i32, ((max - self.offset.x - EPSILON) / self.tile_width).floor() as i32);
+    }
+
+    // range of tile rows covered by the given vertical span (excluding tiles only touched at the edges)
+    fn rows(&self, min: f32, max: f32) -> (i32, i32) {
+        return (((min - self.offset.y) / self.tile_height).floor() as i32, ((max - self.offset.y - EPSILON) / self.tile_height).floor() as i32);
+    }
+
+    /// Get the height of a slope tile's floor at the given horizontal position (clamped to the tile), or None if the tile isn't a slope
+    pub fn slope_surface(&self, x: i32, y: i32, world_x: f32) -> Option<f32> {
+        return match self.get(x, y) {
+            TileShape::Slope { left, right } => {
+                let rect = self.tile_rect(x, y);
+                let t = ((world_x - rect.min.x) / self.tile_width).clamp(0.0, 1.0);
+                Some(rect.max.y - (left + (right - left) * t) * self.tile_height)
+            },
+            _ => { None }
+        };
+    }
+
+    /// Gets whether the given box overlaps any solid tile or the solid part of any slope tile
+    pub fn overlaps(&self, rect: &Rect2D) -> bool {
+        let (x0, x1) = self.columns(rect.min.x, rect.max.x);
+        let (y0, y1) = self.rows(rect.min.y, rect.max.y);
+
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                match self.get(x, y) {
+                    TileShape::Solid => {
+                        return true;
+                    },
+                    TileShape::Slope { .. } => {
+                        // the floor is linear, so its highest point under the box is at one of the box's edges
+                        let a = self.slope_surface(x, y, rect.min.x).unwrap();
+                        let b = self.slope_surface(x, y, rect.max.x).unwrap();
+                        if rect.max.y > a.min(b) + EPSILON {
+                            return true;
+                        }
+                    },
+                    _ => {
+                    }
+                }
+            }
+        }
+
+        return false;
+    }
+
+    /// Cast a ray from the origin along the given direction (whose length is the length of the ray), returning the first tile hit <br/>
+    /// One-way tiles are only hit on their top edge by rays moving downwards
+    pub fn raycast(&self, origin: Vector2, dir: Vector2) -> Option<TileHit> {
+        let local = origin - self.offset;
+        let mut x = (local.x / self.tile_width).floor() as i32;
+        let mut y = (local.y / self.tile_height).floor() as i32;
+        let end_x = ((local.x + dir.x) / self.tile_width).floor() as i32;
+        let end_y = ((local.y + dir.y) / self.tile_height).floor() as i32;
+
+        // walk the grid cells along the ray in order, stopping at the first cell containing a hit
+        let step_x = if dir.x > 0.0 { 1 } else { -1 };
+        let step_y = if dir.y > 0.0 { 1 } else { -1 };
+        let delta_x = if dir.x != 0.0 { (self.tile_width / dir.x).abs() } else { f32::INFINITY };
+        let delta_y = if dir.y != 0.0 { (self.tile_height / dir.y).abs() } else { f32::INFINITY };
+        let next_edge_x = if dir.x > 0.0 { (x + 1) as f32 * self.tile_width } else { x as f32 * self.tile_width };
+        let next_edge_y = if dir.y > 0.0 { (y + 1) as f32 * self.tile_height } else { y as f32 * self.tile_height };
+        let mut t_x = if dir.x != 0.0 { (next_edge_x - local.x) / dir.x } else { f32::INFINITY };
+        let mut t_y = if dir.y != 0.0 { (next_edge_y - local.y) / dir.y } else { f32::INFINITY };
+
+        let max_cells = (end_x - x).abs() + (end_y - y).abs() + 1;
+        for _ in 0..max_cells {
+            if let Some(hit) = self.raycast_tile(origin, dir, x, y) {
+                return Some(hit);
+            }
+
+            if t_x < t_y {
+                x += step_x;
+                t_x += delta_x;
+            } else {
+                y += step_y;
+                t_y += delta_y;
+            }
+        }
+
+        return None;
+    }
+
+    fn raycast_tile(&self, origin: Vector2, dir: Vector2, x: i32, y: i32) -> Option<TileHit> {
+        let rect = self.tile_rect(x, y);
+
+        // each tile shape is convex, so clip the ray against the planes bounding it (n . p <= d)
+        let mut planes = [
+            (Vector2::new(-1.0, 0.0), -rect.min.x),
+            (Vector2::new(1.0, 0.0), rect.max.x),
+            (Vector2::new(0.0, -1.0), -rect.min.y),
+            (Vector2::new(0.0, 1.0), rect.max.y),
+            (Vector2::zero(), 0.0),
+        ];
+        let mut one_way = false;
+
+        match self.get(x, y) {
+            TileShape::Empty => {
+                return None;
+            },
+            TileShape::Solid => {
+            },
+            TileShape::OneWay => {
+                if dir.y <= 0.0 || origin.y > rect.min.y {
+                    return None;
+                }
+                one_way = true;
+            },
+            TileShape::Slope { left, right } => {
+                if left <= 0.0 && right <= 0.0 {
+                    return None;
+                }
+
+                // the floor line, with a normal pointing up out of the slope
+                let y_left = rect.max.y - left * self.tile_height;
+                let y_right = rect.max.y - right * self.tile_height;
+                let k = (y_right - y_left) / self.tile_width;
+                planes[4] = (Vector2::new(k, -1.0), k * rect.min.x - y_left);
+            }
+        };
+
+        let mut t_enter = 0.0;
+        let mut t_exit = 1.0;
+        let mut normal = None;
+        let mut entered_top = false;
+
+        for (i, (n, d)) in planes.into_iter().enumerate() {
+            let denom = Vector2::dot(&n, &dir);
+            let dist = d - Vector2::dot(&n, &origin);
+
+            if denom == 0.0 {
+                if dist < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+
+            let t = dist / denom;
+            if denom < 0.0 {
+                if t > t_enter {
+                    t_enter = t;
+                    normal = Some(n);
+                    entered_top = i == 2;
+                }
+            } else if t < t_exit {
+                t_exit = t;
+            }
+
+            if t_enter > t_exit {
+                return None;
+            }
+        }
+
+        // one-way tiles can only be entered through their top edge
+        if one_way && !entered_top {
+            return None;
+        }
+
+        let normal = match normal {
+            Some(v) => { v.normalized() },
+            None => {
+                // started inside the tile
+                if dir.length_sq() > 0.0 { -dir.normalized() } else { Vector2::zero() }
+            }
+        };
+
+        return Some(TileHit { time: t_enter, normal: normal, tile_x: x, tile_y: y });
+    }
+
+    /// Find the highest floor (smallest Y) under the given horizontal span between the given heights <br/>
+    /// Solid & one-way tiles are floors along their top edge (one-way tiles only if above_y is above them), while slopes are sampled at the center of the span
+    fn floor(&self, min_x: f32, max_x: f32, from_y: f32, to_y: f32, above_y: f32, one_way: bool) -> Option<f32> {
+        let (x0, x1) = self.columns(min_x, max_x);
+        let (y0, y1) = self.rows(from_y - EPSILON, to_y + EPSILON);
+        let center = (min_x + max_x) * 0.5;
+        let center_col = ((center - self.offset.x) / self.tile_width).floor() as i32;
+
+        let mut result: Option<f32> = None;
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let surface = match self.get(x, y) {
+                    TileShape::Solid => {
+                        // tiles below another solid tile have no exposed top edge
+                        if self.get(x, y - 1) == TileShape::Solid { None } else { Some(self.tile_rect(x, y).min.y) }
+                    },
+                    TileShape::OneWay if one_way => {
+                        let top = self.tile_rect(x, y).min.y;
+                        if above_y <= top + EPSILON { Some(top) } else { None }
+                    },
+                    TileShape::Slope { .. } if x == center_col => {
+                        self.slope_surface(x, y, center)
+                    },
+                    _ => { None }
+                };
+
+                if let Some(s) = surface {
+                    if s >= from_y - EPSILON && s <= to_y + EPSILON {
+                        result = Some(result.map_or(s, |r: f32| r.min(s)));
+                    }
+                }
+            }
+        }
+
+        return result;
+    }
+}
+
+/// A box-shaped body which moves through a collision layer, such as a platformer character <br/>
+/// Y points down, so gravity should be applied as positive Y motion
+pub struct KinematicBody2D {
+    /// Position of the top left corner of the body in pixels
+    pub position: Vector2,
+    pub size: Vector2,
+    /// Maximum height in pixels of ledges the body climbs automatically while on the ground
+    pub step_height: f32,
+    /// Distance in pixels the body snaps down to stay on the ground when walking down slopes & ledges
+    pub snap_distance: f32,
+    /// If set, the body falls through one-way platforms
+    pub drop_through: bool,
+    on_floor: bool,
+    on_ceiling: bool,
+    on_wall: bool,
+}
+
+impl KinematicBody2D {
+    /// Construct a new body at the given position
+    pub fn new(position: Vector2, size: Vector2) -> KinematicBody2D {
+        return KinematicBody2D {
+            position: position,
+            size: size,
+            step_height: 0.0,
+            snap_distance: 4.0,
+            drop_through: false,
+            on_floor: false,
+            on_ceiling: false,
+            on_wall: false,
+        };
+    }
+
+    /// Get the box covered by the body
+    pub fn bounds(&self) -> Rect2D {
+        return Rect2D::from_position_size(self.position, self.size);
+    }
+
+    /// Gets whether the body was standing on a floor at the end of the last move
+    pub fn is_on_floor(&self) -> bool {
+        return self.on_floor;
+    }
+
+    /// Gets whether the body hit a ceiling during the last move
+    pub fn is_on_ceiling(&self) -> bool {
+        return self.on_ceiling;
+    }
+
+    /// Gets whether the body was blocked horizontally during the last move
+    pub fn is_on_wall(&self) -> bool {
+        return self.on_wall;
+    }
+
+    /// Move the body by the given motion in pixels, stopping at any tiles in the way <br/>
+    /// Horizontal movement is applied first, followed by vertical movement. Returns the distance actually moved (so a caller can zero velocity along blocked axes)
+    pub fn move_and_collide(&mut self, motion: Vector2, layer: &CollisionLayer) -> Vector2 {
+        let start = self.position;
+        let was_on_floor = self.on_floor;
+
+        self.on_ceiling = false;
+        self.on_wall = false;
+
+        // bodies standing on slopes climb the slope's rise over their half width, so the edges of the slope aren't treated as walls
+        let step = if was_on_floor { self.step_height.max(self.slope_rise(layer)) } else { 0.0 };
+
+        self.move_x(motion.x, step, layer);
+
+        // keep the body on the floor while walking over steps, up slopes, and down slopes
+        if was_on_floor && motion.y >= 0.0 {
+            let b = self.bounds();
+            if let Some(floor) = layer.floor(b.min.x, b.max.x, b.max.y - step, b.max.y + step.max(self.snap_distance), b.max.y - step, !self.drop_through) {
+                self.position.y = floor - self.size.y;
+            }
+        }
+
+        self.on_floor = self.move_y(motion.y, layer);
+        return self.position - start;
+    }
+
+    // vertical rise of a slope over half the body's width, if the body is centered over a slope
+    fn slope_rise(&self, layer: &CollisionLayer) -> f32 {
+        let b = self.bounds();
+        let center = b.center().x;
+        let col = ((center - layer.offset.x) / layer.tile_width).floor() as i32;
+        let (y0, y1) = layer.rows(b.max.y - layer.tile_height, b.max.y + layer.tile_height);
+
+        for y in y0..=y1 {
+            if let TileShape::Slope { left, right } = layer.get(col, y) {
+                return (right - left).abs() * (layer.tile_height / layer.tile_width) * self.size.x * 0.5 + EPSILON;
+            }
+        }
+
+        return 0.0;
+    }
+
+    fn move_x(&mut self, dx: f32, step: f32, layer: &CollisionLayer) {
+        if dx == 0.0 {
+            return;
+        }
+
+        let b = self.bounds();
+        let (y0, y1) = layer.rows(b.min.y, b.max.y - step);
+
+        // scan the columns entered by the leading edge in order of movement, skipping columns the body already overlaps
+        let (first, last) = layer.columns(b.min.x, b.max.x);
+        let cols: Vec<i32> = if dx > 0.0 {
+            ((last + 1)..=layer.columns(b.min.x, b.max.x + dx).1).collect()
+        } else {
+            (layer.columns(b.min.x + dx, b.max.x).0..first).rev().collect()
+        };
+
+        let mut moved = dx;
+        for x in cols {
+            if (y0..=y1).any(|y| layer.get(x, y) == TileShape::Solid) {
+                let rect = layer.tile_rect(x, y0);
+                moved = if dx > 0.0 { (rect.min.x - b.max.x).max(0.0) } else { (rect.max.x - b.min.x).min(0.0) };
+                self.on_wall = true;
+                break;
+            }
+        }
+
+        self.position.x += moved;
+    }
+
+    // returns whether the body landed on a floor
+    fn move_y(&mut self, dy: f32, layer: &CollisionLayer) -> bool {
+        let b = self.bounds();
+
+        if dy >= 0.0 {
+            return match layer.floor(b.min.x, b.max.x, b.max.y, b.max.y + dy, b.max.y, !self.drop_through) {
+                Some(floor) => {
+                    self.position.y = floor - self.size.y;
+                    true
+                },
+                None => {
+                    self.position.y += dy;
+                    false
+                }
+            };
+        }
+
+        // find the lowest solid tile bottom edge crossed by the top of the body
+        let (x0, x1) = layer.columns(b.min.x, b.max.x);
+        let (r0, r1) = layer.rows(b.min.y + dy, b.min.y);
+
+        for y in (r0..=r1).rev() {
+            let bottom = layer.tile_rect(x0, y).max.y;
+            if bottom > b.min.y + EPSILON {
+                continue;
+            }
+
+            if (x0..=x1).any(|x| layer.get(x, y) == TileShape::Solid) {
+                self.position.y = bottom;
+                self.on_ceiling = true;
+                return false;
+            }
+        }
+
+        self.position.y += dy;
+        return false;
+    }
+}
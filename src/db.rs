@@ -1,5 +1,63 @@
-use std::{ffi::CString};
-use crate::db_internal::db_log;
+use std::{ffi::CString, fmt::Arguments, panic::PanicHookInfo, sync::{Mutex, atomic::{AtomicBool, Ordering}}};
+use crate::{db_internal::db_log, vdp::{self, Color32, SCREEN_HEIGHT, SCREEN_WIDTH}};
+use debug_draw::{CHAR_ADVANCE, DebugDraw, LINE_HEIGHT};
+
+pub mod profile;
+pub mod debug_draw;
+pub mod heap;
+pub mod overlay;
+
+/// Severity of a log message
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Get the name of this level as printed in log output
+    pub fn name(self) -> &'static str {
+        return match self {
+            LogLevel::Trace => { "TRACE" },
+            LogLevel::Debug => { "DEBUG" },
+            LogLevel::Info => { "INFO" },
+            LogLevel::Warn => { "WARN" },
+            LogLevel::Error => { "ERROR" },
+        };
+    }
+}
+
+/// Most verbose level which is compiled in at all, selected with the max_level_* features (None if logging is compiled out entirely) <br/>
+/// Messages above this level are removed at compile time regardless of runtime filters
+pub const STATIC_MAX_LEVEL: Option<LogLevel> = if cfg!(feature = "max_level_off") {
+    None
+} else if cfg!(feature = "max_level_error") {
+    Some(LogLevel::Error)
+} else if cfg!(feature = "max_level_warn") {
+    Some(LogLevel::Warn)
+} else if cfg!(feature = "max_level_info") {
+    Some(LogLevel::Info)
+} else if cfg!(feature = "max_level_debug") {
+    Some(LogLevel::Debug)
+} else {
+    Some(LogLevel::Trace)
+};
+
+struct LogFilter {
+    level: LogLevel,
+    modules: Vec<(String, LogLevel)>,
+}
+
+// set when a failed assertion has already been logged, so the panic handler doesn't log it a second time
+static FAILURE_LOGGED: AtomicBool = AtomicBool::new(false);
+
+static FILTER: Mutex<LogFilter> = Mutex::new(LogFilter {
+    level: LogLevel::Trace,
+    modules: Vec::new(),
+});
 
 /// Prints a message to debug output
 pub fn log(str: &str) {
@@ -9,9 +67,239 @@ pub fn log(str: &str) {
     }
 }
 
+/// Set the minimum level of messages which are logged, for modules without their own filter
+pub fn set_log_level(level: LogLevel) {
+    FILTER.lock().unwrap().level = level;
+}
+
+/// Set the minimum level of messages which are logged from the given module path and its submodules (for example, "dbsdk_rs::io" or "mygame::ai") <br/>
+/// When several filters match, the longest module path wins
+pub fn set_module_log_level(module: &str, level: LogLevel) {
+    let mut filter = FILTER.lock().unwrap();
+    match filter.modules.iter_mut().find(|x| x.0 == module) {
+        Some(v) => { v.1 = level; },
+        None => { filter.modules.push((module.to_string(), level)); }
+    };
+}
+
+/// Remove every per-module log filter
+pub fn clear_module_log_levels() {
+    FILTER.lock().unwrap().modules.clear();
+}
+
+/// Check whether a message of the given level from the given module path would be logged
+pub fn log_enabled(level: LogLevel, module: &str) -> bool {
+    match STATIC_MAX_LEVEL {
+        Some(max) => {
+            if level < max {
+                return false;
+            }
+        }
+        None => {
+            return false;
+        }
+    };
+
+    let filter = FILTER.lock().unwrap();
+    let min = filter.modules.iter()
+        .filter(|x| module == x.0 || (module.starts_with(x.0.as_str()) && module[x.0.len()..].starts_with("::")))
+        .max_by_key(|x| x.0.len())
+        .map(|x| x.1)
+        .unwrap_or(filter.level);
+
+    return level >= min;
+}
+
+/// Prints a message to debug output with the given level & module path, if it passes the current filters
+pub fn log_message(level: LogLevel, module: &str, str: &str) {
+    if log_enabled(level, module) {
+        log(format!("[{} {}] {}", level.name(), module, str).as_str());
+    }
+}
+
+/// Prints a formatted message to debug output, regardless of log level
+#[macro_export]
+macro_rules! logfmt {
+    ($($arg:tt)*) => {
+        $crate::db::log(format!($($arg)*).as_str())
+    };
+}
+
+/// Prints a formatted message at the given level, tagged with the calling module's path
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::db::log_enabled($level, module_path!()) {
+            $crate::db::log_message($level, module_path!(), format!($($arg)*).as_str());
+        }
+    };
+}
+
+/// Prints a formatted trace message
+#[macro_export]
+macro_rules! log_trace {
+    ($($arg:tt)*) => { $crate::log_at!($crate::db::LogLevel::Trace, $($arg)*) };
+}
+
+/// Prints a formatted debug message
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { $crate::log_at!($crate::db::LogLevel::Debug, $($arg)*) };
+}
+
+/// Prints a formatted info message
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => { $crate::log_at!($crate::db::LogLevel::Info, $($arg)*) };
+}
+
+/// Prints a formatted warning message
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { $crate::log_at!($crate::db::LogLevel::Warn, $($arg)*) };
+}
+
+/// Prints a formatted error message
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => { $crate::log_at!($crate::db::LogLevel::Error, $($arg)*) };
+}
+
+/// Log a failed assertion & return its message. Used by db_assert! & db_ensure!
+#[doc(hidden)]
+pub fn report_failure(kind: &str, expr: &str, file: &str, line: u32, context: Option<Arguments>) -> String {
+    let msg = match context {
+        Some(ctx) => { format!("{} failed: `{}` at {}:{}: {}", kind, expr, file, line, ctx) },
+        None => { format!("{} failed: `{}` at {}:{}", kind, expr, file, line) }
+    };
+
+    log(format!("ERROR: {}", msg).as_str());
+    return msg;
+}
+
+/// Panic with the message of a failure already logged by report_failure, so the panic handler doesn't log it again. Used by db_assert! & db_ensure!
+#[doc(hidden)]
+#[track_caller]
+pub fn failure_panic(msg: String) -> ! {
+    FAILURE_LOGGED.store(true, Ordering::Relaxed);
+    panic!("{}", msg);
+}
+
+/// Assert that a condition holds, logging the expression, location & optional formatted context through db::log before panicking <br/>
+/// Unlike assert!, the failure is logged even if register_panic hasn't been called
+#[macro_export]
+macro_rules! db_assert {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            let msg = $crate::db::report_failure("Assertion", stringify!($cond), file!(), line!(), None);
+            $crate::db::failure_panic(msg);
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            let msg = $crate::db::report_failure("Assertion", stringify!($cond), file!(), line!(), Some(format_args!($($arg)+)));
+            $crate::db::failure_panic(msg);
+        }
+    };
+}
+
+/// Check that a condition holds, logging the expression, location & optional formatted context through db::log if it doesn't <br/>
+/// In debug builds a failure panics, while in release builds the enclosing function returns Err with the given error
+#[macro_export]
+macro_rules! db_ensure {
+    ($cond:expr, $err:expr $(,)?) => {
+        if !$cond {
+            let msg = $crate::db::report_failure("Ensure", stringify!($cond), file!(), line!(), None);
+            if cfg!(debug_assertions) {
+                $crate::db::failure_panic(msg);
+            }
+            return Err($err.into());
+        }
+    };
+    ($cond:expr, $err:expr, $($arg:tt)+) => {
+        if !$cond {
+            let msg = $crate::db::report_failure("Ensure", stringify!($cond), file!(), line!(), Some(format_args!($($arg)+)));
+            if cfg!(debug_assertions) {
+                $crate::db::failure_panic(msg);
+            }
+            return Err($err.into());
+        }
+    };
+}
+
+fn log_panic(panic_info: &PanicHookInfo) {
+    // failed db_assert!/db_ensure! checks have already logged their details
+    if FAILURE_LOGGED.swap(false, Ordering::Relaxed) {
+        log(format!("FATAL ERROR at {}", match panic_info.location() {
+            Some(loc) => { loc.to_string() },
+            None => { "unknown location".to_string() }
+        }).as_str());
+        return;
+    }
+
+    log(format!("FATAL ERROR: {}", panic_info).as_str());
+}
+
+// wrap text to the given number of characters per line, breaking at spaces where possible
+fn wrap_text(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    for line in text.lines() {
+        let mut len = 0;
+        for word in line.split(' ') {
+            let mut word = word;
+            if len > 0 && len + 1 + word.chars().count() > width {
+                out.push('\n');
+                len = 0;
+            } else if len > 0 {
+                out.push(' ');
+                len += 1;
+            }
+
+            while word.chars().count() > width {
+                let split = word.char_indices().nth(width).map(|x| x.0).unwrap_or(word.len());
+                out.push_str(&word[..split]);
+                out.push('\n');
+                word = &word[split..];
+            }
+
+            out.push_str(word);
+            len += word.chars().count();
+        }
+        out.push('\n');
+    }
+    return out;
+}
+
+fn draw_crash_screen(background: Color32, panic_info: &PanicHookInfo) {
+    const SCALE: f32 = 2.0;
+    const MARGIN: f32 = 16.0;
+
+    let chars_per_line = ((SCREEN_WIDTH as f32 - MARGIN * 2.0) / (CHAR_ADVANCE as f32 * SCALE)) as usize;
+    let message = wrap_text(panic_info.to_string().as_str(), chars_per_line);
+
+    vdp::viewport(vdp::Rectangle::new(0, 0, SCREEN_WIDTH, SCREEN_HEIGHT));
+    vdp::clear_color(background);
+    vdp::clear_depth(1.0);
+
+    let mut batch = DebugDraw::new();
+    let white = Color32::new(255, 255, 255, 255);
+    batch.text("FATAL ERROR", MARGIN, MARGIN, SCALE * 2.0, white);
+    batch.text(message.as_str(), MARGIN, MARGIN + (LINE_HEIGHT as f32 * SCALE * 3.0), SCALE, white);
+    batch.flush();
+}
+
 /// Register custom DreamBox-specific panic handler
 pub fn register_panic() {
     std::panic::set_hook(Box::new(|panic_info| {
-        log(format!("FATAL ERROR: {}", panic_info).as_str());
+        log_panic(panic_info);
     }));
-}
\ No newline at end of file
+}
+
+/// Register custom DreamBox-specific panic handler which also draws a crash screen showing the panic message & location over the given background color <br/>
+/// The crash screen is drawn from the panic handler with a built-in font, so it doesn't depend on any game resources
+pub fn register_panic_screen(background: Color32) {
+    std::panic::set_hook(Box::new(move |panic_info| {
+        log_panic(panic_info);
+        draw_crash_screen(background, panic_info);
+    }));
+}
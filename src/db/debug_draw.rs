@@ -0,0 +1,204 @@
+use crate::{math::{Vector2, Vector4}, vdp::{self, Color32, PackedVertex, SCREEN_HEIGHT, SCREEN_WIDTH}};
+
+/// Width of a debug font glyph in pixels (before scaling)
+pub const GLYPH_WIDTH: i32 = 3;
+
+/// Height of a debug font glyph in pixels (before scaling)
+pub const GLYPH_HEIGHT: i32 = 5;
+
+/// Horizontal distance between characters in pixels (before scaling)
+pub const CHAR_ADVANCE: i32 = GLYPH_WIDTH + 1;
+
+/// Vertical distance between lines in pixels (before scaling)
+pub const LINE_HEIGHT: i32 = GLYPH_HEIGHT + 2;
+
+// 3x5 glyphs for ASCII 32 through 95, one bit per pixel with the top-left pixel in the highest bit
+const FONT: [u16;64] = [
+    0b000_000_000_000_000, // ' '
+    0b010_010_010_000_010, // '!'
+    0b101_101_000_000_000, // '"'
+    0b101_111_101_111_101, // '#'
+    0b011_110_010_011_110, // '$'
+    0b101_001_010_100_101, // '%'
+    0b010_101_010_101_011, // '&'
+    0b010_010_000_000_000, // '\''
+    0b001_010_010_010_001, // '('
+    0b100_010_010_010_100, // ')'
+    0b000_101_010_101_000, // '*'
+    0b000_010_111_010_000, // '+'
+    0b000_000_000_010_100, // ','
+    0b000_000_111_000_000, // '-'
+    0b000_000_000_000_010, // '.'
+    0b001_001_010_100_100, // '/'
+    0b111_101_101_101_111, // '0'
+    0b010_110_010_010_111, // '1'
+    0b111_001_111_100_111, // '2'
+    0b111_001_111_001_111, // '3'
+    0b101_101_111_001_001, // '4'
+    0b111_100_111_001_111, // '5'
+    0b111_100_111_101_111, // '6'
+    0b111_001_001_001_001, // '7'
+    0b111_101_111_101_111, // '8'
+    0b111_101_111_001_111, // '9'
+    0b000_010_000_010_000, // ':'
+    0b000_010_000_010_100, // ';'
+    0b001_010_100_010_001, // '<'
+    0b000_111_000_111_000, // '='
+    0b100_010_001_010_100, // '>'
+    0b111_001_011_000_010, // '?'
+    0b111_101_111_100_111, // '@'
+    0b010_101_111_101_101, // 'A'
+    0b110_101_110_101_110, // 'B'
+    0b011_100_100_100_011, // 'C'
+    0b110_101_101_101_110, // 'D'
+    0b111_100_110_100_111, // 'E'
+    0b111_100_110_100_100, // 'F'
+    0b011_100_101_101_011, // 'G'
+    0b101_101_111_101_101, // 'H'
+    0b111_010_010_010_111, // 'I'
+    0b001_001_001_101_010, // 'J'
+    0b101_101_110_101_101, // 'K'
+    0b100_100_100_100_111, // 'L'
+    0b101_111_111_101_101, // 'M'
+    0b110_101_101_101_101, // 'N'
+    0b010_101_101_101_010, // 'O'
+    0b110_101_110_100_100, // 'P'
+    0b010_101_101_110_011, // 'Q'
+    0b110_101_110_101_101, // 'R'
+    0b011_100_010_001_110, // 'S'
+    0b111_010_010_010_010, // 'T'
+    0b101_101_101_101_111, // 'U'
+    0b101_101_101_101_010, // 'V'
+    0b101_101_111_111_101, // 'W'
+    0b101_101_010_101_101, // 'X'
+    0b101_101_010_010_010, // 'Y'
+    0b111_001_010_100_111, // 'Z'
+    0b011_010_010_010_011, // '['
+    0b100_100_010_001_001, // '\\'
+    0b110_010_010_010_110, // ']'
+    0b010_101_000_000_000, // '^'
+    0b000_000_000_000_111, // '_'
+];
+
+fn glyph(c: char) -> u16 {
+    let c = c.to_ascii_uppercase();
+    let c = match c {
+        '{' => { '(' },
+        '}' => { ')' },
+        '|' => { '!' },
+        '`' => { '\'' },
+        '~' => { '-' },
+        _ => { c }
+    } as u32;
+
+    if (32..96).contains(&c) {
+        return FONT[(c - 32) as usize];
+    }
+
+    return FONT[('?' as u32 - 32) as usize];
+}
+
+fn to_clip(x: f32, y: f32) -> Vector4 {
+    return Vector4::new((x / SCREEN_WIDTH as f32) * 2.0 - 1.0, 1.0 - (y / SCREEN_HEIGHT as f32) * 2.0, 0.0, 1.0);
+}
+
+/// Immediate-mode batch of untextured rectangles & debug text in screen pixel coordinates (origin at the top left) <br/>
+/// Text uses a tiny built-in font which doesn't require any textures, so it works even when the rest of the game's resources are unavailable
+pub struct DebugDraw {
+    vertices: Vec<PackedVertex>,
+}
+
+impl DebugDraw {
+    /// Construct a new empty batch
+    pub fn new() -> DebugDraw {
+        return DebugDraw {
+            vertices: Vec::new(),
+        };
+    }
+
+    /// Add a filled rectangle to the batch
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color32) {
+        let a = to_clip(x, y);
+        let b = to_clip(x + width, y);
+        let c = to_clip(x + width, y + height);
+        let d = to_clip(x, y + height);
+        let ocolor = Color32::new(0, 0, 0, 0);
+
+        for p in [a, b, c, c, d, a] {
+            self.vertices.push(PackedVertex::new(p, Vector2::zero(), color, ocolor));
+        }
+    }
+
+    /// Add a line of text to the batch, with each font pixel drawn as a square of the given size. Newlines start a new line
+    pub fn text(&mut self, text: &str, x: f32, y: f32, scale: f32, color: Color32) {
+        let mut cx = x;
+        let mut cy = y;
+
+        for c in text.chars() {
+            if c == '\n' {
+                cx = x;
+                cy += LINE_HEIGHT as f32 * scale;
+                continue;
+            }
+
+            let bits = glyph(c);
+            for row in 0..GLYPH_HEIGHT {
+                for col in 0..GLYPH_WIDTH {
+                    let bit = (GLYPH_HEIGHT - 1 - row) * GLYPH_WIDTH + (GLYPH_WIDTH - 1 - col);
+                    if bits & (1 << bit) != 0 {
+                        self.rect(cx + col as f32 * scale, cy + row as f32 * scale, scale, scale, color);
+                    }
+                }
+            }
+
+            cx += CHAR_ADVANCE as f32 * scale;
+        }
+    }
+
+    /// Get the size in pixels of the given text when drawn at the given scale
+    pub fn measure_text(text: &str, scale: f32) -> Vector2 {
+        let lines = text.split('\n');
+        let mut width = 0;
+        let mut count = 0;
+        for line in lines {
+            width = width.max(line.chars().count() as i32);
+            count += 1;
+        }
+
+        return Vector2::new((width * CHAR_ADVANCE) as f32 * scale, (count * LINE_HEIGHT) as f32 * scale);
+    }
+
+    /// Gets whether the batch contains no geometry
+    pub fn is_empty(&self) -> bool {
+        return self.vertices.is_empty();
+    }
+
+    /// Remove all geometry from the batch
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    /// Draw the batch and then clear it <br/>
+    /// This unbinds the current texture, disables depth testing & enables alpha blending
+    pub fn flush(&mut self) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        vdp::bind_texture(None);
+        vdp::depth_write(false);
+        vdp::depth_func(vdp::Compare::Always);
+        vdp::blend_equation(vdp::BlendEquation::Add);
+        vdp::blend_func(vdp::BlendFactor::SrcAlpha, vdp::BlendFactor::OneMinusSrcAlpha);
+        vdp::set_culling(false);
+        vdp::draw_geometry_packed(vdp::Topology::TriangleList, &self.vertices);
+
+        self.vertices.clear();
+    }
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        return DebugDraw::new();
+    }
+}
@@ -0,0 +1,74 @@
+use std::{alloc::{GlobalAlloc, Layout, System}, sync::atomic::{AtomicUsize, Ordering}};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Heap usage as recorded by TrackingAllocator
+#[derive(Clone, Copy, Debug)]
+pub struct HeapStats {
+    /// Bytes currently allocated
+    pub allocated: usize,
+    /// Highest number of bytes allocated at once
+    pub peak: usize,
+    /// Number of live allocations
+    pub allocations: usize,
+}
+
+/// A global allocator which wraps the system allocator and records heap usage <br/>
+/// To enable it, add `#[global_allocator] static ALLOC: TrackingAllocator = TrackingAllocator;` to your game
+pub struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        return ptr;
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        return ptr;
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        ALLOCATIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            let total = ALLOCATED.fetch_add(new_size, Ordering::Relaxed) + new_size;
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            PEAK.fetch_max(total, Ordering::Relaxed);
+        }
+        return new_ptr;
+    }
+}
+
+fn record_alloc(size: usize) {
+    let total = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    PEAK.fetch_max(total, Ordering::Relaxed);
+}
+
+/// Get current heap usage (all zero unless TrackingAllocator is installed as the global allocator)
+pub fn stats() -> HeapStats {
+    return HeapStats {
+        allocated: ALLOCATED.load(Ordering::Relaxed),
+        peak: PEAK.load(Ordering::Relaxed),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+    };
+}
+
+/// Check whether TrackingAllocator is installed & has recorded any allocations
+pub fn is_tracking() -> bool {
+    return PEAK.load(Ordering::Relaxed) > 0;
+}
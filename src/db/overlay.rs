@@ -0,0 +1,126 @@
+use crate::{audio, clock::{FrameTimer, NOMINAL_FRAME_TIME}, db::{debug_draw::DebugDraw, heap}, vdp::{self, Color32, SCREEN_HEIGHT, SCREEN_WIDTH}};
+
+/// Number of frames shown in the frame time graph
+pub const GRAPH_LENGTH: usize = 60;
+
+const GRAPH_HEIGHT: f32 = 16.0;
+const PADDING: f32 = 2.0;
+const MARGIN: f32 = 4.0;
+
+/// Corner of the screen the stats overlay is drawn in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverlayCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Debug overlay showing frame rate, a frame time graph, memory usage, draw call counts & culled object counts <br/>
+/// Call draw once per frame after the rest of the frame has been rendered. Draw call counts are reset by each call to draw
+pub struct StatsOverlay {
+    pub visible: bool,
+    pub corner: OverlayCorner,
+    /// Size of each font pixel
+    pub scale: f32,
+    timer: FrameTimer,
+    history: [f32;GRAPH_LENGTH],
+    head: usize,
+    batch: DebugDraw,
+}
+
+fn kb(bytes: usize) -> usize {
+    return bytes.div_ceil(1024);
+}
+
+impl StatsOverlay {
+    /// Construct a new hidden stats overlay
+    pub fn new() -> StatsOverlay {
+        return StatsOverlay {
+            visible: false,
+            corner: OverlayCorner::TopLeft,
+            scale: 2.0,
+            timer: FrameTimer::new(),
+            history: [NOMINAL_FRAME_TIME;GRAPH_LENGTH],
+            head: 0,
+            batch: DebugDraw::new(),
+        };
+    }
+
+    /// Toggle the overlay between visible & hidden
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Record this frame's timings & draw the overlay if it is visible (should be called once per frame, after everything else has been drawn)
+    pub fn draw(&mut self) {
+        let delta = self.timer.tick();
+        self.history[self.head] = delta;
+        self.head = (self.head + 1) % GRAPH_LENGTH;
+
+        let draw_stats = vdp::get_draw_stats();
+        vdp::reset_draw_stats();
+
+        if !self.visible {
+            return;
+        }
+
+        let heap_line = if heap::is_tracking() {
+            let h = heap::stats();
+            format!("HEAP {} KB (PEAK {} KB)", kb(h.allocated), kb(h.peak))
+        } else {
+            "HEAP N/A".to_string()
+        };
+
+        let text = format!("FPS {:.1} ({:.2} MS)\nVRAM {} KB\nAUDIO {} KB\n{}\nDRAWS {} ({} VERTS)\nCULLED {}",
+            1.0 / delta, delta * 1000.0,
+            kb(vdp::get_usage().max(0) as usize),
+            kb(audio::get_usage().max(0) as usize),
+            heap_line,
+            draw_stats.draw_calls, draw_stats.vertices, draw_stats.culled);
+
+        let text_size = DebugDraw::measure_text(&text, self.scale);
+        let graph_height = GRAPH_HEIGHT * self.scale;
+        let bar_width = self.scale;
+        let width = text_size.x.max(GRAPH_LENGTH as f32 * bar_width) + PADDING * 2.0 * self.scale;
+        let height = text_size.y + graph_height + PADDING * 3.0 * self.scale;
+
+        let x = match self.corner {
+            OverlayCorner::TopLeft | OverlayCorner::BottomLeft => { MARGIN * self.scale },
+            OverlayCorner::TopRight | OverlayCorner::BottomRight => { SCREEN_WIDTH as f32 - width - MARGIN * self.scale },
+        };
+        let y = match self.corner {
+            OverlayCorner::TopLeft | OverlayCorner::TopRight => { MARGIN * self.scale },
+            OverlayCorner::BottomLeft | OverlayCorner::BottomRight => { SCREEN_HEIGHT as f32 - height - MARGIN * self.scale },
+        };
+
+        let padding = PADDING * self.scale;
+        self.batch.rect(x, y, width, height, Color32::new(0, 0, 0, 160));
+        self.batch.text(&text, x + padding, y + padding, self.scale, Color32::new(255, 255, 255, 255));
+
+        // frame time graph, where the full height is two nominal frames & the guide line marks one nominal frame
+        let graph_x = x + padding;
+        let graph_y = y + padding * 2.0 + text_size.y;
+        for i in 0..GRAPH_LENGTH {
+            let dt = self.history[(self.head + i) % GRAPH_LENGTH];
+            let h = (dt / (NOMINAL_FRAME_TIME * 2.0)).min(1.0) * graph_height;
+            let color = if dt <= NOMINAL_FRAME_TIME * 1.1 {
+                Color32::new(64, 255, 64, 255)
+            } else if dt <= NOMINAL_FRAME_TIME * 2.1 {
+                Color32::new(255, 255, 64, 255)
+            } else {
+                Color32::new(255, 64, 64, 255)
+            };
+            self.batch.rect(graph_x + i as f32 * bar_width, graph_y + graph_height - h, bar_width, h, color);
+        }
+        self.batch.rect(graph_x, graph_y + graph_height * 0.5, GRAPH_LENGTH as f32 * bar_width, 1.0, Color32::new(255, 255, 255, 128));
+
+        self.batch.flush();
+    }
+}
+
+impl Default for StatsOverlay {
+    fn default() -> Self {
+        return StatsOverlay::new();
+    }
+}
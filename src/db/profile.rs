@@ -0,0 +1,155 @@
+use std::sync::Mutex;
+
+use crate::{clock::get_high_res_time, db::log};
+
+struct ProfileNode {
+    name: &'static str,
+    parent: Option<usize>,
+    depth: usize,
+    time: f64,
+    calls: u32,
+}
+
+struct ProfilerState {
+    enabled: bool,
+    nodes: Vec<ProfileNode>,
+    stack: Vec<(usize, f64)>,
+    frame_start: f64,
+    report: Vec<ProfileEntry>,
+    frame_time: f64,
+}
+
+static STATE: Mutex<ProfilerState> = Mutex::new(ProfilerState {
+    enabled: false,
+    nodes: Vec::new(),
+    stack: Vec::new(),
+    frame_start: 0.0,
+    report: Vec::new(),
+    frame_time: 0.0,
+});
+
+/// Timing of a single profiler scope over one frame
+#[derive(Clone, Copy, Debug)]
+pub struct ProfileEntry {
+    pub name: &'static str,
+    /// Nesting depth of the scope (0 for top-level scopes)
+    pub depth: usize,
+    /// Total time in seconds spent inside the scope during the frame
+    pub time: f64,
+    /// Number of times the scope was entered during the frame
+    pub calls: u32,
+}
+
+/// Guard returned by begin, which ends its profiler scope when dropped
+pub struct ProfileScope {
+    active: bool,
+}
+
+impl Drop for ProfileScope {
+    fn drop(&mut self) {
+        if self.active {
+            end();
+        }
+    }
+}
+
+/// Enable or disable the profiler. While disabled, scopes cost a single lock & check
+pub fn set_enabled(enabled: bool) {
+    let mut state = STATE.lock().unwrap();
+    state.enabled = enabled;
+    state.stack.clear();
+}
+
+/// Check whether the profiler is enabled
+pub fn is_enabled() -> bool {
+    return STATE.lock().unwrap().enabled;
+}
+
+/// Begin a named profiler scope which lasts until the returned guard is dropped <br/>
+/// Scopes opened while another scope is open are nested under it in the report. Usually used through the scope! macro
+pub fn begin(name: &'static str) -> ProfileScope {
+    let mut state = STATE.lock().unwrap();
+    if !state.enabled {
+        return ProfileScope { active: false };
+    }
+
+    let parent = state.stack.last().map(|x| x.0);
+    let node = match state.nodes.iter().position(|x| x.parent == parent && x.name == name) {
+        Some(v) => { v },
+        None => {
+            let depth = state.stack.len();
+            state.nodes.push(ProfileNode { name: name, parent: parent, depth: depth, time: 0.0, calls: 0 });
+            state.nodes.len() - 1
+        }
+    };
+
+    state.stack.push((node, get_high_res_time()));
+    return ProfileScope { active: true };
+}
+
+fn end() {
+    let now = get_high_res_time();
+    let mut state = STATE.lock().unwrap();
+    if let Some((node, start)) = state.stack.pop() {
+        let node = &mut state.nodes[node];
+        node.time += now - start;
+        node.calls += 1;
+    }
+}
+
+/// Finish the current frame, storing its timings as the latest report and resetting them for the next frame (should be called once per frame)
+pub fn end_frame() {
+    let now = get_high_res_time();
+    let mut state = STATE.lock().unwrap();
+    if !state.enabled {
+        return;
+    }
+
+    let mut report: Vec<ProfileEntry> = Vec::with_capacity(state.nodes.len());
+    collect(&state.nodes, None, &mut report);
+    state.report = report;
+    state.frame_time = now - state.frame_start;
+    state.frame_start = now;
+
+    for node in &mut state.nodes {
+        node.time = 0.0;
+        node.calls = 0;
+    }
+}
+
+// depth-first so that children are listed directly under their parent
+fn collect(nodes: &[ProfileNode], parent: Option<usize>, report: &mut Vec<ProfileEntry>) {
+    for (i, node) in nodes.iter().enumerate() {
+        if node.parent == parent && node.calls > 0 {
+            report.push(ProfileEntry { name: node.name, depth: node.depth, time: node.time, calls: node.calls });
+            collect(nodes, Some(i), report);
+        }
+    }
+}
+
+/// Get the scope timings of the most recently finished frame, in hierarchical order
+pub fn report() -> Vec<ProfileEntry> {
+    return STATE.lock().unwrap().report.clone();
+}
+
+/// Get the total time in seconds between the two most recent calls to end_frame
+pub fn frame_time() -> f64 {
+    return STATE.lock().unwrap().frame_time;
+}
+
+/// Log the scope timings of the most recently finished frame
+pub fn dump() {
+    let report = report();
+    log(format!("Frame time: {:.3} ms", frame_time() * 1000.0).as_str());
+    for entry in report {
+        log(format!("{:indent$}{}: {:.3} ms ({} calls)", "", entry.name, entry.time * 1000.0, entry.calls, indent = (entry.depth + 1) * 4).as_str());
+    }
+}
+
+/// Profile the rest of the enclosing block under the given name
+#[macro_export]
+macro_rules! scope {
+    ($name:expr) => {
+        let _profile_scope = $crate::db::profile::begin($name);
+    };
+}
@@ -63,6 +63,10 @@ extern {
     pub fn audio_playMidi(dataPtr: *const u8, dataLen: i32, looping: bool) -> bool;
     pub fn audio_setMidiReverb(enable: bool);
     pub fn audio_setMidiVolume(volume: f32);
+    pub fn audio_stopMidi();
+    pub fn audio_setMidiPaused(paused: bool);
+    pub fn audio_getMidiPosition() -> f64;
+    pub fn audio_setMidiTempo(scale: f32);
     pub fn gamepad_isConnected(slot: GamepadSlot) -> bool;
     pub fn gamepad_readState(slot: GamepadSlot, ptr: *mut GamepadState);
     pub fn gamepad_setRumble(slot: GamepadSlot, enable: bool);
@@ -83,6 +87,7 @@ extern {
     pub fn fs_closeDir(dir: i32);
     pub fn fs_allocMemoryCard(filenamestr: *const c_char, icondata: *const u8, iconpalette: *const u16, blocks: i32) -> i32;
     pub fn clock_getTimestamp() -> u64;
+    pub fn clock_getHighResTime() -> f64;
     pub fn clock_timestampToDatetime(timestamp: u64, datetime: *mut DateTime);
     // pub fn clock_datetimeToTimestamp(datetime: *const DateTime) -> u64;
 }
@@ -0,0 +1,211 @@
+use std::f32::consts::PI;
+
+use crate::math::Lerp;
+
+pub mod tweener;
+
+const BACK_C1: f32 = 1.70158;
+const BACK_C2: f32 = BACK_C1 * 1.525;
+const BACK_C3: f32 = BACK_C1 + 1.0;
+const ELASTIC_C4: f32 = (2.0 * PI) / 3.0;
+const ELASTIC_C5: f32 = (2.0 * PI) / 4.5;
+
+/// Enumeration of standard easing curves
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+}
+
+impl Easing {
+    /// Apply this easing curve to the given normalized time value (clamped to 0.0 .. 1.0)
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        return match self {
+            Easing::Linear => { t }
+            Easing::QuadIn => { quad_in(t) }
+            Easing::QuadOut => { quad_out(t) }
+            Easing::QuadInOut => { quad_in_out(t) }
+            Easing::CubicIn => { cubic_in(t) }
+            Easing::CubicOut => { cubic_out(t) }
+            Easing::CubicInOut => { cubic_in_out(t) }
+            Easing::BackIn => { back_in(t) }
+            Easing::BackOut => { back_out(t) }
+            Easing::BackInOut => { back_in_out(t) }
+            Easing::BounceIn => { bounce_in(t) }
+            Easing::BounceOut => { bounce_out(t) }
+            Easing::BounceInOut => { bounce_in_out(t) }
+            Easing::ElasticIn => { elastic_in(t) }
+            Easing::ElasticOut => { elastic_out(t) }
+            Easing::ElasticInOut => { elastic_in_out(t) }
+        };
+    }
+}
+
+pub fn quad_in(t: f32) -> f32 {
+    return t * t;
+}
+
+pub fn quad_out(t: f32) -> f32 {
+    return 1.0 - (1.0 - t) * (1.0 - t);
+}
+
+pub fn quad_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        return 2.0 * t * t;
+    }
+    return 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0;
+}
+
+pub fn cubic_in(t: f32) -> f32 {
+    return t * t * t;
+}
+
+pub fn cubic_out(t: f32) -> f32 {
+    return 1.0 - (1.0 - t).powi(3);
+}
+
+pub fn cubic_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        return 4.0 * t * t * t;
+    }
+    return 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0;
+}
+
+pub fn back_in(t: f32) -> f32 {
+    return BACK_C3 * t * t * t - BACK_C1 * t * t;
+}
+
+pub fn back_out(t: f32) -> f32 {
+    return 1.0 + BACK_C3 * (t - 1.0).powi(3) + BACK_C1 * (t - 1.0).powi(2);
+}
+
+pub fn back_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        return ((2.0 * t).powi(2) * ((BACK_C2 + 1.0) * 2.0 * t - BACK_C2)) / 2.0;
+    }
+    return ((2.0 * t - 2.0).powi(2) * ((BACK_C2 + 1.0) * (t * 2.0 - 2.0) + BACK_C2) + 2.0) / 2.0;
+}
+
+pub fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+
+    if t < 1.0 / D1 {
+        return N1 * t * t;
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        return N1 * t * t + 0.75;
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        return N1 * t * t + 0.9375;
+    } else {
+        let t = t - 2.625 / D1;
+        return N1 * t * t + 0.984375;
+    }
+}
+
+pub fn bounce_in(t: f32) -> f32 {
+    return 1.0 - bounce_out(1.0 - t);
+}
+
+pub fn bounce_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        return (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0;
+    }
+    return (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0;
+}
+
+pub fn elastic_in(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    return -(2.0f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * ELASTIC_C4).sin();
+}
+
+pub fn elastic_out(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    return 2.0f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * ELASTIC_C4).sin() + 1.0;
+}
+
+pub fn elastic_in_out(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    if t < 0.5 {
+        return -(2.0f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * ELASTIC_C5).sin()) / 2.0;
+    }
+    return (2.0f32.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * ELASTIC_C5).sin()) / 2.0 + 1.0;
+}
+
+/// Interpolates a value from a start to an end value over time using an easing curve
+#[derive(Clone, Copy)]
+pub struct Tween<T> where T : Clone + Copy + Lerp<T> {
+    pub from: T,
+    pub to: T,
+    pub duration: f32,
+    pub easing: Easing,
+    elapsed: f32,
+}
+
+impl<T> Tween<T> where T : Clone + Copy + Lerp<T> {
+    /// Construct a new tween between two values over the given duration in seconds
+    pub fn new(from: T, to: T, duration: f32, easing: Easing) -> Tween<T> {
+        return Tween { from: from, to: to, duration: duration, easing: easing, elapsed: 0.0 };
+    }
+
+    /// Advance the tween by the given delta time in seconds and return the new value
+    pub fn update(&mut self, delta: f32) -> T {
+        self.elapsed = (self.elapsed + delta).min(self.duration.max(0.0));
+        return self.value();
+    }
+
+    /// Get the current value of the tween
+    pub fn value(&self) -> T {
+        return T::lerp(self.from, self.to, self.easing.apply(self.progress()));
+    }
+
+    /// Get the normalized progress of the tween (0.0 .. 1.0)
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        return self.elapsed / self.duration;
+    }
+
+    /// Get the elapsed time of the tween in seconds
+    pub fn elapsed(&self) -> f32 {
+        return self.elapsed;
+    }
+
+    /// Gets whether the tween has reached its end value
+    pub fn is_finished(&self) -> bool {
+        return self.elapsed >= self.duration;
+    }
+
+    /// Restart the tween from the beginning
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    /// Jump straight to the end of the tween
+    pub fn finish(&mut self) {
+        self.elapsed = self.duration.max(0.0);
+    }
+}
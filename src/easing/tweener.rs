@@ -0,0 +1,223 @@
+use crate::{clock::FrameTimer, easing::Tween, math::Lerp};
+
+/// Handle to a sequence playing in a Tweener
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TweenId(u32);
+
+type Callback<C> = Box<dyn FnMut(&mut C) + Send>;
+
+// a tween which writes its value into the context each time it advances
+trait Track<C> {
+    /// Advance by the given time, returning the time left over once the tween has finished (or None if it's still running)
+    fn advance(&mut self, ctx: &mut C, delta: f32) -> Option<f32>;
+
+    fn duration(&self) -> f32;
+}
+
+struct TweenTrack<T, F> where T : Clone + Copy + Lerp<T> {
+    tween: Tween<T>,
+    apply: F,
+}
+
+impl<C, T, F> Track<C> for TweenTrack<T, F> where T : Clone + Copy + Lerp<T>, F : FnMut(&mut C, T) {
+    fn advance(&mut self, ctx: &mut C, delta: f32) -> Option<f32> {
+        let remaining = self.tween.duration.max(0.0) - self.tween.elapsed();
+        let value = self.tween.update(delta);
+        (self.apply)(ctx, value);
+
+        if self.tween.is_finished() {
+            return Some((delta - remaining).max(0.0));
+        }
+
+        return None;
+    }
+
+    fn duration(&self) -> f32 {
+        return self.tween.duration.max(0.0);
+    }
+}
+
+enum Step<C> {
+    Wait(f32),
+    Tween(Box<dyn Track<C> + Send>),
+    Call(Callback<C>),
+}
+
+/// A list of tweens, delays & callbacks which run one after another <br/>
+/// Tweens write their values through a setter which is given a mutable reference to the context passed to Tweener::update (such as the game state), so they can animate any field
+pub struct Sequence<C = ()> {
+    steps: Vec<Step<C>>,
+    on_complete: Option<Callback<C>>,
+}
+
+impl<C> Sequence<C> {
+    /// Construct a new empty sequence
+    pub fn new() -> Sequence<C> {
+        return Sequence {
+            steps: Vec::new(),
+            on_complete: None,
+        };
+    }
+
+    /// Wait for the given number of seconds before running the next step
+    pub fn delay(mut self, seconds: f32) -> Sequence<C> {
+        self.steps.push(Step::Wait(seconds.max(0.0)));
+        return self;
+    }
+
+    /// Run the given tween, passing its value to the setter each time it advances
+    pub fn tween<T, F>(mut self, tween: Tween<T>, apply: F) -> Sequence<C> where T : Clone + Copy + Lerp<T> + Send + 'static, F : FnMut(&mut C, T) + Send + 'static, C : 'static {
+        self.steps.push(Step::Tween(Box::new(TweenTrack { tween: tween, apply: apply })));
+        return self;
+    }
+
+    /// Call the given function once the previous steps have finished
+    pub fn call<F>(mut self, callback: F) -> Sequence<C> where F : FnMut(&mut C) + Send + 'static {
+        self.steps.push(Step::Call(Box::new(callback)));
+        return self;
+    }
+
+    /// Call the given function once every step has finished (also called if the sequence is skipped with Tweener::finish, but not if it is stopped)
+    pub fn on_complete<F>(mut self, callback: F) -> Sequence<C> where F : FnMut(&mut C) + Send + 'static {
+        self.on_complete = Some(Box::new(callback));
+        return self;
+    }
+
+    /// Get the total duration of the sequence in seconds
+    pub fn duration(&self) -> f32 {
+        return self.steps.iter().map(|x| match x {
+            Step::Wait(v) => { *v },
+            Step::Tween(v) => { v.duration() },
+            Step::Call(_) => { 0.0 }
+        }).sum();
+    }
+}
+
+impl<C> Default for Sequence<C> {
+    fn default() -> Self {
+        return Sequence::new();
+    }
+}
+
+struct Playing<C> {
+    id: TweenId,
+    sequence: Sequence<C>,
+    step: usize,
+    waited: f32,
+}
+
+impl<C> Playing<C> {
+    // advance through the steps, returning true once every step has finished
+    fn advance(&mut self, ctx: &mut C, delta: f32) -> bool {
+        let mut remaining = delta;
+
+        while let Some(step) = self.sequence.steps.get_mut(self.step) {
+            match step {
+                Step::Wait(duration) => {
+                    self.waited += remaining;
+                    if self.waited < *duration {
+                        return false;
+                    }
+                    remaining = self.waited - *duration;
+                    self.waited = 0.0;
+                },
+                Step::Tween(track) => {
+                    remaining = match track.advance(ctx, remaining) {
+                        Some(v) => { v },
+                        None => { return false; }
+                    };
+                },
+                Step::Call(callback) => {
+                    callback(ctx);
+                }
+            };
+
+            self.step += 1;
+        }
+
+        if let Some(callback) = self.sequence.on_complete.as_mut() {
+            callback(ctx);
+        }
+
+        return true;
+    }
+}
+
+/// Plays sequences of tweens in parallel, writing animated values into a shared context (such as the game state) <br/>
+/// Finished sequences are removed automatically
+pub struct Tweener<C = ()> {
+    playing: Vec<Playing<C>>,
+    next_id: u32,
+}
+
+impl<C> Tweener<C> {
+    /// Construct a new tweener with nothing playing
+    pub fn new() -> Tweener<C> {
+        return Tweener {
+            playing: Vec::new(),
+            next_id: 0,
+        };
+    }
+
+    /// Start playing the given sequence, returning a handle which can be used to stop or skip it
+    pub fn play(&mut self, sequence: Sequence<C>) -> TweenId {
+        let id = TweenId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        self.playing.push(Playing { id: id, sequence: sequence, step: 0, waited: 0.0 });
+        return id;
+    }
+
+    /// Start playing a single tween, passing its value to the setter each time it advances
+    pub fn play_tween<T, F>(&mut self, tween: Tween<T>, apply: F) -> TweenId where T : Clone + Copy + Lerp<T> + Send + 'static, F : FnMut(&mut C, T) + Send + 'static, C : 'static {
+        return self.play(Sequence::new().tween(tween, apply));
+    }
+
+    /// Advance every playing sequence by the given delta time in seconds
+    pub fn update(&mut self, ctx: &mut C, delta: f32) {
+        self.playing.retain_mut(|x| !x.advance(ctx, delta));
+    }
+
+    /// Advance every playing sequence by the frame timer's most recent delta time
+    pub fn update_with_timer(&mut self, ctx: &mut C, timer: &FrameTimer) {
+        self.update(ctx, timer.delta());
+    }
+
+    /// Gets whether the given sequence is still playing
+    pub fn is_playing(&self, id: TweenId) -> bool {
+        return self.playing.iter().any(|x| x.id == id);
+    }
+
+    /// Stop the given sequence, leaving animated values where they are. Its remaining callbacks are not called
+    pub fn stop(&mut self, id: TweenId) {
+        self.playing.retain(|x| x.id != id);
+    }
+
+    /// Skip the given sequence to its end, applying the final value of each remaining tween & calling its remaining callbacks in order
+    pub fn finish(&mut self, ctx: &mut C, id: TweenId) {
+        if let Some(idx) = self.playing.iter().position(|x| x.id == id) {
+            let mut playing = self.playing.remove(idx);
+            playing.advance(ctx, f32::INFINITY);
+        }
+    }
+
+    /// Stop every playing sequence
+    pub fn clear(&mut self) {
+        self.playing.clear();
+    }
+
+    /// Get the number of sequences playing
+    pub fn len(&self) -> usize {
+        return self.playing.len();
+    }
+
+    /// Gets whether nothing is playing
+    pub fn is_empty(&self) -> bool {
+        return self.playing.is_empty();
+    }
+}
+
+impl<C> Default for Tweener<C> {
+    fn default() -> Self {
+        return Tweener::new();
+    }
+}
@@ -1,9 +1,14 @@
 use bitmask::bitmask;
 
-use crate::db_internal::{gamepad_readState, gamepad_setRumble, gamepad_isConnected};
+use crate::{db_internal::{gamepad_readState, gamepad_setRumble, gamepad_isConnected}, math::Vector2};
+
+pub mod actions;
+pub mod rumble;
+pub mod players;
+pub mod replay;
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum GamepadSlot {
     SlotA,
     SlotB,
@@ -11,6 +16,17 @@ pub enum GamepadSlot {
     SlotD,
 }
 
+pub const ALL_SLOTS: [GamepadSlot;4] = [
+    GamepadSlot::SlotA, GamepadSlot::SlotB, GamepadSlot::SlotC, GamepadSlot::SlotD,
+];
+
+/// A change in a gamepad's connection state
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConnectionEvent {
+    Connected(GamepadSlot),
+    Disconnected(GamepadSlot),
+}
+
 bitmask! {
     #[repr(C)]
     pub mask GamepadButtonMask: u16 where flags GamepadButton {
@@ -33,9 +49,114 @@ bitmask! {
     }
 }
 
+pub const ALL_BUTTONS: [GamepadButton;16] = [
+    GamepadButton::A, GamepadButton::B, GamepadButton::X, GamepadButton::Y,
+    GamepadButton::Up, GamepadButton::Down, GamepadButton::Left, GamepadButton::Right,
+    GamepadButton::L1, GamepadButton::L2, GamepadButton::L3,
+    GamepadButton::R1, GamepadButton::R2, GamepadButton::R3,
+    GamepadButton::Select, GamepadButton::Start,
+];
+
+impl GamepadButtonMask {
+    /// Construct a button mask from its raw bit representation
+    pub fn from_bits(bits: u16) -> GamepadButtonMask {
+        let mut mask = GamepadButtonMask::none();
+        for button in ALL_BUTTONS {
+            if bits & (button as u16) != 0 {
+                mask.set(button);
+            }
+        }
+        return mask;
+    }
+
+    /// Get the raw bit representation of this button mask
+    pub fn bits(&self) -> u16 {
+        return **self;
+    }
+}
+
+#[cfg(feature = "serde")]
+mod button_mask_serde {
+    use serde::{Serialize, Deserialize, Serializer, Deserializer};
+
+    use super::GamepadButtonMask;
+
+    pub fn serialize<S>(mask: &GamepadButtonMask, serializer: S) -> Result<S::Ok, S::Error> where S : Serializer {
+        return mask.bits().serialize(serializer);
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<GamepadButtonMask, D::Error> where D : Deserializer<'de> {
+        return Ok(GamepadButtonMask::from_bits(u16::deserialize(deserializer)?));
+    }
+}
+
+/// Shape of the curve applied to stick input after the deadzone is removed
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResponseCurve {
+    Linear,
+    /// Squares the input magnitude, giving finer control near the center
+    Quadratic,
+    /// Cubes the input magnitude
+    Cubic,
+    /// Raises the input magnitude to the given power
+    Power(f32),
+}
+
+impl ResponseCurve {
+    /// Apply this curve to a value in 0..1
+    pub fn apply(self, value: f32) -> f32 {
+        return match self {
+            ResponseCurve::Linear => { value },
+            ResponseCurve::Quadratic => { value * value },
+            ResponseCurve::Cubic => { value * value * value },
+            ResponseCurve::Power(p) => { value.powf(p) },
+        };
+    }
+}
+
+/// Settings used to convert raw analog stick values into a normalized vector
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StickSettings {
+    /// Radius (from 0 to 1) below which stick input is ignored
+    pub deadzone: f32,
+    /// Radius (from 0 to 1) above which stick input is treated as fully pushed
+    pub outer_deadzone: f32,
+    /// Curve applied to the stick magnitude after the deadzones are removed
+    pub curve: ResponseCurve,
+}
+
+impl StickSettings {
+    pub const DEFAULT: StickSettings = StickSettings { deadzone: 0.2, outer_deadzone: 0.95, curve: ResponseCurve::Linear };
+
+    /// Convert raw stick values into a vector from -1 to 1, applying a radial deadzone & response curve <br/>
+    /// The direction of the stick is preserved, only its magnitude is rescaled
+    pub fn apply(&self, x: i16, y: i16) -> Vector2 {
+        let v = Vector2::new(x as f32 / i16::MAX as f32, y as f32 / i16::MAX as f32);
+        let len = v.length();
+
+        if len <= self.deadzone || len == 0.0 {
+            return Vector2::zero();
+        }
+
+        let range = (self.outer_deadzone - self.deadzone).max(f32::EPSILON);
+        let scaled = ((len - self.deadzone) / range).clamp(0.0, 1.0);
+        return v * (self.curve.apply(scaled) / len);
+    }
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
+        return StickSettings::DEFAULT;
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GamepadState {
+    #[cfg_attr(feature = "serde", serde(with = "button_mask_serde"))]
     pub button_mask: GamepadButtonMask,
     pub left_stick_x: i16,
     pub left_stick_y: i16,
@@ -44,10 +165,72 @@ pub struct GamepadState {
 }
 
 impl GamepadState {
+    /// Construct a state with no buttons pressed & sticks centered
+    pub const fn empty() -> GamepadState {
+        return GamepadState { button_mask: GamepadButtonMask { mask: 0 }, left_stick_x: 0, left_stick_y: 0, right_stick_x: 0, right_stick_y: 0 };
+    }
+
     /// Check if the given button is pressed
     pub fn is_pressed(self, button: GamepadButton) -> bool {
         return self.button_mask.contains(button);
     }
+
+    /// Get the left stick as a vector from -1 to 1, using the default deadzone settings
+    pub fn left_stick(&self) -> Vector2 {
+        return StickSettings::DEFAULT.apply(self.left_stick_x, self.left_stick_y);
+    }
+
+    /// Get the right stick as a vector from -1 to 1, using the default deadzone settings
+    pub fn right_stick(&self) -> Vector2 {
+        return StickSettings::DEFAULT.apply(self.right_stick_x, self.right_stick_y);
+    }
+
+    /// Get the dpad as a normalized vector (positive Y is down, matching the stick axes), or zero if no direction is held
+    pub fn dpad(&self) -> Vector2 {
+        let mut dir = Vector2::zero();
+        if self.is_pressed(GamepadButton::Left) { dir.x -= 1.0; }
+        if self.is_pressed(GamepadButton::Right) { dir.x += 1.0; }
+        if self.is_pressed(GamepadButton::Up) { dir.y -= 1.0; }
+        if self.is_pressed(GamepadButton::Down) { dir.y += 1.0; }
+
+        if dir.x != 0.0 && dir.y != 0.0 {
+            dir *= std::f32::consts::FRAC_1_SQRT_2;
+        }
+
+        return dir;
+    }
+
+    /// Get a movement direction merging the dpad & left stick, using the default deadzone settings <br/>
+    /// If any dpad direction is held the dpad takes priority, otherwise the left stick is used. The result never exceeds a length of 1
+    pub fn direction(&self) -> Vector2 {
+        return self.direction_with(&StickSettings::DEFAULT);
+    }
+
+    /// Get a movement direction merging the dpad & left stick, using the given deadzone settings for the stick
+    pub fn direction_with(&self, settings: &StickSettings) -> Vector2 {
+        let dpad = self.dpad();
+        if dpad.x != 0.0 || dpad.y != 0.0 {
+            return dpad;
+        }
+
+        let stick = self.left_stick_with(settings);
+        let len = stick.length();
+        if len > 1.0 {
+            return stick * (1.0 / len);
+        }
+
+        return stick;
+    }
+
+    /// Get the left stick as a vector from -1 to 1, using the given deadzone settings
+    pub fn left_stick_with(&self, settings: &StickSettings) -> Vector2 {
+        return settings.apply(self.left_stick_x, self.left_stick_y);
+    }
+
+    /// Get the right stick as a vector from -1 to 1, using the given deadzone settings
+    pub fn right_stick_with(&self, settings: &StickSettings) -> Vector2 {
+        return settings.apply(self.right_stick_x, self.right_stick_y);
+    }
 }
 
 pub struct Gamepad {
@@ -67,7 +250,7 @@ impl Gamepad {
 
     /// Read the state of this gamepad
     pub fn read_state(&self) -> GamepadState {
-        let mut state = GamepadState { button_mask: GamepadButtonMask::none(), left_stick_x: 0, left_stick_y: 0, right_stick_x: 0, right_stick_y: 0 };
+        let mut state = GamepadState::empty();
         unsafe { gamepad_readState(self.slot, &mut state); }
         return state;
     }
@@ -76,4 +259,199 @@ impl Gamepad {
     pub fn set_rumble(&self, enable: bool) {
         unsafe { gamepad_setRumble(self.slot, enable); }
     }
-}
\ No newline at end of file
+}
+
+/// Tracks which gamepads are connected across frames, to detect controllers being plugged in & unplugged
+pub struct ConnectionTracker {
+    connected: [bool;4],
+    events: Vec<ConnectionEvent>,
+}
+
+impl ConnectionTracker {
+    /// Construct a new connection tracker. Gamepads which are already connected are reported as connected on the first poll
+    pub const fn new() -> ConnectionTracker {
+        return ConnectionTracker {
+            connected: [false;4],
+            events: Vec::new(),
+        };
+    }
+
+    /// Check every slot for connection changes since the last poll (should be called once per frame)
+    pub fn poll(&mut self) -> &[ConnectionEvent] {
+        self.events.clear();
+
+        for (i, slot) in ALL_SLOTS.iter().enumerate() {
+            let connected = Gamepad::new(*slot).is_connected();
+            if connected != self.connected[i] {
+                self.connected[i] = connected;
+                self.events.push(if connected { ConnectionEvent::Connected(*slot) } else { ConnectionEvent::Disconnected(*slot) });
+            }
+        }
+
+        return &self.events;
+    }
+
+    /// Get the connection changes detected by the most recent poll
+    pub fn events(&self) -> &[ConnectionEvent] {
+        return &self.events;
+    }
+
+    /// Check whether the given slot was connected as of the most recent poll
+    pub fn is_connected(&self, slot: GamepadSlot) -> bool {
+        return self.connected[slot as usize];
+    }
+}
+
+impl Default for ConnectionTracker {
+    fn default() -> Self {
+        return ConnectionTracker::new();
+    }
+}
+
+/// Settings for repeating button presses while a button is held (for example, for menu navigation)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RepeatSettings {
+    /// Number of frames a button must be held before it starts repeating
+    pub delay: u32,
+    /// Number of frames between each repeat
+    pub interval: u32,
+}
+
+impl RepeatSettings {
+    pub const DEFAULT: RepeatSettings = RepeatSettings { delay: 24, interval: 6 };
+}
+
+impl Default for RepeatSettings {
+    fn default() -> Self {
+        return RepeatSettings::DEFAULT;
+    }
+}
+
+/// Tracks a gamepad's state across frames, to detect buttons being pressed & released
+pub struct InputManager {
+    pub gamepad: Gamepad,
+    /// Deadzone settings used by left_stick & right_stick
+    pub stick_settings: StickSettings,
+    /// Repeat settings used by repeat_pressed
+    pub repeat_settings: RepeatSettings,
+    held_frames: [u32;16],
+    current: GamepadState,
+    previous: GamepadState,
+    connected: bool,
+    was_connected: bool,
+}
+
+impl InputManager {
+    /// Construct a new input manager for the given slot
+    pub const fn new(slot: GamepadSlot) -> InputManager {
+        return InputManager {
+            gamepad: Gamepad::new(slot),
+            stick_settings: StickSettings::DEFAULT,
+            repeat_settings: RepeatSettings::DEFAULT,
+            held_frames: [0;16],
+            current: GamepadState::empty(),
+            previous: GamepadState::empty(),
+            connected: false,
+            was_connected: false,
+        };
+    }
+
+    /// Read the latest gamepad state (should be called once per frame, before querying input)
+    pub fn update(&mut self) {
+        self.was_connected = self.connected;
+        self.connected = self.gamepad.is_connected();
+
+        let state = if self.connected { self.gamepad.read_state() } else { GamepadState::empty() };
+        self.update_with(state);
+    }
+
+    /// Advance to the next frame using the given state instead of reading it from the gamepad (for example, for input playback)
+    pub fn update_with(&mut self, state: GamepadState) {
+        self.previous = self.current;
+        self.current = state;
+
+        for (i, button) in ALL_BUTTONS.iter().enumerate() {
+            self.held_frames[i] = if state.is_pressed(*button) { self.held_frames[i].saturating_add(1) } else { 0 };
+        }
+    }
+
+    /// Check whether the gamepad was connected as of the last update
+    pub fn is_connected(&self) -> bool {
+        return self.connected;
+    }
+
+    /// Check if the gamepad was plugged in since the previous update
+    pub fn just_connected(&self) -> bool {
+        return self.connected && !self.was_connected;
+    }
+
+    /// Check if the gamepad was unplugged since the previous update
+    pub fn just_disconnected(&self) -> bool {
+        return !self.connected && self.was_connected;
+    }
+
+    /// Get the gamepad state for the current frame
+    pub fn state(&self) -> GamepadState {
+        return self.current;
+    }
+
+    /// Get the gamepad state for the previous frame
+    pub fn previous_state(&self) -> GamepadState {
+        return self.previous;
+    }
+
+    /// Check if the given button is currently held
+    pub fn held(&self, button: GamepadButton) -> bool {
+        return self.current.is_pressed(button);
+    }
+
+    /// Check if the given button was pressed this frame
+    pub fn just_pressed(&self, button: GamepadButton) -> bool {
+        return self.current.is_pressed(button) && !self.previous.is_pressed(button);
+    }
+
+    /// Check if the given button was released this frame
+    pub fn just_released(&self, button: GamepadButton) -> bool {
+        return !self.current.is_pressed(button) && self.previous.is_pressed(button);
+    }
+
+    /// Get the current left stick as a vector from -1 to 1, using this manager's deadzone settings
+    pub fn left_stick(&self) -> Vector2 {
+        return self.current.left_stick_with(&self.stick_settings);
+    }
+
+    /// Get the current movement direction merging the dpad & left stick, using this manager's deadzone settings
+    pub fn direction(&self) -> Vector2 {
+        return self.current.direction_with(&self.stick_settings);
+    }
+
+    /// Get the current right stick as a vector from -1 to 1, using this manager's deadzone settings
+    pub fn right_stick(&self) -> Vector2 {
+        return self.current.right_stick_with(&self.stick_settings);
+    }
+
+    /// Get the number of frames the given button has been held for (0 if it isn't held)
+    pub fn held_frames(&self, button: GamepadButton) -> u32 {
+        return match ALL_BUTTONS.iter().position(|x| *x == button) {
+            Some(i) => { self.held_frames[i] },
+            None => { 0 }
+        };
+    }
+
+    /// Check if the given button was pressed this frame, or has been held long enough to repeat according to the repeat settings
+    pub fn repeat_pressed(&self, button: GamepadButton) -> bool {
+        let frames = self.held_frames(button);
+        if frames == 1 {
+            return true;
+        }
+
+        let delay = self.repeat_settings.delay.max(1);
+        let interval = self.repeat_settings.interval.max(1);
+        return frames > delay && (frames - 1 - delay).is_multiple_of(interval);
+    }
+
+    /// Check if any button was pressed this frame
+    pub fn any_just_pressed(&self) -> bool {
+        return self.current.button_mask.bits() & !self.previous.button_mask.bits() != 0;
+    }
+}
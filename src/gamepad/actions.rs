@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::gamepad::{ALL_BUTTONS, GamepadButton, GamepadState, InputManager};
+
+/// A single analog stick axis
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StickAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+}
+
+impl StickAxis {
+    /// Get the value of this axis in the given state, from -1.0 to 1.0
+    pub fn value(self, state: &GamepadState) -> f32 {
+        let raw = match self {
+            StickAxis::LeftX => { state.left_stick_x },
+            StickAxis::LeftY => { state.left_stick_y },
+            StickAxis::RightX => { state.right_stick_x },
+            StickAxis::RightY => { state.right_stick_y },
+        };
+
+        return (raw as f32 / i16::MAX as f32).clamp(-1.0, 1.0);
+    }
+}
+
+/// An input which can trigger an action
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Button(GamepadButton),
+    /// An axis pushed in the positive direction
+    AxisPositive(StickAxis),
+    /// An axis pushed in the negative direction
+    AxisNegative(StickAxis),
+}
+
+impl Binding {
+    /// Get how strongly this binding is activated in the given state, from 0.0 to 1.0
+    pub fn value(self, state: &GamepadState) -> f32 {
+        return match self {
+            Binding::Button(button) => { if state.is_pressed(button) { 1.0 } else { 0.0 } },
+            Binding::AxisPositive(axis) => { axis.value(state).max(0.0) },
+            Binding::AxisNegative(axis) => { (-axis.value(state)).max(0.0) },
+        };
+    }
+}
+
+#[cfg(feature = "serde")]
+mod binding_serde {
+    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::Error};
+
+    use crate::gamepad::ALL_BUTTONS;
+    use super::{Binding, StickAxis};
+
+    // buttons are stored by their bit value
+    #[derive(Serialize, Deserialize)]
+    enum BindingRepr {
+        Button(u16),
+        AxisPositive(StickAxis),
+        AxisNegative(StickAxis),
+    }
+
+    impl Serialize for Binding {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S : Serializer {
+            let repr = match *self {
+                Binding::Button(button) => { BindingRepr::Button(button as u16) },
+                Binding::AxisPositive(axis) => { BindingRepr::AxisPositive(axis) },
+                Binding::AxisNegative(axis) => { BindingRepr::AxisNegative(axis) },
+            };
+            return repr.serialize(serializer);
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Binding {
+        fn deserialize<D>(deserializer: D) -> Result<Binding, D::Error> where D : Deserializer<'de> {
+            return match BindingRepr::deserialize(deserializer)? {
+                BindingRepr::Button(bits) => {
+                    match ALL_BUTTONS.iter().find(|x| **x as u16 == bits) {
+                        Some(button) => { Ok(Binding::Button(*button)) },
+                        None => { Err(D::Error::custom("invalid gamepad button")) }
+                    }
+                },
+                BindingRepr::AxisPositive(axis) => { Ok(Binding::AxisPositive(axis)) },
+                BindingRepr::AxisNegative(axis) => { Ok(Binding::AxisNegative(axis)) },
+            };
+        }
+    }
+}
+
+/// Default value above which an analog binding counts as pressed
+pub const DEFAULT_AXIS_THRESHOLD: f32 = 0.5;
+
+/// Maps named actions (such as "jump" or "menu_up") to the buttons & stick directions which trigger them, so controls can be rebound at runtime <br/>
+/// Use one action map per player slot to give each player their own bindings
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionMap {
+    bindings: HashMap<String, Vec<Binding>>,
+    /// Value above which an analog binding counts as pressed
+    pub axis_threshold: f32,
+}
+
+impl ActionMap {
+    /// Construct a new empty action map
+    pub fn new() -> ActionMap {
+        return ActionMap {
+            bindings: HashMap::new(),
+            axis_threshold: DEFAULT_AXIS_THRESHOLD,
+        };
+    }
+
+    /// Add a binding which triggers the given action
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let list = self.bindings.entry(action.to_string()).or_default();
+        if !list.contains(&binding) {
+            list.push(binding);
+        }
+    }
+
+    /// Replace every binding of the given action with a single new binding
+    pub fn rebind(&mut self, action: &str, binding: Binding) {
+        self.bindings.insert(action.to_string(), vec![binding]);
+    }
+
+    /// Remove a single binding from the given action
+    pub fn unbind(&mut self, action: &str, binding: Binding) {
+        if let Some(list) = self.bindings.get_mut(action) {
+            list.retain(|x| *x != binding);
+        }
+    }
+
+    /// Remove every binding from the given action
+    pub fn clear(&mut self, action: &str) {
+        self.bindings.remove(action);
+    }
+
+    /// Get the bindings of the given action
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        return match self.bindings.get(action) {
+            Some(v) => { v.as_slice() },
+            None => { &[] }
+        };
+    }
+
+    /// Get an iterator over the names of every bound action
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        return self.bindings.keys().map(|x| x.as_str());
+    }
+
+    /// Find the first button pressed this frame, for example while waiting for the player to choose a new binding
+    pub fn detect_button(input: &InputManager) -> Option<Binding> {
+        return ALL_BUTTONS.iter().find(|x| input.just_pressed(**x)).map(|x| Binding::Button(*x));
+    }
+
+    fn state_value(&self, state: &GamepadState, action: &str) -> f32 {
+        return self.bindings(action).iter().map(|x| x.value(state)).fold(0.0, f32::max);
+    }
+
+    /// Get how strongly the given action is activated, from 0.0 to 1.0
+    pub fn value(&self, input: &InputManager, action: &str) -> f32 {
+        return self.state_value(&input.state(), action);
+    }
+
+    /// Check if the given action is currently held
+    pub fn held(&self, input: &InputManager, action: &str) -> bool {
+        return self.state_value(&input.state(), action) > self.axis_threshold;
+    }
+
+    /// Check if the given action was triggered this frame
+    pub fn just_pressed(&self, input: &InputManager, action: &str) -> bool {
+        return self.state_value(&input.state(), action) > self.axis_threshold && self.state_value(&input.previous_state(), action) <= self.axis_threshold;
+    }
+
+    /// Check if the given action was released this frame
+    pub fn just_released(&self, input: &InputManager, action: &str) -> bool {
+        return self.state_value(&input.state(), action) <= self.axis_threshold && self.state_value(&input.previous_state(), action) > self.axis_threshold;
+    }
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        return ActionMap::new();
+    }
+}
@@ -0,0 +1,140 @@
+use crate::gamepad::{ALL_SLOTS, GamepadButton, GamepadSlot, InputManager};
+
+/// Maximum number of local players
+pub const MAX_PLAYERS: usize = 4;
+
+/// Tracks local players and the gamepad slot each one is using <br/>
+/// While joining is enabled, pressing a button on an unassigned, connected gamepad adds a new player for that slot. Players keep their slot when
+/// the gamepad is unplugged, so plugging it back in resumes control
+pub struct Players {
+    inputs: [InputManager;4],
+    players: Vec<GamepadSlot>,
+    joined: Vec<usize>,
+    joining: bool,
+    /// Button which must be pressed to join (None allows any button)
+    pub join_button: Option<GamepadButton>,
+}
+
+impl Players {
+    /// Construct a new player manager with no players & joining enabled
+    pub fn new() -> Players {
+        return Players {
+            inputs: ALL_SLOTS.map(InputManager::new),
+            players: Vec::with_capacity(MAX_PLAYERS),
+            joined: Vec::new(),
+            joining: true,
+            join_button: None,
+        };
+    }
+
+    /// Poll every gamepad slot and add players who pressed the join button (should be called once per frame, before querying input)
+    pub fn update(&mut self) {
+        self.joined.clear();
+
+        for input in &mut self.inputs {
+            input.update();
+        }
+
+        if !self.joining {
+            return;
+        }
+
+        for slot in ALL_SLOTS {
+            if self.player_for_slot(slot).is_some() {
+                continue;
+            }
+
+            let input = &self.inputs[slot as usize];
+            let pressed = match self.join_button {
+                Some(button) => { input.just_pressed(button) },
+                None => { input.any_just_pressed() }
+            };
+
+            if input.is_connected() && pressed {
+                self.players.push(slot);
+                self.joined.push(self.players.len() - 1);
+            }
+        }
+    }
+
+    /// Enable or disable joining new players
+    pub fn set_joining(&mut self, joining: bool) {
+        self.joining = joining;
+    }
+
+    /// Check whether joining new players is enabled
+    pub fn is_joining(&self) -> bool {
+        return self.joining;
+    }
+
+    /// Get the indices of players who joined during the most recent update
+    pub fn joined(&self) -> &[usize] {
+        return &self.joined;
+    }
+
+    /// Manually add a player for the given slot, returning the new player's index (or None if the slot is already assigned)
+    pub fn add(&mut self, slot: GamepadSlot) -> Option<usize> {
+        if self.player_for_slot(slot).is_some() {
+            return None;
+        }
+
+        self.players.push(slot);
+        return Some(self.players.len() - 1);
+    }
+
+    /// Remove the given player. Players after it move down by one index
+    pub fn remove(&mut self, player: usize) {
+        if player < self.players.len() {
+            self.players.remove(player);
+        }
+    }
+
+    /// Remove every player
+    pub fn clear(&mut self) {
+        self.players.clear();
+    }
+
+    /// Get the number of active players
+    pub fn count(&self) -> usize {
+        return self.players.len();
+    }
+
+    /// Get the gamepad slot used by the given player
+    pub fn slot(&self, player: usize) -> Option<GamepadSlot> {
+        return self.players.get(player).copied();
+    }
+
+    /// Get the player using the given gamepad slot
+    pub fn player_for_slot(&self, slot: GamepadSlot) -> Option<usize> {
+        return self.players.iter().position(|x| *x == slot);
+    }
+
+    /// Get the input of the given player
+    pub fn input(&self, player: usize) -> Option<&InputManager> {
+        return self.slot(player).map(|slot| &self.inputs[slot as usize]);
+    }
+
+    /// Get the input of the given player for modification (for example, to change its stick settings)
+    pub fn input_mut(&mut self, player: usize) -> Option<&mut InputManager> {
+        return match self.slot(player) {
+            Some(slot) => { Some(&mut self.inputs[slot as usize]) },
+            None => { None }
+        };
+    }
+
+    /// Get the input of the given gamepad slot, whether or not a player is using it
+    pub fn slot_input(&self, slot: GamepadSlot) -> &InputManager {
+        return &self.inputs[slot as usize];
+    }
+
+    /// Get the indices of players whose gamepad is currently unplugged
+    pub fn disconnected(&self) -> Vec<usize> {
+        return (0..self.players.len()).filter(|x| !self.inputs[self.players[*x] as usize].is_connected()).collect();
+    }
+}
+
+impl Default for Players {
+    fn default() -> Self {
+        return Players::new();
+    }
+}
@@ -0,0 +1,179 @@
+use crate::gamepad::{GamepadButtonMask, GamepadState};
+
+/// Size in bytes of a packed gamepad state
+pub const PACKED_SIZE: usize = 6;
+
+const DELTA_BUTTONS: u8 = 1;
+const DELTA_LEFT_X: u8 = 1 << 1;
+const DELTA_LEFT_Y: u8 = 1 << 2;
+const DELTA_RIGHT_X: u8 = 1 << 3;
+const DELTA_RIGHT_Y: u8 = 1 << 4;
+
+fn quantize(v: i16) -> u8 {
+    return ((v >> 8) as i8) as u8;
+}
+
+fn dequantize(v: u8) -> i16 {
+    return ((v as i8) as i16) << 8;
+}
+
+/// Pack a gamepad state into 6 bytes (2 bytes of buttons followed by each stick axis quantized to 8 bits)
+pub fn pack(state: &GamepadState) -> [u8;PACKED_SIZE] {
+    let buttons = state.button_mask.bits().to_le_bytes();
+    return [
+        buttons[0], buttons[1],
+        quantize(state.left_stick_x), quantize(state.left_stick_y),
+        quantize(state.right_stick_x), quantize(state.right_stick_y),
+    ];
+}
+
+/// Unpack a gamepad state packed with pack
+pub fn unpack(data: &[u8;PACKED_SIZE]) -> GamepadState {
+    return GamepadState {
+        button_mask: GamepadButtonMask::from_bits(u16::from_le_bytes([data[0], data[1]])),
+        left_stick_x: dequantize(data[2]),
+        left_stick_y: dequantize(data[3]),
+        right_stick_x: dequantize(data[4]),
+        right_stick_y: dequantize(data[5]),
+    };
+}
+
+/// Quantize a gamepad state to the precision stored by pack
+pub fn quantized(state: &GamepadState) -> GamepadState {
+    return unpack(&pack(state));
+}
+
+/// Append the difference between two packed states to the given buffer <br/>
+/// This is a flags byte marking which fields changed, followed by the new value of each changed field (so an unchanged frame costs a single byte)
+pub fn encode_delta(prev: &[u8;PACKED_SIZE], cur: &[u8;PACKED_SIZE], out: &mut Vec<u8>) {
+    let mut flags = 0;
+    if prev[0..2] != cur[0..2] { flags |= DELTA_BUTTONS; }
+    if prev[2] != cur[2] { flags |= DELTA_LEFT_X; }
+    if prev[3] != cur[3] { flags |= DELTA_LEFT_Y; }
+    if prev[4] != cur[4] { flags |= DELTA_RIGHT_X; }
+    if prev[5] != cur[5] { flags |= DELTA_RIGHT_Y; }
+
+    out.push(flags);
+    if flags & DELTA_BUTTONS != 0 { out.extend_from_slice(&cur[0..2]); }
+    if flags & DELTA_LEFT_X != 0 { out.push(cur[2]); }
+    if flags & DELTA_LEFT_Y != 0 { out.push(cur[3]); }
+    if flags & DELTA_RIGHT_X != 0 { out.push(cur[4]); }
+    if flags & DELTA_RIGHT_Y != 0 { out.push(cur[5]); }
+}
+
+/// Decode a delta written by encode_delta from the front of the given buffer, advancing past it <br/>
+/// Returns None if the buffer is empty or truncated
+pub fn decode_delta(prev: &[u8;PACKED_SIZE], data: &mut &[u8]) -> Option<[u8;PACKED_SIZE]> {
+    let (flags, mut rest) = data.split_first()?;
+    let mut cur = *prev;
+
+    let mut take = |dst: &mut [u8]| -> Option<()> {
+        if rest.len() < dst.len() {
+            return None;
+        }
+        let (head, tail) = rest.split_at(dst.len());
+        dst.copy_from_slice(head);
+        rest = tail;
+        return Some(());
+    };
+
+    if flags & DELTA_BUTTONS != 0 { take(&mut cur[0..2])?; }
+    if flags & DELTA_LEFT_X != 0 { take(&mut cur[2..3])?; }
+    if flags & DELTA_LEFT_Y != 0 { take(&mut cur[3..4])?; }
+    if flags & DELTA_RIGHT_X != 0 { take(&mut cur[4..5])?; }
+    if flags & DELTA_RIGHT_Y != 0 { take(&mut cur[5..6])?; }
+
+    *data = rest;
+    return Some(cur);
+}
+
+/// A compact delta-encoded recording of gamepad input, one state per frame
+#[derive(Clone, Default)]
+pub struct InputRecording {
+    data: Vec<u8>,
+    frames: usize,
+    last: [u8;PACKED_SIZE],
+}
+
+impl InputRecording {
+    /// Construct a new empty recording
+    pub fn new() -> InputRecording {
+        return InputRecording {
+            data: Vec::new(),
+            frames: 0,
+            last: [0;PACKED_SIZE],
+        };
+    }
+
+    /// Load a recording from bytes previously returned by as_bytes <br/>
+    /// Returns None if the data is truncated
+    pub fn from_bytes(data: &[u8]) -> Option<InputRecording> {
+        let mut frames = 0;
+        let mut last = [0;PACKED_SIZE];
+        let mut cursor = data;
+
+        while !cursor.is_empty() {
+            last = decode_delta(&last, &mut cursor)?;
+            frames += 1;
+        }
+
+        return Some(InputRecording {
+            data: data.to_vec(),
+            frames: frames,
+            last: last,
+        });
+    }
+
+    /// Append a frame of input to the recording
+    pub fn push(&mut self, state: &GamepadState) {
+        let cur = pack(state);
+        encode_delta(&self.last, &cur, &mut self.data);
+        self.last = cur;
+        self.frames += 1;
+    }
+
+    /// Get the number of frames in the recording
+    pub fn len(&self) -> usize {
+        return self.frames;
+    }
+
+    /// Gets whether the recording contains no frames
+    pub fn is_empty(&self) -> bool {
+        return self.frames == 0;
+    }
+
+    /// Get the encoded recording (for example, to write it to a save file)
+    pub fn as_bytes(&self) -> &[u8] {
+        return &self.data;
+    }
+
+    /// Remove every frame from the recording
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.frames = 0;
+        self.last = [0;PACKED_SIZE];
+    }
+
+    /// Get an iterator over each recorded frame, which can be fed to InputManager::update_with to play the recording back
+    pub fn iter(&self) -> InputPlayback<'_> {
+        return InputPlayback {
+            data: &self.data,
+            last: [0;PACKED_SIZE],
+        };
+    }
+}
+
+/// Iterator over the frames of an InputRecording
+pub struct InputPlayback<'a> {
+    data: &'a [u8],
+    last: [u8;PACKED_SIZE],
+}
+
+impl Iterator for InputPlayback<'_> {
+    type Item = GamepadState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.last = decode_delta(&self.last, &mut self.data)?;
+        return Some(unpack(&self.last));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::gamepad::{Gamepad, GamepadSlot};
+
+/// A timed vibration pattern
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RumblePattern {
+    /// Vibrate at a fixed intensity (from 0 to 1) for the given duration in seconds
+    Constant { intensity: f32, duration: f32 },
+    /// Alternate between on & off for the given number of pulses, with on & off times in seconds
+    Pulses { on: f32, off: f32, count: u32 },
+    /// Start at the given intensity (from 0 to 1) and fade out linearly over the given duration in seconds
+    Decay { intensity: f32, duration: f32 },
+}
+
+impl RumblePattern {
+    /// Get the length of one iteration of this pattern in seconds
+    pub fn duration(&self) -> f32 {
+        return match *self {
+            RumblePattern::Constant { duration, .. } => { duration },
+            RumblePattern::Pulses { on, off, count } => { (on + off) * count as f32 },
+            RumblePattern::Decay { duration, .. } => { duration },
+        };
+    }
+
+    /// Get the intensity of this pattern (from 0 to 1) at the given time in seconds
+    pub fn intensity_at(&self, time: f32) -> f32 {
+        if time < 0.0 || time >= self.duration() {
+            return 0.0;
+        }
+
+        return match *self {
+            RumblePattern::Constant { intensity, .. } => { intensity },
+            RumblePattern::Pulses { on, off, .. } => {
+                if time % (on + off) < on { 1.0 } else { 0.0 }
+            },
+            RumblePattern::Decay { intensity, duration } => { intensity * (1.0 - (time / duration)) },
+        }.clamp(0.0, 1.0);
+    }
+}
+
+/// Handle to a rumble pattern playing on a RumbleManager
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RumbleHandle(u32);
+
+struct ActiveRumble {
+    handle: RumbleHandle,
+    pattern: RumblePattern,
+    priority: u8,
+    looping: bool,
+    time: f32,
+}
+
+/// Plays timed rumble patterns on a gamepad, mixing them down to the hardware's single on/off vibration state each tick <br/>
+/// The highest priority pattern currently playing takes control of the motor (with ties going to the strongest pattern). Intensities below 1 are
+/// approximated by rapidly switching the motor on & off
+pub struct RumbleManager {
+    pub gamepad: Gamepad,
+    active: Vec<ActiveRumble>,
+    next_handle: u32,
+    accum: f32,
+    enabled: bool,
+    motor: bool,
+}
+
+impl RumbleManager {
+    /// Construct a new rumble manager for the given slot
+    pub fn new(slot: GamepadSlot) -> RumbleManager {
+        return RumbleManager {
+            gamepad: Gamepad::new(slot),
+            active: Vec::new(),
+            next_handle: 0,
+            accum: 0.0,
+            enabled: true,
+            motor: false,
+        };
+    }
+
+    /// Start playing a rumble pattern with the given priority (higher priorities override lower ones)
+    pub fn play(&mut self, pattern: RumblePattern, priority: u8, looping: bool) -> RumbleHandle {
+        let handle = RumbleHandle(self.next_handle);
+        self.next_handle = self.next_handle.wrapping_add(1);
+
+        self.active.push(ActiveRumble {
+            handle: handle,
+            pattern: pattern,
+            priority: priority,
+            looping: looping,
+            time: 0.0,
+        });
+
+        return handle;
+    }
+
+    /// Stop a playing rumble pattern
+    pub fn stop(&mut self, handle: RumbleHandle) {
+        self.active.retain(|x| x.handle != handle);
+    }
+
+    /// Stop every playing rumble pattern
+    pub fn stop_all(&mut self) {
+        self.active.clear();
+    }
+
+    /// Check if the given rumble pattern is still playing
+    pub fn is_playing(&self, handle: RumbleHandle) -> bool {
+        return self.active.iter().any(|x| x.handle == handle);
+    }
+
+    /// Enable or disable vibration entirely (for example, from an options menu). Patterns keep advancing while disabled
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Check whether vibration is enabled
+    pub fn is_enabled(&self) -> bool {
+        return self.enabled;
+    }
+
+    /// Get the intensity (from 0 to 1) currently being output
+    pub fn intensity(&self) -> f32 {
+        let mut best: Option<(u8, f32)> = None;
+        for rumble in &self.active {
+            let intensity = rumble.pattern.intensity_at(rumble.time);
+            best = match best {
+                Some((p, i)) if p > rumble.priority || (p == rumble.priority && i >= intensity) => { Some((p, i)) },
+                _ => { Some((rumble.priority, intensity)) }
+            };
+        }
+
+        return match best {
+            Some((_, i)) => { i },
+            None => { 0.0 }
+        };
+    }
+
+    /// Advance playing patterns by the given time in seconds and update the gamepad's vibration state (should be called once per frame)
+    pub fn update(&mut self, delta: f32) {
+        for rumble in &mut self.active {
+            rumble.time += delta;
+
+            let duration = rumble.pattern.duration();
+            if rumble.looping && duration > 0.0 {
+                rumble.time %= duration;
+            }
+        }
+
+        self.active.retain(|x| x.looping || x.time < x.pattern.duration());
+
+        // accumulate intensity so that partial intensities switch the motor on for a matching fraction of ticks
+        let intensity = if self.enabled { self.intensity() } else { 0.0 };
+        self.accum += intensity;
+        let motor = if self.accum >= 1.0 {
+            self.accum -= 1.0;
+            true
+        } else {
+            false
+        };
+
+        if intensity <= 0.0 {
+            self.accum = 0.0;
+        }
+
+        if motor != self.motor {
+            self.gamepad.set_rumble(motor);
+            self.motor = motor;
+        }
+    }
+}
+
+impl Drop for RumbleManager {
+    fn drop(&mut self) {
+        if self.motor {
+            self.gamepad.set_rumble(false);
+        }
+    }
+}
@@ -0,0 +1,945 @@
+use std::{collections::HashMap, io::Read, sync::Arc};
+
+use half::f16;
+use serde::Deserialize;
+
+use crate::{anim::{AnimationCurve, BINDING_ROTATION, BINDING_SCALE, BINDING_TRANSLATION, ChannelKey, DBAnimationClip, Lerp}, io::{self, BufferedStream, FileMode, FileStream, IOError}, log_debug, math::{Matrix4x4, Quaternion, Vector3, Vector4, bounds::Aabb}, mesh::{DBMaterialInfo, DBMesh, DBMeshPart, DBMeshVertex, DBSkelNode, DBSkeleton}, vdp::Texture};
+
+const GLB_VER: u32 = 2;
+
+const COMPONENT_I8: u32 = 5120;
+const COMPONENT_U8: u32 = 5121;
+const COMPONENT_I16: u32 = 5122;
+const COMPONENT_U16: u32 = 5123;
+const COMPONENT_U32: u32 = 5125;
+const COMPONENT_F32: u32 = 5126;
+
+const MODE_TRIANGLES: u32 = 4;
+
+/// Maximum number of joints in a skin (bone indices are stored as u8)
+pub const MAX_JOINTS: usize = 256;
+
+/// Enumeration of errors which can result from importing a glTF file
+#[derive(Debug)]
+pub enum GltfError {
+    ParseError,
+    VersionError,
+    /// The file uses a glTF feature outside of the supported subset
+    Unsupported,
+    JsonError(serde_json::Error),
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for GltfError {
+    fn from(value: std::io::Error) -> Self {
+        return GltfError::IOError(value);
+    }
+}
+
+impl From<IOError> for GltfError {
+    fn from(value: IOError) -> Self {
+        return GltfError::IOError(value.into());
+    }
+}
+
+impl From<serde_json::Error> for GltfError {
+    fn from(value: serde_json::Error) -> Self {
+        return GltfError::JsonError(value);
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Document {
+    scene: Option<usize>,
+    scenes: Vec<Scene>,
+    nodes: Vec<Node>,
+    meshes: Vec<Mesh>,
+    accessors: Vec<Accessor>,
+    buffer_views: Vec<BufferView>,
+    buffers: Vec<Buffer>,
+    materials: Vec<Material>,
+    textures: Vec<TextureRef>,
+    images: Vec<Image>,
+    skins: Vec<Skin>,
+    animations: Vec<Animation>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Scene {
+    nodes: Vec<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Node {
+    name: Option<String>,
+    children: Vec<usize>,
+    mesh: Option<usize>,
+    skin: Option<usize>,
+    matrix: Option<[f32;16]>,
+    translation: Option<[f32;3]>,
+    rotation: Option<[f32;4]>,
+    scale: Option<[f32;3]>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Mesh {
+    name: Option<String>,
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    mode: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    byte_offset: usize,
+    component_type: u32,
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+    sparse: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Buffer {
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Material {
+    name: Option<String>,
+    pbr_metallic_roughness: Option<PbrMetallicRoughness>,
+    alpha_mode: Option<String>,
+    double_sided: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct PbrMetallicRoughness {
+    base_color_factor: Option<[f32;4]>,
+    base_color_texture: Option<TextureInfo>,
+    roughness_factor: Option<f32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct TextureInfo {
+    index: usize,
+    tex_coord: usize,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TextureRef {
+    source: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Image {
+    uri: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Skin {
+    inverse_bind_matrices: Option<usize>,
+    joints: Vec<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Animation {
+    name: Option<String>,
+    channels: Vec<Channel>,
+    samplers: Vec<Sampler>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Channel {
+    sampler: usize,
+    target: Target,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Target {
+    node: Option<usize>,
+    path: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Sampler {
+    input: usize,
+    output: usize,
+    interpolation: Option<String>,
+}
+
+fn component_size(component_type: u32) -> Result<usize,GltfError> {
+    return match component_type {
+        COMPONENT_I8 | COMPONENT_U8 => { Ok(1) },
+        COMPONENT_I16 | COMPONENT_U16 => { Ok(2) },
+        COMPONENT_U32 | COMPONENT_F32 => { Ok(4) },
+        _ => { Err(GltfError::ParseError) }
+    };
+}
+
+fn component_count(kind: &str) -> Result<usize,GltfError> {
+    return match kind {
+        "SCALAR" => { Ok(1) },
+        "VEC2" => { Ok(2) },
+        "VEC3" => { Ok(3) },
+        "VEC4" => { Ok(4) },
+        "MAT4" => { Ok(16) },
+        "MAT2" | "MAT3" => { Err(GltfError::Unsupported) },
+        _ => { Err(GltfError::ParseError) }
+    };
+}
+
+fn read_component_f32(bytes: &[u8], component_type: u32, normalized: bool) -> f32 {
+    return match component_type {
+        COMPONENT_I8 => {
+            let v = bytes[0] as i8 as f32;
+            if normalized { (v / 127.0).max(-1.0) } else { v }
+        },
+        COMPONENT_U8 => {
+            let v = bytes[0] as f32;
+            if normalized { v / 255.0 } else { v }
+        },
+        COMPONENT_I16 => {
+            let v = i16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized { (v / 32767.0).max(-1.0) } else { v }
+        },
+        COMPONENT_U16 => {
+            let v = u16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized { v / 65535.0 } else { v }
+        },
+        COMPONENT_U32 => {
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32
+        },
+        _ => {
+            f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        }
+    };
+}
+
+fn read_component_u32(bytes: &[u8], component_type: u32) -> u32 {
+    return match component_type {
+        COMPONENT_I8 | COMPONENT_U8 => { bytes[0] as u32 },
+        COMPONENT_I16 | COMPONENT_U16 => { u16::from_le_bytes([bytes[0], bytes[1]]) as u32 },
+        COMPONENT_F32 => { f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u32 },
+        _ => { u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) }
+    };
+}
+
+/// Convert a column-major glTF matrix into a row-major Matrix4x4
+fn matrix_from_gltf(m: &[f32]) -> Matrix4x4 {
+    let mut mat = Matrix4x4::identity();
+    for (i, v) in m.iter().take(16).enumerate() {
+        mat.m[i / 4][i % 4] = *v;
+    }
+    return mat;
+}
+
+impl Node {
+    fn translation(&self) -> Vector3 {
+        let t = self.translation.unwrap_or([0.0, 0.0, 0.0]);
+        return Vector3::new(t[0], t[1], t[2]);
+    }
+
+    fn rotation(&self) -> Quaternion {
+        let r = self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        return Quaternion::new(r[0], r[1], r[2], r[3]);
+    }
+
+    fn scale(&self) -> Vector3 {
+        let s = self.scale.unwrap_or([1.0, 1.0, 1.0]);
+        return Vector3::new(s[0], s[1], s[2]);
+    }
+
+    fn local_matrix(&self) -> Matrix4x4 {
+        if let Some(m) = &self.matrix {
+            return matrix_from_gltf(m);
+        }
+        return Matrix4x4::scale(self.scale()) * Matrix4x4::rotation(self.rotation()) * Matrix4x4::translation(self.translation());
+    }
+}
+
+struct Importer<'a> {
+    doc: &'a Document,
+    buffers: Vec<Vec<u8>>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<'a> Importer<'a> {
+    fn new<BL>(doc: &'a Document, mut bin_chunk: Option<Vec<u8>>, buffer_load_fn: &mut BL) -> Result<Importer<'a>,GltfError>
+        where BL : FnMut(&str) -> Result<Vec<u8>,GltfError>
+    {
+        let mut buffers: Vec<Vec<u8>> = Vec::with_capacity(doc.buffers.len());
+        for (i, buffer) in doc.buffers.iter().enumerate() {
+            let data = match &buffer.uri {
+                Some(uri) => {
+                    if let Some(data_uri) = uri.strip_prefix("data:") {
+                        match data_uri.split_once(',') {
+                            Some((header, payload)) if header.ends_with(";base64") => { io::decode_base64(payload).ok_or(GltfError::ParseError)? },
+                            _ => { return Err(GltfError::Unsupported); }
+                        }
+                    } else {
+                        buffer_load_fn(uri.as_str())?
+                    }
+                },
+                None => {
+                    // the first buffer of a GLB file without a uri refers to the binary chunk
+                    match bin_chunk.take() {
+                        Some(v) if i == 0 => { v },
+                        _ => { return Err(GltfError::ParseError); }
+                    }
+                }
+            };
+
+            if data.len() < buffer.byte_length {
+                return Err(GltfError::ParseError);
+            }
+
+            buffers.push(data);
+        }
+
+        // nodes must form disjoint trees, so nodes with several parents are rejected
+        let mut parents: Vec<Option<usize>> = vec![None;doc.nodes.len()];
+        for (i, node) in doc.nodes.iter().enumerate() {
+            for child in &node.children {
+                match parents.get_mut(*child) {
+                    Some(v) if v.is_none() => { *v = Some(i); },
+                    _ => { return Err(GltfError::ParseError); }
+                };
+            }
+        }
+
+        // reject cycles, which would otherwise loop forever when walking up or down the hierarchy
+        for i in 0..parents.len() {
+            let mut visited = 0;
+            let mut parent = parents[i];
+            while let Some(p) = parent {
+                visited += 1;
+                if visited > parents.len() {
+                    return Err(GltfError::ParseError);
+                }
+                parent = parents[p];
+            }
+        }
+
+        return Ok(Importer { doc: doc, buffers: buffers, parents: parents });
+    }
+
+    fn node(&self, index: usize) -> Result<&'a Node,GltfError> {
+        return self.doc.nodes.get(index).ok_or(GltfError::ParseError);
+    }
+
+    fn world_matrix(&self, index: usize) -> Matrix4x4 {
+        let mut mat = self.doc.nodes[index].local_matrix();
+        let mut parent = self.parents[index];
+        while let Some(p) = parent {
+            mat *= self.doc.nodes[p].local_matrix();
+            parent = self.parents[p];
+        }
+        return mat;
+    }
+
+    /// Read every element of an accessor, calling the given function with the bytes of each component
+    fn read_accessor<T, F>(&self, index: usize, read_fn: F) -> Result<(Vec<T>, usize),GltfError>
+        where T : Copy + Default,
+        F : Fn(&[u8], u32, bool) -> T
+    {
+        let accessor = self.doc.accessors.get(index).ok_or(GltfError::ParseError)?;
+        if accessor.sparse.is_some() {
+            return Err(GltfError::Unsupported);
+        }
+
+        let comp_size = component_size(accessor.component_type)?;
+        let comp_count = component_count(accessor.kind.as_str())?;
+        let elem_size = comp_size * comp_count;
+
+        // accessors without a buffer view are only useful with sparse data, and their count isn't bounded by any buffer
+        let view = match accessor.buffer_view {
+            Some(v) => { self.doc.buffer_views.get(v).ok_or(GltfError::ParseError)? },
+            None => { return Err(GltfError::Unsupported); }
+        };
+
+        let buffer = self.buffers.get(view.buffer).ok_or(GltfError::ParseError)?;
+        let stride = view.byte_stride.unwrap_or(elem_size);
+
+        // a stride shorter than an element would read overlapping elements
+        if stride < elem_size {
+            return Err(GltfError::ParseError);
+        }
+
+        if accessor.count > 0 {
+            let end = stride.checked_mul(accessor.count - 1)
+                .and_then(|x| x.checked_add(accessor.byte_offset))
+                .and_then(|x| x.checked_add(elem_size))
+                .ok_or(GltfError::ParseError)?;
+            let view_end = view.byte_offset.checked_add(view.byte_length).ok_or(GltfError::ParseError)?;
+            if end > view.byte_length || view_end > buffer.len() {
+                return Err(GltfError::ParseError);
+            }
+        }
+
+        let data = &buffer[view.byte_offset + accessor.byte_offset..];
+        let mut values: Vec<T> = Vec::with_capacity(accessor.count.checked_mul(comp_count).ok_or(GltfError::ParseError)?);
+
+        for i in 0..accessor.count {
+            let elem = &data[i * stride..];
+            for c in 0..comp_count {
+                values.push(read_fn(&elem[c * comp_size..], accessor.component_type, accessor.normalized));
+            }
+        }
+
+        return Ok((values, comp_count));
+    }
+
+    fn read_f32(&self, index: usize) -> Result<(Vec<f32>, usize),GltfError> {
+        return self.read_accessor(index, read_component_f32);
+    }
+
+    fn read_u32(&self, index: usize) -> Result<(Vec<u32>, usize),GltfError> {
+        return self.read_accessor(index, |bytes, component_type, _| read_component_u32(bytes, component_type));
+    }
+
+    /// Collect every node in the default scene (or every node, if the file has no scenes)
+    fn scene_nodes(&self) -> Result<Vec<usize>,GltfError> {
+        let scene = match self.doc.scenes.get(self.doc.scene.unwrap_or(0)) {
+            Some(v) => { v },
+            None => { return Ok((0..self.doc.nodes.len()).collect()); }
+        };
+
+        let mut nodes: Vec<usize> = Vec::new();
+        let mut stack: Vec<usize> = scene.nodes.clone();
+        while let Some(n) = stack.pop() {
+            if nodes.contains(&n) {
+                return Err(GltfError::ParseError);
+            }
+            stack.extend(self.node(n)?.children.iter().copied());
+            nodes.push(n);
+        }
+        nodes.sort();
+
+        return Ok(nodes);
+    }
+
+    fn read_skeleton(&self, skin: &Skin) -> Result<DBSkeleton,GltfError> {
+        if skin.joints.len() > MAX_JOINTS {
+            return Err(GltfError::Unsupported);
+        }
+
+        let inv_bind: Vec<Matrix4x4> = match skin.inverse_bind_matrices {
+            Some(v) => {
+                let (data, comps) = self.read_f32(v)?;
+                if comps != 16 || data.len() < skin.joints.len() * 16 {
+                    return Err(GltfError::ParseError);
+                }
+                data.chunks_exact(16).map(matrix_from_gltf).collect()
+            },
+            None => { vec![Matrix4x4::identity();skin.joints.len()] }
+        };
+
+        // joints with no parent joint become roots of the skeleton, with the world transform of their non-joint parents as their rest pose
+        let mut nodes: Vec<DBSkelNode> = Vec::new();
+        for (i, joint) in skin.joints.iter().enumerate() {
+            let parent = self.parents.get(*joint).copied().ok_or(GltfError::ParseError)?;
+            if parent.is_some_and(|p| skin.joints.contains(&p)) {
+                continue;
+            }
+
+            let rest = match parent {
+                Some(p) => { self.world_matrix(p) },
+                None => { Matrix4x4::identity() }
+            };
+
+            nodes.push(self.read_skel_node(skin, &inv_bind, i, rest)?);
+        }
+
+        let bone_names = skin.joints.iter().map(|x| self.doc.nodes[*x].name.clone().unwrap_or_default()).collect();
+
+        return Ok(DBSkeleton { bone_count: skin.joints.len() as u32, nodes: nodes, bone_names: bone_names });
+    }
+
+    fn read_skel_node(&self, skin: &Skin, inv_bind: &[Matrix4x4], joint: usize, rest: Matrix4x4) -> Result<DBSkelNode,GltfError> {
+        let node = self.node(skin.joints[joint])?;
+
+        // joint transforms are stored in animation channels, which cannot be derived from a matrix
+        if node.matrix.is_some() {
+            return Err(GltfError::Unsupported);
+        }
+
+        let mut children: Vec<DBSkelNode> = Vec::new();
+        for child in &node.children {
+            if let Some(idx) = skin.joints.iter().position(|x| x == child) {
+                children.push(self.read_skel_node(skin, inv_bind, idx, Matrix4x4::identity())?);
+            }
+        }
+
+        return Ok(DBSkelNode {
+            bone_index: joint as u8,
+            inv_bind_pose: inv_bind[joint],
+            local_rest_pose: rest,
+            children: children,
+        });
+    }
+
+    fn read_material<TL>(&self, index: Option<usize>, tex_load_fn: &mut TL) -> Result<DBMaterialInfo,GltfError>
+        where TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        let material = match index {
+            Some(v) => { self.doc.materials.get(v).ok_or(GltfError::ParseError)? },
+            None => {
+                return Ok(DBMaterialInfo {
+                    name: String::new(),
+                    texture: None,
+                    blend_enable: false,
+                    enable_cull: true,
+                    diffuse_color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+                    spec_color: Vector3::new(1.0, 1.0, 1.0),
+                    roughness: 1.0,
+                });
+            }
+        };
+
+        let mut diffuse_color = Vector4::new(1.0, 1.0, 1.0, 1.0);
+        let mut roughness = 1.0;
+        let mut texture: Option<Arc<Texture>> = None;
+
+        if let Some(pbr) = &material.pbr_metallic_roughness {
+            if let Some(c) = pbr.base_color_factor {
+                diffuse_color = Vector4::new(c[0], c[1], c[2], c[3]);
+            }
+
+            roughness = pbr.roughness_factor.unwrap_or(1.0);
+
+            if let Some(info) = &pbr.base_color_texture {
+                if info.tex_coord != 0 {
+                    return Err(GltfError::Unsupported);
+                }
+
+                let image = self.doc.textures.get(info.index)
+                    .and_then(|x| x.source)
+                    .and_then(|x| self.doc.images.get(x))
+                    .ok_or(GltfError::ParseError)?;
+
+                if let Some(name) = image.uri.as_ref().or(image.name.as_ref()) {
+                    texture = tex_load_fn(name.as_str()).ok();
+                }
+            }
+        }
+
+        return Ok(DBMaterialInfo {
+            name: material.name.clone().unwrap_or_default(),
+            texture: texture,
+            blend_enable: material.alpha_mode.as_deref() == Some("BLEND"),
+            enable_cull: !material.double_sided,
+            diffuse_color: diffuse_color,
+            spec_color: Vector3::new(1.0, 1.0, 1.0),
+            roughness: roughness,
+        });
+    }
+
+    fn read_primitive(&self, primitive: &Primitive, skinned: bool) -> Result<Vec<DBMeshVertex>,GltfError> {
+        if primitive.mode.unwrap_or(MODE_TRIANGLES) != MODE_TRIANGLES {
+            return Err(GltfError::Unsupported);
+        }
+
+        let attrib = |name: &str| primitive.attributes.get(name).copied();
+
+        let (positions, pos_comps) = self.read_f32(attrib("POSITION").ok_or(GltfError::ParseError)?)?;
+        if pos_comps != 3 {
+            return Err(GltfError::ParseError);
+        }
+        let vtx_count = positions.len() / 3;
+
+        let normals = match attrib("NORMAL") {
+            Some(v) => { Some(self.read_f32(v)?) },
+            None => { None }
+        };
+        let texcoords = match attrib("TEXCOORD_0") {
+            Some(v) => { Some(self.read_f32(v)?) },
+            None => { None }
+        };
+        let colors = match attrib("COLOR_0") {
+            Some(v) => { Some(self.read_f32(v)?) },
+            None => { None }
+        };
+        let skin = match (attrib("JOINTS_0"), attrib("WEIGHTS_0")) {
+            (Some(j), Some(w)) if skinned => { Some((self.read_u32(j)?, self.read_f32(w)?)) },
+            _ => { None }
+        };
+
+        // indices are only checked against the position count, so every other attribute needs at least as many elements
+        let short = |len: usize, comps: usize| len < vtx_count * comps;
+        if normals.as_ref().is_some_and(|(data, comps)| short(data.len(), *comps))
+            || texcoords.as_ref().is_some_and(|(data, comps)| short(data.len(), *comps))
+            || colors.as_ref().is_some_and(|(data, comps)| short(data.len(), *comps))
+            || skin.as_ref().is_some_and(|((joints, joint_comps), (weights, weight_comps))| short(joints.len(), *joint_comps) || short(weights.len(), *weight_comps))
+        {
+            return Err(GltfError::ParseError);
+        }
+
+        let indices: Vec<u32> = match primitive.indices {
+            Some(v) => { self.read_u32(v)?.0 },
+            None => { (0..vtx_count as u32).collect() }
+        };
+
+        if !indices.len().is_multiple_of(3) {
+            return Err(GltfError::ParseError);
+        }
+
+        let mut vertices: Vec<DBMeshVertex> = Vec::with_capacity(indices.len());
+        for tri in indices.chunks_exact(3) {
+            if tri.iter().any(|x| *x as usize >= vtx_count) {
+                return Err(GltfError::ParseError);
+            }
+
+            let pos = |i: usize| Vector3::new(positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]);
+
+            // meshes without normals are given flat normals
+            let face_nrm = Vector3::cross(&(pos(tri[1] as usize) - pos(tri[0] as usize)), &(pos(tri[2] as usize) - pos(tri[0] as usize))).normalized();
+
+            for idx in tri {
+                let i = *idx as usize;
+                let p = pos(i);
+
+                let nrm = match &normals {
+                    Some((data, 3)) => { Vector3::new(data[i * 3], data[i * 3 + 1], data[i * 3 + 2]) },
+                    _ => { face_nrm }
+                };
+
+                let tex = match &texcoords {
+                    Some((data, 2)) => { [data[i * 2], data[i * 2 + 1]] },
+                    _ => { [0.0, 0.0] }
+                };
+
+                let col = match &colors {
+                    Some((data, 3)) => { [data[i * 3], data[i * 3 + 1], data[i * 3 + 2], 1.0] },
+                    Some((data, 4)) => { [data[i * 4], data[i * 4 + 1], data[i * 4 + 2], data[i * 4 + 3]] },
+                    _ => { [1.0, 1.0, 1.0, 1.0] }
+                };
+
+                let (bweight, bidx) = match &skin {
+                    Some(((joints, 4), (weights, 4))) => { top_two_weights(&joints[i * 4..i * 4 + 4], &weights[i * 4..i * 4 + 4]) },
+                    _ => { ([255, 0], [0, 0]) }
+                };
+
+                vertices.push(DBMeshVertex {
+                    pos: [f16::from_f32(p.x), f16::from_f32(p.y), f16::from_f32(p.z)],
+                    nrm: [f16::from_f32(nrm.x), f16::from_f32(nrm.y), f16::from_f32(nrm.z)],
+                    col: col.map(|x| (x.clamp(0.0, 1.0) * 255.0).round() as u8),
+                    tex: [f16::from_f32(tex[0]), f16::from_f32(tex[1])],
+                    bweight: bweight,
+                    bidx: bidx,
+                });
+            }
+        }
+
+        return Ok(vertices);
+    }
+
+    fn read_animation(&self, animation: &Animation, skin: &Skin, rest_pose: &DBAnimationClip) -> Result<DBAnimationClip,GltfError> {
+        let mut clip = DBAnimationClip::default();
+
+        for channel in &animation.channels {
+            // only joint transforms can be animated
+            let bone = match channel.target.node.and_then(|n| skin.joints.iter().position(|x| *x == n)) {
+                Some(v) => { v as u32 },
+                None => { continue; }
+            };
+
+            let binding = match channel.target.path.as_str() {
+                "translation" => { BINDING_TRANSLATION },
+                "rotation" => { BINDING_ROTATION },
+                "scale" => { BINDING_SCALE },
+                _ => { continue; }
+            };
+
+            let sampler = animation.samplers.get(channel.sampler).ok_or(GltfError::ParseError)?;
+            let (times, _) = self.read_f32(sampler.input)?;
+            let (values, comps) = self.read_f32(sampler.output)?;
+
+            let interpolation = sampler.interpolation.as_deref().unwrap_or("LINEAR");
+            let step = interpolation == "STEP";
+
+            // cubic spline samplers store an in-tangent, value & out-tangent per keyframe. Only the values are kept, interpolated linearly
+            let (stride, offset) = if interpolation == "CUBICSPLINE" { (comps * 3, comps) } else { (comps, 0) };
+            if values.len() < times.len() * stride {
+                return Err(GltfError::ParseError);
+            }
+
+            let key = ChannelKey::new(bone, binding);
+            if binding == BINDING_ROTATION {
+                if comps != 4 {
+                    return Err(GltfError::ParseError);
+                }
+                let curve = build_curve(&times, step, |i| {
+                    let v = &values[i * stride + offset..];
+                    return Quaternion::new(v[0], v[1], v[2], v[3]);
+                });
+                clip.channels_quat.insert(key, curve);
+            } else {
+                if comps != 3 {
+                    return Err(GltfError::ParseError);
+                }
+                let curve = build_curve(&times, step, |i| {
+                    let v = &values[i * stride + offset..];
+                    return Vector3::new(v[0], v[1], v[2]);
+                });
+                clip.channels_vec3.insert(key, curve);
+            }
+        }
+
+        // joint transforms are absolute rather than relative to the rest pose, so every joint needs every channel
+        for (key, curve) in &rest_pose.channels_vec3 {
+            if !clip.channels_vec3.contains_key(key) {
+                clip.channels_vec3.insert(*key, copy_curve(curve));
+            }
+        }
+        for (key, curve) in &rest_pose.channels_quat {
+            if !clip.channels_quat.contains_key(key) {
+                clip.channels_quat.insert(*key, copy_curve(curve));
+            }
+        }
+
+        clip.recalculate_duration();
+        return Ok(clip);
+    }
+}
+
+/// Pick the two most influential joints, renormalizing their weights to sum to 255
+fn top_two_weights(joints: &[u32], weights: &[f32]) -> ([u8;2], [u8;2]) {
+    let mut order: [usize;4] = [0, 1, 2, 3];
+    order.sort_by(|a, b| weights[*b].total_cmp(&weights[*a]));
+
+    let w0 = weights[order[0]].max(0.0);
+    let w1 = weights[order[1]].max(0.0);
+    let total = w0 + w1;
+    if total <= 0.0 {
+        return ([255, 0], [joints[order[0]] as u8, 0]);
+    }
+
+    let b0 = (w0 / total * 255.0).round() as u8;
+    return ([b0, 255 - b0], [joints[order[0]] as u8, joints[order[1]] as u8]);
+}
+
+fn build_curve<T, F>(times: &[f32], step: bool, value_fn: F) -> AnimationCurve<T>
+    where T : Clone + Copy + Lerp<T>,
+    F : Fn(usize) -> T
+{
+    let mut curve: AnimationCurve<T> = AnimationCurve::new();
+    for (i, time) in times.iter().enumerate() {
+        // step interpolation holds each value until the next keyframe
+        if step && i > 0 {
+            curve.insert_keyframe(value_fn(i - 1), *time);
+        }
+        curve.insert_keyframe(value_fn(i), *time);
+    }
+    return curve;
+}
+
+fn copy_curve<T>(curve: &AnimationCurve<T>) -> AnimationCurve<T> where T : Clone + Copy + Lerp<T> {
+    let mut copy: AnimationCurve<T> = AnimationCurve::new();
+    for key in curve.keyframes() {
+        copy.insert_keyframe(key.value, key.time);
+    }
+    return copy;
+}
+
+/// A mesh, skeleton & set of animation clips imported from a glTF 2.0 file <br/>
+/// Only a subset of glTF is supported: triangle list primitives with a single UV set, a single skin, and translation/rotation/scale animation of joints.
+/// Animated joint transforms are stored in each clip rather than in the skeleton's rest pose, so skinned meshes should always be posed with a clip (see rest_pose)
+pub struct GltfAsset {
+    pub mesh: DBMesh,
+    /// A single-frame clip holding the rest transform of every joint
+    pub rest_pose: DBAnimationClip,
+    /// Animation clips, by name (unnamed animations are named by their index)
+    pub animations: HashMap<String, DBAnimationClip>,
+}
+
+impl GltfAsset {
+    /// Import a .gltf or .glb file from the given path <br/>
+    /// External buffers are loaded relative to the file, and tex_load_fn is called with the uri (or name) of each material's base color image
+    pub fn load<TL>(path: &str, tex_load_fn: TL) -> Result<GltfAsset,GltfError>
+        where TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        let mut data: Vec<u8> = Vec::new();
+        BufferedStream::new(FileStream::open(path, FileMode::Read)?).read_to_end(&mut data)?;
+
+        let dir = match path.rfind('/') {
+            Some(i) => { &path[..i + 1] },
+            None => { "" }
+        };
+
+        return GltfAsset::from_bytes(&data, |uri| {
+            let mut buffer: Vec<u8> = Vec::new();
+            BufferedStream::new(FileStream::open(format!("{}{}", dir, uri).as_str(), FileMode::Read)?).read_to_end(&mut buffer)?;
+            return Ok(buffer);
+        }, tex_load_fn);
+    }
+
+    /// Import a glTF file from the given bytes (either JSON or binary GLB) <br/>
+    /// buffer_load_fn is called with the uri of each external buffer to load its contents, and tex_load_fn is called with the uri (or name) of each material's base color image
+    pub fn from_bytes<BL,TL>(data: &[u8], mut buffer_load_fn: BL, mut tex_load_fn: TL) -> Result<GltfAsset,GltfError>
+        where BL : FnMut(&str) -> Result<Vec<u8>,GltfError>,
+        TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        let (json, bin_chunk) = if data.starts_with(b"glTF") {
+            read_glb(data)?
+        } else {
+            (data, None)
+        };
+
+        let doc: Document = serde_json::from_slice(json)?;
+        let importer = Importer::new(&doc, bin_chunk, &mut buffer_load_fn)?;
+
+        let no_skin = Skin::default();
+        let skin = doc.skins.first().unwrap_or(&no_skin);
+
+        let mut mesh = DBMesh {
+            mesh_parts: Vec::new(),
+            skeleton: None,
+        };
+
+        if !skin.joints.is_empty() {
+            let skeleton = importer.read_skeleton(skin)?;
+            log_debug!("Parsed skeleton (bone count: {})", skeleton.bone_count);
+            mesh.skeleton = Some(skeleton);
+        }
+
+        for n in importer.scene_nodes()? {
+            let node = importer.node(n)?;
+            let src_mesh = match node.mesh {
+                Some(v) => { doc.meshes.get(v).ok_or(GltfError::ParseError)? },
+                None => { continue; }
+            };
+
+            // skinned meshes ignore their node transform, as joints are already in world space
+            let skinned = match node.skin {
+                Some(0) => { true },
+                Some(_) => { return Err(GltfError::Unsupported); },
+                None => { false }
+            };
+
+            let transform = if skinned { Matrix4x4::identity() } else { importer.world_matrix(n) };
+            let name = node.name.as_ref().or(src_mesh.name.as_ref()).cloned().unwrap_or_default();
+
+            for primitive in &src_mesh.primitives {
+                let mut mesh_part = DBMeshPart {
+                    name: name.clone(),
+                    transform: transform,
+                    material: importer.read_material(primitive.material, &mut tex_load_fn)?,
+                    vertices: importer.read_primitive(primitive, skinned)?,
+                    bounds: Aabb::EMPTY,
+                    morph_targets: Vec::new(),
+                    lods: Vec::new(),
+                };
+                mesh_part.recalculate_bounds();
+                log_debug!("Parsed mesh part (name: {}, material: {}, vertex count: {})", mesh_part.name, mesh_part.material.name, mesh_part.vertices.len());
+                mesh.mesh_parts.push(mesh_part);
+            }
+        }
+
+        let mut rest_pose = DBAnimationClip::default();
+        for (i, joint) in skin.joints.iter().enumerate() {
+            let node = importer.node(*joint)?;
+
+            let mut translation: AnimationCurve<Vector3> = AnimationCurve::new();
+            translation.insert_keyframe(node.translation(), 0.0);
+            rest_pose.channels_vec3.insert(ChannelKey::new(i as u32, BINDING_TRANSLATION), translation);
+
+            let mut rotation: AnimationCurve<Quaternion> = AnimationCurve::new();
+            rotation.insert_keyframe(node.rotation(), 0.0);
+            rest_pose.channels_quat.insert(ChannelKey::new(i as u32, BINDING_ROTATION), rotation);
+
+            let mut scale: AnimationCurve<Vector3> = AnimationCurve::new();
+            scale.insert_keyframe(node.scale(), 0.0);
+            rest_pose.channels_vec3.insert(ChannelKey::new(i as u32, BINDING_SCALE), scale);
+        }
+
+        let mut animations: HashMap<String, DBAnimationClip> = HashMap::new();
+        for (i, animation) in doc.animations.iter().enumerate() {
+            let clip = importer.read_animation(animation, skin, &rest_pose)?;
+            let name = animation.name.clone().unwrap_or_else(|| i.to_string());
+            log_debug!("Animation clip loaded (name: {}, duration: {}s, channels: {})", name, clip.duration(), clip.channel_count());
+            animations.insert(name, clip);
+        }
+
+        return Ok(GltfAsset {
+            mesh: mesh,
+            rest_pose: rest_pose,
+            animations: animations,
+        });
+    }
+}
+
+fn read_glb(data: &[u8]) -> Result<(&[u8], Option<Vec<u8>>),GltfError> {
+    let read_u32 = |offset: usize| -> Result<u32,GltfError> {
+        let bytes = data.get(offset..offset + 4).ok_or(GltfError::ParseError)?;
+        return Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+    };
+
+    if read_u32(4)? != GLB_VER {
+        return Err(GltfError::VersionError);
+    }
+
+    let length = (read_u32(8)? as usize).min(data.len());
+
+    let mut json: Option<&[u8]> = None;
+    let mut bin: Option<Vec<u8>> = None;
+
+    // scan chunks
+    let mut offset = 12;
+    while offset + 8 <= length {
+        let chunk_size = read_u32(offset)? as usize;
+        let chunk_id = &data[offset + 4..offset + 8];
+        let chunk_end = (offset + 8).checked_add(chunk_size).ok_or(GltfError::ParseError)?;
+        let chunk_data = data.get(offset + 8..chunk_end).ok_or(GltfError::ParseError)?;
+
+        match chunk_id {
+            b"JSON" => { json = Some(chunk_data); },
+            b"BIN\0" => { bin = Some(chunk_data.to_vec()); },
+            _ => {
+                // unknown chunk ID, skip
+            }
+        };
+
+        offset = chunk_end;
+    }
+
+    return match json {
+        Some(v) => { Ok((v, bin)) },
+        None => { Err(GltfError::ParseError) }
+    };
+}
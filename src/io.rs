@@ -1,7 +1,14 @@
-use std::{ffi::{CString, CStr}, convert::TryInto};
+use std::{ffi::{CString, CStr}, convert::TryInto, io::{BufRead, Read, Seek, SeekFrom}};
 
 use crate::{db_internal::{fs_close, fs_open, fs_read, fs_write, fs_seek, fs_tell, fs_eof, fs_deviceExists, fs_deviceEject, fs_fileExists, fs_closeDir, fs_openDir, fs_readDir, clock_timestampToDatetime, fs_rewindDir, fs_allocMemoryCard}, clock::DateTime};
 
+pub mod loader;
+pub mod archive;
+#[cfg(feature = "dev")]
+pub mod watch;
+
+pub use archive::{Archive, ArchiveFile, ArchiveError};
+
 const ESUCCESS: i32 = 0;
 const EACCESS: i32 = 2;
 const EEXIST: i32 = 20;
@@ -28,7 +35,7 @@ pub enum SeekOrigin {
     End,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum IOError {
     TooManyFilesOpen,
     ReadOnlyFileSystem,
@@ -40,7 +47,111 @@ pub enum IOError {
     FileTooBig,
     FileAlreadyExists,
     NoSpaceOnDevice,
-    ReachedEndOfFile
+    ReachedEndOfFile,
+    PermissionDenied,
+    InvalidData,
+    /// The operation was cancelled before it finished
+    Cancelled,
+    /// An error code the I/O layer doesn't recognize
+    Unknown(i32),
+}
+
+impl IOError {
+    /// Convert a filesystem errno value into an IOError
+    pub fn from_errno(errno: i32) -> IOError {
+        return match errno {
+            EACCESS => { IOError::PermissionDenied },
+            EEXIST => { IOError::FileAlreadyExists },
+            EFBIG => { IOError::FileTooBig },
+            ENFILE => { IOError::TooManyFilesOpen },
+            ENODEV => { IOError::NoSuchDevice },
+            ENOENT => { IOError::FileNotFound },
+            ENOSPC => { IOError::NoSpaceOnDevice },
+            EROFS => { IOError::ReadOnlyFileSystem },
+            ESPIPE => { IOError::InvalidSeek },
+            _ => { IOError::Unknown(errno) }
+        };
+    }
+
+    /// Get the closest matching std::io::ErrorKind
+    pub fn kind(&self) -> std::io::ErrorKind {
+        return match self {
+            IOError::TooManyFilesOpen => { std::io::ErrorKind::Other },
+            IOError::ReadOnlyFileSystem => { std::io::ErrorKind::ReadOnlyFilesystem },
+            IOError::FileNotFound => { std::io::ErrorKind::NotFound },
+            IOError::DirectoryNotFound => { std::io::ErrorKind::NotFound },
+            IOError::NoSuchDevice => { std::io::ErrorKind::NotFound },
+            IOError::NotSupported => { std::io::ErrorKind::Unsupported },
+            IOError::InvalidSeek => { std::io::ErrorKind::BrokenPipe },
+            IOError::FileTooBig => { std::io::ErrorKind::FileTooLarge },
+            IOError::FileAlreadyExists => { std::io::ErrorKind::AlreadyExists },
+            IOError::NoSpaceOnDevice => { std::io::ErrorKind::StorageFull },
+            IOError::ReachedEndOfFile => { std::io::ErrorKind::UnexpectedEof },
+            IOError::PermissionDenied => { std::io::ErrorKind::PermissionDenied },
+            IOError::InvalidData => { std::io::ErrorKind::InvalidData },
+            IOError::Cancelled => { std::io::ErrorKind::Interrupted },
+            IOError::Unknown(_) => { std::io::ErrorKind::Other },
+        };
+    }
+}
+
+impl std::fmt::Display for IOError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            IOError::Unknown(errno) => { write!(f, "Unknown I/O error (errno {})", errno) },
+            _ => { write!(f, "{:?}", self) }
+        };
+    }
+}
+
+impl std::error::Error for IOError {
+}
+
+impl From<IOError> for std::io::Error {
+    fn from(value: IOError) -> Self {
+        return std::io::Error::new(value.kind(), value);
+    }
+}
+
+impl From<std::io::Error> for IOError {
+    fn from(value: std::io::Error) -> Self {
+        // errors which came from the I/O layer in the first place convert back exactly
+        if let Some(inner) = value.get_ref().and_then(|x| x.downcast_ref::<IOError>()) {
+            return *inner;
+        }
+
+        return match value.kind() {
+            std::io::ErrorKind::PermissionDenied => { IOError::PermissionDenied },
+            std::io::ErrorKind::ReadOnlyFilesystem => { IOError::ReadOnlyFileSystem },
+            std::io::ErrorKind::NotFound => { IOError::FileNotFound },
+            std::io::ErrorKind::AlreadyExists => { IOError::FileAlreadyExists },
+            std::io::ErrorKind::StorageFull => { IOError::NoSpaceOnDevice },
+            std::io::ErrorKind::FileTooLarge => { IOError::FileTooBig },
+            std::io::ErrorKind::UnexpectedEof => { IOError::ReachedEndOfFile },
+            std::io::ErrorKind::BrokenPipe => { IOError::InvalidSeek },
+            std::io::ErrorKind::InvalidData => { IOError::InvalidData },
+            std::io::ErrorKind::Interrupted => { IOError::Cancelled },
+            _ => { IOError::NotSupported }
+        };
+    }
+}
+
+// get the error reported by the last filesystem call, if any
+fn last_error() -> Option<IOError> {
+    let errno = unsafe { crate::db_internal::ERRNO };
+    if errno == ESUCCESS {
+        return None;
+    }
+
+    return Some(IOError::from_errno(errno));
+}
+
+// paths containing a null byte can never name a file
+fn path_to_cstring(path: &str) -> Result<CString, IOError> {
+    return match CString::new(path) {
+        Ok(v) => { Ok(v) },
+        Err(_) => { Err(IOError::FileNotFound) }
+    };
 }
 
 pub struct FileStream {
@@ -50,20 +161,14 @@ pub struct FileStream {
 impl std::io::Read for FileStream {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         unsafe {
-            let result = fs_read(self.handle, buf.as_mut_ptr().cast(), buf.len().try_into().unwrap());
+            let len = buf.len().min(i32::MAX as usize) as i32;
+            let result = fs_read(self.handle, buf.as_mut_ptr().cast(), len);
 
-            match crate::db_internal::ERRNO {
-                ESUCCESS => {
-                }
-                EACCESS => {
-                    return Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
-                }
-                _ => {
-                    panic!("Unhandled errno");
-                }
+            if let Some(e) = last_error() {
+                return Err(e.into());
             }
 
-            return Ok(result.try_into().unwrap());
+            return Ok(result.max(0) as usize);
         }
     }
 }
@@ -71,23 +176,14 @@ impl std::io::Read for FileStream {
 impl std::io::Write for FileStream {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         unsafe {
-            let result = fs_write(self.handle, buf.as_ptr().cast(), buf.len().try_into().unwrap());
+            let len = buf.len().min(i32::MAX as usize) as i32;
+            let result = fs_write(self.handle, buf.as_ptr().cast(), len);
 
-            match crate::db_internal::ERRNO {
-                ESUCCESS => {
-                }
-                EACCESS => {
-                    return Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
-                }
-                EFBIG => {
-                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "File size limit reached"));
-                }
-                _ => {
-                    panic!("Unhandled errno");
-                }
+            if let Some(e) = last_error() {
+                return Err(e.into());
             }
 
-            return Ok(result.try_into().unwrap());
+            return Ok(result.max(0) as usize);
         }
     }
 
@@ -95,17 +191,11 @@ impl std::io::Write for FileStream {
         unsafe {
             crate::db_internal::fs_flush(self.handle);
 
-            match crate::db_internal::ERRNO {
-                ESUCCESS => {
-                    return Ok(());
-                },
-                EACCESS => {
-                    return Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
-                },
-                _ => {
-                    panic!("Unhandled errno");
-                }
+            if let Some(e) = last_error() {
+                return Err(e.into());
             }
+
+            return Ok(());
         }
     }
 }
@@ -113,30 +203,26 @@ impl std::io::Write for FileStream {
 impl std::io::Seek for FileStream {
     fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
         unsafe {
-            let result = match pos {
-                std::io::SeekFrom::Start(position) => {
-                    fs_seek(self.handle, position.try_into().unwrap(), SeekOrigin::Begin)
-                },
-                std::io::SeekFrom::Current(position) => {
-                    fs_seek(self.handle, position.try_into().unwrap(), SeekOrigin::Current)
-                },
-                std::io::SeekFrom::End(position) => {
-                    fs_seek(self.handle, position.try_into().unwrap(), SeekOrigin::End)
-                }
+            // offsets which don't fit the filesystem's 32-bit positions can never be valid
+            let (offset, origin) = match pos {
+                std::io::SeekFrom::Start(position) => { (position.try_into().ok(), SeekOrigin::Begin) },
+                std::io::SeekFrom::Current(position) => { (position.try_into().ok(), SeekOrigin::Current) },
+                std::io::SeekFrom::End(position) => { (position.try_into().ok(), SeekOrigin::End) }
             };
 
-            match crate::db_internal::ERRNO {
-                ESUCCESS => {
-                }
-                ESPIPE => {
-                    return Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
-                }
-                _ => {
-                    panic!("Unhandled errno");
-                }
+            let result = match offset {
+                Some(v) => { fs_seek(self.handle, v, origin) },
+                None => { return Err(IOError::InvalidSeek.into()); }
+            };
+
+            if let Some(e) = last_error() {
+                return Err(e.into());
             }
 
-            return Ok(result.try_into().unwrap());
+            return match result.try_into() {
+                Ok(v) => { Ok(v) },
+                Err(_) => { Err(IOError::InvalidSeek.into()) }
+            };
         }
     }
 }
@@ -146,27 +232,11 @@ impl FileStream {
     /// Valid devices are "cd", "ma", and "mb"
     pub fn open(path: &str, mode: FileMode) -> Result<FileStream, IOError> {
         unsafe {
-            let path_cstr = CString::new(path).expect("Failed creating C string");
+            let path_cstr = path_to_cstring(path)?;
             let handle = fs_open(path_cstr.as_ptr(), mode);
 
             if handle == 0 {
-                match crate::db_internal::ERRNO {
-                    ENFILE => {
-                        return Err(IOError::TooManyFilesOpen);
-                    }
-                    ENOENT => {
-                        return Err(IOError::FileNotFound);
-                    }
-                    EROFS => {
-                        return Err(IOError::ReadOnlyFileSystem);
-                    }
-                    ENODEV => {
-                        return Err(IOError::NoSuchDevice);
-                    }
-                    _ => {
-                        panic!("Unhandled errno");
-                    }
-                }
+                return Err(IOError::from_errno(crate::db_internal::ERRNO));
             }
 
             return Ok(FileStream {
@@ -178,24 +248,11 @@ impl FileStream {
     /// Allocate a new file on the memory card device given in the path string of the given size in 512-byte blocks for writing
     pub fn allocate_memory_card(path: &str, icondata: &[u8;128], iconpalette: &[u16;16], blocks: i32) -> Result<FileStream, IOError> {
         unsafe {
-            let path_cstr = CString::new(path).expect("Failed creating C string");
+            let path_cstr = path_to_cstring(path)?;
             let handle = fs_allocMemoryCard(path_cstr.as_ptr(), icondata.as_ptr(), iconpalette.as_ptr(), blocks);
 
             if handle == 0 {
-                match crate::db_internal::ERRNO {
-                    EEXIST => {
-                        return Err(IOError::FileAlreadyExists);
-                    }
-                    ENOSPC => {
-                        return Err(IOError::NoSpaceOnDevice);
-                    }
-                    ENODEV => {
-                        return Err(IOError::NoSuchDevice);
-                    }
-                    _ => {
-                        panic!("Unhandled errno");
-                    }
-                }
+                return Err(IOError::from_errno(crate::db_internal::ERRNO));
             }
 
             return Ok(FileStream {
@@ -217,6 +274,124 @@ impl FileStream {
             return fs_eof(self.handle);
         }
     }
+
+    /// Flush & close the stream, returning any error from the final flush (dropping the stream closes it without reporting errors)
+    pub fn close(mut self) -> Result<(), IOError> {
+        let result = std::io::Write::flush(&mut self);
+        unsafe { fs_close(self.handle); }
+        std::mem::forget(self);
+
+        return result.map_err(IOError::from);
+    }
+}
+
+#[derive(Clone, Copy)]
+struct MemoryCardAlloc {
+    icon: [u8;128],
+    palette: [u16;16],
+    blocks: i32,
+}
+
+/// Options for opening a file, built up with chained setters <br/>
+/// Device capabilities: "cd" is read-only. Memory cards ("ma" and "mb") support reading & writing, but files have a fixed size in 512-byte blocks set when they are created,
+/// so files can't grow past their allocation & truncating a file clears its contents to zero rather than shrinking it. Files can't be opened for reading & writing at the same time
+#[derive(Clone, Copy)]
+pub struct OpenOptions {
+    read: bool,
+    write: bool,
+    append: bool,
+    truncate: bool,
+    create: Option<MemoryCardAlloc>,
+}
+
+impl OpenOptions {
+    /// Construct new options with every option disabled
+    pub fn new() -> OpenOptions {
+        return OpenOptions {
+            read: false,
+            write: false,
+            append: false,
+            truncate: false,
+            create: None,
+        };
+    }
+
+    /// Set whether the file is opened for reading
+    pub fn read(mut self, read: bool) -> OpenOptions {
+        self.read = read;
+        return self;
+    }
+
+    /// Set whether the file is opened for writing
+    pub fn write(mut self, write: bool) -> OpenOptions {
+        self.write = write;
+        return self;
+    }
+
+    /// Set whether writes start at the end of the existing contents of the file <br/>
+    /// Files can't grow past their allocation, so opening with append always fails with NotSupported
+    pub fn append(mut self, append: bool) -> OpenOptions {
+        self.append = append;
+        return self;
+    }
+
+    /// Set whether the existing contents of the file are cleared to zero when opened for writing
+    pub fn truncate(mut self, truncate: bool) -> OpenOptions {
+        self.truncate = truncate;
+        return self;
+    }
+
+    /// Allocate the file on a memory card with the given icon & size in 512-byte blocks if it doesn't exist yet (implies write)
+    pub fn create(mut self, icondata: &[u8;128], iconpalette: &[u16;16], blocks: i32) -> OpenOptions {
+        self.create = Some(MemoryCardAlloc { icon: *icondata, palette: *iconpalette, blocks: blocks });
+        return self;
+    }
+
+    /// Open the file at the given path with these options
+    pub fn open(&self, path: &str) -> Result<FileStream, IOError> {
+        let write = self.write || self.create.is_some();
+
+        // writing at the end of a fixed-size file would always hit EOF
+        if write == self.read || self.append {
+            return Err(IOError::NotSupported);
+        }
+
+        if !write {
+            return FileStream::open(path, FileMode::Read);
+        }
+
+        let mut file = match self.create {
+            Some(alloc) if !file_exists(path) => {
+                FileStream::allocate_memory_card(path, &alloc.icon, &alloc.palette, alloc.blocks)?
+            },
+            _ => {
+                FileStream::open(path, FileMode::Write)?
+            }
+        };
+
+        if self.truncate {
+            let size = file.seek(SeekFrom::End(0))?;
+            file.seek(SeekFrom::Start(0))?;
+
+            let zeroes: [u8;512] = [0;512];
+            let mut remaining = size;
+            while remaining > 0 {
+                let len = remaining.min(zeroes.len() as u64) as usize;
+                std::io::Write::write_all(&mut file, &zeroes[..len])?;
+                remaining -= len as u64;
+            }
+
+            file.seek(SeekFrom::Start(0))?;
+        }
+
+        return Ok(file);
+    }
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        return OpenOptions::new();
+    }
 }
 
 impl Drop for FileStream {
@@ -225,6 +400,294 @@ impl Drop for FileStream {
     }
 }
 
+/// Default size in bytes of the buffer used by BufferedStream
+pub const DEFAULT_BUFFER_SIZE: usize = 4096;
+
+/// Wraps a stream with a read buffer, so that many small reads (such as reading a file header field by field) only result in a few large reads of the underlying stream <br/>
+/// Seeks which land inside of the currently buffered data don't discard the buffer
+pub struct BufferedStream<S> where S : Read {
+    inner: S,
+    buffer: Vec<u8>,
+    pos: usize,
+    filled: usize,
+}
+
+impl<S> BufferedStream<S> where S : Read {
+    /// Wrap the given stream with a buffer of DEFAULT_BUFFER_SIZE bytes
+    pub fn new(inner: S) -> BufferedStream<S> {
+        return BufferedStream::with_capacity(DEFAULT_BUFFER_SIZE, inner);
+    }
+
+    /// Wrap the given stream with a buffer of the given size in bytes
+    pub fn with_capacity(capacity: usize, inner: S) -> BufferedStream<S> {
+        assert!(capacity > 0, "Buffer capacity must be greater than 0");
+
+        return BufferedStream {
+            inner: inner,
+            buffer: vec![0;capacity],
+            pos: 0,
+            filled: 0,
+        };
+    }
+
+    /// Get the size of the buffer in bytes
+    pub fn capacity(&self) -> usize {
+        return self.buffer.len();
+    }
+
+    /// Get a reference to the underlying stream
+    pub fn get_ref(&self) -> &S {
+        return &self.inner;
+    }
+
+    /// Get a mutable reference to the underlying stream (reading from it directly will desync the buffer)
+    pub fn get_mut(&mut self) -> &mut S {
+        return &mut self.inner;
+    }
+
+    /// Unwrap the underlying stream, discarding any buffered data
+    pub fn into_inner(self) -> S {
+        return self.inner;
+    }
+
+    fn discard_buffer(&mut self) {
+        self.pos = 0;
+        self.filled = 0;
+    }
+}
+
+impl<S> Read for BufferedStream<S> where S : Read {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // large reads bypass the buffer entirely
+        if self.pos == self.filled && buf.len() >= self.buffer.len() {
+            self.discard_buffer();
+            return self.inner.read(buf);
+        }
+
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+
+        return Ok(len);
+    }
+}
+
+impl<S> BufRead for BufferedStream<S> where S : Read {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.pos >= self.filled {
+            self.filled = self.inner.read(&mut self.buffer)?;
+            self.pos = 0;
+        }
+
+        return Ok(&self.buffer[self.pos..self.filled]);
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.filled);
+    }
+}
+
+impl<S> Seek for BufferedStream<S> where S : Read + Seek {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        // the underlying stream is positioned at the end of the buffered data
+        let inner_pos = self.inner.stream_position()?;
+        let buffer_start = inner_pos - self.filled as u64;
+        let current = buffer_start + self.pos as u64;
+
+        let target = match pos {
+            SeekFrom::Start(n) => { n as i64 },
+            SeekFrom::Current(n) => { current as i64 + n },
+            SeekFrom::End(n) => {
+                self.discard_buffer();
+                return self.inner.seek(SeekFrom::End(n));
+            }
+        };
+
+        if target < 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+        }
+
+        let target = target as u64;
+        if target >= buffer_start && target <= inner_pos {
+            self.pos = (target - buffer_start) as usize;
+            return Ok(target);
+        }
+
+        self.discard_buffer();
+        return self.inner.seek(SeekFrom::Start(target));
+    }
+}
+
+/// A stream over an in-memory buffer (such as a Vec<u8> or an asset embedded with include_bytes!), usable anywhere a file stream is <br/>
+/// Streams over a Vec<u8> can also be written to, growing the buffer as needed
+pub struct MemoryStream<T> where T : AsRef<[u8]> {
+    data: T,
+    position: u64,
+}
+
+impl<T> MemoryStream<T> where T : AsRef<[u8]> {
+    /// Construct a new stream over the given buffer, positioned at the start
+    pub fn new(data: T) -> MemoryStream<T> {
+        return MemoryStream {
+            data: data,
+            position: 0,
+        };
+    }
+
+    /// Get the position within the stream
+    pub fn position(&self) -> u64 {
+        return self.position;
+    }
+
+    /// Set the position within the stream
+    pub fn set_position(&mut self, position: u64) {
+        self.position = position;
+    }
+
+    /// Gets whether the stream has reached its end
+    pub fn end_of_file(&self) -> bool {
+        return self.position >= self.data.as_ref().len() as u64;
+    }
+
+    /// Get a reference to the underlying buffer
+    pub fn get_ref(&self) -> &T {
+        return &self.data;
+    }
+
+    /// Unwrap the underlying buffer
+    pub fn into_inner(self) -> T {
+        return self.data;
+    }
+}
+
+impl<T> Read for MemoryStream<T> where T : AsRef<[u8]> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let data = self.data.as_ref();
+        let start = (self.position as usize).min(data.len());
+        let len = (data.len() - start).min(buf.len());
+
+        buf[..len].copy_from_slice(&data[start..start + len]);
+        self.position += len as u64;
+
+        return Ok(len);
+    }
+}
+
+impl<T> Seek for MemoryStream<T> where T : AsRef<[u8]> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => { n as i64 },
+            SeekFrom::Current(n) => { self.position as i64 + n },
+            SeekFrom::End(n) => { self.data.as_ref().len() as i64 + n }
+        };
+
+        if target < 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+        }
+
+        self.position = target as u64;
+        return Ok(self.position);
+    }
+}
+
+impl std::io::Write for MemoryStream<Vec<u8>> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let start: usize = match self.position.try_into() {
+            Ok(v) => { v },
+            Err(_) => { return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput)); }
+        };
+        let end = start + buf.len();
+
+        // writing past the end of the buffer pads the gap with zeroes
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+
+        self.data[start..end].copy_from_slice(buf);
+        self.position = end as u64;
+
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        return Ok(());
+    }
+}
+
+/// Wraps a stream with a write buffer, so that many small writes (such as writing save data field by field) only result in a few large writes of the underlying stream <br/>
+/// Call finish once done writing to find out whether the final writes succeeded. Dropping the writer flushes it, but any errors are ignored
+pub struct BufferedWriter<W> where W : std::io::Write {
+    inner: Option<W>,
+    buffer: Vec<u8>,
+    capacity: usize,
+}
+
+impl<W> BufferedWriter<W> where W : std::io::Write {
+    /// Wrap the given stream with a buffer of DEFAULT_BUFFER_SIZE bytes
+    pub fn new(inner: W) -> BufferedWriter<W> {
+        return BufferedWriter::with_capacity(DEFAULT_BUFFER_SIZE, inner);
+    }
+
+    /// Wrap the given stream with a buffer of the given size in bytes
+    pub fn with_capacity(capacity: usize, inner: W) -> BufferedWriter<W> {
+        assert!(capacity > 0, "Buffer capacity must be greater than 0");
+
+        return BufferedWriter {
+            inner: Some(inner),
+            buffer: Vec::with_capacity(capacity),
+            capacity: capacity,
+        };
+    }
+
+    /// Get a reference to the underlying stream
+    pub fn get_ref(&self) -> &W {
+        return self.inner.as_ref().unwrap();
+    }
+
+    /// Write any buffered data & flush the underlying stream, returning it
+    pub fn finish(mut self) -> Result<W, IOError> {
+        std::io::Write::flush(&mut self)?;
+        return Ok(self.inner.take().unwrap());
+    }
+
+    fn flush_buffer(&mut self) -> std::io::Result<()> {
+        let inner = self.inner.as_mut().unwrap();
+        let result = inner.write_all(&self.buffer);
+        self.buffer.clear();
+        return result;
+    }
+}
+
+impl<W> std::io::Write for BufferedWriter<W> where W : std::io::Write {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.buffer.len() + buf.len() > self.capacity {
+            self.flush_buffer()?;
+        }
+
+        // large writes bypass the buffer entirely
+        if buf.len() >= self.capacity {
+            return self.inner.as_mut().unwrap().write(buf);
+        }
+
+        self.buffer.extend_from_slice(buf);
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flush_buffer()?;
+        return self.inner.as_mut().unwrap().flush();
+    }
+}
+
+impl<W> Drop for BufferedWriter<W> where W : std::io::Write {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.flush_buffer();
+        }
+    }
+}
+
 pub struct DirectoryEntry {
     pub name: String,
     pub is_directory: bool,
@@ -233,6 +696,15 @@ pub struct DirectoryEntry {
     pub modified: DateTime,
 }
 
+/// Size, timestamps, and type of a file or directory
+#[derive(Clone, Copy)]
+pub struct FileMetadata {
+    pub size: i32,
+    pub is_directory: bool,
+    pub created: DateTime,
+    pub modified: DateTime,
+}
+
 pub struct DirectoryInfo {
     handle: i32,
 }
@@ -241,20 +713,20 @@ impl DirectoryInfo {
     /// Open the given directory
     pub fn open(path: &str) -> Result<DirectoryInfo, IOError> {
         unsafe {
-            let path_cstr = CString::new(path).expect("Failed creating C string");
+            let path_cstr = match path_to_cstring(path) {
+                Ok(v) => { v },
+                Err(_) => { return Err(IOError::DirectoryNotFound); }
+            };
             let result = fs_openDir(path_cstr.as_ptr());
 
-            match crate::db_internal::ERRNO {
-                ESUCCESS => {
-                }
-                ENOENT => {
+            match last_error() {
+                Some(IOError::FileNotFound) => {
                     return Err(IOError::DirectoryNotFound);
                 }
-                ENODEV => {
-                    return Err(IOError::NoSuchDevice);
+                Some(e) => {
+                    return Err(e);
                 }
-                _ => {
-                    panic!("Unhandled errno");
+                None => {
                 }
             }
 
@@ -265,7 +737,7 @@ impl DirectoryInfo {
     }
 
     /// Read the next entry from the directory list
-    pub fn read(self) -> Option<DirectoryEntry> {
+    pub fn read(&mut self) -> Option<DirectoryEntry> {
         unsafe {
             let dir_info_ptr = fs_readDir(self.handle);
             
@@ -274,7 +746,7 @@ impl DirectoryInfo {
             }
             
             let name_cstr = CStr::from_ptr((*dir_info_ptr).name.as_ptr());
-            let name_str = name_cstr.to_str().unwrap();
+            let name_str = name_cstr.to_string_lossy();
 
             let mut created_dt = DateTime {
                 year: 0,
@@ -307,13 +779,21 @@ impl DirectoryInfo {
     }
 
     /// Rewind to the beginning of the directory list
-    pub fn rewind(self) {
+    pub fn rewind(&mut self) {
         unsafe {
             fs_rewindDir(self.handle);
         }
     }
 }
 
+impl Iterator for DirectoryInfo {
+    type Item = DirectoryEntry;
+
+    fn next(&mut self) -> Option<DirectoryEntry> {
+        return self.read();
+    }
+}
+
 impl Drop for DirectoryInfo {
     fn drop(&mut self) {
         unsafe { fs_closeDir(self.handle); }
@@ -324,7 +804,10 @@ impl Drop for DirectoryInfo {
 /// Valid devices are "cd", "ma", and "mb"
 pub fn device_exists(device: &str) -> bool {
     unsafe {
-        let path_cstr = CString::new(device).expect("Failed creating C string");
+        let path_cstr = match path_to_cstring(device) {
+            Ok(v) => { v },
+            Err(_) => { return false; }
+        };
         return fs_deviceExists(path_cstr.as_ptr());
     }
 }
@@ -332,7 +815,10 @@ pub fn device_exists(device: &str) -> bool {
 /// Eject the given device, if it supports being ejected
 pub fn device_eject(device: &str) {
     unsafe {
-        let path_cstr = CString::new(device).expect("Failed creating C string");
+        let path_cstr = match path_to_cstring(device) {
+            Ok(v) => { v },
+            Err(_) => { return; }
+        };
         fs_deviceEject(path_cstr.as_ptr());
     }
 }
@@ -340,7 +826,113 @@ pub fn device_eject(device: &str) {
 /// Check if the given file exists
 pub fn file_exists(path: &str) -> bool {
     unsafe {
-        let path_cstr = CString::new(path).expect("Failed creating C string");
+        let path_cstr = match path_to_cstring(path) {
+            Ok(v) => { v },
+            Err(_) => { return false; }
+        };
         return fs_fileExists(path_cstr.as_ptr());
     }
-}
\ No newline at end of file
+}
+
+/// Read the entire contents of the given file
+pub fn read_entire_file(path: &str) -> Result<Vec<u8>, IOError> {
+    let mut file = FileStream::open(path, FileMode::Read)?;
+
+    // seek to the end to find the size of the file so the whole thing can be read in one call
+    let size: usize = file.seek(SeekFrom::End(0))? as usize;
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut data: Vec<u8> = Vec::with_capacity(size);
+    file.read_to_end(&mut data)?;
+
+    return Ok(data);
+}
+
+/// Read the entire contents of the given file as a UTF-8 string
+pub fn read_to_string(path: &str) -> Result<String, IOError> {
+    let data = read_entire_file(path)?;
+    return match String::from_utf8(data) {
+        Ok(v) => { Ok(v) },
+        Err(_) => { Err(IOError::InvalidData) }
+    };
+}
+
+/// Decode standard or URL-safe base64 text (padding optional)
+#[cfg(any(feature = "gltf", feature = "tiled"))]
+pub(crate) fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let mut out: Vec<u8> = Vec::with_capacity(data.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+
+    for c in data.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => { c - b'A' },
+            b'a'..=b'z' => { c - b'a' + 26 },
+            b'0'..=b'9' => { c - b'0' + 52 },
+            b'+' | b'-' => { 62 },
+            b'/' | b'_' => { 63 },
+            b'=' => { break; },
+            _ => { return None; }
+        };
+
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    return Some(out);
+}
+
+/// Recursively list every file & directory under the given directory, returning the full path of each entry along with the entry itself
+pub fn walk(path: &str) -> Result<Vec<(String, DirectoryEntry)>, IOError> {
+    let mut result: Vec<(String, DirectoryEntry)> = Vec::new();
+    let mut pending: Vec<String> = vec![path.trim_end_matches('/').to_string()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in DirectoryInfo::open(dir.as_str())? {
+            if entry.name == "." || entry.name == ".." {
+                continue;
+            }
+
+            let entry_path = format!("{}/{}", dir, entry.name);
+            if entry.is_directory {
+                pending.push(entry_path.clone());
+            }
+
+            result.push((entry_path, entry));
+        }
+    }
+
+    return Ok(result);
+}
+
+/// Get the size, timestamps, and type of the given file or directory <br/>
+/// Note that the filesystem has no direct query for this, so the parent directory is scanned for the entry (the cost grows with the number of entries in that directory)
+pub fn metadata(path: &str) -> Result<FileMetadata, IOError> {
+    let path = path.trim_end_matches('/');
+    let (parent, name) = match path.rfind('/') {
+        Some(i) if i > 0 => { (&path[..i], &path[i + 1..]) },
+        _ => {
+            // the root of a device has no parent entry
+            return Err(IOError::NotSupported);
+        }
+    };
+
+    for entry in DirectoryInfo::open(parent)? {
+        if entry.name == name {
+            return Ok(FileMetadata {
+                size: entry.size,
+                is_directory: entry.is_directory,
+                created: entry.created,
+                modified: entry.modified,
+            });
+        }
+    }
+
+    return Err(IOError::FileNotFound);
+}
@@ -0,0 +1,195 @@
+use std::{collections::HashMap, io::{Read, Seek, SeekFrom}};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::io::{BufferedStream, FileMode, FileStream, IOError};
+
+const DBPK_VER: u32 = 1;
+
+#[derive(Clone, Copy)]
+struct ArchiveEntry {
+    offset: u64,
+    size: u64,
+}
+
+/// Enumeration of errors which can result from opening an archive
+#[derive(Debug)]
+pub enum ArchiveError {
+    ParseError,
+    VersionError,
+    IOError(IOError),
+}
+
+impl From<IOError> for ArchiveError {
+    fn from(value: IOError) -> Self {
+        return ArchiveError::IOError(value);
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(value: std::io::Error) -> Self {
+        return ArchiveError::IOError(IOError::from(value));
+    }
+}
+
+/// A pack file containing many files, which can be opened individually as streams <br/>
+/// The file starts with a "DBPK" identifier, u32 version, and u32 file count, followed by a table of entries.
+/// Each entry contains a u16 name length, the UTF-8 name (a path relative to the archive using forward slashes), a u32 data offset from the start of the archive, and a u32 data size
+pub struct Archive {
+    path: String,
+    entries: HashMap<String, ArchiveEntry>,
+}
+
+// archive paths are matched without any leading slash
+fn normalize_name(name: &str) -> &str {
+    return name.trim_start_matches('/');
+}
+
+impl Archive {
+    /// Open the archive at the given path (paths are given in the form of "/\[device\]/path/to/file")
+    pub fn open(path: &str) -> Result<Archive, ArchiveError> {
+        let file = FileStream::open(path, FileMode::Read)?;
+        let mut reader = BufferedStream::new(file);
+
+        let mut id: [u8;4] = [0;4];
+        reader.read_exact(&mut id)?;
+
+        if &id != b"DBPK" {
+            return Err(ArchiveError::ParseError);
+        }
+
+        let ver = reader.read_u32::<LittleEndian>()?;
+        if ver != DBPK_VER {
+            return Err(ArchiveError::VersionError);
+        }
+
+        let count = reader.read_u32::<LittleEndian>()?;
+        let mut entries: HashMap<String, ArchiveEntry> = HashMap::new();
+
+        for _ in 0..count {
+            let name_len = reader.read_u16::<LittleEndian>()? as usize;
+            let mut name: Vec<u8> = vec![0;name_len];
+            reader.read_exact(&mut name)?;
+
+            let name = match String::from_utf8(name) {
+                Ok(v) => { v },
+                Err(_) => { return Err(ArchiveError::ParseError); }
+            };
+
+            let offset = reader.read_u32::<LittleEndian>()? as u64;
+            let size = reader.read_u32::<LittleEndian>()? as u64;
+
+            entries.insert(normalize_name(name.as_str()).to_string(), ArchiveEntry { offset: offset, size: size });
+        }
+
+        return Ok(Archive {
+            path: path.to_string(),
+            entries: entries,
+        });
+    }
+
+    /// Open the file with the given name within the archive
+    pub fn open_file(&self, name: &str) -> Result<ArchiveFile, IOError> {
+        let entry = match self.entries.get(normalize_name(name)) {
+            Some(v) => { *v },
+            None => { return Err(IOError::FileNotFound); }
+        };
+
+        let mut file = FileStream::open(self.path.as_str(), FileMode::Read)?;
+        file.seek(SeekFrom::Start(entry.offset))?;
+
+        return Ok(ArchiveFile {
+            file: file,
+            start: entry.offset,
+            size: entry.size,
+            position: 0,
+        });
+    }
+
+    /// Read the entire contents of the file with the given name within the archive
+    pub fn read_file(&self, name: &str) -> Result<Vec<u8>, IOError> {
+        let mut file = self.open_file(name)?;
+        let mut data: Vec<u8> = Vec::with_capacity(file.len() as usize);
+        file.read_to_end(&mut data)?;
+        return Ok(data);
+    }
+
+    /// Gets whether the archive contains a file with the given name
+    pub fn contains(&self, name: &str) -> bool {
+        return self.entries.contains_key(normalize_name(name));
+    }
+
+    /// Get the size in bytes of the file with the given name within the archive
+    pub fn file_size(&self, name: &str) -> Option<u64> {
+        return self.entries.get(normalize_name(name)).map(|x| x.size);
+    }
+
+    /// Get an iterator over the names of every file in the archive
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        return self.entries.keys().map(|x| x.as_str());
+    }
+
+    /// Get the number of files in the archive
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    /// Gets whether the archive contains no files
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+}
+
+/// A stream over a single file within an archive
+pub struct ArchiveFile {
+    file: FileStream,
+    start: u64,
+    size: u64,
+    position: u64,
+}
+
+impl ArchiveFile {
+    /// Get the size of the file in bytes
+    pub fn len(&self) -> u64 {
+        return self.size;
+    }
+
+    /// Gets whether the file is empty
+    pub fn is_empty(&self) -> bool {
+        return self.size == 0;
+    }
+}
+
+impl Read for ArchiveFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.size.saturating_sub(self.position);
+        let len = (buf.len() as u64).min(remaining) as usize;
+        if len == 0 {
+            return Ok(0);
+        }
+
+        let read = self.file.read(&mut buf[..len])?;
+        self.position += read as u64;
+
+        return Ok(read);
+    }
+}
+
+impl Seek for ArchiveFile {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => { n as i64 },
+            SeekFrom::Current(n) => { self.position as i64 + n },
+            SeekFrom::End(n) => { self.size as i64 + n }
+        };
+
+        if target < 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+        }
+
+        self.file.seek(SeekFrom::Start(self.start + target as u64))?;
+        self.position = target as u64;
+
+        return Ok(self.position);
+    }
+}
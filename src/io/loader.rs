@@ -0,0 +1,335 @@
+use std::{collections::VecDeque, convert::TryInto, io::{Read, Seek, SeekFrom}, sync::{Arc, Mutex}};
+
+use crate::io::{FileMode, FileStream, IOError};
+
+type LoadCallback = Box<dyn FnOnce(Result<Vec<u8>, IOError>) + Send>;
+
+enum LoadStatus {
+    Pending { loaded: usize, size: usize },
+    Complete { size: usize, result: Result<Vec<u8>, IOError> },
+    Taken { size: usize },
+}
+
+struct LoadRequest {
+    path: String,
+    file: Option<FileStream>,
+    data: Vec<u8>,
+    size: usize,
+    status: Arc<Mutex<LoadStatus>>,
+    callback: Option<LoadCallback>,
+}
+
+struct LoaderState {
+    queue: VecDeque<LoadRequest>,
+}
+
+static STATE: Mutex<LoaderState> = Mutex::new(LoaderState {
+    queue: VecDeque::new(),
+});
+
+/// Handle to a file being loaded in the background by the loader
+pub struct LoadHandle {
+    status: Arc<Mutex<LoadStatus>>,
+}
+
+impl LoadHandle {
+    /// Gets whether the load has finished (successfully or not)
+    pub fn is_complete(&self) -> bool {
+        return !matches!(*self.status.lock().unwrap(), LoadStatus::Pending { .. });
+    }
+
+    /// Get the fraction of the file which has been loaded so far, from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        return match *self.status.lock().unwrap() {
+            LoadStatus::Pending { loaded, size } => {
+                if size == 0 { 0.0 } else { loaded as f32 / size as f32 }
+            },
+            _ => { 1.0 }
+        };
+    }
+
+    /// Get the number of bytes which have been loaded so far (the full size once the load has finished)
+    pub fn bytes_loaded(&self) -> usize {
+        return match *self.status.lock().unwrap() {
+            LoadStatus::Pending { loaded, .. } => { loaded },
+            LoadStatus::Complete { size, .. } | LoadStatus::Taken { size } => { size }
+        };
+    }
+
+    /// Get the size of the file in bytes (0 until the loader has opened the file)
+    pub fn size(&self) -> usize {
+        return match *self.status.lock().unwrap() {
+            LoadStatus::Pending { size, .. } | LoadStatus::Complete { size, .. } | LoadStatus::Taken { size } => { size }
+        };
+    }
+
+    /// Take the result of the load once it has finished (returns None if the load is still pending or the result was already taken)
+    pub fn take(&self) -> Option<Result<Vec<u8>, IOError>> {
+        let mut status = self.status.lock().unwrap();
+        let size = match *status {
+            LoadStatus::Complete { size, .. } => { size },
+            _ => { return None; }
+        };
+
+        return match std::mem::replace(&mut *status, LoadStatus::Taken { size: size }) {
+            LoadStatus::Complete { result, .. } => { Some(result) },
+            _ => { None }
+        };
+    }
+}
+
+fn push_request(path: &str, callback: Option<LoadCallback>) -> LoadHandle {
+    let status = Arc::new(Mutex::new(LoadStatus::Pending { loaded: 0, size: 0 }));
+
+    STATE.lock().unwrap().queue.push_back(LoadRequest {
+        path: path.to_string(),
+        file: None,
+        data: Vec::new(),
+        size: 0,
+        status: status.clone(),
+        callback: callback,
+    });
+
+    return LoadHandle { status: status };
+}
+
+/// Queue the given file to be loaded incrementally by poll, returning a handle which can be checked for the result
+pub fn enqueue(path: &str) -> LoadHandle {
+    return push_request(path, None);
+}
+
+/// Queue the given file to be loaded incrementally by poll, invoking the callback from poll once it has finished <br/>
+/// The result is passed to the callback rather than being stored in the returned handle
+pub fn enqueue_with_callback<F>(path: &str, callback: F) -> LoadHandle where F : FnOnce(Result<Vec<u8>, IOError>) + Send + 'static {
+    return push_request(path, Some(Box::new(callback)));
+}
+
+/// Get the number of loads which haven't finished yet
+pub fn pending() -> usize {
+    return STATE.lock().unwrap().queue.len();
+}
+
+/// Cancel every pending load, finishing each with an IOError::Cancelled result (callbacks are invoked from inside this function)
+pub fn cancel_all() {
+    let cancelled: Vec<(Result<Vec<u8>, IOError>, LoadRequest)> = STATE.lock().unwrap().queue.drain(..).map(|x| (Err(IOError::Cancelled), x)).collect();
+    finish(cancelled);
+}
+
+// store each finished request's result in its handle or pass it to its callback
+fn finish(completed: Vec<(Result<Vec<u8>, IOError>, LoadRequest)>) {
+    // callbacks may queue more loads, so they run after the queue has been unlocked
+    for (result, mut request) in completed {
+        request.file = None;
+        match request.callback.take() {
+            Some(callback) => {
+                *request.status.lock().unwrap() = LoadStatus::Taken { size: request.size };
+                callback(result);
+            },
+            None => {
+                *request.status.lock().unwrap() = LoadStatus::Complete { size: request.size, result: result };
+            }
+        }
+    }
+}
+
+// open the request's file if needed and read up to the given number of bytes, returning the number of bytes read (None once the file is fully read)
+fn read_chunk(request: &mut LoadRequest, budget: usize) -> Result<Option<usize>, IOError> {
+    if request.file.is_none() {
+        let mut file = FileStream::open(request.path.as_str(), FileMode::Read)?;
+        request.size = file.seek(SeekFrom::End(0))?.try_into().unwrap();
+        file.seek(SeekFrom::Start(0))?;
+        request.data.reserve_exact(request.size);
+        request.file = Some(file);
+    }
+
+    let remaining = request.size - request.data.len();
+    if remaining == 0 {
+        return Ok(None);
+    }
+
+    let file = request.file.as_mut().unwrap();
+    let start = request.data.len();
+    let len = remaining.min(budget.max(1));
+    request.data.resize(start + len, 0);
+
+    let read = file.read(&mut request.data[start..])?;
+    request.data.truncate(start + read);
+
+    if read == 0 {
+        return Err(IOError::ReachedEndOfFile);
+    }
+
+    *request.status.lock().unwrap() = LoadStatus::Pending { loaded: request.data.len(), size: request.size };
+
+    return Ok(Some(read));
+}
+
+/// Read up to the given number of bytes from queued loads in the order they were queued, completing any which finish (should be called once per frame) <br/>
+/// Completion callbacks are invoked from inside this function
+pub fn poll(budget_bytes: usize) {
+    let mut completed: Vec<(Result<Vec<u8>, IOError>, LoadRequest)> = Vec::new();
+    let mut budget = budget_bytes;
+
+    {
+        let mut state = STATE.lock().unwrap();
+        while budget > 0 {
+            let request = match state.queue.front_mut() {
+                Some(v) => { v },
+                None => { break; }
+            };
+
+            match read_chunk(request, budget) {
+                Ok(Some(read)) => {
+                    budget -= read.min(budget);
+
+                    // finish as soon as the last byte arrives, rather than waiting until the next poll
+                    if request.data.len() < request.size {
+                        continue;
+                    }
+                    let mut request = state.queue.pop_front().unwrap();
+                    completed.push((Ok(std::mem::take(&mut request.data)), request));
+                },
+                Ok(None) => {
+                    let mut request = state.queue.pop_front().unwrap();
+                    completed.push((Ok(std::mem::take(&mut request.data)), request));
+                },
+                Err(e) => {
+                    let request = state.queue.pop_front().unwrap();
+                    completed.push((Err(e), request));
+                }
+            }
+        }
+    }
+
+    finish(completed);
+}
+
+/// Combined progress of every file in a manifest
+#[derive(Clone, Copy)]
+pub struct ManifestProgress {
+    pub items_loaded: usize,
+    pub items_total: usize,
+    pub bytes_loaded: usize,
+    /// Total size of every file in bytes. Files which haven't been opened yet count their size hint (if any) until their real size is known
+    pub bytes_total: usize,
+}
+
+impl ManifestProgress {
+    /// Get the overall fraction loaded from 0.0 to 1.0, by bytes if any sizes are known & by items otherwise
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total > 0 {
+            return (self.bytes_loaded as f32 / self.bytes_total as f32).min(1.0);
+        }
+        if self.items_total > 0 {
+            return self.items_loaded as f32 / self.items_total as f32;
+        }
+        return 1.0;
+    }
+}
+
+struct ManifestItem {
+    path: String,
+    size_hint: usize,
+    handle: LoadHandle,
+}
+
+/// A set of files queued together on the loader, whose combined progress can be polled (such as to display a loading bar) <br/>
+/// Files are loaded by poll like any other queued load, and their data is retrieved by path with take
+pub struct Manifest {
+    items: Vec<ManifestItem>,
+}
+
+impl Manifest {
+    /// Construct a new empty manifest
+    pub fn new() -> Manifest {
+        return Manifest {
+            items: Vec::new(),
+        };
+    }
+
+    /// Queue every file listed in the given manifest text <br/>
+    /// Each non-empty line not starting with # is a path, optionally followed by whitespace & the file's size in bytes (used as a size hint)
+    pub fn parse(text: &str) -> Manifest {
+        let mut manifest = Manifest::new();
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.rsplit_once(char::is_whitespace) {
+                Some((path, size)) if size.parse::<usize>().is_ok() => {
+                    manifest.enqueue_sized(path.trim_end(), size.parse::<usize>().unwrap());
+                },
+                _ => {
+                    manifest.enqueue(line);
+                }
+            };
+        }
+
+        return manifest;
+    }
+
+    /// Read the manifest file at the given path & queue every file it lists (see parse)
+    pub fn load(path: &str) -> Result<Manifest, IOError> {
+        let text = crate::io::read_to_string(path)?;
+        return Ok(Manifest::parse(text.as_str()));
+    }
+
+    /// Queue the given file
+    pub fn enqueue(&mut self, path: &str) {
+        self.enqueue_sized(path, 0);
+    }
+
+    /// Queue the given file along with its expected size in bytes, so that byte progress is accurate before the loader reaches it
+    pub fn enqueue_sized(&mut self, path: &str, size_hint: usize) {
+        self.items.push(ManifestItem {
+            path: path.to_string(),
+            size_hint: size_hint,
+            handle: enqueue(path),
+        });
+    }
+
+    /// Get the combined progress of every file in the manifest
+    pub fn progress(&self) -> ManifestProgress {
+        let mut progress = ManifestProgress { items_loaded: 0, items_total: self.items.len(), bytes_loaded: 0, bytes_total: 0 };
+
+        for item in &self.items {
+            let size = item.handle.size();
+            let size = if size > 0 { size } else { item.size_hint };
+            progress.bytes_total += size;
+
+            // failed loads count as fully loaded so that progress still reaches the end
+            if item.handle.is_complete() {
+                progress.items_loaded += 1;
+                progress.bytes_loaded += size;
+            } else {
+                progress.bytes_loaded += item.handle.bytes_loaded();
+            }
+        }
+
+        return progress;
+    }
+
+    /// Gets whether every file in the manifest has finished loading (successfully or not)
+    pub fn is_complete(&self) -> bool {
+        return self.items.iter().all(|x| x.handle.is_complete());
+    }
+
+    /// Get the paths of every file in the manifest, in the order they were queued
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        return self.items.iter().map(|x| x.path.as_str());
+    }
+
+    /// Take the result of loading the given file once it has finished (returns None if it isn't in the manifest, is still pending, or the result was already taken)
+    pub fn take(&self, path: &str) -> Option<Result<Vec<u8>, IOError>> {
+        return self.items.iter().find(|x| x.path == path)?.handle.take();
+    }
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        return Manifest::new();
+    }
+}
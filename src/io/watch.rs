@@ -0,0 +1,84 @@
+use crate::{clock::DateTime, io::metadata};
+
+// modification timestamp & size, used to detect changes
+type FileStamp = ((u16, u8, u8, u8, u8, u8), i32);
+
+fn stamp(path: &str) -> Option<FileStamp> {
+    let m = match metadata(path) {
+        Ok(v) => { v },
+        Err(_) => { return None; }
+    };
+
+    let dt: DateTime = m.modified;
+    return Some(((dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second), m.size));
+}
+
+struct WatchEntry {
+    path: String,
+    stamp: Option<FileStamp>,
+    callback: Box<dyn FnMut(&str)>,
+}
+
+/// Development helper which polls the modification timestamps of registered files & invokes callbacks when they change, so assets can be reloaded without restarting the game <br/>
+/// Each check scans the file's parent directory, so this is intended for use in the emulator during development
+pub struct Watcher {
+    entries: Vec<WatchEntry>,
+    interval: u32,
+    counter: u32,
+}
+
+impl Watcher {
+    /// Construct a new watcher which checks files every frame
+    pub fn new() -> Watcher {
+        return Watcher {
+            entries: Vec::new(),
+            interval: 1,
+            counter: 0,
+        };
+    }
+
+    /// Set how many calls to poll there are between each check of the watched files
+    pub fn set_interval(&mut self, frames: u32) {
+        self.interval = frames.max(1);
+    }
+
+    /// Watch the given file, invoking the callback with its path whenever it is modified
+    pub fn watch<F>(&mut self, path: &str, callback: F) where F : FnMut(&str) + 'static {
+        self.entries.push(WatchEntry {
+            path: path.to_string(),
+            stamp: stamp(path),
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Stop watching the given file
+    pub fn unwatch(&mut self, path: &str) {
+        self.entries.retain(|x| x.path != path);
+    }
+
+    /// Check watched files for changes, invoking callbacks for any which were modified (should be called once per frame)
+    pub fn poll(&mut self) {
+        self.counter += 1;
+        if self.counter < self.interval {
+            return;
+        }
+        self.counter = 0;
+
+        for entry in &mut self.entries {
+            let current = stamp(entry.path.as_str());
+
+            // files which are missing (for example, partway through being rewritten) are picked up again once they reappear
+            if current.is_some() && current != entry.stamp {
+                (entry.callback)(entry.path.as_str());
+            }
+
+            entry.stamp = current;
+        }
+    }
+}
+
+impl Default for Watcher {
+    fn default() -> Self {
+        return Watcher::new();
+    }
+}
@@ -0,0 +1,365 @@
+use std::{collections::HashMap, io::{ErrorKind, Read}, sync::{Arc, RwLock, Weak}};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{log_debug, log_warn, lighting::SphericalHarmonics, math::{Matrix4x4, Quaternion, Vector3, bounds::Aabb}, mesh::DBMesh, scene::{NodeId, Renderable, Scene, Transform}, sounddriver::{self, AttenuationType, PlayParams, SoundDriver, SoundSource}, tilemap::{Properties, PropertyValue}};
+
+const DBLV_VER: u32 = 1;
+
+/// The kind of light attached to a level entity
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LightKind {
+    /// Light arriving equally from every direction
+    Ambient,
+    /// Light shining along the entity's forward (-Z) axis
+    Directional,
+    /// Light radiating from the entity's position up to the light's range
+    Point,
+}
+
+/// A light attached to a level entity
+#[derive(Clone, Copy)]
+pub struct LevelLight {
+    pub kind: LightKind,
+    /// Linear RGB color
+    pub color: Vector3,
+    pub intensity: f32,
+    /// Distance at which a point light's contribution reaches zero
+    pub range: f32,
+}
+
+impl LevelLight {
+    /// Add this light to the given lighting environment, using the world transform of the entity it's attached to <br/>
+    /// Point lights are approximated as a directional light from the given position, faded by distance
+    pub fn apply(&self, sh: &mut SphericalHarmonics, world: &Matrix4x4, position: Vector3) {
+        let color = self.color * self.intensity;
+        match self.kind {
+            LightKind::Ambient => {
+                sh.add_ambient_light(color);
+            },
+            LightKind::Directional => {
+                sh.add_directional_light(Vector3::new(world.m[2][0], world.m[2][1], world.m[2][2]), color);
+            },
+            LightKind::Point => {
+                let origin = Vector3::new(world.m[3][0], world.m[3][1], world.m[3][2]);
+                let offset = origin - position;
+                let dist = offset.length();
+                if dist > 0.0 && dist < self.range {
+                    sh.add_directional_light(offset, color * (1.0 - (dist / self.range)));
+                }
+            }
+        };
+    }
+}
+
+/// A sound source attached to a level entity
+#[derive(Clone)]
+pub struct SoundEmitter {
+    /// Name of the sound to play (such as an entry in a SoundBank)
+    pub sound: String,
+    pub volume: f32,
+    /// Distance at which the sound becomes inaudible
+    pub radius: f32,
+    pub looping: bool,
+    /// Whether the sound should start playing as soon as the level is spawned
+    pub autoplay: bool,
+}
+
+/// A box-shaped region attached to a level entity which game code can test against (for example, to start a cutscene)
+#[derive(Clone, Copy)]
+pub struct TriggerVolume {
+    /// Half size of the box, centered on the entity's origin in its local space
+    pub half_extents: Vector3,
+}
+
+impl TriggerVolume {
+    /// Compute the world space bounds of the volume, given the world transform of the entity it's attached to
+    pub fn bounds(&self, world: &Matrix4x4) -> Aabb {
+        return Aabb::from_center_extents(Vector3::zero(), self.half_extents).transformed(world);
+    }
+}
+
+/// A single object placed in a level
+#[derive(Clone)]
+pub struct LevelEntity {
+    pub name: String,
+    /// Index of the parent entity in the level, or None if it's a root entity
+    pub parent: Option<usize>,
+    /// Transform relative to the parent entity
+    pub transform: Transform,
+    /// Path of the DBM mesh drawn at this entity
+    pub mesh: Option<String>,
+    pub light: Option<LevelLight>,
+    pub sound: Option<SoundEmitter>,
+    pub trigger: Option<TriggerVolume>,
+    pub properties: Properties,
+}
+
+/// Enumeration of errors which can result from parsing a DBLV level file
+#[derive(Debug)]
+pub enum LevelError {
+    ParseError,
+    VersionError,
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for LevelError {
+    fn from(value: std::io::Error) -> Self {
+        return LevelError::IOError(value);
+    }
+}
+
+fn read_string<R>(reader: &mut R) -> Result<String,LevelError> where R : Read {
+    let len = reader.read_u16::<LittleEndian>()?;
+    let mut data: Vec<u8> = vec![0;len as usize];
+    reader.read_exact(&mut data)?;
+    return match String::from_utf8(data) {
+        Ok(v) => { Ok(v) },
+        Err(_) => { Err(LevelError::ParseError) }
+    };
+}
+
+fn read_vector3<R>(reader: &mut R) -> Result<Vector3,std::io::Error> where R : Read {
+    return Ok(Vector3::new(reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?));
+}
+
+/// The nodes, lights, trigger volumes & sounds instantiated by spawning a level into a scene
+pub struct SpawnedLevel {
+    /// The node created for each entity, in the same order as the level's entities
+    pub nodes: Vec<NodeId>,
+    /// Each entity light along with the node it's attached to (pass the node's world matrix to LevelLight::apply)
+    pub lights: Vec<(NodeId, LevelLight)>,
+    /// Each entity trigger volume along with the node it's attached to (pass the node's world matrix to TriggerVolume::bounds)
+    pub triggers: Vec<(NodeId, TriggerVolume)>,
+    /// The emitter started for each autoplay sound along with the node it's attached to
+    pub sounds: Vec<(NodeId, Weak<RwLock<sounddriver::SoundEmitter>>)>,
+}
+
+/// The entities of a level loaded from a DBLV file, which can be spawned into a scene <br/>
+/// The file starts with a "DBLV" identifier and u32 version, followed by chunks of a 4-byte id and u32 size. Strings are stored as a u16 length followed by UTF-8 data. <br/>
+/// Each "ENTY" chunk begins a new entity: name string, i32 parent entity index (-1 for a root entity, otherwise an earlier entity), f32x3 position, f32x4 rotation (x, y, z, w), and f32x3 scale. <br/>
+/// The remaining chunks attach to the preceding entity: "MESH" contains a mesh path string, "LGHT" a u8 kind (0 = ambient, 1 = directional, 2 = point), f32x3 color, f32 intensity & f32 range,
+/// "SNDE" a sound name string, f32 volume, f32 radius, u8 looping & u8 autoplay, "TRIG" f32x3 half extents, and "PROP" a name string, u8 type (0 = bool, 1 = i64, 2 = f32, 3 = string) & value. <br/>
+/// Unknown chunks & any extra data at the end of a known chunk are skipped
+pub struct Level {
+    pub entities: Vec<LevelEntity>,
+}
+
+impl Level {
+    /// Load a level from the given stream
+    pub fn new<R>(reader: &mut R) -> Result<Level,LevelError> where R : Read {
+        let mut id: [u8;4] = [0;4];
+        reader.read_exact(&mut id)?;
+
+        if &id != b"DBLV" {
+            return Err(LevelError::ParseError);
+        }
+
+        let ver = reader.read_u32::<LittleEndian>()?;
+        if ver != DBLV_VER {
+            return Err(LevelError::VersionError);
+        }
+
+        let mut entities: Vec<LevelEntity> = Vec::new();
+
+        // scan chunks
+        loop {
+            let mut chunk_id: [u8;4] = [0;4];
+            match reader.read_exact(&mut chunk_id) {
+                Ok(_) => {
+                },
+                Err(e) => {
+                    // EOF, no more chunks in stream
+                    if e.kind() == ErrorKind::UnexpectedEof {
+                        break;
+                    }
+                    return Err(LevelError::IOError(e));
+                }
+            };
+
+            let chunk_size = reader.read_u32::<LittleEndian>()?;
+            let mut chunk_data: Vec<u8> = vec![0;chunk_size as usize];
+            reader.read_exact(&mut chunk_data)?;
+            let mut chunk = chunk_data.as_slice();
+
+            if &chunk_id == b"ENTY" {
+                let name = read_string(&mut chunk)?;
+                let parent = match chunk.read_i32::<LittleEndian>()? {
+                    -1 => { None },
+                    v if v >= 0 && (v as usize) < entities.len() => { Some(v as usize) },
+                    _ => { return Err(LevelError::ParseError); }
+                };
+
+                let position = read_vector3(&mut chunk)?;
+                let rotation = Quaternion::new(chunk.read_f32::<LittleEndian>()?, chunk.read_f32::<LittleEndian>()?, chunk.read_f32::<LittleEndian>()?, chunk.read_f32::<LittleEndian>()?);
+                let scale = read_vector3(&mut chunk)?;
+
+                entities.push(LevelEntity {
+                    name: name,
+                    parent: parent,
+                    transform: Transform::new(position, rotation, scale),
+                    mesh: None,
+                    light: None,
+                    sound: None,
+                    trigger: None,
+                    properties: Properties::new(),
+                });
+                continue;
+            }
+
+            let entity = match entities.last_mut() {
+                Some(v) => { v },
+                None => {
+                    // unknown chunks may appear before the first entity, but components may not
+                    if [b"MESH", b"LGHT", b"SNDE", b"TRIG", b"PROP"].contains(&&chunk_id) {
+                        return Err(LevelError::ParseError);
+                    }
+                    continue;
+                }
+            };
+
+            match &chunk_id {
+                b"MESH" => {
+                    entity.mesh = Some(read_string(&mut chunk)?);
+                },
+                b"LGHT" => {
+                    let kind = match chunk.read_u8()? {
+                        0 => { LightKind::Ambient },
+                        1 => { LightKind::Directional },
+                        2 => { LightKind::Point },
+                        _ => { return Err(LevelError::ParseError); }
+                    };
+
+                    entity.light = Some(LevelLight {
+                        kind: kind,
+                        color: read_vector3(&mut chunk)?,
+                        intensity: chunk.read_f32::<LittleEndian>()?,
+                        range: chunk.read_f32::<LittleEndian>()?,
+                    });
+                },
+                b"SNDE" => {
+                    entity.sound = Some(SoundEmitter {
+                        sound: read_string(&mut chunk)?,
+                        volume: chunk.read_f32::<LittleEndian>()?,
+                        radius: chunk.read_f32::<LittleEndian>()?,
+                        looping: chunk.read_u8()? != 0,
+                        autoplay: chunk.read_u8()? != 0,
+                    });
+                },
+                b"TRIG" => {
+                    entity.trigger = Some(TriggerVolume { half_extents: read_vector3(&mut chunk)? });
+                },
+                b"PROP" => {
+                    let name = read_string(&mut chunk)?;
+                    let value = match chunk.read_u8()? {
+                        0 => { PropertyValue::Bool(chunk.read_u8()? != 0) },
+                        1 => { PropertyValue::Int(chunk.read_i64::<LittleEndian>()?) },
+                        2 => { PropertyValue::Float(chunk.read_f32::<LittleEndian>()?) },
+                        3 => { PropertyValue::String(read_string(&mut chunk)?) },
+                        _ => { return Err(LevelError::ParseError); }
+                    };
+                    entity.properties.insert(name, value);
+                },
+                _ => {
+                    // unknown chunk ID, skip
+                }
+            };
+        }
+
+        log_debug!("Parsed level (entity count: {})", entities.len());
+        return Ok(Level { entities: entities });
+    }
+
+    /// Find the index of the first entity with the given name
+    pub fn find(&self, name: &str) -> Option<usize> {
+        return self.entities.iter().position(|x| x.name == name);
+    }
+
+    /// Add a scene node for every entity under the given parent node (or as root nodes if None), preserving the level's hierarchy, and instantiate each entity's light, trigger volume & sound <br/>
+    /// Returns None if the parent doesn't exist. Entities with a mesh get a child node drawing each of its parts, and autoplay sounds start playing on the sound driver at their entity's world position <br/>
+    /// mesh_load_fn is called once with each distinct mesh path to load (or fetch from a cache) its mesh, and sound_load_fn with the name of each autoplay sound. Entities whose mesh or sound fails to load are spawned without one
+    pub fn spawn<ML, SL>(&self, scene: &mut Scene, parent: Option<NodeId>, sound_driver: &mut SoundDriver, mut mesh_load_fn: ML, mut sound_load_fn: SL) -> Option<SpawnedLevel>
+        where ML : FnMut(&str) -> Result<Arc<DBMesh>,()>, SL : FnMut(&str) -> Result<Arc<dyn SoundSource>,()>
+    {
+        if let Some(p) = parent {
+            scene.node(p)?;
+        }
+
+        let mut meshes: HashMap<&str, Option<Arc<DBMesh>>> = HashMap::new();
+        let mut nodes: Vec<NodeId> = Vec::with_capacity(self.entities.len());
+        let mut lights: Vec<(NodeId, LevelLight)> = Vec::new();
+        let mut triggers: Vec<(NodeId, TriggerVolume)> = Vec::new();
+        let mut sounds: Vec<(NodeId, Weak<RwLock<sounddriver::SoundEmitter>>)> = Vec::new();
+
+        for entity in &self.entities {
+            let entity_parent = match entity.parent {
+                Some(v) => { Some(nodes[v]) },
+                None => { parent }
+            };
+
+            // entity parents always come before their children, so their nodes already exist
+            let node = scene.add(&entity.name, entity.transform, None, entity_parent)?;
+            nodes.push(node);
+
+            if let Some(light) = entity.light {
+                lights.push((node, light));
+            }
+
+            if let Some(trigger) = entity.trigger {
+                triggers.push((node, trigger));
+            }
+
+            let path = match &entity.mesh {
+                Some(v) => { v.as_str() },
+                None => { continue; }
+            };
+
+            let mesh = meshes.entry(path).or_insert_with(|| {
+                let mesh = mesh_load_fn(path).ok();
+                if mesh.is_none() {
+                    log_warn!("Failed to load level mesh: {}", path);
+                }
+                mesh
+            });
+
+            if let Some(mesh) = mesh {
+                for (i, part) in mesh.mesh_parts.iter().enumerate() {
+                    scene.add(&part.name, Transform::IDENTITY, Some(Renderable::MeshPart { mesh: mesh.clone(), part: i }), Some(node));
+                }
+            }
+        }
+
+        // sounds are positioned in world space, so every node's transform needs to be known first
+        scene.update_transforms();
+
+        for (entity, node) in self.entities.iter().zip(&nodes) {
+            let sound = match &entity.sound {
+                Some(v) if v.autoplay => { v },
+                _ => { continue; }
+            };
+
+            let source = match sound_load_fn(&sound.sound) {
+                Ok(v) => { v },
+                Err(_) => {
+                    log_warn!("Failed to load level sound: {}", sound.sound);
+                    continue;
+                }
+            };
+
+            let world = scene.node(*node)?.world_matrix();
+            let mut params = PlayParams::new().volume(sound.volume).looping(sound.looping);
+            if sound.radius > 0.0 {
+                params = params.spatial(Vector3::new(world.m[3][0], world.m[3][1], world.m[3][2]), AttenuationType::Linear, 0.0, sound.radius, 1.0);
+            }
+
+            sounds.push((*node, sound_driver.play(source.as_ref(), &params)));
+        }
+
+        return Some(SpawnedLevel {
+            nodes: nodes,
+            lights: lights,
+            triggers: triggers,
+            sounds: sounds,
+        });
+    }
+}
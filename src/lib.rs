@@ -1,4 +1,5 @@
 mod db_internal;
+mod budget;
 pub mod db;
 pub mod vdp;
 pub mod math;
@@ -7,4 +8,29 @@ pub mod gamepad;
 pub mod io;
 pub mod clock;
 pub mod sounddriver;
+pub mod easing;
+pub mod texture;
+pub mod sprite;
+pub mod tilemap;
+pub mod scene;
+pub mod state;
+pub mod collision;
+pub mod collision2d;
+pub mod camera;
+pub mod lighting;
+pub mod ui;
+#[cfg(feature = "save")]
+pub mod save;
+#[cfg(feature = "mesh")]
+pub mod mesh;
+#[cfg(feature = "anim")]
+pub mod anim;
+#[cfg(feature = "anim")]
+pub mod timeline;
+#[cfg(feature = "gltf")]
+pub mod gltf;
+#[cfg(feature = "obj")]
+pub mod obj;
+#[cfg(feature = "level")]
+pub mod level;
 pub extern crate field_offset;
\ No newline at end of file
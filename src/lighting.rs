@@ -0,0 +1,195 @@
+use crate::{math::{Lerp, Matrix4x4, Quaternion, Vector3, Vector4}, vdp::Color32};
+
+const BASIS_WEIGHT_CONSTANT: f32 = 0.282095;
+const BASIS_WEIGHT_LINEAR: f32 = 0.325735;
+
+/// L1 spherical harmonics lighting, stored in a matrix so that normals can be transformed into light colors with Matrix4x4::transform_vertex_simd <br/>
+/// The first three rows store the directional RGB coefficients for the X, Y, and Z axes, and the fourth row stores the constant RGB terms. Normals must have W set to 1.0 before transforming
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SphericalHarmonics {
+    pub coeff: Matrix4x4
+}
+
+impl SphericalHarmonics {
+    pub const fn new() -> SphericalHarmonics {
+        let coeff = Matrix4x4 {
+            m: [
+                [0.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+        };
+
+        return SphericalHarmonics {
+            coeff: coeff
+        };
+    }
+
+    /// Project an environment given as a set of (direction, color) samples, which should be spread evenly over the sphere (see sphere_directions) <br/>
+    /// A uniform environment projects to the same result as add_ambient_light with that color
+    pub fn from_samples(samples: &[(Vector3, Vector3)]) -> SphericalHarmonics {
+        let mut sh = SphericalHarmonics::new();
+
+        if samples.is_empty() {
+            return sh;
+        }
+
+        let weight = 1.0 / samples.len() as f32;
+        for (dir, color) in samples {
+            sh.add_weighted_sample(*dir, *color, weight);
+        }
+
+        return sh;
+    }
+
+    /// Project an environment given as a cubemap, with faces of size * size texels in the order +X, -X, +Y, -Y, +Z, -Z <br/>
+    /// Each texel is weighted by the solid angle it covers. A uniform environment projects to the same result as add_ambient_light with that color
+    pub fn from_cubemap(size: usize, faces: [&[Color32];6]) -> SphericalHarmonics {
+        let mut sh = SphericalHarmonics::new();
+
+        if size == 0 {
+            return sh;
+        }
+
+        let mut samples = Vec::with_capacity(size * size * 6);
+        let mut total_weight = 0.0;
+
+        for (face, texels) in faces.iter().enumerate() {
+            for (i, texel) in texels.iter().take(size * size).enumerate() {
+                // map texel center to -1 .. 1 across the face
+                let u = (((i % size) as f32 + 0.5) / size as f32) * 2.0 - 1.0;
+                let v = (((i / size) as f32 + 0.5) / size as f32) * 2.0 - 1.0;
+
+                let dir = match face {
+                    0 => { Vector3::new(1.0, -v, -u) },
+                    1 => { Vector3::new(-1.0, -v, u) },
+                    2 => { Vector3::new(u, 1.0, v) },
+                    3 => { Vector3::new(u, -1.0, -v) },
+                    4 => { Vector3::new(u, -v, 1.0) },
+                    _ => { Vector3::new(-u, -v, -1.0) }
+                };
+
+                // solid angle of a texel falls off towards the edges of each face
+                let len_sq = dir.length_sq();
+                let weight = 1.0 / (len_sq * len_sq.sqrt());
+                total_weight += weight;
+
+                let c = Vector4::from(*texel);
+                samples.push((dir, Vector3::new(c.x, c.y, c.z), weight));
+            }
+        }
+
+        for (dir, color, weight) in samples {
+            sh.add_weighted_sample(dir, color, weight / total_weight);
+        }
+
+        return sh;
+    }
+
+    /// Generate the given number of directions spread evenly over the sphere, for sampling an environment to pass to from_samples
+    pub fn sphere_directions(count: usize) -> Vec<Vector3> {
+        let golden_angle = std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+
+        return (0..count).map(|i| {
+            let y = 1.0 - ((i as f32 + 0.5) / count as f32) * 2.0;
+            let r = (1.0 - y * y).max(0.0).sqrt();
+            let theta = golden_angle * i as f32;
+            Vector3::new(theta.cos() * r, y, theta.sin() * r)
+        }).collect();
+    }
+
+    pub fn add_ambient_light(&mut self, color: Vector3) {
+        // when storing L1 coefficients in a matrix, the fourth column of the matrix stores constant terms for R, G, and B channels.
+        // we can just add ambient color to this column.
+
+        self.coeff.m[3][0] += color.x;
+        self.coeff.m[3][1] += color.y;
+        self.coeff.m[3][2] += color.z;
+    }
+
+    pub fn add_directional_light(&mut self, dir: Vector3, color: Vector3) {
+        let mut direction = dir;
+        direction.normalize();
+
+        // when storing L1 coefficients in a matrix, the first three columns store the directional coefficients of RGB channels for X, Y, and Z axes respectively.
+        // you can think of this as storing average directional intensity of each R, G, and B.
+        // this WILL of course result in ringing artifacts the more intense the directional component, but that's a known limitation of L1 spherical harmonics
+
+        for axis in 0..3 {
+            self.coeff.m[axis][0] += direction[axis] * color.x * BASIS_WEIGHT_LINEAR;
+            self.coeff.m[axis][1] += direction[axis] * color.y * BASIS_WEIGHT_LINEAR;
+            self.coeff.m[axis][2] += direction[axis] * color.z * BASIS_WEIGHT_LINEAR;
+        }
+
+        self.coeff.m[3][0] += color.x * BASIS_WEIGHT_CONSTANT;
+        self.coeff.m[3][1] += color.y * BASIS_WEIGHT_CONSTANT;
+        self.coeff.m[3][2] += color.z * BASIS_WEIGHT_CONSTANT;
+    }
+
+    /// Rotate the lighting environment by the given rotation (for example, to match a room which has been rotated after its lighting was baked)
+    pub fn rotated(&self, rotation: Quaternion) -> SphericalHarmonics {
+        let mut result = *self;
+
+        // the directional terms of each color channel form a vector which rotates with the environment
+        for channel in 0..3 {
+            let v = rotation * Vector3::new(self.coeff.m[0][channel], self.coeff.m[1][channel], self.coeff.m[2][channel]);
+            result.coeff.m[0][channel] = v.x;
+            result.coeff.m[1][channel] = v.y;
+            result.coeff.m[2][channel] = v.z;
+        }
+
+        return result;
+    }
+
+    /// Compute the light color for a surface with the given normal on the CPU
+    pub fn evaluate(&self, normal: Vector3) -> Vector3 {
+        let m = &self.coeff.m;
+        let n = [normal.x, normal.y, normal.z, 1.0];
+
+        let mut color = Vector3::zero();
+        for (row, weight) in n.iter().enumerate() {
+            color += Vector3::new(m[row][0], m[row][1], m[row][2]) * *weight;
+        }
+
+        return color;
+    }
+
+    // add a sample of incoming light with the given weight, scaled so that uniform light adds the same constant term as add_ambient_light
+    fn add_weighted_sample(&mut self, dir: Vector3, color: Vector3, weight: f32) {
+        let direction = dir.normalized();
+        let linear = weight * (BASIS_WEIGHT_LINEAR / BASIS_WEIGHT_CONSTANT);
+
+        for axis in 0..3 {
+            self.coeff.m[axis][0] += direction[axis] * color.x * linear;
+            self.coeff.m[axis][1] += direction[axis] * color.y * linear;
+            self.coeff.m[axis][2] += direction[axis] * color.z * linear;
+        }
+
+        self.coeff.m[3][0] += color.x * weight;
+        self.coeff.m[3][1] += color.y * weight;
+        self.coeff.m[3][2] += color.z * weight;
+    }
+}
+
+impl Default for SphericalHarmonics {
+    fn default() -> Self {
+        return SphericalHarmonics::new();
+    }
+}
+
+impl Lerp<SphericalHarmonics> for SphericalHarmonics {
+    /// Blend between two lighting environments (for example, when moving between rooms with different baked lighting)
+    fn lerp(lhs: SphericalHarmonics, rhs: SphericalHarmonics, time: f32) -> SphericalHarmonics {
+        let mut result = lhs;
+
+        for row in 0..4 {
+            for col in 0..4 {
+                result.coeff.m[row][col] = f32::lerp(lhs.coeff.m[row][col], rhs.coeff.m[row][col], time);
+            }
+        }
+
+        return result;
+    }
+}
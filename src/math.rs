@@ -4,8 +4,90 @@ use field_offset::FieldOffset;
 
 use crate::db_internal::{mat4_loadSIMD, mat4_storeSIMD, mat4_mulSIMD, mat4_transformSIMD};
 
+pub mod fixed;
+pub mod noise;
+pub mod curve;
+pub mod bounds;
+pub mod batch;
+pub mod stack;
+pub mod skin;
+
+#[cfg(feature = "mint")]
+mod mint_interop;
+#[cfg(feature = "glam")]
+mod glam_interop;
+
+/// Trait for types which can be linearly interpolated <br/>
+/// Time is not clamped, so values outside of 0.0 .. 1.0 extrapolate (allowing for overshooting easing curves)
+pub trait Lerp<T> where T : Clone + Copy {
+    fn lerp(lhs: T, rhs: T, time: f32) -> T;
+}
+
+impl Lerp<f32> for f32 {
+    fn lerp(lhs: f32, rhs: f32, time: f32) -> f32 {
+        return lhs + ((rhs - lhs) * time);
+    }
+}
+
+impl Lerp<Vector2> for Vector2 {
+    fn lerp(lhs: Vector2, rhs: Vector2, time: f32) -> Vector2 {
+        return lhs + ((rhs - lhs) * time);
+    }
+}
+
+impl Lerp<Vector3> for Vector3 {
+    fn lerp(lhs: Vector3, rhs: Vector3, time: f32) -> Vector3 {
+        return lhs + ((rhs - lhs) * time);
+    }
+}
+
+impl Lerp<Vector4> for Vector4 {
+    fn lerp(lhs: Vector4, rhs: Vector4, time: f32) -> Vector4 {
+        return lhs + ((rhs - lhs) * time);
+    }
+}
+
+impl Lerp<Quaternion> for Quaternion {
+    /// Spherically interpolate between two quaternions
+    fn lerp(lhs: Quaternion, rhs: Quaternion, time: f32) -> Quaternion {
+        let num = time;
+        let num2: f32;
+        let num3: f32;
+
+        let mut num4 =
+            (lhs.x * rhs.x) +
+            (lhs.y * rhs.y) +
+            (lhs.z * rhs.z) +
+            (lhs.w * rhs.w);
+
+        let mut flag: f32 = 1.0;
+        
+        if num4 < 0.0 {
+            flag = -1.0;
+            num4 = -num4;
+        }
+
+        if num4 > 0.999999 {
+            num3 = 1.0 - num;
+            num2 = num * flag;
+        } else {
+            let num5 = num4.acos();
+            let num6 = 1.0 / num5.sin();
+            num3 = ((1.0 - num) * num5).sin() * num6;
+            num2 = flag * (num * num5).sin() * num6;
+        }
+
+        return Quaternion::new(
+            (num3 * lhs.x) + (num2 * rhs.x),
+            (num3 * lhs.y) + (num2 * rhs.y),
+            (num3 * lhs.z) + (num2 * rhs.z),
+            (num3 * lhs.w) + (num2 * rhs.w));
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector2 {
     pub x: f32,
     pub y: f32,
@@ -69,6 +151,49 @@ impl Vector2 {
     pub fn dot(lhs: &Vector2, rhs: &Vector2) -> f32 {
         return (lhs.x * rhs.x) + (lhs.y * rhs.y);
     }
+
+    /// Extend this vector into a Vector3 with the given Z component
+    pub const fn extend(self, z: f32) -> Vector3 {
+        return Vector3 { x: self.x, y: self.y, z: z };
+    }
+
+    /// Convert this vector into an array of components
+    pub const fn to_array(self) -> [f32;2] {
+        return [self.x, self.y];
+    }
+
+    /// Check whether each component of this vector is within epsilon of the other vector
+    pub fn approx_eq(&self, other: &Vector2, epsilon: f32) -> bool {
+        return (self.x - other.x).abs() <= epsilon && (self.y - other.y).abs() <= epsilon;
+    }
+
+    /// Check whether every component of this vector is finite (not infinite or NaN)
+    pub fn is_finite(&self) -> bool {
+        return self.x.is_finite() && self.y.is_finite();
+    }
+
+    /// Check whether this vector has a length of 1 (within a small tolerance)
+    pub fn is_normalized(&self) -> bool {
+        return (self.length_sq() - 1.0).abs() <= 1e-4;
+    }
+}
+
+impl From<[f32;2]> for Vector2 {
+    fn from(v: [f32;2]) -> Vector2 {
+        return Vector2 { x: v[0], y: v[1] };
+    }
+}
+
+impl From<Vector2> for [f32;2] {
+    fn from(v: Vector2) -> [f32;2] {
+        return [v.x, v.y];
+    }
+}
+
+impl From<(f32, f32)> for Vector2 {
+    fn from(v: (f32, f32)) -> Vector2 {
+        return Vector2 { x: v.0, y: v.1 };
+    }
 }
 
 impl ops::Add<Vector2> for Vector2 {
@@ -135,8 +260,81 @@ impl ops::Div<Vector2> for f32 {
     }
 }
 
+impl ops::AddAssign<Vector2> for Vector2 {
+    fn add_assign(&mut self, rhs: Vector2) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl ops::SubAssign<Vector2> for Vector2 {
+    fn sub_assign(&mut self, rhs: Vector2) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl ops::MulAssign<Vector2> for Vector2 {
+    fn mul_assign(&mut self, rhs: Vector2) {
+        self.x *= rhs.x;
+        self.y *= rhs.y;
+    }
+}
+
+impl ops::MulAssign<f32> for Vector2 {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl ops::DivAssign<Vector2> for Vector2 {
+    fn div_assign(&mut self, rhs: Vector2) {
+        self.x /= rhs.x;
+        self.y /= rhs.y;
+    }
+}
+
+impl ops::DivAssign<f32> for Vector2 {
+    fn div_assign(&mut self, rhs: f32) {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
+impl ops::Neg for Vector2 {
+    type Output = Vector2;
+
+    fn neg(self) -> Vector2 {
+        return Vector2 { x: -self.x, y: -self.y };
+    }
+}
+
+impl ops::Index<usize> for Vector2 {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => { return &self.x; }
+            1 => { return &self.y; }
+            _ => { panic!("Vector2 index out of range: {}", index); }
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for Vector2 {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => { return &mut self.x; }
+            1 => { return &mut self.y; }
+            _ => { panic!("Vector2 index out of range: {}", index); }
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector3 {
     pub x: f32,
     pub y: f32,
@@ -217,6 +415,59 @@ impl Vector3 {
             z: lhs.x * rhs.y - lhs.y * rhs.x
         };
     }
+
+    /// Extend this vector into a Vector4 with the given W component
+    pub const fn extend(self, w: f32) -> Vector4 {
+        return Vector4 { x: self.x, y: self.y, z: self.z, w: w };
+    }
+
+    /// Truncate this vector into a Vector2, dropping the Z component
+    pub const fn truncate(self) -> Vector2 {
+        return Vector2 { x: self.x, y: self.y };
+    }
+
+    /// Get the X and Y components of this vector
+    pub const fn xy(self) -> Vector2 {
+        return Vector2 { x: self.x, y: self.y };
+    }
+
+    /// Convert this vector into an array of components
+    pub const fn to_array(self) -> [f32;3] {
+        return [self.x, self.y, self.z];
+    }
+
+    /// Check whether each component of this vector is within epsilon of the other vector
+    pub fn approx_eq(&self, other: &Vector3, epsilon: f32) -> bool {
+        return (self.x - other.x).abs() <= epsilon && (self.y - other.y).abs() <= epsilon && (self.z - other.z).abs() <= epsilon;
+    }
+
+    /// Check whether every component of this vector is finite (not infinite or NaN)
+    pub fn is_finite(&self) -> bool {
+        return self.x.is_finite() && self.y.is_finite() && self.z.is_finite();
+    }
+
+    /// Check whether this vector has a length of 1 (within a small tolerance)
+    pub fn is_normalized(&self) -> bool {
+        return (self.length_sq() - 1.0).abs() <= 1e-4;
+    }
+}
+
+impl From<[f32;3]> for Vector3 {
+    fn from(v: [f32;3]) -> Vector3 {
+        return Vector3 { x: v[0], y: v[1], z: v[2] };
+    }
+}
+
+impl From<Vector3> for [f32;3] {
+    fn from(v: Vector3) -> [f32;3] {
+        return [v.x, v.y, v.z];
+    }
+}
+
+impl From<(f32, f32, f32)> for Vector3 {
+    fn from(v: (f32, f32, f32)) -> Vector3 {
+        return Vector3 { x: v.0, y: v.1, z: v.2 };
+    }
 }
 
 impl ops::Add<Vector3> for Vector3 {
@@ -283,8 +534,89 @@ impl ops::Div<Vector3> for f32 {
     }
 }
 
+impl ops::AddAssign<Vector3> for Vector3 {
+    fn add_assign(&mut self, rhs: Vector3) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl ops::SubAssign<Vector3> for Vector3 {
+    fn sub_assign(&mut self, rhs: Vector3) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl ops::MulAssign<Vector3> for Vector3 {
+    fn mul_assign(&mut self, rhs: Vector3) {
+        self.x *= rhs.x;
+        self.y *= rhs.y;
+        self.z *= rhs.z;
+    }
+}
+
+impl ops::MulAssign<f32> for Vector3 {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+    }
+}
+
+impl ops::DivAssign<Vector3> for Vector3 {
+    fn div_assign(&mut self, rhs: Vector3) {
+        self.x /= rhs.x;
+        self.y /= rhs.y;
+        self.z /= rhs.z;
+    }
+}
+
+impl ops::DivAssign<f32> for Vector3 {
+    fn div_assign(&mut self, rhs: f32) {
+        self.x /= rhs;
+        self.y /= rhs;
+        self.z /= rhs;
+    }
+}
+
+impl ops::Neg for Vector3 {
+    type Output = Vector3;
+
+    fn neg(self) -> Vector3 {
+        return Vector3 { x: -self.x, y: -self.y, z: -self.z };
+    }
+}
+
+impl ops::Index<usize> for Vector3 {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => { return &self.x; }
+            1 => { return &self.y; }
+            2 => { return &self.z; }
+            _ => { panic!("Vector3 index out of range: {}", index); }
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for Vector3 {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => { return &mut self.x; }
+            1 => { return &mut self.y; }
+            2 => { return &mut self.z; }
+            _ => { panic!("Vector3 index out of range: {}", index); }
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector4 {
     pub x: f32,
     pub y: f32,
@@ -364,6 +696,146 @@ impl Vector4 {
     pub fn dot(lhs: &Vector4, rhs: &Vector4) -> f32 {
         return (lhs.x * rhs.x) + (lhs.y * rhs.y) + (lhs.z * rhs.z) + (lhs.w * rhs.w);
     }
+
+    /// Truncate this vector into a Vector3, dropping the W component
+    pub const fn truncate(self) -> Vector3 {
+        return Vector3 { x: self.x, y: self.y, z: self.z };
+    }
+
+    /// Get the X and Y components of this vector
+    pub const fn xy(self) -> Vector2 {
+        return Vector2 { x: self.x, y: self.y };
+    }
+
+    /// Get the X, Y, and Z components of this vector
+    pub const fn xyz(self) -> Vector3 {
+        return Vector3 { x: self.x, y: self.y, z: self.z };
+    }
+
+    /// Convert this vector into an array of components
+    pub const fn to_array(self) -> [f32;4] {
+        return [self.x, self.y, self.z, self.w];
+    }
+
+    /// Check whether each component of this vector is within epsilon of the other vector
+    pub fn approx_eq(&self, other: &Vector4, epsilon: f32) -> bool {
+        return (self.x - other.x).abs() <= epsilon && (self.y - other.y).abs() <= epsilon && (self.z - other.z).abs() <= epsilon && (self.w - other.w).abs() <= epsilon;
+    }
+
+    /// Check whether every component of this vector is finite (not infinite or NaN)
+    pub fn is_finite(&self) -> bool {
+        return self.x.is_finite() && self.y.is_finite() && self.z.is_finite() && self.w.is_finite();
+    }
+
+    /// Check whether this vector has a length of 1 (within a small tolerance)
+    pub fn is_normalized(&self) -> bool {
+        return (self.length_sq() - 1.0).abs() <= 1e-4;
+    }
+
+    /// Construct a normalized (0.0 .. 1.0) RGBA color from a packed 0xRRGGBBAA value
+    pub fn from_hex(hex: u32) -> Vector4 {
+        return Vector4 {
+            x: ((hex >> 24) & 0xFF) as f32 / 255.0,
+            y: ((hex >> 16) & 0xFF) as f32 / 255.0,
+            z: ((hex >> 8) & 0xFF) as f32 / 255.0,
+            w: (hex & 0xFF) as f32 / 255.0,
+        };
+    }
+
+    /// Convert this normalized RGBA color to a packed 0xRRGGBBAA value, clamping out of range components
+    pub fn to_hex(self) -> u32 {
+        let c = |v: f32| -> u32 { (v.clamp(0.0, 1.0) * 255.0).round() as u32 };
+        return (c(self.x) << 24) | (c(self.y) << 16) | (c(self.z) << 8) | c(self.w);
+    }
+
+    /// Construct a normalized RGBA color from hue (in degrees), saturation, and value (both 0.0 .. 1.0), plus alpha
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Vector4 {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let s = saturation.clamp(0.0, 1.0);
+        let v = value.clamp(0.0, 1.0);
+
+        let c = v * s;
+        let x = c * (1.0 - ((h % 2.0) - 1.0).abs());
+        let m = v - c;
+
+        let (r, g, b) = match h as i32 {
+            0 => { (c, x, 0.0) }
+            1 => { (x, c, 0.0) }
+            2 => { (0.0, c, x) }
+            3 => { (0.0, x, c) }
+            4 => { (x, 0.0, c) }
+            _ => { (c, 0.0, x) }
+        };
+
+        return Vector4 { x: r + m, y: g + m, z: b + m, w: alpha };
+    }
+
+    /// Convert this normalized RGBA color to hue (in degrees), saturation, and value (both 0.0 .. 1.0)
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let max = self.x.max(self.y).max(self.z);
+        let min = self.x.min(self.y).min(self.z);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == self.x {
+            60.0 * ((self.y - self.z) / delta).rem_euclid(6.0)
+        } else if max == self.y {
+            60.0 * (((self.z - self.x) / delta) + 2.0)
+        } else {
+            60.0 * (((self.x - self.y) / delta) + 4.0)
+        };
+
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+        return (hue, saturation, max);
+    }
+
+    /// Produce a copy of this RGBA color with RGB premultiplied by alpha
+    pub fn premultiplied(self) -> Vector4 {
+        return Vector4 { x: self.x * self.w, y: self.y * self.w, z: self.z * self.w, w: self.w };
+    }
+
+    /// Produce a copy of this premultiplied RGBA color with alpha divided back out of RGB
+    pub fn unpremultiplied(self) -> Vector4 {
+        if self.w == 0.0 {
+            return Vector4::zero();
+        }
+
+        return Vector4 { x: self.x / self.w, y: self.y / self.w, z: self.z / self.w, w: self.w };
+    }
+}
+
+impl From<[f32;4]> for Vector4 {
+    fn from(v: [f32;4]) -> Vector4 {
+        return Vector4 { x: v[0], y: v[1], z: v[2], w: v[3] };
+    }
+}
+
+impl From<Vector4> for [f32;4] {
+    fn from(v: Vector4) -> [f32;4] {
+        return [v.x, v.y, v.z, v.w];
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Vector4 {
+    fn from(v: (f32, f32, f32, f32)) -> Vector4 {
+        return Vector4 { x: v.0, y: v.1, z: v.2, w: v.3 };
+    }
+}
+
+impl From<Vector2> for Vector3 {
+    /// Zero-extend a Vector2 into a Vector3
+    fn from(v: Vector2) -> Vector3 {
+        return Vector3 { x: v.x, y: v.y, z: 0.0 };
+    }
+}
+
+impl From<Vector3> for Vector4 {
+    /// Zero-extend a Vector3 into a Vector4
+    fn from(v: Vector3) -> Vector4 {
+        return Vector4 { x: v.x, y: v.y, z: v.z, w: 0.0 };
+    }
 }
 
 impl ops::Add<Vector4> for Vector4 {
@@ -430,8 +902,97 @@ impl ops::Div<Vector4> for f32 {
     }
 }
 
+impl ops::AddAssign<Vector4> for Vector4 {
+    fn add_assign(&mut self, rhs: Vector4) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+        self.w += rhs.w;
+    }
+}
+
+impl ops::SubAssign<Vector4> for Vector4 {
+    fn sub_assign(&mut self, rhs: Vector4) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+        self.w -= rhs.w;
+    }
+}
+
+impl ops::MulAssign<Vector4> for Vector4 {
+    fn mul_assign(&mut self, rhs: Vector4) {
+        self.x *= rhs.x;
+        self.y *= rhs.y;
+        self.z *= rhs.z;
+        self.w *= rhs.w;
+    }
+}
+
+impl ops::MulAssign<f32> for Vector4 {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+        self.w *= rhs;
+    }
+}
+
+impl ops::DivAssign<Vector4> for Vector4 {
+    fn div_assign(&mut self, rhs: Vector4) {
+        self.x /= rhs.x;
+        self.y /= rhs.y;
+        self.z /= rhs.z;
+        self.w /= rhs.w;
+    }
+}
+
+impl ops::DivAssign<f32> for Vector4 {
+    fn div_assign(&mut self, rhs: f32) {
+        self.x /= rhs;
+        self.y /= rhs;
+        self.z /= rhs;
+        self.w /= rhs;
+    }
+}
+
+impl ops::Neg for Vector4 {
+    type Output = Vector4;
+
+    fn neg(self) -> Vector4 {
+        return Vector4 { x: -self.x, y: -self.y, z: -self.z, w: -self.w };
+    }
+}
+
+impl ops::Index<usize> for Vector4 {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => { return &self.x; }
+            1 => { return &self.y; }
+            2 => { return &self.z; }
+            3 => { return &self.w; }
+            _ => { panic!("Vector4 index out of range: {}", index); }
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for Vector4 {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => { return &mut self.x; }
+            1 => { return &mut self.y; }
+            2 => { return &mut self.z; }
+            3 => { return &mut self.w; }
+            _ => { panic!("Vector4 index out of range: {}", index); }
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Quaternion {
     pub x: f32,
     pub y: f32,
@@ -488,6 +1049,28 @@ impl Quaternion {
         self.z *= -n;
         self.w *= n;
     }
+
+    /// Check whether each component of this quaternion is within epsilon of the other quaternion <br/>
+    /// Note that q and -q represent the same rotation - use rotation_approx_eq to treat them as equal
+    pub fn approx_eq(&self, other: &Quaternion, epsilon: f32) -> bool {
+        return (self.x - other.x).abs() <= epsilon && (self.y - other.y).abs() <= epsilon && (self.z - other.z).abs() <= epsilon && (self.w - other.w).abs() <= epsilon;
+    }
+
+    /// Check whether this quaternion represents the same rotation as the other quaternion within epsilon
+    pub fn rotation_approx_eq(&self, other: &Quaternion, epsilon: f32) -> bool {
+        return self.approx_eq(other, epsilon) || self.approx_eq(&-*other, epsilon);
+    }
+
+    /// Check whether every component of this quaternion is finite (not infinite or NaN)
+    pub fn is_finite(&self) -> bool {
+        return self.x.is_finite() && self.y.is_finite() && self.z.is_finite() && self.w.is_finite();
+    }
+
+    /// Check whether this quaternion has a length of 1 (within a small tolerance)
+    pub fn is_normalized(&self) -> bool {
+        let len_sq = (self.x * self.x) + (self.y * self.y) + (self.z * self.z) + (self.w * self.w);
+        return (len_sq - 1.0).abs() <= 1e-4;
+    }
 }
 
 impl ops::Mul<Quaternion> for Quaternion {
@@ -518,8 +1101,245 @@ impl ops::Mul<Vector3> for Quaternion {
     }
 }
 
+impl ops::Add<Quaternion> for Quaternion {
+    type Output = Quaternion;
+
+    fn add(self, rhs: Quaternion) -> Quaternion {
+        return Quaternion { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z, w: self.w + rhs.w };
+    }
+}
+
+impl ops::Sub<Quaternion> for Quaternion {
+    type Output = Quaternion;
+
+    fn sub(self, rhs: Quaternion) -> Quaternion {
+        return Quaternion { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z, w: self.w - rhs.w };
+    }
+}
+
+impl ops::Mul<f32> for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: f32) -> Quaternion {
+        return Quaternion { x: self.x * rhs, y: self.y * rhs, z: self.z * rhs, w: self.w * rhs };
+    }
+}
+
+impl ops::AddAssign<Quaternion> for Quaternion {
+    fn add_assign(&mut self, rhs: Quaternion) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+        self.w += rhs.w;
+    }
+}
+
+impl ops::SubAssign<Quaternion> for Quaternion {
+    fn sub_assign(&mut self, rhs: Quaternion) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+        self.w -= rhs.w;
+    }
+}
+
+impl ops::MulAssign<Quaternion> for Quaternion {
+    fn mul_assign(&mut self, rhs: Quaternion) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::MulAssign<f32> for Quaternion {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+        self.w *= rhs;
+    }
+}
+
+impl ops::Neg for Quaternion {
+    type Output = Quaternion;
+
+    fn neg(self) -> Quaternion {
+        return Quaternion { x: -self.x, y: -self.y, z: -self.z, w: -self.w };
+    }
+}
+
+impl ops::Index<usize> for Quaternion {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => { return &self.x; }
+            1 => { return &self.y; }
+            2 => { return &self.z; }
+            3 => { return &self.w; }
+            _ => { panic!("Quaternion index out of range: {}", index); }
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for Quaternion {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => { return &mut self.x; }
+            1 => { return &mut self.y; }
+            2 => { return &mut self.z; }
+            3 => { return &mut self.w; }
+            _ => { panic!("Quaternion index out of range: {}", index); }
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix3x3 {
+    pub m: [[f32;3];3],
+}
+
+impl Matrix3x3 {
+    pub const fn identity() -> Matrix3x3 {
+        return Matrix3x3 { m: [
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ] };
+    }
+
+    /// Construct a rotation matrix
+    pub fn rotation(rotation: Quaternion) -> Matrix3x3 {
+        return Matrix4x4::rotation(rotation).to_matrix3x3();
+    }
+
+    /// Construct a scale matrix
+    pub fn scale(scale: Vector3) -> Matrix3x3 {
+        return Matrix3x3 { m: [
+            [scale.x, 0.0, 0.0],
+            [0.0, scale.y, 0.0],
+            [0.0, 0.0, scale.z],
+        ] };
+    }
+
+    /// Expand this matrix into a Matrix4x4 with no translation, suitable for loading into the SIMD register
+    pub fn to_matrix4x4(&self) -> Matrix4x4 {
+        return Matrix4x4 { m: [
+            [self.m[0][0], self.m[0][1], self.m[0][2], 0.0],
+            [self.m[1][0], self.m[1][1], self.m[1][2], 0.0],
+            [self.m[2][0], self.m[2][1], self.m[2][2], 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ] };
+    }
+
+    /// Produce a transposed copy of the matrix
+    pub fn transpose(&self) -> Matrix3x3 {
+        return Matrix3x3 { m: [
+            [self.m[0][0], self.m[1][0], self.m[2][0]],
+            [self.m[0][1], self.m[1][1], self.m[2][1]],
+            [self.m[0][2], self.m[1][2], self.m[2][2]],
+        ] };
+    }
+
+    /// Compute the determinant of the matrix
+    pub fn determinant(&self) -> f32 {
+        let m = &self.m;
+        return m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    }
+
+    /// Compute the inverse of the matrix, or None if the matrix is singular
+    pub fn inverse(&self) -> Option<Matrix3x3> {
+        let det = self.determinant();
+        if det == 0.0 {
+            return None;
+        }
+
+        let m = &self.m;
+        let inv_det = 1.0 / det;
+
+        return Some(Matrix3x3 { m: [
+            [
+                (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv_det,
+                (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det,
+                (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det,
+            ],
+            [
+                (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv_det,
+                (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det,
+                (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det,
+            ],
+            [
+                (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv_det,
+                (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det,
+                (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det,
+            ],
+        ] });
+    }
+
+    /// Check whether each element of this matrix is within epsilon of the other matrix
+    pub fn approx_eq(&self, other: &Matrix3x3, epsilon: f32) -> bool {
+        return self.m.iter().flatten().zip(other.m.iter().flatten()).all(|(a, b)| (a - b).abs() <= epsilon);
+    }
+
+    /// Check whether every element of this matrix is finite (not infinite or NaN)
+    pub fn is_finite(&self) -> bool {
+        return self.m.iter().flatten().all(|v| v.is_finite());
+    }
+}
+
+impl ops::Mul<Vector3> for Matrix3x3 {
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        let x = (rhs.x * self.m[0][0]) + (rhs.y * self.m[1][0]) + (rhs.z * self.m[2][0]);
+        let y = (rhs.x * self.m[0][1]) + (rhs.y * self.m[1][1]) + (rhs.z * self.m[2][1]);
+        let z = (rhs.x * self.m[0][2]) + (rhs.y * self.m[1][2]) + (rhs.z * self.m[2][2]);
+
+        return Vector3 { x: x, y: y, z: z };
+    }
+}
+
+impl ops::Mul<Matrix3x3> for Matrix3x3 {
+    type Output = Matrix3x3;
+
+    fn mul(self, rhs: Matrix3x3) -> Matrix3x3 {
+        let mut result = Matrix3x3 { m: [[0.0;3];3] };
+
+        for i in 0..3 {
+            for j in 0..3 {
+                result.m[i][j] = (self.m[i][0] * rhs.m[0][j]) + (self.m[i][1] * rhs.m[1][j]) + (self.m[i][2] * rhs.m[2][j]);
+            }
+        }
+
+        return result;
+    }
+}
+
+impl ops::MulAssign<Matrix3x3> for Matrix3x3 {
+    fn mul_assign(&mut self, rhs: Matrix3x3) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::Index<usize> for Matrix3x3 {
+    type Output = [f32;3];
+
+    fn index(&self, index: usize) -> &[f32;3] {
+        return &self.m[index];
+    }
+}
+
+impl ops::IndexMut<usize> for Matrix3x3 {
+    fn index_mut(&mut self, index: usize) -> &mut [f32;3] {
+        return &mut self.m[index];
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Matrix4x4 {
     pub m: [[f32;4];4],
 }
@@ -587,6 +1407,20 @@ impl Matrix4x4 {
         return result;
     }
 
+    /// Construct a view matrix for a camera at the given position looking towards a target point (the camera looks down -Z, matching projection_perspective)
+    pub fn look_at(eye: Vector3, target: Vector3, up: Vector3) -> Matrix4x4 {
+        let z = (eye - target).normalized();
+        let x = Vector3::cross(&up, &z).normalized();
+        let y = Vector3::cross(&z, &x);
+
+        return Matrix4x4 { m: [
+            [x.x, y.x, z.x, 0.0],
+            [x.y, y.y, z.y, 0.0],
+            [x.z, y.z, z.z, 0.0],
+            [-Vector3::dot(&x, &eye), -Vector3::dot(&y, &eye), -Vector3::dot(&z, &eye), 1.0],
+        ] };
+    }
+
     /// Construct a new off-center orthographic projection matrix
     pub fn projection_ortho(left: f32, right: f32, top: f32, bottom: f32, near: f32, far: f32) -> Matrix4x4 {
         let scale_x = 2.0 / (right - left);
@@ -643,6 +1477,59 @@ impl Matrix4x4 {
         return mat;
     }
 
+    /// Get a row of the matrix
+    pub fn row(&self, index: usize) -> Vector4 {
+        let r = &self.m[index];
+        return Vector4 { x: r[0], y: r[1], z: r[2], w: r[3] };
+    }
+
+    /// Get a column of the matrix
+    pub fn column(&self, index: usize) -> Vector4 {
+        return Vector4 { x: self.m[0][index], y: self.m[1][index], z: self.m[2][index], w: self.m[3][index] };
+    }
+
+    /// Set a row of the matrix
+    pub fn set_row(&mut self, index: usize, row: Vector4) {
+        self.m[index] = [row.x, row.y, row.z, row.w];
+    }
+
+    /// Set a column of the matrix
+    pub fn set_column(&mut self, index: usize, column: Vector4) {
+        self.m[0][index] = column.x;
+        self.m[1][index] = column.y;
+        self.m[2][index] = column.z;
+        self.m[3][index] = column.w;
+    }
+
+    /// Get the upper-left 3x3 portion of this matrix
+    pub fn to_matrix3x3(&self) -> Matrix3x3 {
+        return Matrix3x3 { m: [
+            [self.m[0][0], self.m[0][1], self.m[0][2]],
+            [self.m[1][0], self.m[1][1], self.m[1][2]],
+            [self.m[2][0], self.m[2][1], self.m[2][2]],
+        ] };
+    }
+
+    /// Compute the matrix used to transform normals by this matrix (the inverse transpose of the upper-left 3x3 portion) <br/>
+    /// Unlike transforming normals by the matrix itself, this remains correct under non-uniform scale
+    pub fn normal_matrix(&self) -> Matrix3x3 {
+        let m = self.to_matrix3x3();
+        return match m.inverse() {
+            Some(inv) => { inv.transpose() }
+            None => { m }
+        };
+    }
+
+    /// Check whether each element of this matrix is within epsilon of the other matrix
+    pub fn approx_eq(&self, other: &Matrix4x4, epsilon: f32) -> bool {
+        return self.m.iter().flatten().zip(other.m.iter().flatten()).all(|(a, b)| (a - b).abs() <= epsilon);
+    }
+
+    /// Check whether every element of this matrix is finite (not infinite or NaN)
+    pub fn is_finite(&self) -> bool {
+        return self.m.iter().flatten().all(|v| v.is_finite());
+    }
+
     /// Load an identity matrix into the SIMD register
     pub fn load_identity_simd() {
         let m = Matrix4x4::identity();
@@ -695,3 +1582,67 @@ impl ops::Mul<Vector4> for Matrix4x4 {
         return Vector4 { x: x, y: y, z: z, w: w };
     }
 }
+
+impl ops::Mul<Matrix4x4> for Matrix4x4 {
+    type Output = Matrix4x4;
+
+    fn mul(self, rhs: Matrix4x4) -> Matrix4x4 {
+        let mut result = Matrix4x4 { m: [[0.0;4];4] };
+
+        for i in 0..4 {
+            for j in 0..4 {
+                result.m[i][j] = (self.m[i][0] * rhs.m[0][j]) + (self.m[i][1] * rhs.m[1][j]) + (self.m[i][2] * rhs.m[2][j]) + (self.m[i][3] * rhs.m[3][j]);
+            }
+        }
+
+        return result;
+    }
+}
+
+impl ops::Mul<f32> for Matrix4x4 {
+    type Output = Matrix4x4;
+
+    fn mul(self, rhs: f32) -> Matrix4x4 {
+        let mut result = self;
+        result *= rhs;
+        return result;
+    }
+}
+
+impl ops::MulAssign<Matrix4x4> for Matrix4x4 {
+    fn mul_assign(&mut self, rhs: Matrix4x4) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::MulAssign<f32> for Matrix4x4 {
+    fn mul_assign(&mut self, rhs: f32) {
+        for row in &mut self.m {
+            for v in row {
+                *v *= rhs;
+            }
+        }
+    }
+}
+
+impl ops::Neg for Matrix4x4 {
+    type Output = Matrix4x4;
+
+    fn neg(self) -> Matrix4x4 {
+        return self * -1.0;
+    }
+}
+
+impl ops::Index<usize> for Matrix4x4 {
+    type Output = [f32;4];
+
+    fn index(&self, index: usize) -> &[f32;4] {
+        return &self.m[index];
+    }
+}
+
+impl ops::IndexMut<usize> for Matrix4x4 {
+    fn index_mut(&mut self, index: usize) -> &mut [f32;4] {
+        return &mut self.m[index];
+    }
+}
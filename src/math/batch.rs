@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use field_offset::FieldOffset;
+
+use crate::math::{Matrix4x4, Vector4};
+
+struct BatchOp<T> {
+    matrix: Matrix4x4,
+    range: Range<usize>,
+    field: FieldOffset<T,Vector4>,
+    // ops which touch the same vectors as an earlier op must run after it
+    pass: usize,
+}
+
+/// Records transforms of ranges of a vertex buffer (for example, one range per mesh part) & applies them with as few SIMD matrix loads & transform calls as possible <br/>
+/// Ranges sharing a matrix & field are transformed together when contiguous, and transforms are grouped by matrix so each is loaded as few times as possible. Transforms applied to the same vectors still run in the order they were added
+pub struct TransformBatch<T> {
+    ops: Vec<BatchOp<T>>,
+}
+
+impl<T> TransformBatch<T> {
+    /// Construct a new empty batch
+    pub fn new() -> TransformBatch<T> {
+        return TransformBatch {
+            ops: Vec::new(),
+        };
+    }
+
+    /// Record a transform of the given field in a range of vertices <br/>
+    /// Matrices can be combined on the CPU beforehand (such as a part transform multiplied by the model-view-projection matrix) to avoid extra mul_simd calls
+    pub fn add(&mut self, range: Range<usize>, matrix: &Matrix4x4, field: FieldOffset<T,Vector4>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let offset = field.get_byte_offset();
+        let pass = self.ops.iter()
+            .filter(|x| x.field.get_byte_offset() == offset && x.range.start < range.end && range.start < x.range.end)
+            .map(|x| x.pass + 1)
+            .max()
+            .unwrap_or(0);
+
+        self.ops.push(BatchOp { matrix: *matrix, range: range, field: field, pass: pass });
+    }
+
+    /// Get the number of transforms recorded
+    pub fn len(&self) -> usize {
+        return self.ops.len();
+    }
+
+    /// Gets whether no transforms have been recorded
+    pub fn is_empty(&self) -> bool {
+        return self.ops.is_empty();
+    }
+
+    /// Remove every recorded transform
+    pub fn clear(&mut self) {
+        self.ops.clear();
+    }
+
+    /// Apply every recorded transform to the given vertices & clear the batch, returning the number of transform calls made <br/>
+    /// Panics if any recorded range is outside of the vertex buffer
+    pub fn apply(&mut self, vertices: &mut [T]) -> usize {
+        // group ops by pass, then by matrix & field, keeping ranges in order so contiguous ones can be merged
+        self.ops.sort_by(|a, b| {
+            a.pass.cmp(&b.pass)
+                .then_with(|| a.matrix.m.iter().flatten().map(|x| x.to_bits()).cmp(b.matrix.m.iter().flatten().map(|x| x.to_bits())))
+                .then_with(|| a.field.get_byte_offset().cmp(&b.field.get_byte_offset()))
+                .then_with(|| a.range.start.cmp(&b.range.start))
+        });
+
+        let mut calls = 0;
+        let mut loaded: Option<Matrix4x4> = None;
+        let mut idx = 0;
+
+        while idx < self.ops.len() {
+            let op = &self.ops[idx];
+            let mut end = op.range.end;
+
+            // extend over following ranges which continue this one with the same matrix & field
+            idx += 1;
+            while let Some(next) = self.ops.get(idx) {
+                if next.pass != op.pass || next.matrix.m != op.matrix.m || next.field.get_byte_offset() != op.field.get_byte_offset() || next.range.start > end {
+                    break;
+                }
+                end = end.max(next.range.end);
+                idx += 1;
+            }
+
+            // the SIMD register keeps its value between transforms, so only reload it when the matrix changes
+            let is_loaded = match &loaded {
+                Some(m) => { m.m == op.matrix.m },
+                None => { false }
+            };
+
+            if !is_loaded {
+                Matrix4x4::load_simd(&op.matrix);
+                loaded = Some(op.matrix);
+            }
+
+            Matrix4x4::transform_vertex_simd(&mut vertices[op.range.start..end], op.field);
+            calls += 1;
+        }
+
+        self.ops.clear();
+        return calls;
+    }
+}
+
+impl<T> Default for TransformBatch<T> {
+    fn default() -> Self {
+        return TransformBatch::new();
+    }
+}
@@ -0,0 +1,143 @@
+use crate::math::{Matrix4x4, Vector3, Vector4};
+
+/// An axis-aligned bounding box
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Aabb {
+    /// An empty box, which contains nothing and can be grown by adding points
+    pub const EMPTY: Aabb = Aabb { min: Vector3::new(f32::MAX, f32::MAX, f32::MAX), max: Vector3::new(f32::MIN, f32::MIN, f32::MIN) };
+
+    pub const fn new(min: Vector3, max: Vector3) -> Aabb {
+        return Aabb { min: min, max: max };
+    }
+
+    /// Construct a box from its center & half size along each axis
+    pub fn from_center_extents(center: Vector3, extents: Vector3) -> Aabb {
+        return Aabb { min: center - extents, max: center + extents };
+    }
+
+    /// Construct the smallest box containing every given point (empty if there are no points)
+    pub fn from_points<I>(points: I) -> Aabb where I : IntoIterator<Item = Vector3> {
+        let mut aabb = Aabb::EMPTY;
+        for p in points {
+            aabb.add_point(p);
+        }
+        return aabb;
+    }
+
+    /// Gets whether this box contains nothing
+    pub fn is_empty(&self) -> bool {
+        return self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z;
+    }
+
+    pub fn center(&self) -> Vector3 {
+        return (self.min + self.max) * 0.5;
+    }
+
+    /// Get the half size of the box along each axis
+    pub fn extents(&self) -> Vector3 {
+        return (self.max - self.min) * 0.5;
+    }
+
+    /// Grow the box to contain the given point
+    pub fn add_point(&mut self, point: Vector3) {
+        self.min = Vector3::new(self.min.x.min(point.x), self.min.y.min(point.y), self.min.z.min(point.z));
+        self.max = Vector3::new(self.max.x.max(point.x), self.max.y.max(point.y), self.max.z.max(point.z));
+    }
+
+    /// Construct the smallest box containing both boxes
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        return Aabb {
+            min: Vector3::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z)),
+            max: Vector3::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z)),
+        };
+    }
+
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        return point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+            && point.z >= self.min.z && point.z <= self.max.z;
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        return self.min.x <= other.max.x && self.max.x >= other.min.x
+            && self.min.y <= other.max.y && self.max.y >= other.min.y
+            && self.min.z <= other.max.z && self.max.z >= other.min.z;
+    }
+
+    /// Compute the axis-aligned box containing this box after transformation by the given matrix
+    pub fn transformed(&self, mat: &Matrix4x4) -> Aabb {
+        if self.is_empty() {
+            return *self;
+        }
+
+        let c = self.center();
+        let e = self.extents();
+
+        let mut center = Vector3::new(mat.m[3][0], mat.m[3][1], mat.m[3][2]);
+        let mut extents = Vector3::zero();
+        for (i, (ci, ei)) in [(c.x, e.x), (c.y, e.y), (c.z, e.z)].into_iter().enumerate() {
+            let row = Vector3::new(mat.m[i][0], mat.m[i][1], mat.m[i][2]);
+            center += row * ci;
+            extents += Vector3::new(row.x.abs(), row.y.abs(), row.z.abs()) * ei;
+        }
+
+        return Aabb::from_center_extents(center, extents);
+    }
+}
+
+/// The six planes bounding a camera's view volume, each stored as (normal, distance) with normals pointing inwards
+#[derive(Clone, Copy)]
+pub struct Frustum {
+    pub planes: [Vector4;6],
+}
+
+impl Frustum {
+    /// Extract the frustum from a combined view & projection matrix (planes are in the space that the matrix transforms from, usually world space)
+    pub fn from_matrix(view_proj: &Matrix4x4) -> Frustum {
+        let m = &view_proj.m;
+        let col = |j: usize| Vector4::new(m[0][j], m[1][j], m[2][j], m[3][j]);
+        let (c0, c1, c2, c3) = (col(0), col(1), col(2), col(3));
+
+        // clip space depth runs from 0 at the near plane to w at the far plane
+        let mut planes = [c3 + c0, c3 - c0, c3 + c1, c3 - c1, c2, c3 - c2];
+        for p in &mut planes {
+            let len = Vector3::new(p.x, p.y, p.z).length();
+            if len > 0.0 {
+                *p *= 1.0 / len;
+            }
+        }
+
+        return Frustum { planes: planes };
+    }
+
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        return self.planes.iter().all(|p| p.x * point.x + p.y * point.y + p.z * point.z + p.w >= 0.0);
+    }
+
+    /// Gets whether the given sphere is at least partially inside the frustum
+    pub fn intersects_sphere(&self, center: Vector3, radius: f32) -> bool {
+        return self.planes.iter().all(|p| p.x * center.x + p.y * center.y + p.z * center.z + p.w >= -radius);
+    }
+
+    /// Gets whether the given box is at least partially inside the frustum (may conservatively return true for boxes just outside a corner)
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        if aabb.is_empty() {
+            return false;
+        }
+
+        let c = aabb.center();
+        let e = aabb.extents();
+
+        return self.planes.iter().all(|p| {
+            let dist = p.x * c.x + p.y * c.y + p.z * c.z + p.w;
+            let radius = p.x.abs() * e.x + p.y.abs() * e.y + p.z.abs() * e.z;
+            return dist >= -radius;
+        });
+    }
+}
@@ -0,0 +1,294 @@
+use std::ops;
+
+use crate::{easing::Easing, math::{Vector2, Vector3}};
+
+/// Trait for vector types which can be evaluated along a curve
+pub trait CurvePoint : Clone + Copy + ops::Add<Self, Output = Self> + ops::Sub<Self, Output = Self> + ops::Mul<f32, Output = Self> {
+    /// Compute the distance between two points
+    fn distance(lhs: &Self, rhs: &Self) -> f32;
+}
+
+impl CurvePoint for Vector2 {
+    fn distance(lhs: &Vector2, rhs: &Vector2) -> f32 {
+        return Vector2::distance(lhs, rhs);
+    }
+}
+
+impl CurvePoint for Vector3 {
+    fn distance(lhs: &Vector3, rhs: &Vector3) -> f32 {
+        return Vector3::distance(lhs, rhs);
+    }
+}
+
+/// Evaluate a uniform Catmull-Rom segment between p1 and p2 at the given time (0.0 .. 1.0)
+pub fn catmull_rom<T: CurvePoint>(p0: T, p1: T, p2: T, p3: T, t: f32) -> T {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    return ((p1 * 2.0)
+        + ((p2 - p0) * t)
+        + (((p0 * 2.0) - (p1 * 5.0) + (p2 * 4.0) - p3) * t2)
+        + (((p1 * 3.0) - p0 - (p2 * 3.0) + p3) * t3)) * 0.5;
+}
+
+/// Evaluate the tangent (first derivative) of a uniform Catmull-Rom segment at the given time (0.0 .. 1.0)
+pub fn catmull_rom_tangent<T: CurvePoint>(p0: T, p1: T, p2: T, p3: T, t: f32) -> T {
+    let t2 = t * t;
+
+    return ((p2 - p0)
+        + (((p0 * 2.0) - (p1 * 5.0) + (p2 * 4.0) - p3) * (2.0 * t))
+        + (((p1 * 3.0) - p0 - (p2 * 3.0) + p3) * (3.0 * t2))) * 0.5;
+}
+
+/// Evaluate a quadratic Bezier curve at the given time (0.0 .. 1.0)
+pub fn quadratic_bezier<T: CurvePoint>(p0: T, p1: T, p2: T, t: f32) -> T {
+    let u = 1.0 - t;
+    return (p0 * (u * u)) + (p1 * (2.0 * u * t)) + (p2 * (t * t));
+}
+
+/// Evaluate a cubic Bezier curve at the given time (0.0 .. 1.0)
+pub fn cubic_bezier<T: CurvePoint>(p0: T, p1: T, p2: T, p3: T, t: f32) -> T {
+    let u = 1.0 - t;
+    let u2 = u * u;
+    let t2 = t * t;
+    return (p0 * (u2 * u)) + (p1 * (3.0 * u2 * t)) + (p2 * (3.0 * u * t2)) + (p3 * (t2 * t));
+}
+
+/// Evaluate the tangent (first derivative) of a cubic Bezier curve at the given time (0.0 .. 1.0)
+pub fn cubic_bezier_tangent<T: CurvePoint>(p0: T, p1: T, p2: T, p3: T, t: f32) -> T {
+    let u = 1.0 - t;
+    return ((p1 - p0) * (3.0 * u * u)) + ((p2 - p1) * (6.0 * u * t)) + ((p3 - p2) * (3.0 * t * t));
+}
+
+/// A Catmull-Rom spline passing through a list of control points
+#[derive(Clone)]
+pub struct CatmullRomSpline<T> where T : CurvePoint {
+    pub points: Vec<T>,
+    pub looping: bool,
+}
+
+impl<T> CatmullRomSpline<T> where T : CurvePoint {
+    /// Construct a new spline passing through the given points
+    pub fn new(points: Vec<T>, looping: bool) -> CatmullRomSpline<T> {
+        return CatmullRomSpline { points: points, looping: looping };
+    }
+
+    /// Get the number of segments in the spline
+    pub fn segment_count(&self) -> usize {
+        let n = self.points.len();
+        if n < 2 {
+            return 0;
+        }
+        return if self.looping { n } else { n - 1 };
+    }
+
+    fn point(&self, index: isize) -> T {
+        let n = self.points.len() as isize;
+        let idx = if self.looping {
+            index.rem_euclid(n)
+        } else {
+            index.clamp(0, n - 1)
+        };
+        return self.points[idx as usize];
+    }
+
+    fn segment(&self, t: f32) -> (isize, f32) {
+        let segments = self.segment_count();
+        let st = t.clamp(0.0, 1.0) * segments as f32;
+        let seg = (st.floor() as usize).min(segments - 1);
+        return (seg as isize, st - seg as f32);
+    }
+
+    /// Evaluate the spline at the given normalized time (0.0 .. 1.0 across the entire spline)
+    pub fn evaluate(&self, t: f32) -> T {
+        assert!(!self.points.is_empty(), "Cannot evaluate spline with no points");
+        if self.points.len() == 1 {
+            return self.points[0];
+        }
+
+        let (seg, lt) = self.segment(t);
+        return catmull_rom(self.point(seg - 1), self.point(seg), self.point(seg + 1), self.point(seg + 2), lt);
+    }
+
+    /// Evaluate the tangent of the spline at the given normalized time (0.0 .. 1.0 across the entire spline)
+    pub fn tangent(&self, t: f32) -> T {
+        assert!(self.points.len() >= 2, "Cannot evaluate tangent of spline with fewer than two points");
+
+        let (seg, lt) = self.segment(t);
+        return catmull_rom_tangent(self.point(seg - 1), self.point(seg), self.point(seg + 1), self.point(seg + 2), lt);
+    }
+}
+
+/// A control point of a TimedSpline
+#[derive(Clone, Copy)]
+pub struct SplineKey<T> where T : CurvePoint {
+    /// Time in seconds at which the spline passes through this point
+    pub time: f32,
+    pub point: T,
+    /// Easing applied to the time between this key & the next (Linear for constant timing, InOut curves to slow down into & out of keys)
+    pub easing: Easing,
+}
+
+/// A Catmull-Rom spline whose control points are placed at specific times rather than spaced evenly
+#[derive(Clone)]
+pub struct TimedSpline<T> where T : CurvePoint {
+    keys: Vec<SplineKey<T>>,
+}
+
+impl<T> TimedSpline<T> where T : CurvePoint {
+    /// Construct a new spline with no keys
+    pub fn new() -> TimedSpline<T> {
+        return TimedSpline { keys: Vec::new() };
+    }
+
+    /// Insert a key into the spline, keeping keys ordered by time
+    pub fn insert_key(&mut self, time: f32, point: T, easing: Easing) {
+        let idx = self.keys.partition_point(|k| k.time <= time);
+        self.keys.insert(idx, SplineKey { time: time, point: point, easing: easing });
+    }
+
+    /// Get the keys of the spline, ordered by time
+    pub fn keys(&self) -> &[SplineKey<T>] {
+        return &self.keys;
+    }
+
+    /// Remove every key from the spline
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// Get the time of the last key in the spline
+    pub fn duration(&self) -> f32 {
+        return match self.keys.last() {
+            Some(v) => { v.time },
+            None => { 0.0 }
+        };
+    }
+
+    /// Evaluate the spline at the given time in seconds (clamped to the first & last keys), or None if it has no keys
+    pub fn evaluate(&self, time: f32) -> Option<T> {
+        let (seg, t) = self.segment(time)?;
+        return Some(catmull_rom(self.point(seg - 1), self.point(seg), self.point(seg + 1), self.point(seg + 2), t));
+    }
+
+    /// Evaluate the tangent of the spline at the given time in seconds (relative to the segment containing that time), or None if it has fewer than two keys
+    pub fn tangent(&self, time: f32) -> Option<T> {
+        if self.keys.len() < 2 {
+            return None;
+        }
+
+        let (seg, t) = self.segment(time)?;
+        return Some(catmull_rom_tangent(self.point(seg - 1), self.point(seg), self.point(seg + 1), self.point(seg + 2), t));
+    }
+
+    fn point(&self, index: isize) -> T {
+        return self.keys[index.clamp(0, self.keys.len() as isize - 1) as usize].point;
+    }
+
+    // find the segment containing the given time & the eased time within it
+    fn segment(&self, time: f32) -> Option<(isize, f32)> {
+        if self.keys.is_empty() {
+            return None;
+        }
+
+        let idx = self.keys.partition_point(|k| k.time <= time);
+        if idx == 0 {
+            return Some((0, 0.0));
+        }
+        if idx >= self.keys.len() {
+            return Some((self.keys.len() as isize - 1, 0.0));
+        }
+
+        let k0 = &self.keys[idx - 1];
+        let k1 = &self.keys[idx];
+        let t = (time - k0.time) / (k1.time - k0.time);
+        return Some(((idx - 1) as isize, k0.easing.apply(t.clamp(0.0, 1.0))));
+    }
+}
+
+impl<T> Default for TimedSpline<T> where T : CurvePoint {
+    fn default() -> Self {
+        return TimedSpline::new();
+    }
+}
+
+/// A cubic Bezier curve
+#[derive(Clone, Copy)]
+pub struct CubicBezier<T> where T : CurvePoint {
+    pub p0: T,
+    pub p1: T,
+    pub p2: T,
+    pub p3: T,
+}
+
+impl<T> CubicBezier<T> where T : CurvePoint {
+    pub fn new(p0: T, p1: T, p2: T, p3: T) -> CubicBezier<T> {
+        return CubicBezier { p0: p0, p1: p1, p2: p2, p3: p3 };
+    }
+
+    /// Evaluate the curve at the given time (0.0 .. 1.0)
+    pub fn evaluate(&self, t: f32) -> T {
+        return cubic_bezier(self.p0, self.p1, self.p2, self.p3, t);
+    }
+
+    /// Evaluate the tangent of the curve at the given time (0.0 .. 1.0)
+    pub fn tangent(&self, t: f32) -> T {
+        return cubic_bezier_tangent(self.p0, self.p1, self.p2, self.p3, t);
+    }
+}
+
+/// Lookup table mapping distance along a curve to curve parameter, used to move along a curve at constant speed
+#[derive(Clone)]
+pub struct ArcLengthTable {
+    lengths: Vec<f32>,
+}
+
+impl ArcLengthTable {
+    /// Build a table by sampling the given curve function (defined over 0.0 .. 1.0) at the given number of evenly spaced steps
+    pub fn new<T, F>(samples: usize, curve: F) -> ArcLengthTable where T : CurvePoint, F : Fn(f32) -> T {
+        let samples = samples.max(1);
+        let mut lengths: Vec<f32> = Vec::with_capacity(samples + 1);
+        let mut prev = curve(0.0);
+        let mut total = 0.0;
+
+        lengths.push(0.0);
+        for i in 1..=samples {
+            let p = curve(i as f32 / samples as f32);
+            total += T::distance(&prev, &p);
+            lengths.push(total);
+            prev = p;
+        }
+
+        return ArcLengthTable { lengths: lengths };
+    }
+
+    /// Get the approximate total length of the curve
+    pub fn total_length(&self) -> f32 {
+        return *self.lengths.last().unwrap();
+    }
+
+    /// Convert a distance along the curve into a curve parameter (0.0 .. 1.0)
+    pub fn parameter_at_distance(&self, distance: f32) -> f32 {
+        let total = self.total_length();
+        if total <= 0.0 || distance <= 0.0 {
+            return 0.0;
+        }
+        if distance >= total {
+            return 1.0;
+        }
+
+        // find the first sample at or beyond the requested distance & interpolate with the one before it
+        let idx = self.lengths.partition_point(|l| *l < distance).max(1);
+        let l0 = self.lengths[idx - 1];
+        let l1 = self.lengths[idx];
+        let segment_t = if l1 > l0 { (distance - l0) / (l1 - l0) } else { 0.0 };
+        let steps = (self.lengths.len() - 1) as f32;
+
+        return ((idx - 1) as f32 + segment_t) / steps;
+    }
+
+    /// Convert a normalized distance along the curve (0.0 .. 1.0 of total length) into a curve parameter (0.0 .. 1.0)
+    pub fn parameter_at_fraction(&self, fraction: f32) -> f32 {
+        return self.parameter_at_distance(fraction * self.total_length());
+    }
+}
@@ -0,0 +1,631 @@
+use std::ops;
+
+use crate::math::{Vector2, Vector3};
+
+const FRAC_BITS: u32 = 16;
+
+/// A signed Q16.16 fixed-point number
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed {
+    pub raw: i32,
+}
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed { raw: 0 };
+    pub const ONE: Fixed = Fixed { raw: 1 << FRAC_BITS };
+    pub const HALF: Fixed = Fixed { raw: 1 << (FRAC_BITS - 1) };
+    pub const MIN: Fixed = Fixed { raw: i32::MIN };
+    pub const MAX: Fixed = Fixed { raw: i32::MAX };
+    pub const EPSILON: Fixed = Fixed { raw: 1 };
+
+    /// Construct a fixed-point number from its raw Q16.16 representation
+    pub const fn from_raw(raw: i32) -> Fixed {
+        return Fixed { raw: raw };
+    }
+
+    /// Construct a fixed-point number from an integer
+    pub const fn from_int(v: i32) -> Fixed {
+        return Fixed { raw: v << FRAC_BITS };
+    }
+
+    /// Construct a fixed-point number from a float, rounding to the nearest representable value
+    pub fn from_f32(v: f32) -> Fixed {
+        return Fixed { raw: (v * (1 << FRAC_BITS) as f32).round() as i32 };
+    }
+
+    /// Convert to a float
+    pub fn to_f32(self) -> f32 {
+        return self.raw as f32 / (1 << FRAC_BITS) as f32;
+    }
+
+    /// Convert to an integer, rounding towards negative infinity
+    pub const fn to_int(self) -> i32 {
+        return self.raw >> FRAC_BITS;
+    }
+
+    /// Round towards negative infinity
+    pub const fn floor(self) -> Fixed {
+        return Fixed { raw: self.raw & !((1 << FRAC_BITS) - 1) };
+    }
+
+    /// Round towards positive infinity (wrapping around for values above the largest representable integer)
+    pub const fn ceil(self) -> Fixed {
+        return Fixed { raw: self.raw.wrapping_add((1 << FRAC_BITS) - 1) & !((1 << FRAC_BITS) - 1) };
+    }
+
+    /// Get the fractional part of the number
+    pub const fn fract(self) -> Fixed {
+        return Fixed { raw: self.raw & ((1 << FRAC_BITS) - 1) };
+    }
+
+    /// Compute the absolute value
+    pub const fn abs(self) -> Fixed {
+        return Fixed { raw: self.raw.wrapping_abs() };
+    }
+
+    /// Get the smaller of two values
+    pub fn min(self, other: Fixed) -> Fixed {
+        return if self.raw < other.raw { self } else { other };
+    }
+
+    /// Get the larger of two values
+    pub fn max(self, other: Fixed) -> Fixed {
+        return if self.raw > other.raw { self } else { other };
+    }
+
+    /// Clamp the value between the given minimum and maximum
+    pub fn clamp(self, min: Fixed, max: Fixed) -> Fixed {
+        return self.max(min).min(max);
+    }
+
+    /// Compute the square root (returns zero for negative inputs)
+    pub fn sqrt(self) -> Fixed {
+        if self.raw <= 0 {
+            return Fixed::ZERO;
+        }
+
+        // sqrt(raw / 2^16) * 2^16 == sqrt(raw * 2^16)
+        let n = (self.raw as u64) << FRAC_BITS;
+        let mut x = n;
+        let mut y = x.div_ceil(2);
+        while y < x {
+            x = y;
+            y = (x + n / x) / 2;
+        }
+
+        return Fixed { raw: x as i32 };
+    }
+
+    /// Linearly interpolate between two values
+    pub fn lerp(a: Fixed, b: Fixed, t: Fixed) -> Fixed {
+        return a + ((b - a) * t);
+    }
+}
+
+impl From<i32> for Fixed {
+    fn from(v: i32) -> Fixed {
+        return Fixed::from_int(v);
+    }
+}
+
+impl ops::Add<Fixed> for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        return Fixed { raw: self.raw.wrapping_add(rhs.raw) };
+    }
+}
+
+impl ops::Sub<Fixed> for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        return Fixed { raw: self.raw.wrapping_sub(rhs.raw) };
+    }
+}
+
+impl ops::Mul<Fixed> for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Fixed) -> Fixed {
+        return Fixed { raw: ((self.raw as i64 * rhs.raw as i64) >> FRAC_BITS) as i32 };
+    }
+}
+
+impl ops::Div<Fixed> for Fixed {
+    type Output = Fixed;
+
+    fn div(self, rhs: Fixed) -> Fixed {
+        // saturate instead of panicking on division by zero
+        if rhs.raw == 0 {
+            return if self.raw > 0 { Fixed::MAX } else if self.raw < 0 { Fixed::MIN } else { Fixed::ZERO };
+        }
+
+        return Fixed { raw: (((self.raw as i64) << FRAC_BITS) / rhs.raw as i64) as i32 };
+    }
+}
+
+impl ops::Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        return Fixed { raw: self.raw.wrapping_neg() };
+    }
+}
+
+impl ops::AddAssign<Fixed> for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::SubAssign<Fixed> for Fixed {
+    fn sub_assign(&mut self, rhs: Fixed) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::MulAssign<Fixed> for Fixed {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::DivAssign<Fixed> for Fixed {
+    fn div_assign(&mut self, rhs: Fixed) {
+        *self = *self / rhs;
+    }
+}
+
+/// A 2D vector of Q16.16 fixed-point numbers
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedVector2 {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedVector2 {
+    pub const fn new(x: Fixed, y: Fixed) -> FixedVector2 {
+        return FixedVector2 { x: x, y: y };
+    }
+
+    pub const fn zero() -> FixedVector2 {
+        return FixedVector2 { x: Fixed::ZERO, y: Fixed::ZERO };
+    }
+
+    pub const fn unit_x() -> FixedVector2 {
+        return FixedVector2 { x: Fixed::ONE, y: Fixed::ZERO };
+    }
+
+    pub const fn unit_y() -> FixedVector2 {
+        return FixedVector2 { x: Fixed::ZERO, y: Fixed::ONE };
+    }
+
+    /// Construct a fixed-point vector from a float vector
+    pub fn from_vector2(v: Vector2) -> FixedVector2 {
+        return FixedVector2 { x: Fixed::from_f32(v.x), y: Fixed::from_f32(v.y) };
+    }
+
+    /// Convert to a float vector
+    pub fn to_vector2(self) -> Vector2 {
+        return Vector2 { x: self.x.to_f32(), y: self.y.to_f32() };
+    }
+
+    /// Compute the squared distance between two vectors
+    pub fn distance_sq(lhs: &FixedVector2, rhs: &FixedVector2) -> Fixed {
+        let dx = lhs.x - rhs.x;
+        let dy = lhs.y - rhs.y;
+        return (dx * dx) + (dy * dy);
+    }
+
+    /// Compute the distance between two vectors
+    pub fn distance(lhs: &FixedVector2, rhs: &FixedVector2) -> Fixed {
+        return FixedVector2::distance_sq(lhs, rhs).sqrt();
+    }
+
+    /// Compute the squared length of the vector
+    pub fn length_sq(self) -> Fixed {
+        return (self.x * self.x) + (self.y * self.y);
+    }
+
+    /// Compute the length of the vector
+    pub fn length(self) -> Fixed {
+        return self.length_sq().sqrt();
+    }
+
+    /// Normalize the vector (zero-length vectors are left unchanged)
+    pub fn normalize(&mut self) {
+        *self = self.normalized();
+    }
+
+    /// Produce a normalized copy of the vector (zero-length vectors are returned unchanged)
+    pub fn normalized(&self) -> FixedVector2 {
+        let len = self.length();
+        if len == Fixed::ZERO {
+            return *self;
+        }
+        return FixedVector2 { x: self.x / len, y: self.y / len };
+    }
+
+    /// Compute the dot product of two vectors
+    pub fn dot(lhs: &FixedVector2, rhs: &FixedVector2) -> Fixed {
+        return (lhs.x * rhs.x) + (lhs.y * rhs.y);
+    }
+}
+
+impl ops::Add<FixedVector2> for FixedVector2 {
+    type Output = FixedVector2;
+
+    fn add(self, rhs: FixedVector2) -> FixedVector2 {
+        return FixedVector2 { x: self.x + rhs.x, y: self.y + rhs.y };
+    }
+}
+
+impl ops::Sub<FixedVector2> for FixedVector2 {
+    type Output = FixedVector2;
+
+    fn sub(self, rhs: FixedVector2) -> FixedVector2 {
+        return FixedVector2 { x: self.x - rhs.x, y: self.y - rhs.y };
+    }
+}
+
+impl ops::Mul<FixedVector2> for FixedVector2 {
+    type Output = FixedVector2;
+
+    fn mul(self, rhs: FixedVector2) -> FixedVector2 {
+        return FixedVector2 { x: self.x * rhs.x, y: self.y * rhs.y };
+    }
+}
+
+impl ops::Mul<Fixed> for FixedVector2 {
+    type Output = FixedVector2;
+
+    fn mul(self, rhs: Fixed) -> FixedVector2 {
+        return FixedVector2 { x: self.x * rhs, y: self.y * rhs };
+    }
+}
+
+impl ops::Mul<FixedVector2> for Fixed {
+    type Output = FixedVector2;
+
+    fn mul(self, rhs: FixedVector2) -> FixedVector2 {
+        return FixedVector2 { x: self * rhs.x, y: self * rhs.y };
+    }
+}
+
+impl ops::Div<FixedVector2> for FixedVector2 {
+    type Output = FixedVector2;
+
+    fn div(self, rhs: FixedVector2) -> FixedVector2 {
+        return FixedVector2 { x: self.x / rhs.x, y: self.y / rhs.y };
+    }
+}
+
+impl ops::Div<Fixed> for FixedVector2 {
+    type Output = FixedVector2;
+
+    fn div(self, rhs: Fixed) -> FixedVector2 {
+        return FixedVector2 { x: self.x / rhs, y: self.y / rhs };
+    }
+}
+
+impl ops::Div<FixedVector2> for Fixed {
+    type Output = FixedVector2;
+
+    fn div(self, rhs: FixedVector2) -> FixedVector2 {
+        return FixedVector2 { x: self / rhs.x, y: self / rhs.y };
+    }
+}
+
+impl ops::AddAssign<FixedVector2> for FixedVector2 {
+    fn add_assign(&mut self, rhs: FixedVector2) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl ops::SubAssign<FixedVector2> for FixedVector2 {
+    fn sub_assign(&mut self, rhs: FixedVector2) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl ops::MulAssign<FixedVector2> for FixedVector2 {
+    fn mul_assign(&mut self, rhs: FixedVector2) {
+        self.x *= rhs.x;
+        self.y *= rhs.y;
+    }
+}
+
+impl ops::MulAssign<Fixed> for FixedVector2 {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl ops::DivAssign<FixedVector2> for FixedVector2 {
+    fn div_assign(&mut self, rhs: FixedVector2) {
+        self.x /= rhs.x;
+        self.y /= rhs.y;
+    }
+}
+
+impl ops::DivAssign<Fixed> for FixedVector2 {
+    fn div_assign(&mut self, rhs: Fixed) {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
+impl ops::Neg for FixedVector2 {
+    type Output = FixedVector2;
+
+    fn neg(self) -> FixedVector2 {
+        return FixedVector2 { x: -self.x, y: -self.y };
+    }
+}
+
+impl ops::Index<usize> for FixedVector2 {
+    type Output = Fixed;
+
+    fn index(&self, index: usize) -> &Fixed {
+        match index {
+            0 => { return &self.x; }
+            1 => { return &self.y; }
+            _ => { panic!("FixedVector2 index out of range: {}", index); }
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for FixedVector2 {
+    fn index_mut(&mut self, index: usize) -> &mut Fixed {
+        match index {
+            0 => { return &mut self.x; }
+            1 => { return &mut self.y; }
+            _ => { panic!("FixedVector2 index out of range: {}", index); }
+        }
+    }
+}
+
+/// A 3D vector of Q16.16 fixed-point numbers
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedVector3 {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+}
+
+impl FixedVector3 {
+    pub const fn new(x: Fixed, y: Fixed, z: Fixed) -> FixedVector3 {
+        return FixedVector3 { x: x, y: y, z: z };
+    }
+
+    pub const fn zero() -> FixedVector3 {
+        return FixedVector3 { x: Fixed::ZERO, y: Fixed::ZERO, z: Fixed::ZERO };
+    }
+
+    pub const fn unit_x() -> FixedVector3 {
+        return FixedVector3 { x: Fixed::ONE, y: Fixed::ZERO, z: Fixed::ZERO };
+    }
+
+    pub const fn unit_y() -> FixedVector3 {
+        return FixedVector3 { x: Fixed::ZERO, y: Fixed::ONE, z: Fixed::ZERO };
+    }
+
+    pub const fn unit_z() -> FixedVector3 {
+        return FixedVector3 { x: Fixed::ZERO, y: Fixed::ZERO, z: Fixed::ONE };
+    }
+
+    /// Construct a fixed-point vector from a float vector
+    pub fn from_vector3(v: Vector3) -> FixedVector3 {
+        return FixedVector3 { x: Fixed::from_f32(v.x), y: Fixed::from_f32(v.y), z: Fixed::from_f32(v.z) };
+    }
+
+    /// Convert to a float vector
+    pub fn to_vector3(self) -> Vector3 {
+        return Vector3 { x: self.x.to_f32(), y: self.y.to_f32(), z: self.z.to_f32() };
+    }
+
+    /// Compute the squared distance between two vectors
+    pub fn distance_sq(lhs: &FixedVector3, rhs: &FixedVector3) -> Fixed {
+        let dx = lhs.x - rhs.x;
+        let dy = lhs.y - rhs.y;
+        let dz = lhs.z - rhs.z;
+        return (dx * dx) + (dy * dy) + (dz * dz);
+    }
+
+    /// Compute the distance between two vectors
+    pub fn distance(lhs: &FixedVector3, rhs: &FixedVector3) -> Fixed {
+        return FixedVector3::distance_sq(lhs, rhs).sqrt();
+    }
+
+    /// Compute the squared length of the vector
+    pub fn length_sq(self) -> Fixed {
+        return (self.x * self.x) + (self.y * self.y) + (self.z * self.z);
+    }
+
+    /// Compute the length of the vector
+    pub fn length(self) -> Fixed {
+        return self.length_sq().sqrt();
+    }
+
+    /// Normalize the vector (zero-length vectors are left unchanged)
+    pub fn normalize(&mut self) {
+        *self = self.normalized();
+    }
+
+    /// Produce a normalized copy of the vector (zero-length vectors are returned unchanged)
+    pub fn normalized(&self) -> FixedVector3 {
+        let len = self.length();
+        if len == Fixed::ZERO {
+            return *self;
+        }
+        return FixedVector3 { x: self.x / len, y: self.y / len, z: self.z / len };
+    }
+
+    /// Compute the dot product of two vectors
+    pub fn dot(lhs: &FixedVector3, rhs: &FixedVector3) -> Fixed {
+        return (lhs.x * rhs.x) + (lhs.y * rhs.y) + (lhs.z * rhs.z);
+    }
+
+    /// Compute the cross product of two vectors
+    pub fn cross(lhs: &FixedVector3, rhs: &FixedVector3) -> FixedVector3 {
+        return FixedVector3 {
+            x: lhs.y * rhs.z - lhs.z * rhs.y,
+            y: lhs.z * rhs.x - lhs.x * rhs.z,
+            z: lhs.x * rhs.y - lhs.y * rhs.x
+        };
+    }
+}
+
+impl ops::Add<FixedVector3> for FixedVector3 {
+    type Output = FixedVector3;
+
+    fn add(self, rhs: FixedVector3) -> FixedVector3 {
+        return FixedVector3 { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z };
+    }
+}
+
+impl ops::Sub<FixedVector3> for FixedVector3 {
+    type Output = FixedVector3;
+
+    fn sub(self, rhs: FixedVector3) -> FixedVector3 {
+        return FixedVector3 { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z };
+    }
+}
+
+impl ops::Mul<FixedVector3> for FixedVector3 {
+    type Output = FixedVector3;
+
+    fn mul(self, rhs: FixedVector3) -> FixedVector3 {
+        return FixedVector3 { x: self.x * rhs.x, y: self.y * rhs.y, z: self.z * rhs.z };
+    }
+}
+
+impl ops::Mul<Fixed> for FixedVector3 {
+    type Output = FixedVector3;
+
+    fn mul(self, rhs: Fixed) -> FixedVector3 {
+        return FixedVector3 { x: self.x * rhs, y: self.y * rhs, z: self.z * rhs };
+    }
+}
+
+impl ops::Mul<FixedVector3> for Fixed {
+    type Output = FixedVector3;
+
+    fn mul(self, rhs: FixedVector3) -> FixedVector3 {
+        return FixedVector3 { x: self * rhs.x, y: self * rhs.y, z: self * rhs.z };
+    }
+}
+
+impl ops::Div<FixedVector3> for FixedVector3 {
+    type Output = FixedVector3;
+
+    fn div(self, rhs: FixedVector3) -> FixedVector3 {
+        return FixedVector3 { x: self.x / rhs.x, y: self.y / rhs.y, z: self.z / rhs.z };
+    }
+}
+
+impl ops::Div<Fixed> for FixedVector3 {
+    type Output = FixedVector3;
+
+    fn div(self, rhs: Fixed) -> FixedVector3 {
+        return FixedVector3 { x: self.x / rhs, y: self.y / rhs, z: self.z / rhs };
+    }
+}
+
+impl ops::Div<FixedVector3> for Fixed {
+    type Output = FixedVector3;
+
+    fn div(self, rhs: FixedVector3) -> FixedVector3 {
+        return FixedVector3 { x: self / rhs.x, y: self / rhs.y, z: self / rhs.z };
+    }
+}
+
+impl ops::AddAssign<FixedVector3> for FixedVector3 {
+    fn add_assign(&mut self, rhs: FixedVector3) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl ops::SubAssign<FixedVector3> for FixedVector3 {
+    fn sub_assign(&mut self, rhs: FixedVector3) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl ops::MulAssign<FixedVector3> for FixedVector3 {
+    fn mul_assign(&mut self, rhs: FixedVector3) {
+        self.x *= rhs.x;
+        self.y *= rhs.y;
+        self.z *= rhs.z;
+    }
+}
+
+impl ops::MulAssign<Fixed> for FixedVector3 {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+    }
+}
+
+impl ops::DivAssign<FixedVector3> for FixedVector3 {
+    fn div_assign(&mut self, rhs: FixedVector3) {
+        self.x /= rhs.x;
+        self.y /= rhs.y;
+        self.z /= rhs.z;
+    }
+}
+
+impl ops::DivAssign<Fixed> for FixedVector3 {
+    fn div_assign(&mut self, rhs: Fixed) {
+        self.x /= rhs;
+        self.y /= rhs;
+        self.z /= rhs;
+    }
+}
+
+impl ops::Neg for FixedVector3 {
+    type Output = FixedVector3;
+
+    fn neg(self) -> FixedVector3 {
+        return FixedVector3 { x: -self.x, y: -self.y, z: -self.z };
+    }
+}
+
+impl ops::Index<usize> for FixedVector3 {
+    type Output = Fixed;
+
+    fn index(&self, index: usize) -> &Fixed {
+        match index {
+            0 => { return &self.x; }
+            1 => { return &self.y; }
+            2 => { return &self.z; }
+            _ => { panic!("FixedVector3 index out of range: {}", index); }
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for FixedVector3 {
+    fn index_mut(&mut self, index: usize) -> &mut Fixed {
+        match index {
+            0 => { return &mut self.x; }
+            1 => { return &mut self.y; }
+            2 => { return &mut self.z; }
+            _ => { panic!("FixedVector3 index out of range: {}", index); }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::math::{Vector2, Vector3, Vector4, Quaternion, Matrix3x3, Matrix4x4};
+
+impl From<glam::Vec2> for Vector2 {
+    fn from(v: glam::Vec2) -> Vector2 {
+        return Vector2 { x: v.x, y: v.y };
+    }
+}
+
+impl From<Vector2> for glam::Vec2 {
+    fn from(v: Vector2) -> glam::Vec2 {
+        return glam::Vec2::new(v.x, v.y);
+    }
+}
+
+impl From<glam::Vec3> for Vector3 {
+    fn from(v: glam::Vec3) -> Vector3 {
+        return Vector3 { x: v.x, y: v.y, z: v.z };
+    }
+}
+
+impl From<Vector3> for glam::Vec3 {
+    fn from(v: Vector3) -> glam::Vec3 {
+        return glam::Vec3::new(v.x, v.y, v.z);
+    }
+}
+
+impl From<glam::Vec4> for Vector4 {
+    fn from(v: glam::Vec4) -> Vector4 {
+        return Vector4 { x: v.x, y: v.y, z: v.z, w: v.w };
+    }
+}
+
+impl From<Vector4> for glam::Vec4 {
+    fn from(v: Vector4) -> glam::Vec4 {
+        return glam::Vec4::new(v.x, v.y, v.z, v.w);
+    }
+}
+
+impl From<glam::Quat> for Quaternion {
+    fn from(q: glam::Quat) -> Quaternion {
+        return Quaternion { x: q.x, y: q.y, z: q.z, w: q.w };
+    }
+}
+
+impl From<Quaternion> for glam::Quat {
+    fn from(q: Quaternion) -> glam::Quat {
+        return glam::Quat::from_xyzw(q.x, q.y, q.z, q.w);
+    }
+}
+
+// NOTE: dbsdk matrices transform row vectors, so each row of a dbsdk matrix is a column of the equivalent glam matrix
+
+impl From<glam::Mat3> for Matrix3x3 {
+    fn from(m: glam::Mat3) -> Matrix3x3 {
+        return Matrix3x3 { m: m.to_cols_array_2d() };
+    }
+}
+
+impl From<Matrix3x3> for glam::Mat3 {
+    fn from(m: Matrix3x3) -> glam::Mat3 {
+        return glam::Mat3::from_cols_array_2d(&m.m);
+    }
+}
+
+impl From<glam::Mat4> for Matrix4x4 {
+    fn from(m: glam::Mat4) -> Matrix4x4 {
+        return Matrix4x4 { m: m.to_cols_array_2d() };
+    }
+}
+
+impl From<Matrix4x4> for glam::Mat4 {
+    fn from(m: Matrix4x4) -> glam::Mat4 {
+        return glam::Mat4::from_cols_array_2d(&m.m);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::math::{Vector2, Vector3, Vector4, Quaternion, Matrix3x3, Matrix4x4};
+
+impl From<mint::Vector2<f32>> for Vector2 {
+    fn from(v: mint::Vector2<f32>) -> Vector2 {
+        return Vector2 { x: v.x, y: v.y };
+    }
+}
+
+impl From<Vector2> for mint::Vector2<f32> {
+    fn from(v: Vector2) -> mint::Vector2<f32> {
+        return mint::Vector2 { x: v.x, y: v.y };
+    }
+}
+
+impl From<mint::Point2<f32>> for Vector2 {
+    fn from(v: mint::Point2<f32>) -> Vector2 {
+        return Vector2 { x: v.x, y: v.y };
+    }
+}
+
+impl From<mint::Vector3<f32>> for Vector3 {
+    fn from(v: mint::Vector3<f32>) -> Vector3 {
+        return Vector3 { x: v.x, y: v.y, z: v.z };
+    }
+}
+
+impl From<Vector3> for mint::Vector3<f32> {
+    fn from(v: Vector3) -> mint::Vector3<f32> {
+        return mint::Vector3 { x: v.x, y: v.y, z: v.z };
+    }
+}
+
+impl From<mint::Point3<f32>> for Vector3 {
+    fn from(v: mint::Point3<f32>) -> Vector3 {
+        return Vector3 { x: v.x, y: v.y, z: v.z };
+    }
+}
+
+impl From<mint::Vector4<f32>> for Vector4 {
+    fn from(v: mint::Vector4<f32>) -> Vector4 {
+        return Vector4 { x: v.x, y: v.y, z: v.z, w: v.w };
+    }
+}
+
+impl From<Vector4> for mint::Vector4<f32> {
+    fn from(v: Vector4) -> mint::Vector4<f32> {
+        return mint::Vector4 { x: v.x, y: v.y, z: v.z, w: v.w };
+    }
+}
+
+impl From<mint::Quaternion<f32>> for Quaternion {
+    fn from(q: mint::Quaternion<f32>) -> Quaternion {
+        return Quaternion { x: q.v.x, y: q.v.y, z: q.v.z, w: q.s };
+    }
+}
+
+impl From<Quaternion> for mint::Quaternion<f32> {
+    fn from(q: Quaternion) -> mint::Quaternion<f32> {
+        return mint::Quaternion { v: mint::Vector3 { x: q.x, y: q.y, z: q.z }, s: q.w };
+    }
+}
+
+// NOTE: dbsdk matrices transform row vectors, so each row of a dbsdk matrix is a column of the equivalent column-vector matrix
+
+impl From<mint::ColumnMatrix3<f32>> for Matrix3x3 {
+    fn from(m: mint::ColumnMatrix3<f32>) -> Matrix3x3 {
+        return Matrix3x3 { m: m.into() };
+    }
+}
+
+impl From<Matrix3x3> for mint::ColumnMatrix3<f32> {
+    fn from(m: Matrix3x3) -> mint::ColumnMatrix3<f32> {
+        return m.m.into();
+    }
+}
+
+impl From<mint::ColumnMatrix4<f32>> for Matrix4x4 {
+    fn from(m: mint::ColumnMatrix4<f32>) -> Matrix4x4 {
+        return Matrix4x4 { m: m.into() };
+    }
+}
+
+impl From<Matrix4x4> for mint::ColumnMatrix4<f32> {
+    fn from(m: Matrix4x4) -> mint::ColumnMatrix4<f32> {
+        return m.m.into();
+    }
+}
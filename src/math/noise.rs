@@ -0,0 +1,206 @@
+use crate::math::{Vector2, Vector3};
+
+/// Gradient noise generator producing deterministic results for a given seed
+#[derive(Clone)]
+pub struct Noise {
+    perm: [u8;512],
+}
+
+const GRAD3: [[f32;3];12] = [
+    [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0], [0.0, -1.0, 1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0],
+];
+
+const SIMPLEX_F2: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+const SIMPLEX_G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+
+fn fade(t: f32) -> f32 {
+    return t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    return a + ((b - a) * t);
+}
+
+impl Noise {
+    /// Construct a new noise generator from the given seed
+    pub fn new(seed: u32) -> Noise {
+        let mut table: [u8;256] = [0;256];
+        for (i, v) in table.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        // shuffle the permutation table with a simple xorshift generator so results only depend on the seed
+        let mut state = seed ^ 0x9E37_79B9;
+        if state == 0 {
+            state = 1;
+        }
+
+        for i in (1..256).rev() {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let j = (state % (i as u32 + 1)) as usize;
+            table.swap(i, j);
+        }
+
+        let mut perm: [u8;512] = [0;512];
+        for (i, v) in perm.iter_mut().enumerate() {
+            *v = table[i & 255];
+        }
+
+        return Noise { perm: perm };
+    }
+
+    fn hash(&self, i: i32) -> usize {
+        return self.perm[(i & 255) as usize] as usize;
+    }
+
+    fn grad1(hash: usize, x: f32) -> f32 {
+        let g = ((hash & 7) + 1) as f32;
+        return if hash & 8 != 0 { -g * x } else { g * x };
+    }
+
+    fn grad2(hash: usize, x: f32, y: f32) -> f32 {
+        let g = &GRAD3[hash % 12];
+        return (g[0] * x) + (g[1] * y);
+    }
+
+    fn grad3(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+        let g = &GRAD3[hash % 12];
+        return (g[0] * x) + (g[1] * y) + (g[2] * z);
+    }
+
+    /// Sample 1D Perlin noise at the given coordinate (output is roughly in the range -1.0 .. 1.0)
+    pub fn perlin1(&self, x: f32) -> f32 {
+        let xi = x.floor() as i32;
+        let xf = x - x.floor();
+
+        let n0 = Noise::grad1(self.hash(xi), xf);
+        let n1 = Noise::grad1(self.hash(xi + 1), xf - 1.0);
+
+        // 1D gradients range up to 8, so scale back down into -1 .. 1
+        return lerp(n0, n1, fade(xf)) * 0.25;
+    }
+
+    /// Sample 2D Perlin noise at the given coordinate (output is roughly in the range -1.0 .. 1.0)
+    pub fn perlin2(&self, p: Vector2) -> f32 {
+        let xi = p.x.floor() as i32;
+        let yi = p.y.floor() as i32;
+        let xf = p.x - p.x.floor();
+        let yf = p.y - p.y.floor();
+
+        let aa = self.hash(xi + self.hash(yi) as i32);
+        let ab = self.hash(xi + self.hash(yi + 1) as i32);
+        let ba = self.hash(xi + 1 + self.hash(yi) as i32);
+        let bb = self.hash(xi + 1 + self.hash(yi + 1) as i32);
+
+        let u = fade(xf);
+        let v = fade(yf);
+
+        let x0 = lerp(Noise::grad2(aa, xf, yf), Noise::grad2(ba, xf - 1.0, yf), u);
+        let x1 = lerp(Noise::grad2(ab, xf, yf - 1.0), Noise::grad2(bb, xf - 1.0, yf - 1.0), u);
+
+        return lerp(x0, x1, v);
+    }
+
+    /// Sample 3D Perlin noise at the given coordinate (output is roughly in the range -1.0 .. 1.0)
+    pub fn perlin3(&self, p: Vector3) -> f32 {
+        let xi = p.x.floor() as i32;
+        let yi = p.y.floor() as i32;
+        let zi = p.z.floor() as i32;
+        let xf = p.x - p.x.floor();
+        let yf = p.y - p.y.floor();
+        let zf = p.z - p.z.floor();
+
+        let h = |x: i32, y: i32, z: i32| -> usize {
+            return self.hash(x + self.hash(y + self.hash(z) as i32) as i32);
+        };
+
+        let u = fade(xf);
+        let v = fade(yf);
+        let w = fade(zf);
+
+        let x00 = lerp(Noise::grad3(h(xi, yi, zi), xf, yf, zf), Noise::grad3(h(xi + 1, yi, zi), xf - 1.0, yf, zf), u);
+        let x10 = lerp(Noise::grad3(h(xi, yi + 1, zi), xf, yf - 1.0, zf), Noise::grad3(h(xi + 1, yi + 1, zi), xf - 1.0, yf - 1.0, zf), u);
+        let x01 = lerp(Noise::grad3(h(xi, yi, zi + 1), xf, yf, zf - 1.0), Noise::grad3(h(xi + 1, yi, zi + 1), xf - 1.0, yf, zf - 1.0), u);
+        let x11 = lerp(Noise::grad3(h(xi, yi + 1, zi + 1), xf, yf - 1.0, zf - 1.0), Noise::grad3(h(xi + 1, yi + 1, zi + 1), xf - 1.0, yf - 1.0, zf - 1.0), u);
+
+        return lerp(lerp(x00, x10, v), lerp(x01, x11, v), w);
+    }
+
+    /// Sample 2D simplex noise at the given coordinate (output is roughly in the range -1.0 .. 1.0)
+    pub fn simplex2(&self, p: Vector2) -> f32 {
+        // skew input space to determine which simplex cell we're in
+        let s = (p.x + p.y) * SIMPLEX_F2;
+        let i = (p.x + s).floor() as i32;
+        let j = (p.y + s).floor() as i32;
+
+        let t = (i + j) as f32 * SIMPLEX_G2;
+        let x0 = p.x - (i as f32 - t);
+        let y0 = p.y - (j as f32 - t);
+
+        // determine which of the two triangles in the cell we're in
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        let x1 = x0 - i1 as f32 + SIMPLEX_G2;
+        let y1 = y0 - j1 as f32 + SIMPLEX_G2;
+        let x2 = x0 - 1.0 + 2.0 * SIMPLEX_G2;
+        let y2 = y0 - 1.0 + 2.0 * SIMPLEX_G2;
+
+        let gi0 = self.hash(i + self.hash(j) as i32);
+        let gi1 = self.hash(i + i1 + self.hash(j + j1) as i32);
+        let gi2 = self.hash(i + 1 + self.hash(j + 1) as i32);
+
+        let corner = |gi: usize, x: f32, y: f32| -> f32 {
+            let t = 0.5 - (x * x) - (y * y);
+            if t < 0.0 {
+                return 0.0;
+            }
+            let t2 = t * t;
+            return t2 * t2 * Noise::grad2(gi, x, y);
+        };
+
+        let n = corner(gi0, x0, y0) + corner(gi1, x1, y1) + corner(gi2, x2, y2);
+
+        // scale result to fit roughly into -1 .. 1
+        return 70.0 * n;
+    }
+
+    /// Sample fractal Brownian motion built from 1D Perlin noise
+    pub fn fbm1(&self, x: f32, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        return fbm(octaves, lacunarity, gain, |freq| self.perlin1(x * freq));
+    }
+
+    /// Sample fractal Brownian motion built from 2D Perlin noise
+    pub fn fbm2(&self, p: Vector2, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        return fbm(octaves, lacunarity, gain, |freq| self.perlin2(p * freq));
+    }
+
+    /// Sample fractal Brownian motion built from 3D Perlin noise
+    pub fn fbm3(&self, p: Vector3, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        return fbm(octaves, lacunarity, gain, |freq| self.perlin3(p * freq));
+    }
+}
+
+/// Sum octaves of the given noise function at increasing frequency and decreasing amplitude, normalized back into the range of a single octave
+pub fn fbm<F>(octaves: u32, lacunarity: f32, gain: f32, sample: F) -> f32 where F : Fn(f32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    let mut total_amplitude = 0.0;
+
+    for _ in 0..octaves {
+        sum += sample(frequency) * amplitude;
+        total_amplitude += amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+
+    if total_amplitude == 0.0 {
+        return 0.0;
+    }
+
+    return sum / total_amplitude;
+}
@@ -0,0 +1,65 @@
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+use core::arch::wasm32::{f32x4, f32x4_add, f32x4_extract_lane, f32x4_mul, f32x4_splat, v128};
+
+use crate::math::{Matrix4x4, Vector4};
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+fn load_row(row: &[f32;4]) -> v128 {
+    return f32x4(row[0], row[1], row[2], row[3]);
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+fn store_row(v: v128) -> [f32;4] {
+    return [f32x4_extract_lane::<0>(v), f32x4_extract_lane::<1>(v), f32x4_extract_lane::<2>(v), f32x4_extract_lane::<3>(v)];
+}
+
+// compute (a * wa) + (b * wb), a row at a time
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+fn blend(a: &Matrix4x4, wa: f32, b: &Matrix4x4, wb: f32) -> Matrix4x4 {
+    let wa = f32x4_splat(wa);
+    let wb = f32x4_splat(wb);
+    return Matrix4x4 { m: [0, 1, 2, 3].map(|i| store_row(f32x4_add(f32x4_mul(load_row(&a.m[i]), wa), f32x4_mul(load_row(&b.m[i]), wb)))) };
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+fn blend(a: &Matrix4x4, wa: f32, b: &Matrix4x4, wb: f32) -> Matrix4x4 {
+    return Matrix4x4 { m: [0, 1, 2, 3].map(|i| [0, 1, 2, 3].map(|j| (a.m[i][j] * wa) + (b.m[i][j] * wb))) };
+}
+
+/// Transform a vector by the given matrix (equivalent to matrix * v), using wasm SIMD when built with the simd128 target feature
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+pub fn transform(matrix: &Matrix4x4, v: Vector4) -> Vector4 {
+    let r = f32x4_add(
+        f32x4_add(f32x4_mul(f32x4_splat(v.x), load_row(&matrix.m[0])), f32x4_mul(f32x4_splat(v.y), load_row(&matrix.m[1]))),
+        f32x4_add(f32x4_mul(f32x4_splat(v.z), load_row(&matrix.m[2])), f32x4_mul(f32x4_splat(v.w), load_row(&matrix.m[3]))));
+    let r = store_row(r);
+    return Vector4::new(r[0], r[1], r[2], r[3]);
+}
+
+/// Transform a vector by the given matrix (equivalent to matrix * v), using wasm SIMD when built with the simd128 target feature
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+pub fn transform(matrix: &Matrix4x4, v: Vector4) -> Vector4 {
+    return *matrix * v;
+}
+
+/// Compute the skinning matrix of a vertex blended between two bones of the palette, with weights from 0 (no influence) to 255 (full influence) <br/>
+/// Bones outside of the palette are treated as identity. Blending the matrices once lets a vertex's position & normal share the same skinning matrix
+pub fn blend_bones(bone_palette: &[Matrix4x4], indices: [u8;2], weights: [u8;2]) -> Matrix4x4 {
+    let identity = Matrix4x4::identity();
+    let a = bone_palette.get(indices[0] as usize).unwrap_or(&identity);
+    let b = bone_palette.get(indices[1] as usize).unwrap_or(&identity);
+    return blend(a, (weights[0] as f32) / 255.0, b, (weights[1] as f32) / 255.0);
+}
+
+/// Skin each vector in place, blending it between the two bones of the palette given by its indices & weights (see blend_bones) <br/>
+/// Positions should have a w of 1.0 and normals a w of 0.0. Panics if the index & weight slices are shorter than the vertex slice.
+/// Build with the simd128 target feature (-C target-feature=+simd128) to use wasm SIMD, otherwise a scalar fallback is used
+pub fn skin_vertices(vertices: &mut [Vector4], bone_palette: &[Matrix4x4], indices: &[[u8;2]], weights: &[[u8;2]]) {
+    let indices = &indices[..vertices.len()];
+    let weights = &weights[..vertices.len()];
+
+    for ((v, idx), w) in vertices.iter_mut().zip(indices).zip(weights) {
+        let skin = blend_bones(bone_palette, *idx, *w);
+        *v = transform(&skin, *v);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::math::{Matrix4x4, Quaternion, Vector3};
+
+/// A stack of transform matrices for drawing nested hierarchies without going through the SIMD register <br/>
+/// Each level starts as a copy of its parent, and transforms multiplied into it are applied in that level's local space (so children are transformed by every matrix above them)
+pub struct MatrixStack {
+    stack: Vec<Matrix4x4>,
+}
+
+impl MatrixStack {
+    /// Construct a new stack with an identity matrix at its root
+    pub fn new() -> MatrixStack {
+        return MatrixStack::with_root(&Matrix4x4::identity());
+    }
+
+    /// Construct a new stack with the given matrix at its root (for example, a view-projection matrix)
+    pub fn with_root(root: &Matrix4x4) -> MatrixStack {
+        return MatrixStack {
+            stack: vec![*root],
+        };
+    }
+
+    /// Get the matrix at the top of the stack
+    pub fn current(&self) -> Matrix4x4 {
+        return *self.stack.last().unwrap();
+    }
+
+    /// Push a new level onto the stack, starting as a copy of the current matrix
+    pub fn push(&mut self) {
+        let top = self.current();
+        self.stack.push(top);
+    }
+
+    /// Pop the top level off of the stack, returning its matrix <br/>
+    /// The root level is never popped, so this returns None if nothing has been pushed
+    pub fn pop(&mut self) -> Option<Matrix4x4> {
+        if self.stack.len() == 1 {
+            return None;
+        }
+
+        return self.stack.pop();
+    }
+
+    /// Get the number of levels pushed above the root
+    pub fn depth(&self) -> usize {
+        return self.stack.len() - 1;
+    }
+
+    /// Multiply the given local transform into the current matrix
+    pub fn mul(&mut self, matrix: &Matrix4x4) {
+        let top = self.stack.last_mut().unwrap();
+        *top = *matrix * *top;
+    }
+
+    /// Multiply a translation into the current matrix
+    pub fn translate(&mut self, translation: Vector3) {
+        self.mul(&Matrix4x4::translation(translation));
+    }
+
+    /// Multiply a rotation into the current matrix
+    pub fn rotate(&mut self, rotation: Quaternion) {
+        self.mul(&Matrix4x4::rotation(rotation));
+    }
+
+    /// Multiply a scale into the current matrix
+    pub fn scale(&mut self, scale: Vector3) {
+        self.mul(&Matrix4x4::scale(scale));
+    }
+
+    /// Replace the current matrix with the given matrix
+    pub fn load(&mut self, matrix: &Matrix4x4) {
+        *self.stack.last_mut().unwrap() = *matrix;
+    }
+
+    /// Pop every pushed level, leaving only the root
+    pub fn reset(&mut self) {
+        self.stack.truncate(1);
+    }
+
+    /// Load the current matrix into the SIMD register, ready for transform_vertex_simd
+    pub fn load_simd(&self) {
+        Matrix4x4::load_simd(self.stack.last().unwrap());
+    }
+}
+
+impl Default for MatrixStack {
+    fn default() -> Self {
+        return MatrixStack::new();
+    }
+}
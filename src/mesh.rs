@@ -0,0 +1,744 @@
+use std::{io::{ErrorKind, Read, Seek, SeekFrom}, ops::Range, sync::Arc};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+use half::f16;
+
+use field_offset::{FieldOffset, offset_of};
+
+use crate::{lighting::SphericalHarmonics, log_debug, math::{Matrix4x4, Quaternion, Vector2, Vector3, Vector4, batch::TransformBatch, bounds::{Aabb, Frustum}, skin}, vdp::{self, BlendEquation, BlendFactor, Color32, PackedVertex, Texture, Vertex, WindingOrder}};
+
+const DBM_VER: u32 = 1;
+
+/// Size in bytes of a single serialized skeleton node (two 4x4 matrices, bone index & child count)
+const SKEL_NODE_SIZE: u32 = 130;
+
+/// Size in bytes of a single serialized morph delta (u32 vertex index & two f16 vectors)
+const MORPH_DELTA_SIZE: usize = 16;
+
+/// Represents a skeleton loaded from DBM mesh file
+pub struct DBSkeleton {
+    pub bone_count: u32,
+    pub nodes: Vec<DBSkelNode>,
+    /// Name of each bone, indexed by bone index (DBM files don't store bone names, so these are empty unless assigned with set_bone_name)
+    pub bone_names: Vec<String>,
+}
+
+/// Represents a single node in a skeleton
+pub struct DBSkelNode {
+    pub bone_index: u8,
+    pub inv_bind_pose: Matrix4x4,
+    pub local_rest_pose: Matrix4x4,
+    pub children: Vec<DBSkelNode>,
+}
+
+/// Represents a vertex loaded from DBM mesh file
+#[derive(Clone, Copy)]
+pub struct DBMeshVertex {
+    pub pos: [f16;3],
+    pub nrm: [f16;3],
+    pub col: [u8;4],
+    pub tex: [f16;2],
+    pub bweight: [u8;2],
+    pub bidx: [u8;2],
+}
+
+/// Represents a material loaded from DBM mesh file
+pub struct DBMaterialInfo {
+    pub name: String,
+    pub texture: Option<Arc<Texture>>,
+    pub blend_enable: bool,
+    pub enable_cull: bool,
+    pub diffuse_color: Vector4,
+    pub spec_color: Vector3,
+    pub roughness: f32,
+}
+
+/// Represents a mesh part loaded from DBM mesh file
+pub struct DBMeshPart {
+    pub name: String,
+    pub transform: Matrix4x4,
+    pub material: DBMaterialInfo,
+    pub vertices: Vec<DBMeshVertex>,
+    /// Bounds of the part's vertices (before the part transform & skinning), computed at load
+    pub bounds: Aabb,
+    pub morph_targets: Vec<DBMorphTarget>,
+    /// Lower detail levels of this part, sorted by switch distance (the part's own vertices are used below the first switch distance)
+    pub lods: Vec<DBMeshLod>,
+}
+
+/// Represents a lower detail level of a mesh part loaded from DBM mesh file
+pub struct DBMeshLod {
+    /// Distance from the camera at or beyond which this level is used
+    pub distance: f32,
+    pub vertices: Vec<DBMeshVertex>,
+}
+
+/// Represents the offset applied to a single vertex by a morph target
+#[derive(Clone, Copy)]
+pub struct DBMorphDelta {
+    pub vertex_index: u32,
+    pub pos: Vector3,
+    pub nrm: Vector3,
+}
+
+/// Represents a morph target (blend shape) loaded from DBM mesh file, storing offsets only for the vertices it moves
+pub struct DBMorphTarget {
+    pub name: String,
+    pub deltas: Vec<DBMorphDelta>,
+}
+
+/// A mesh loaded from DBM mesh file
+pub struct DBMesh {
+    pub mesh_parts: Vec<DBMeshPart>,
+    pub skeleton: Option<DBSkeleton>,
+}
+
+/// Enumeration of errors which can result from parsing a DBM mesh file
+#[derive(Debug)]
+pub enum DBMeshError {
+    ParseError,
+    VersionError,
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for DBMeshError {
+    fn from(value: std::io::Error) -> Self {
+        return DBMeshError::IOError(value);
+    }
+}
+
+// vertex types which mesh parts can be unpacked into for drawing
+trait GpuVertex : Sized {
+    fn position() -> FieldOffset<Self,Vector4>;
+    fn draw(vertices: &[Self]);
+}
+
+impl GpuVertex for Vertex {
+    fn position() -> FieldOffset<Vertex,Vector4> {
+        return offset_of!(Vertex => position);
+    }
+
+    fn draw(vertices: &[Vertex]) {
+        vdp::draw_geometry(vdp::Topology::TriangleList, vertices);
+    }
+}
+
+impl GpuVertex for PackedVertex {
+    fn position() -> FieldOffset<PackedVertex,Vector4> {
+        return offset_of!(PackedVertex => position);
+    }
+
+    fn draw(vertices: &[PackedVertex]) {
+        vdp::draw_geometry_packed(vdp::Topology::TriangleList, vertices);
+    }
+}
+
+fn str_from_null_terminated(s: &[u8]) -> Result<String,DBMeshError> {
+    let len = s.iter().position(|x| *x == 0).unwrap_or(s.len());
+    return match std::str::from_utf8(&s[..len]) {
+        Ok(v) => { Ok(v.to_string()) },
+        Err(_) => { Err(DBMeshError::ParseError) }
+    };
+}
+
+fn read_matrix<R>(reader: &mut R) -> Result<Matrix4x4,std::io::Error> where R : Read {
+    let mut mat = Matrix4x4::identity();
+    for j in 0..4 {
+        for i in 0..4 {
+            mat.m[i][j] = reader.read_f32::<LittleEndian>()?;
+        }
+    }
+    return Ok(mat);
+}
+
+fn read_f16<R>(reader: &mut R) -> Result<f16,std::io::Error> where R : Read {
+    return Ok(f16::from_bits(reader.read_u16::<LittleEndian>()?));
+}
+
+fn read_vector3_f16<R>(reader: &mut R) -> Result<Vector3,std::io::Error> where R : Read {
+    return Ok(Vector3::new(read_f16(reader)?.to_f32(), read_f16(reader)?.to_f32(), read_f16(reader)?.to_f32()));
+}
+
+fn read_skel_node<R>(reader: &mut R) -> Result<Option<DBSkelNode>,DBMeshError> where R : Read {
+    // running out of data at the start of a node marks the end of the skeleton
+    let inv_bind_mat = match read_matrix(reader) {
+        Ok(v) => { v },
+        Err(e) => {
+            if e.kind() == ErrorKind::UnexpectedEof {
+                return Ok(None);
+            }
+            return Err(DBMeshError::IOError(e));
+        }
+    };
+
+    let local_rest_mat = read_matrix(reader)?;
+    let bone_index = reader.read_u8()?;
+    let child_count = reader.read_u8()? as usize;
+
+    let mut children: Vec<DBSkelNode> = Vec::with_capacity(child_count);
+
+    for _ in 0..child_count {
+        match read_skel_node(reader)? {
+            Some(v) => {
+                children.push(v);
+            }
+            None => {
+                return Err(DBMeshError::ParseError);
+            }
+        };
+    }
+
+    return Ok(Some(DBSkelNode { bone_index: bone_index, inv_bind_pose: inv_bind_mat, local_rest_pose: local_rest_mat, children: children }));
+}
+
+fn read_vertex<R>(reader: &mut R) -> Result<DBMeshVertex,std::io::Error> where R : Read {
+    let pos = [read_f16(reader)?, read_f16(reader)?, read_f16(reader)?];
+    let nrm = [read_f16(reader)?, read_f16(reader)?, read_f16(reader)?];
+
+    let mut col: [u8;4] = [0;4];
+    reader.read_exact(&mut col)?;
+
+    let tex = [read_f16(reader)?, read_f16(reader)?];
+
+    let mut bweight: [u8;2] = [0;2];
+    reader.read_exact(&mut bweight)?;
+
+    let mut bidx: [u8;2] = [0;2];
+    reader.read_exact(&mut bidx)?;
+
+    return Ok(DBMeshVertex { pos: pos, nrm: nrm, col: col, tex: tex, bweight: bweight, bidx: bidx });
+}
+
+fn read_vertices<R>(reader: &mut R) -> Result<Vec<DBMeshVertex>,std::io::Error> where R : Read {
+    let tri_count = reader.read_u16::<LittleEndian>()?;
+    let vtx_count = (tri_count as usize) * 3;
+
+    let mut vertices: Vec<DBMeshVertex> = Vec::with_capacity(vtx_count);
+    for _ in 0..vtx_count {
+        vertices.push(read_vertex(reader)?);
+    }
+
+    return Ok(vertices);
+}
+
+impl DBSkeleton {
+    fn read(chunk: &[u8]) -> Result<DBSkeleton,DBMeshError> {
+        let bone_count = chunk.len() as u32 / SKEL_NODE_SIZE;
+        let mut skeleton = DBSkeleton {
+            bone_count: bone_count,
+            nodes: Vec::new(),
+            bone_names: vec![String::new();bone_count as usize],
+        };
+
+        let mut reader = chunk;
+        while let Some(node) = read_skel_node(&mut reader)? {
+            skeleton.nodes.push(node);
+        }
+
+        return Ok(skeleton);
+    }
+
+    /// Get the index of the bone with the given name
+    pub fn bone_index(&self, name: &str) -> Option<u8> {
+        return self.bone_names.iter().position(|x| x == name).map(|x| x as u8);
+    }
+
+    /// Assign a name to the given bone, so that it can be looked up with bone_index
+    pub fn set_bone_name(&mut self, bone_index: u8, name: &str) {
+        let idx = bone_index as usize;
+        if idx >= self.bone_names.len() {
+            self.bone_names.resize(idx + 1, String::new());
+        }
+        self.bone_names[idx] = name.to_string();
+    }
+}
+
+impl DBMeshPart {
+    fn read<R,TL>(reader: &mut R, tex_load_fn: &mut TL) -> Result<DBMeshPart,DBMeshError>
+        where R : Read,
+        TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        let mut mesh_name: [u8;32] = [0;32];
+        reader.read_exact(&mut mesh_name)?;
+
+        // translation + rotation + scale
+        let translation = Vector3::new(reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?);
+        let rotation = Quaternion::new(reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?);
+        let scale = Vector3::new(reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?, reader.read_f32::<LittleEndian>()?);
+
+        Matrix4x4::load_simd(&Matrix4x4::scale(scale));
+        Matrix4x4::mul_simd(&Matrix4x4::rotation(rotation));
+        Matrix4x4::mul_simd(&Matrix4x4::translation(translation));
+
+        let mut transform = Matrix4x4::identity();
+        Matrix4x4::store_simd(&mut transform);
+
+        // material info
+        let mut mat_name: [u8;32] = [0;32];
+        reader.read_exact(&mut mat_name)?;
+
+        let mat_has_texture = reader.read_u8()? != 0;
+        let mat_blend_enable = reader.read_u8()? != 0;
+        let mat_enable_culling = reader.read_u8()? != 0;
+
+        let mut diffuse_color: [u8;4] = [0;4];
+        reader.read_exact(&mut diffuse_color)?;
+
+        let mut spec_color: [u8;3] = [0;3];
+        reader.read_exact(&mut spec_color)?;
+
+        let roughness = reader.read_u8()?;
+
+        let mat_name = str_from_null_terminated(&mat_name)?;
+
+        let texture: Option<Arc<Texture>> = if mat_has_texture {
+            tex_load_fn(mat_name.as_str()).ok()
+        } else {
+            None
+        };
+
+        let mat_info = DBMaterialInfo {
+            name: mat_name,
+            texture: texture,
+            blend_enable: mat_blend_enable,
+            enable_cull: mat_enable_culling,
+            diffuse_color: Vector4::new((diffuse_color[0] as f32) / 255.0, (diffuse_color[1] as f32) / 255.0, (diffuse_color[2] as f32) / 255.0, (diffuse_color[3] as f32) / 255.0),
+            spec_color: Vector3::new((spec_color[0] as f32) / 255.0, (spec_color[1] as f32) / 255.0, (spec_color[2] as f32) / 255.0),
+            roughness: (roughness as f32) / 255.0,
+        };
+
+        let vertices = read_vertices(reader)?;
+
+        let mut part = DBMeshPart {
+            name: str_from_null_terminated(&mesh_name)?,
+            transform: transform,
+            material: mat_info,
+            vertices: vertices,
+            bounds: Aabb::EMPTY,
+            morph_targets: Vec::new(),
+            lods: Vec::new(),
+        };
+        part.recalculate_bounds();
+
+        return Ok(part);
+    }
+
+    /// Recompute bounds from the part's vertices (should be called after modifying vertices)
+    pub fn recalculate_bounds(&mut self) {
+        self.bounds = Aabb::from_points(self.vertices.iter().map(|v| Vector3::new(v.pos[0].to_f32(), v.pos[1].to_f32(), v.pos[2].to_f32())));
+    }
+
+    /// Get the index of the detail level to use at the given distance from the camera (0 is the part's own vertices, 1 is the first entry in lods, etc)
+    pub fn lod_level(&self, distance: f32) -> usize {
+        return self.lods.iter().take_while(|x| distance >= x.distance).count();
+    }
+
+    /// Get the vertices of the detail level to use at the given distance from the camera
+    pub fn lod_vertices(&self, distance: f32) -> &[DBMeshVertex] {
+        return match self.lod_level(distance) {
+            0 => { &self.vertices },
+            level => { &self.lods[level - 1].vertices }
+        };
+    }
+
+    /// Draw the given vertices (such as a detail level or the output of apply_morph_targets) with this part's transform & material <br/>
+    /// Vertices are skinned with the given bone palette if it isn't empty, and are unlit (vertex color multiplied by the material's diffuse color)
+    pub fn draw_vertices(&self, vertices: &[DBMeshVertex], mvp: &Matrix4x4, bone_palette: &[Matrix4x4]) {
+        let mut vtx_buffer = vdp::take_vertex_buffer(vertices.len());
+        self.unpack_vertices(vertices, bone_palette, &mut vtx_buffer);
+
+        Matrix4x4::load_simd(&self.transform);
+        Matrix4x4::mul_simd(mvp);
+        Matrix4x4::transform_vertex_simd(vtx_buffer.as_mut_slice(), offset_of!(Vertex => position));
+
+        self.bind_material();
+        vdp::draw_geometry(vdp::Topology::TriangleList, vtx_buffer.as_slice());
+    }
+
+    // unpack mesh part vertices into GPU vertices (skinned but not yet transformed), appending them to the output buffer
+    fn unpack_vertices(&self, vertices: &[DBMeshVertex], bone_palette: &[Matrix4x4], out: &mut Vec<Vertex>) {
+        let diffuse = self.material.diffuse_color;
+
+        for vertex in vertices {
+            let mut pos = Vector4::new(vertex.pos[0].to_f32(), vertex.pos[1].to_f32(), vertex.pos[2].to_f32(), 1.0);
+
+            if !bone_palette.is_empty() {
+                pos = skin::transform(&skin::blend_bones(bone_palette, vertex.bidx, vertex.bweight), pos);
+            }
+
+            let col = Vector4::new(vertex.col[0] as f32, vertex.col[1] as f32, vertex.col[2] as f32, vertex.col[3] as f32) * (1.0 / 255.0);
+
+            out.push(Vertex::new(
+                pos,
+                col * diffuse,
+                Vector4::zero(),
+                Vector4::new(vertex.tex[0].to_f32(), vertex.tex[1].to_f32(), 0.0, 0.0)));
+        }
+    }
+
+    /// Like draw_vertices, but building PackedVertex vertices (with 8-bit colors) & drawing them with draw_geometry_packed, roughly halving vertex bandwidth <br/>
+    /// If lighting is given, vertex colors are also multiplied by the lighting environment evaluated for each skinned normal, transformed into world space by model (the mesh's world transform)
+    pub fn draw_vertices_packed(&self, vertices: &[DBMeshVertex], model: &Matrix4x4, mvp: &Matrix4x4, bone_palette: &[Matrix4x4], lighting: Option<&SphericalHarmonics>) {
+        let mut vtx_buffer = vdp::take_packed_vertex_buffer(vertices.len());
+        self.unpack_vertices_packed(vertices, bone_palette, &(self.transform * *model), lighting, &mut vtx_buffer);
+
+        Matrix4x4::load_simd(&self.transform);
+        Matrix4x4::mul_simd(mvp);
+        Matrix4x4::transform_vertex_simd(vtx_buffer.as_mut_slice(), offset_of!(PackedVertex => position));
+
+        self.bind_material();
+        vdp::draw_geometry_packed(vdp::Topology::TriangleList, vtx_buffer.as_slice());
+    }
+
+    // unpack mesh part vertices into packed GPU vertices (skinned & lit but not yet transformed), appending them to the output buffer
+    fn unpack_vertices_packed(&self, vertices: &[DBMeshVertex], bone_palette: &[Matrix4x4], world: &Matrix4x4, lighting: Option<&SphericalHarmonics>, out: &mut Vec<PackedVertex>) {
+        let diffuse = self.material.diffuse_color;
+        let normal_matrix = world.normal_matrix();
+        let ocolor = Color32::new(0, 0, 0, 0);
+
+        for vertex in vertices {
+            let mut pos = Vector4::new(vertex.pos[0].to_f32(), vertex.pos[1].to_f32(), vertex.pos[2].to_f32(), 1.0);
+            let mut nrm = Vector4::new(vertex.nrm[0].to_f32(), vertex.nrm[1].to_f32(), vertex.nrm[2].to_f32(), 0.0);
+
+            if !bone_palette.is_empty() {
+                let bone = skin::blend_bones(bone_palette, vertex.bidx, vertex.bweight);
+                pos = skin::transform(&bone, pos);
+                nrm = skin::transform(&bone, nrm);
+            }
+
+            let mut col = Vector4::new(vertex.col[0] as f32, vertex.col[1] as f32, vertex.col[2] as f32, vertex.col[3] as f32) * (1.0 / 255.0) * diffuse;
+
+            if let Some(sh) = lighting {
+                let mut n = normal_matrix * Vector3::new(nrm.x, nrm.y, nrm.z);
+                if n.length_sq() > 0.0 {
+                    n.normalize();
+                }
+                let light = sh.evaluate(n);
+                col *= Vector4::new(light.x, light.y, light.z, 1.0);
+            }
+
+            out.push(PackedVertex::new(
+                pos,
+                Vector2::new(vertex.tex[0].to_f32(), vertex.tex[1].to_f32()),
+                Color32::from(col),
+                ocolor));
+        }
+    }
+
+    // set render state for this part's material
+    fn bind_material(&self) {
+        vdp::set_culling(self.material.enable_cull);
+        vdp::set_winding(WindingOrder::CounterClockwise);
+        vdp::bind_texture(self.material.texture.as_deref());
+        vdp::blend_equation(BlendEquation::Add);
+
+        if self.material.blend_enable {
+            vdp::blend_func(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha);
+            vdp::depth_write(false);
+        } else {
+            vdp::blend_func(BlendFactor::One, BlendFactor::Zero);
+            vdp::depth_write(true);
+        }
+    }
+
+    /// Draw the detail level to use at the given distance from the camera (see draw_vertices)
+    pub fn draw_lod(&self, distance: f32, mvp: &Matrix4x4, bone_palette: &[Matrix4x4]) {
+        self.draw_vertices(self.lod_vertices(distance), mvp, bone_palette);
+    }
+
+    /// Get the index of the morph target with the given name
+    pub fn morph_target_index(&self, name: &str) -> Option<usize> {
+        return self.morph_targets.iter().position(|x| x.name == name);
+    }
+
+    /// Write this part's vertices into the output buffer with each morph target applied according to its weight (indexed by morph target index) <br/>
+    /// The result can be skinned & drawn in place of the part's vertices. Missing weights are treated as zero
+    pub fn apply_morph_targets(&self, weights: &[f32], out: &mut Vec<DBMeshVertex>) {
+        out.clear();
+        out.extend_from_slice(&self.vertices);
+
+        if !weights.iter().any(|x| *x != 0.0) {
+            return;
+        }
+
+        // accumulate at full precision, then convert back to half precision once
+        let mut pos_offsets: Vec<Vector3> = vec![Vector3::zero();self.vertices.len()];
+        let mut nrm_offsets: Vec<Vector3> = vec![Vector3::zero();self.vertices.len()];
+
+        for (target, weight) in self.morph_targets.iter().zip(weights) {
+            if *weight == 0.0 {
+                continue;
+            }
+
+            for delta in &target.deltas {
+                let idx = delta.vertex_index as usize;
+                if idx < pos_offsets.len() {
+                    pos_offsets[idx] += delta.pos * *weight;
+                    nrm_offsets[idx] += delta.nrm * *weight;
+                }
+            }
+        }
+
+        for (vtx, (pos_offset, nrm_offset)) in out.iter_mut().zip(pos_offsets.iter().zip(&nrm_offsets)) {
+            let pos = Vector3::new(vtx.pos[0].to_f32(), vtx.pos[1].to_f32(), vtx.pos[2].to_f32()) + *pos_offset;
+            vtx.pos = [f16::from_f32(pos.x), f16::from_f32(pos.y), f16::from_f32(pos.z)];
+
+            if nrm_offset.length_sq() > 0.0 {
+                let mut nrm = Vector3::new(vtx.nrm[0].to_f32(), vtx.nrm[1].to_f32(), vtx.nrm[2].to_f32()) + *nrm_offset;
+                if nrm.length_sq() > 0.0 {
+                    nrm.normalize();
+                }
+                vtx.nrm = [f16::from_f32(nrm.x), f16::from_f32(nrm.y), f16::from_f32(nrm.z)];
+            }
+        }
+    }
+}
+
+impl DBMeshLod {
+    fn read(chunk: &[u8], mesh_parts: &mut [DBMeshPart]) -> Result<(),DBMeshError> {
+        let mut reader = chunk;
+
+        let mut part_name: [u8;32] = [0;32];
+        reader.read_exact(&mut part_name)?;
+
+        // detail levels apply to the most recent mesh part with the given name
+        let part_name = str_from_null_terminated(&part_name)?;
+        let part = match mesh_parts.iter_mut().rev().find(|x| x.name == part_name) {
+            Some(v) => { v },
+            None => { return Err(DBMeshError::ParseError); }
+        };
+
+        let distance = reader.read_f32::<LittleEndian>()?;
+        let vertices = read_vertices(&mut reader)?;
+
+        log_debug!("Parsed mesh LOD (part: {}, distance: {}, vertex count: {})", part.name, distance, vertices.len());
+
+        let idx = part.lods.partition_point(|x| x.distance <= distance);
+        part.lods.insert(idx, DBMeshLod { distance: distance, vertices: vertices });
+
+        return Ok(());
+    }
+}
+
+impl DBMorphTarget {
+    fn read(chunk: &[u8], mesh_parts: &mut [DBMeshPart]) -> Result<(),DBMeshError> {
+        let mut reader = chunk;
+
+        let mut part_name: [u8;32] = [0;32];
+        reader.read_exact(&mut part_name)?;
+
+        let mut target_name: [u8;32] = [0;32];
+        reader.read_exact(&mut target_name)?;
+
+        // morph targets apply to the most recent mesh part with the given name
+        let part_name = str_from_null_terminated(&part_name)?;
+        let target_name = str_from_null_terminated(&target_name)?;
+        let part = match mesh_parts.iter_mut().rev().find(|x| x.name == part_name) {
+            Some(v) => { v },
+            None => { return Err(DBMeshError::ParseError); }
+        };
+
+        let delta_count = reader.read_u32::<LittleEndian>()?;
+
+        if delta_count as usize > reader.len() / MORPH_DELTA_SIZE {
+            return Err(DBMeshError::ParseError);
+        }
+
+        let mut deltas: Vec<DBMorphDelta> = Vec::with_capacity(delta_count as usize);
+
+        for _ in 0..delta_count {
+            let vertex_index = reader.read_u32::<LittleEndian>()?;
+            if vertex_index as usize >= part.vertices.len() {
+                return Err(DBMeshError::ParseError);
+            }
+
+            let pos = read_vector3_f16(&mut reader)?;
+            let nrm = read_vector3_f16(&mut reader)?;
+            deltas.push(DBMorphDelta { vertex_index: vertex_index, pos: pos, nrm: nrm });
+        }
+
+        log_debug!("Parsed morph target (part: {}, name: {}, delta count: {})", part.name, target_name, deltas.len());
+
+        part.morph_targets.push(DBMorphTarget {
+            name: target_name,
+            deltas: deltas,
+        });
+
+        return Ok(());
+    }
+}
+
+impl DBMesh {
+    /// Load a DBM mesh from the given stream <br/>
+    /// MLOD chunks contain a lower detail level for a preceding mesh part: a 32-byte part name, f32 switch distance, then a u16 triangle count & vertices in the same layout as MESH chunks <br/>
+    /// MRPH chunks contain a morph target for a preceding mesh part: a 32-byte part name, 32-byte target name, u32 delta count, and per delta a u32 vertex index followed by f16 position & normal offsets <br/>
+    /// tex_load_fn is called with the material name of each textured material to load its texture. Materials whose texture fails to load are left untextured
+    pub fn new<R,TL>(reader: &mut R, mut tex_load_fn: TL) -> Result<DBMesh,DBMeshError>
+        where R : Read + Seek,
+        TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        // read header
+        let mut id: [u8;4] = [0;4];
+        reader.read_exact(&mut id)?;
+
+        if &id != b"DBM\0" {
+            return Err(DBMeshError::ParseError);
+        }
+
+        let ver = reader.read_u32::<LittleEndian>()?;
+        if ver != DBM_VER {
+            return Err(DBMeshError::VersionError);
+        }
+
+        let mut mesh = DBMesh {
+            mesh_parts: Vec::new(),
+            skeleton: None,
+        };
+
+        // scan chunks
+        loop {
+            let mut chunk_id: [u8;4] = [0;4];
+            match reader.read_exact(&mut chunk_id) {
+                Ok(_) => {
+                },
+                Err(e) => {
+                    // EOF, no more chunks in stream
+                    if e.kind() == ErrorKind::UnexpectedEof {
+                        break;
+                    }
+                    return Err(DBMeshError::IOError(e));
+                }
+            };
+
+            let chunk_size = reader.read_u32::<LittleEndian>()?;
+
+            match &chunk_id {
+                b"SKEL" => {
+                    let mut chunk_data: Vec<u8> = vec![0;chunk_size as usize];
+                    reader.read_exact(&mut chunk_data)?;
+
+                    let skeleton = DBSkeleton::read(chunk_data.as_slice())?;
+                    log_debug!("Parsed skeleton (bone count: {})", skeleton.bone_count);
+                    mesh.skeleton = Some(skeleton);
+                },
+                b"MESH" => {
+                    let mesh_part = DBMeshPart::read(reader, &mut tex_load_fn)?;
+                    log_debug!("Parsed mesh part (name: {}, material: {}, vertex count: {})", mesh_part.name, mesh_part.material.name, mesh_part.vertices.len());
+                    mesh.mesh_parts.push(mesh_part);
+                },
+                b"MLOD" => {
+                    let mut chunk_data: Vec<u8> = vec![0;chunk_size as usize];
+                    reader.read_exact(&mut chunk_data)?;
+
+                    DBMeshLod::read(chunk_data.as_slice(), &mut mesh.mesh_parts)?;
+                },
+                b"MRPH" => {
+                    let mut chunk_data: Vec<u8> = vec![0;chunk_size as usize];
+                    reader.read_exact(&mut chunk_data)?;
+
+                    DBMorphTarget::read(chunk_data.as_slice(), &mut mesh.mesh_parts)?;
+                },
+                _ => {
+                    // unknown chunk ID, skip
+                    reader.seek(SeekFrom::Current(chunk_size as i64))?;
+                }
+            };
+        }
+
+        return Ok(mesh);
+    }
+
+    /// Load a DBM mesh from the given stream without loading any textures
+    pub fn new_untextured<R>(reader: &mut R) -> Result<DBMesh,DBMeshError> where R : Read + Seek {
+        return DBMesh::new(reader, |_| Err(()));
+    }
+
+    /// Get the mesh part with the given name
+    pub fn part(&self, name: &str) -> Option<&DBMeshPart> {
+        return self.mesh_parts.iter().find(|x| x.name == name);
+    }
+
+    /// Draw every mesh part, selecting each part's detail level from the distance between the camera & the part's origin <br/>
+    /// model is the mesh's world transform and view_proj the camera's combined view & projection transform. See DBMeshPart::draw_vertices <br/>
+    /// Every part is unpacked into a single vertex buffer & transformed with a TransformBatch, so parts sharing a transform only cost a single transform call
+    pub fn draw(&self, model: &Matrix4x4, view_proj: &Matrix4x4, camera_pos: Vector3, bone_palette: &[Matrix4x4]) {
+        let mut vtx_buffer = vdp::take_vertex_buffer(self.vertex_count());
+        self.draw_parts(model, view_proj, camera_pos, None, &mut vtx_buffer,
+            |part, vertices, _, out| part.unpack_vertices(vertices, bone_palette, out));
+    }
+
+    /// Like draw, but skipping mesh parts whose bounds are outside the camera frustum. Skipped parts are counted in the draw stats <br/>
+    /// Part bounds don't account for skinning or morph targets, so animated meshes may be culled while partially visible
+    pub fn draw_culled(&self, model: &Matrix4x4, view_proj: &Matrix4x4, camera_pos: Vector3, bone_palette: &[Matrix4x4]) {
+        let frustum = Frustum::from_matrix(view_proj);
+        let mut vtx_buffer = vdp::take_vertex_buffer(self.vertex_count());
+        self.draw_parts(model, view_proj, camera_pos, Some(&frustum), &mut vtx_buffer,
+            |part, vertices, _, out| part.unpack_vertices(vertices, bone_palette, out));
+    }
+
+    /// Like draw, but using PackedVertex vertices to roughly halve vertex bandwidth, optionally lit by the given lighting environment. See DBMeshPart::draw_vertices_packed
+    pub fn draw_packed(&self, model: &Matrix4x4, view_proj: &Matrix4x4, camera_pos: Vector3, bone_palette: &[Matrix4x4], lighting: Option<&SphericalHarmonics>) {
+        let mut vtx_buffer = vdp::take_packed_vertex_buffer(self.vertex_count());
+        self.draw_parts(model, view_proj, camera_pos, None, &mut vtx_buffer,
+            |part, vertices, world, out| part.unpack_vertices_packed(vertices, bone_palette, world, lighting, out));
+    }
+
+    /// Like draw_packed, but skipping mesh parts whose bounds are outside the camera frustum. See draw_culled
+    pub fn draw_packed_culled(&self, model: &Matrix4x4, view_proj: &Matrix4x4, camera_pos: Vector3, bone_palette: &[Matrix4x4], lighting: Option<&SphericalHarmonics>) {
+        let frustum = Frustum::from_matrix(view_proj);
+        let mut vtx_buffer = vdp::take_packed_vertex_buffer(self.vertex_count());
+        self.draw_parts(model, view_proj, camera_pos, Some(&frustum), &mut vtx_buffer,
+            |part, vertices, world, out| part.unpack_vertices_packed(vertices, bone_palette, world, lighting, out));
+    }
+
+    // unpack each visible part's detail level into the buffer, transform every part with a single batch, then draw each part with its material
+    fn draw_parts<T, U>(&self, model: &Matrix4x4, view_proj: &Matrix4x4, camera_pos: Vector3, frustum: Option<&Frustum>, vtx_buffer: &mut Vec<T>, unpack: U)
+        where T : GpuVertex,
+        U : Fn(&DBMeshPart, &[DBMeshVertex], &Matrix4x4, &mut Vec<T>)
+    {
+        let mvp = *model * *view_proj;
+
+        let mut batch: TransformBatch<T> = TransformBatch::new();
+        let mut ranges: Vec<(&DBMeshPart, Range<usize>)> = Vec::with_capacity(self.mesh_parts.len());
+        let mut culled = 0;
+
+        for part in &self.mesh_parts {
+            let world = part.transform * *model;
+
+            if let Some(f) = frustum {
+                if !f.intersects_aabb(&part.bounds.transformed(&world)) {
+                    culled += 1;
+                    continue;
+                }
+            }
+
+            let origin = Vector3::new(world.m[3][0], world.m[3][1], world.m[3][2]);
+            let start = vtx_buffer.len();
+            unpack(part, part.lod_vertices(Vector3::distance(&origin, &camera_pos)), &world, vtx_buffer);
+
+            batch.add(start..vtx_buffer.len(), &(part.transform * mvp), T::position());
+            ranges.push((part, start..vtx_buffer.len()));
+        }
+
+        batch.apply(vtx_buffer.as_mut_slice());
+
+        for (part, range) in ranges {
+            part.bind_material();
+            T::draw(&vtx_buffer[range]);
+        }
+
+        if frustum.is_some() {
+            vdp::record_culled(culled);
+        }
+    }
+
+    /// Get the bounds of every mesh part combined, in mesh space (after each part's transform)
+    pub fn bounds(&self) -> Aabb {
+        return self.mesh_parts.iter().fold(Aabb::EMPTY, |acc, x| acc.union(&x.bounds.transformed(&x.transform)));
+    }
+
+    /// Get the total number of vertices across every mesh part
+    pub fn vertex_count(&self) -> usize {
+        return self.mesh_parts.iter().map(|x| x.vertices.len()).sum();
+    }
+}
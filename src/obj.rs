@@ -0,0 +1,320 @@
+use std::{io::{BufRead, BufReader, Read}, sync::Arc};
+
+use crate::{io::{FileMode, FileStream, IOError}, log_debug, math::{Vector2, Vector3, Vector4}, vdp::{Color32, PackedVertex, Texture, Vertex}};
+
+/// Enumeration of errors which can result from loading an OBJ or MTL file
+#[derive(Debug)]
+pub enum ObjError {
+    ParseError,
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for ObjError {
+    fn from(value: std::io::Error) -> Self {
+        return ObjError::IOError(value);
+    }
+}
+
+impl From<IOError> for ObjError {
+    fn from(value: IOError) -> Self {
+        return ObjError::IOError(value.into());
+    }
+}
+
+/// Represents a material loaded from an MTL file
+pub struct ObjMaterial {
+    pub name: String,
+    /// Diffuse color (Kd), with alpha from dissolve (d)
+    pub diffuse_color: Vector4,
+    /// Diffuse texture (map_Kd), if present & loaded successfully
+    pub texture: Option<Arc<Texture>>,
+}
+
+impl ObjMaterial {
+    fn new(name: &str) -> ObjMaterial {
+        return ObjMaterial {
+            name: name.to_string(),
+            diffuse_color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            texture: None,
+        };
+    }
+}
+
+/// A triangle list sharing a single material
+pub struct ObjGroup {
+    pub material: ObjMaterial,
+    /// Triangle list vertices, with color set to the material's diffuse color multiplied by any vertex color
+    pub vertices: Vec<Vertex>,
+    /// Normal of each vertex (zero if the file doesn't specify normals)
+    pub normals: Vec<Vector3>,
+}
+
+impl ObjGroup {
+    /// Convert this group's vertices into packed vertices
+    pub fn packed_vertices(&self) -> Vec<PackedVertex> {
+        return self.vertices.iter().map(|v| {
+            return PackedVertex::new(v.position, Vector2::new(v.texcoord.x, v.texcoord.y), to_color32(v.color), to_color32(v.ocolor));
+        }).collect();
+    }
+}
+
+/// Static geometry loaded from a Wavefront OBJ file, grouped by material <br/>
+/// Polygons are triangulated as fans, and texture coordinates are flipped so that V runs top to bottom
+pub struct ObjMesh {
+    pub groups: Vec<ObjGroup>,
+}
+
+fn to_color32(v: Vector4) -> Color32 {
+    let c = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+    return Color32::new(c(v.x), c(v.y), c(v.z), c(v.w));
+}
+
+fn parse_floats<'a, I>(tokens: I, out: &mut [f32]) -> Result<usize,ObjError> where I : Iterator<Item = &'a str> {
+    let mut count = 0;
+    for (slot, token) in out.iter_mut().zip(tokens) {
+        *slot = match token.parse::<f32>() {
+            Ok(v) => { v },
+            Err(_) => { return Err(ObjError::ParseError); }
+        };
+        count += 1;
+    }
+    return Ok(count);
+}
+
+/// Resolve a 1-based (or negative, relative to the end) OBJ index into a 0-based index
+fn resolve_index(token: &str, len: usize) -> Result<Option<usize>,ObjError> {
+    if token.is_empty() {
+        return Ok(None);
+    }
+
+    let idx = match token.parse::<i64>() {
+        Ok(v) => { v },
+        Err(_) => { return Err(ObjError::ParseError); }
+    };
+
+    let resolved = if idx < 0 { len as i64 + idx } else { idx - 1 };
+    if resolved < 0 || resolved >= len as i64 {
+        return Err(ObjError::ParseError);
+    }
+
+    return Ok(Some(resolved as usize));
+}
+
+/// Load every material in an MTL file from the given stream <br/>
+/// tex_load_fn is called with the path of each material's diffuse texture (map_Kd) to load it
+pub fn load_mtl<R,TL>(reader: &mut R, mut tex_load_fn: TL) -> Result<Vec<ObjMaterial>,ObjError>
+    where R : Read,
+    TL : FnMut(&str) -> Result<Arc<Texture>,()>
+{
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+        let mut tokens = line.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(v) => { v },
+            None => { continue; }
+        };
+
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(line[keyword.len()..].trim()));
+            continue;
+        }
+
+        let material = match materials.last_mut() {
+            Some(v) => { v },
+            None => { continue; }
+        };
+
+        match keyword {
+            "Kd" => {
+                let mut rgb = [1.0;3];
+                parse_floats(tokens, &mut rgb)?;
+                material.diffuse_color = Vector4::new(rgb[0], rgb[1], rgb[2], material.diffuse_color.w);
+            },
+            "d" => {
+                let mut d = [1.0];
+                parse_floats(tokens, &mut d)?;
+                material.diffuse_color.w = d[0];
+            },
+            "Tr" => {
+                let mut tr = [0.0];
+                parse_floats(tokens, &mut tr)?;
+                material.diffuse_color.w = 1.0 - tr[0];
+            },
+            "map_Kd" => {
+                // options may precede the file name, which is always last
+                if let Some(path) = tokens.last() {
+                    material.texture = tex_load_fn(path).ok();
+                }
+            },
+            _ => {
+                // unsupported statement, skip
+            }
+        };
+    }
+
+    return Ok(materials);
+}
+
+impl ObjMesh {
+    /// Load an OBJ mesh from the given stream <br/>
+    /// mtl_load_fn is called with the path of each referenced material library to load its materials (see load_mtl). Missing materials are left white & untextured
+    pub fn new<R,ML>(reader: &mut R, mut mtl_load_fn: ML) -> Result<ObjMesh,ObjError>
+        where R : Read,
+        ML : FnMut(&str) -> Result<Vec<ObjMaterial>,ObjError>
+    {
+        let mut positions: Vec<Vector4> = Vec::new();
+        let mut colors: Vec<Vector4> = Vec::new();
+        let mut texcoords: Vec<Vector2> = Vec::new();
+        let mut normals: Vec<Vector3> = Vec::new();
+
+        let mut materials: Vec<ObjMaterial> = Vec::new();
+        let mut groups: Vec<ObjGroup> = Vec::new();
+        let mut current: Option<usize> = None;
+
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.trim();
+            let mut tokens = line.split_whitespace();
+
+            let keyword = match tokens.next() {
+                Some(v) => { v },
+                None => { continue; }
+            };
+
+            match keyword {
+                "v" => {
+                    // x y z, optionally followed by either w or an r g b vertex color
+                    let mut v = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+                    let count = parse_floats(tokens, &mut v)?;
+                    if count < 3 {
+                        return Err(ObjError::ParseError);
+                    }
+                    if count >= 6 {
+                        positions.push(Vector4::new(v[0], v[1], v[2], 1.0));
+                        colors.push(Vector4::new(v[3], v[4], v[5], 1.0));
+                    } else {
+                        positions.push(Vector4::new(v[0], v[1], v[2], v[3]));
+                        colors.push(Vector4::new(1.0, 1.0, 1.0, 1.0));
+                    }
+                },
+                "vt" => {
+                    let mut vt = [0.0;2];
+                    if parse_floats(tokens, &mut vt)? < 1 {
+                        return Err(ObjError::ParseError);
+                    }
+                    texcoords.push(Vector2::new(vt[0], 1.0 - vt[1]));
+                },
+                "vn" => {
+                    let mut vn = [0.0;3];
+                    if parse_floats(tokens, &mut vn)? < 3 {
+                        return Err(ObjError::ParseError);
+                    }
+                    normals.push(Vector3::new(vn[0], vn[1], vn[2]));
+                },
+                "mtllib" => {
+                    // file names may contain spaces
+                    let path = line[keyword.len()..].trim();
+                    if let Ok(loaded) = mtl_load_fn(path) {
+                        materials.extend(loaded);
+                    }
+                },
+                "usemtl" => {
+                    let name = line[keyword.len()..].trim();
+                    current = Some(match groups.iter().position(|x| x.material.name == name) {
+                        Some(v) => { v },
+                        None => {
+                            let material = match materials.iter().position(|x| x.name == name) {
+                                Some(idx) => { materials.swap_remove(idx) },
+                                None => { ObjMaterial::new(name) }
+                            };
+                            groups.push(ObjGroup { material: material, vertices: Vec::new(), normals: Vec::new() });
+                            groups.len() - 1
+                        }
+                    });
+                },
+                "f" => {
+                    let group_idx = match current {
+                        Some(v) => { v },
+                        None => {
+                            // faces before any usemtl use a default material
+                            groups.push(ObjGroup { material: ObjMaterial::new(""), vertices: Vec::new(), normals: Vec::new() });
+                            current = Some(groups.len() - 1);
+                            groups.len() - 1
+                        }
+                    };
+                    let group = &mut groups[group_idx];
+
+                    let mut face: Vec<(Vertex, Vector3)> = Vec::new();
+                    for token in tokens {
+                        let mut parts = token.split('/');
+                        let v = match resolve_index(parts.next().unwrap_or(""), positions.len())? {
+                            Some(v) => { v },
+                            None => { return Err(ObjError::ParseError); }
+                        };
+                        let vt = resolve_index(parts.next().unwrap_or(""), texcoords.len())?;
+                        let vn = resolve_index(parts.next().unwrap_or(""), normals.len())?;
+
+                        let tex = vt.map(|x| texcoords[x]).unwrap_or(Vector2::zero());
+                        let nrm = vn.map(|x| normals[x]).unwrap_or(Vector3::zero());
+                        let col = colors[v] * group.material.diffuse_color;
+
+                        face.push((Vertex::new(positions[v], col, Vector4::zero(), Vector4::new(tex.x, tex.y, 0.0, 0.0)), nrm));
+                    }
+
+                    if face.len() < 3 {
+                        return Err(ObjError::ParseError);
+                    }
+
+                    for i in 1..face.len() - 1 {
+                        for (vtx, nrm) in [face[0], face[i], face[i + 1]] {
+                            group.vertices.push(vtx);
+                            group.normals.push(nrm);
+                        }
+                    }
+                },
+                _ => {
+                    // unsupported statement (objects, smoothing groups, etc), skip
+                }
+            };
+        }
+
+        groups.retain(|x| !x.vertices.is_empty());
+
+        for group in &groups {
+            log_debug!("Parsed OBJ group (material: {}, vertex count: {})", group.material.name, group.vertices.len());
+        }
+
+        return Ok(ObjMesh { groups: groups });
+    }
+
+    /// Load an OBJ mesh from the given path, along with any material libraries it references (resolved relative to the OBJ file) <br/>
+    /// tex_load_fn is called with the path of each material's diffuse texture, also relative to the OBJ file
+    pub fn load<TL>(path: &str, mut tex_load_fn: TL) -> Result<ObjMesh,ObjError>
+        where TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        let dir = match path.rfind('/') {
+            Some(i) => { &path[..i + 1] },
+            None => { "" }
+        };
+
+        let mut file = FileStream::open(path, FileMode::Read)?;
+        return ObjMesh::new(&mut file, |mtl_path| {
+            let mut mtl_file = FileStream::open(format!("{}{}", dir, mtl_path).as_str(), FileMode::Read)?;
+            return load_mtl(&mut mtl_file, |tex_path| tex_load_fn(format!("{}{}", dir, tex_path).as_str()));
+        });
+    }
+
+    /// Get the group using the material with the given name
+    pub fn group(&self, material: &str) -> Option<&ObjGroup> {
+        return self.groups.iter().find(|x| x.material.name == material);
+    }
+
+    /// Get the total number of vertices across every group
+    pub fn vertex_count(&self) -> usize {
+        return self.groups.iter().map(|x| x.vertices.len()).sum();
+    }
+}
@@ -0,0 +1,268 @@
+use std::{convert::TryInto, io::{Read, Write}};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::io::{FileMode, FileStream, IOError, file_exists};
+
+pub mod codec;
+
+const SAVE_MAGIC: &[u8;4] = b"DBSV";
+const TAGGED_MAGIC: &[u8;4] = b"DBST";
+const HEADER_SIZE: usize = 16;
+
+/// Size in bytes of a single memory card block
+pub const BLOCK_SIZE: usize = 512;
+
+/// Encoding used for the payload of a save file
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SaveFormat {
+    /// Compact postcard encoding. Fields are stored by position, so any change to the data type requires a new data version & migration
+    Postcard,
+    /// Self-describing encoding from the codec module. Fields are stored by name, so fields can be added (with #[serde(default)]) or removed without a new data version, at the cost of a larger payload
+    Tagged,
+}
+
+/// Enumeration of errors which can result from saving or loading
+#[derive(Debug)]
+pub enum SaveError {
+    /// There isn't enough free space on the memory card to allocate the save file
+    NoSpaceOnDevice,
+    /// The serialized data doesn't fit in the save slot's block budget
+    TooLarge,
+    /// The save file doesn't exist
+    NotFound,
+    /// The save file is damaged or isn't a save file
+    CorruptData,
+    /// The save file was written with a different data version (contains the version found in the file)
+    VersionError(u32),
+    /// The data couldn't be serialized or deserialized
+    SerializeError,
+    IOError(IOError),
+}
+
+impl From<IOError> for SaveError {
+    fn from(value: IOError) -> Self {
+        return match value {
+            IOError::NoSpaceOnDevice => { SaveError::NoSpaceOnDevice },
+            IOError::FileNotFound => { SaveError::NotFound },
+            _ => { SaveError::IOError(value) }
+        };
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(value: std::io::Error) -> Self {
+        return SaveError::from(IOError::from(value));
+    }
+}
+
+/// A save file on a memory card, along with the icon shown in the memory card manager and the number of blocks it occupies <br/>
+/// Save files start with a "DBSV" (postcard) or "DBST" (tagged) identifier, u32 data version, u32 payload length, and u32 payload checksum, followed by the serialized payload
+pub struct SaveSlot {
+    pub path: String,
+    /// 32x32 4-bit icon data
+    pub icon: [u8;128],
+    /// 16-color RGB565 icon palette
+    pub palette: [u16;16],
+    /// Number of 512-byte blocks allocated for the save file <br/>
+    /// The size of a save file is fixed when it is first allocated with fs_allocMemoryCard and can't grow afterwards, so this should leave headroom for data added in later versions of the game
+    /// (particularly with the tagged format, where every field name is stored in the file). Changing this has no effect on save files which already exist
+    pub blocks: i32,
+    /// Format used when writing the save file (either format can be loaded regardless of this setting)
+    pub format: SaveFormat,
+}
+
+/// Get the number of memory card blocks needed to store a save file with a payload of the given size in bytes (see codec::to_bytes to measure tagged data)
+pub fn required_blocks(payload_size: usize) -> i32 {
+    return (HEADER_SIZE + payload_size).div_ceil(BLOCK_SIZE) as i32;
+}
+
+// FNV-1a hash, used to detect corrupted save data
+fn checksum(data: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811C_9DC5;
+    for b in data {
+        hash ^= *b as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    return hash;
+}
+
+/// Deserialize postcard save data payload bytes, for use in migrations which decode an older data layout
+pub fn decode<T>(payload: &[u8]) -> Result<T, SaveError> where T : DeserializeOwned {
+    return decode_format(payload, SaveFormat::Postcard);
+}
+
+/// Deserialize save data payload bytes in the given format, for use in migrations which decode an older data layout
+pub fn decode_format<T>(payload: &[u8], format: SaveFormat) -> Result<T, SaveError> where T : DeserializeOwned {
+    let result = match format {
+        SaveFormat::Postcard => { postcard::from_bytes(payload).ok() },
+        SaveFormat::Tagged => { codec::from_bytes(payload).ok() }
+    };
+
+    return match result {
+        Some(v) => { Ok(v) },
+        None => { Err(SaveError::CorruptData) }
+    };
+}
+
+/// Converts the payload bytes of save data written with an older data version (passed as the second argument) into the current data type <br/>
+/// The payload is in the format the file was written with - use decode for postcard saves & decode_format for tagged saves
+pub type MigrationFn<T> = fn(&[u8], u32) -> Result<T, SaveError>;
+
+/// Registry of migrations used to upgrade save data from older data versions to the current one
+pub struct Migrations<T> {
+    current: u32,
+    migrations: Vec<(u32, MigrationFn<T>)>,
+}
+
+impl<T> Migrations<T> {
+    /// Construct a new registry for the given current data version
+    pub fn new(current_version: u32) -> Migrations<T> {
+        return Migrations {
+            current: current_version,
+            migrations: Vec::new(),
+        };
+    }
+
+    /// Get the current data version
+    pub fn current_version(&self) -> u32 {
+        return self.current;
+    }
+
+    /// Register a migration from the given older data version, replacing any previously registered for that version
+    pub fn register(&mut self, from_version: u32, migration: MigrationFn<T>) {
+        self.migrations.retain(|x| x.0 != from_version);
+        self.migrations.push((from_version, migration));
+    }
+}
+
+impl SaveSlot {
+    /// Construct a new save slot at the given path (for example "/ma/mygame.sav"), which writes the tagged format
+    pub fn new(path: &str, icon: &[u8;128], palette: &[u16;16], blocks: i32) -> SaveSlot {
+        return SaveSlot {
+            path: path.to_string(),
+            icon: *icon,
+            palette: *palette,
+            blocks: blocks,
+            format: SaveFormat::Tagged,
+        };
+    }
+
+    /// Get the maximum size in bytes of serialized data which fits in this slot
+    pub fn capacity(&self) -> usize {
+        return (self.blocks.max(0) as usize * BLOCK_SIZE).saturating_sub(HEADER_SIZE);
+    }
+
+    /// Gets whether a save file exists in this slot
+    pub fn exists(&self) -> bool {
+        return file_exists(self.path.as_str());
+    }
+
+    /// Serialize the given data into this slot, allocating the save file on the memory card if it doesn't exist yet
+    pub fn save<T>(&self, version: u32, data: &T) -> Result<(), SaveError> where T : Serialize {
+        let payload = match self.format {
+            SaveFormat::Postcard => { postcard::to_allocvec(data).ok() },
+            SaveFormat::Tagged => { codec::to_bytes(data).ok() }
+        };
+
+        let payload = match payload {
+            Some(v) => { v },
+            None => { return Err(SaveError::SerializeError); }
+        };
+
+        if payload.len() > self.capacity() {
+            return Err(SaveError::TooLarge);
+        }
+
+        let mut file = if self.exists() {
+            FileStream::open(self.path.as_str(), FileMode::Write)?
+        } else {
+            FileStream::allocate_memory_card(self.path.as_str(), &self.icon, &self.palette, self.blocks)?
+        };
+
+        let mut buffer: Vec<u8> = Vec::with_capacity(HEADER_SIZE + payload.len());
+        buffer.extend_from_slice(if self.format == SaveFormat::Tagged { TAGGED_MAGIC } else { SAVE_MAGIC });
+        buffer.write_u32::<LittleEndian>(version)?;
+        buffer.write_u32::<LittleEndian>(payload.len().try_into().unwrap())?;
+        buffer.write_u32::<LittleEndian>(checksum(&payload))?;
+        buffer.extend_from_slice(&payload);
+
+        file.write_all(&buffer)?;
+        file.close()?;
+
+        return Ok(());
+    }
+
+    /// Read the data version of the save file in this slot, so older versions can be detected & migrated
+    pub fn read_version(&self) -> Result<u32, SaveError> {
+        let mut file = FileStream::open(self.path.as_str(), FileMode::Read)?;
+        return SaveSlot::read_header(&mut file).map(|(version, _, _, _)| version);
+    }
+
+    /// Load & deserialize the data in this slot, which must have been saved with the given data version
+    pub fn load<T>(&self, version: u32) -> Result<T, SaveError> where T : DeserializeOwned {
+        let (file_version, format, payload) = self.read_payload()?;
+
+        if file_version != version {
+            return Err(SaveError::VersionError(file_version));
+        }
+
+        return decode_format(&payload, format);
+    }
+
+    /// Load & deserialize the data in this slot, using the given migrations to upgrade data saved with an older data version
+    pub fn load_migrated<T>(&self, migrations: &Migrations<T>) -> Result<T, SaveError> where T : DeserializeOwned {
+        let (file_version, format, payload) = self.read_payload()?;
+
+        if file_version == migrations.current {
+            return decode_format(&payload, format);
+        }
+
+        let migration = match migrations.migrations.iter().find(|x| x.0 == file_version) {
+            Some(v) => { v.1 },
+            None => { return Err(SaveError::VersionError(file_version)); }
+        };
+
+        return migration(&payload, file_version);
+    }
+
+    // read the data version, format & verified payload of the save file
+    fn read_payload(&self) -> Result<(u32, SaveFormat, Vec<u8>), SaveError> {
+        let mut file = FileStream::open(self.path.as_str(), FileMode::Read)?;
+        let (version, format, length, crc) = SaveSlot::read_header(&mut file)?;
+
+        if length > self.capacity() {
+            return Err(SaveError::CorruptData);
+        }
+
+        let mut payload: Vec<u8> = vec![0;length];
+        if file.read_exact(&mut payload).is_err() || checksum(&payload) != crc {
+            return Err(SaveError::CorruptData);
+        }
+
+        return Ok((version, format, payload));
+    }
+
+    fn read_header(file: &mut FileStream) -> Result<(u32, SaveFormat, usize, u32), SaveError> {
+        let mut header: [u8;HEADER_SIZE] = [0;HEADER_SIZE];
+        if file.read_exact(&mut header).is_err() {
+            return Err(SaveError::CorruptData);
+        }
+
+        let format = if &header[0..4] == SAVE_MAGIC {
+            SaveFormat::Postcard
+        } else if &header[0..4] == TAGGED_MAGIC {
+            SaveFormat::Tagged
+        } else {
+            return Err(SaveError::CorruptData);
+        };
+
+        let mut fields = &header[4..];
+        let version = fields.read_u32::<LittleEndian>()?;
+        let length = fields.read_u32::<LittleEndian>()? as usize;
+        let crc = fields.read_u32::<LittleEndian>()?;
+
+        return Ok((version, format, length, crc));
+    }
+}
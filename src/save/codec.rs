@@ -0,0 +1,620 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize, de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor}, ser::{self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant}};
+
+const TAG_UNIT: u8 = 0;
+const TAG_NONE: u8 = 1;
+const TAG_SOME: u8 = 2;
+const TAG_FALSE: u8 = 3;
+const TAG_TRUE: u8 = 4;
+const TAG_UINT: u8 = 5;
+const TAG_INT: u8 = 6;
+const TAG_F32: u8 = 7;
+const TAG_F64: u8 = 8;
+const TAG_STR: u8 = 9;
+const TAG_BYTES: u8 = 10;
+const TAG_SEQ: u8 = 11;
+const TAG_MAP: u8 = 12;
+const TAG_ENUM: u8 = 13;
+const TAG_END: u8 = 14;
+
+/// Deepest nesting of skipped values before decoding fails, so corrupt data can't overflow the stack
+const MAX_SKIP_DEPTH: usize = 128;
+
+/// Enumeration of errors which can result from encoding or decoding tagged data
+#[derive(Debug)]
+pub enum CodecError {
+    /// The data ended in the middle of a value
+    UnexpectedEof,
+    /// The data contains an unknown type tag
+    InvalidTag(u8),
+    /// A string in the data isn't valid UTF-8
+    InvalidUtf8,
+    /// There is data left over after the decoded value
+    TrailingBytes,
+    /// The data nests values more deeply than can be decoded
+    TooDeep,
+    /// An error reported by the type being encoded or decoded (such as a missing field)
+    Message(String),
+}
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            CodecError::UnexpectedEof => { write!(f, "unexpected end of data") },
+            CodecError::InvalidTag(tag) => { write!(f, "invalid type tag {}", tag) },
+            CodecError::InvalidUtf8 => { write!(f, "invalid UTF-8 string") },
+            CodecError::TrailingBytes => { write!(f, "trailing bytes after value") },
+            CodecError::TooDeep => { write!(f, "values nested too deeply") },
+            CodecError::Message(msg) => { write!(f, "{}", msg) }
+        };
+    }
+}
+
+impl std::error::Error for CodecError {
+}
+
+impl ser::Error for CodecError {
+    fn custom<T>(msg: T) -> Self where T : Display {
+        return CodecError::Message(msg.to_string());
+    }
+}
+
+impl de::Error for CodecError {
+    fn custom<T>(msg: T) -> Self where T : Display {
+        return CodecError::Message(msg.to_string());
+    }
+}
+
+/// Encode a value into the tagged binary format <br/>
+/// Every value is prefixed with a one byte type tag, integers are stored as variable length (zigzag for signed types), and struct fields & enum variants are stored by name.
+/// Decoders skip fields they don't recognize, so data stays readable after fields are added or removed - fields added later should be marked #[serde(default)] so that older data without them still decodes
+pub fn to_bytes<T>(value: &T) -> Result<Vec<u8>, CodecError> where T : Serialize + ?Sized {
+    let mut serializer = Serializer { output: Vec::new() };
+    value.serialize(&mut serializer)?;
+    return Ok(serializer.output);
+}
+
+/// Decode a value from the tagged binary format
+pub fn from_bytes<'a, T>(data: &'a [u8]) -> Result<T, CodecError> where T : Deserialize<'a> {
+    let mut deserializer = Deserializer { input: data };
+    let value = T::deserialize(&mut deserializer)?;
+
+    if !deserializer.input.is_empty() {
+        return Err(CodecError::TrailingBytes);
+    }
+
+    return Ok(value);
+}
+
+struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.output.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.output.push(value as u8);
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.output.push(TAG_STR);
+        self.write_varint(value.len() as u64);
+        self.output.extend_from_slice(value.as_bytes());
+    }
+
+    fn write_variant(&mut self, variant: &str) {
+        self.output.push(TAG_ENUM);
+        self.write_varint(variant.len() as u64);
+        self.output.extend_from_slice(variant.as_bytes());
+    }
+}
+
+impl ser::Serializer for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn is_human_readable(&self) -> bool {
+        return false;
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), CodecError> {
+        self.output.push(if v { TAG_TRUE } else { TAG_FALSE });
+        return Ok(());
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), CodecError> {
+        return self.serialize_i64(v as i64);
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), CodecError> {
+        return self.serialize_i64(v as i64);
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), CodecError> {
+        return self.serialize_i64(v as i64);
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), CodecError> {
+        self.output.push(TAG_INT);
+        self.write_varint(((v << 1) ^ (v >> 63)) as u64);
+        return Ok(());
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), CodecError> {
+        return self.serialize_u64(v as u64);
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), CodecError> {
+        return self.serialize_u64(v as u64);
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), CodecError> {
+        return self.serialize_u64(v as u64);
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), CodecError> {
+        self.output.push(TAG_UINT);
+        self.write_varint(v);
+        return Ok(());
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), CodecError> {
+        self.output.push(TAG_F32);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        return Ok(());
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), CodecError> {
+        self.output.push(TAG_F64);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        return Ok(());
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), CodecError> {
+        self.write_str(v.encode_utf8(&mut [0;4]));
+        return Ok(());
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), CodecError> {
+        self.write_str(v);
+        return Ok(());
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), CodecError> {
+        self.output.push(TAG_BYTES);
+        self.write_varint(v.len() as u64);
+        self.output.extend_from_slice(v);
+        return Ok(());
+    }
+
+    fn serialize_none(self) -> Result<(), CodecError> {
+        self.output.push(TAG_NONE);
+        return Ok(());
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        self.output.push(TAG_SOME);
+        return value.serialize(self);
+    }
+
+    fn serialize_unit(self) -> Result<(), CodecError> {
+        self.output.push(TAG_UNIT);
+        return Ok(());
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), CodecError> {
+        return self.serialize_unit();
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<(), CodecError> {
+        self.write_variant(variant);
+        return self.serialize_unit();
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        return value.serialize(self);
+    }
+
+    fn serialize_newtype_variant<T>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        self.write_variant(variant);
+        return value.serialize(self);
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, CodecError> {
+        self.output.push(TAG_SEQ);
+        return Ok(self);
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self, CodecError> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self, CodecError> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self, CodecError> {
+        self.write_variant(variant);
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, CodecError> {
+        self.output.push(TAG_MAP);
+        return Ok(self);
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self, CodecError> {
+        return self.serialize_map(Some(len));
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self, CodecError> {
+        self.write_variant(variant);
+        return self.serialize_map(Some(len));
+    }
+}
+
+impl SerializeSeq for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        return value.serialize(&mut **self);
+    }
+
+    fn end(self) -> Result<(), CodecError> {
+        self.output.push(TAG_END);
+        return Ok(());
+    }
+}
+
+impl SerializeTuple for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        return value.serialize(&mut **self);
+    }
+
+    fn end(self) -> Result<(), CodecError> {
+        self.output.push(TAG_END);
+        return Ok(());
+    }
+}
+
+impl SerializeTupleStruct for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        return value.serialize(&mut **self);
+    }
+
+    fn end(self) -> Result<(), CodecError> {
+        self.output.push(TAG_END);
+        return Ok(());
+    }
+}
+
+impl SerializeTupleVariant for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        return value.serialize(&mut **self);
+    }
+
+    fn end(self) -> Result<(), CodecError> {
+        self.output.push(TAG_END);
+        return Ok(());
+    }
+}
+
+impl SerializeMap for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        return key.serialize(&mut **self);
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        return value.serialize(&mut **self);
+    }
+
+    fn end(self) -> Result<(), CodecError> {
+        self.output.push(TAG_END);
+        return Ok(());
+    }
+}
+
+impl SerializeStruct for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        self.write_str(key);
+        return value.serialize(&mut **self);
+    }
+
+    fn end(self) -> Result<(), CodecError> {
+        self.output.push(TAG_END);
+        return Ok(());
+    }
+}
+
+impl SerializeStructVariant for &mut Serializer {
+    type Ok = ();
+    type Error = CodecError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), CodecError> where T : Serialize + ?Sized {
+        self.write_str(key);
+        return value.serialize(&mut **self);
+    }
+
+    fn end(self) -> Result<(), CodecError> {
+        self.output.push(TAG_END);
+        return Ok(());
+    }
+}
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    fn peek_tag(&self) -> Result<u8, CodecError> {
+        return self.input.first().copied().ok_or(CodecError::UnexpectedEof);
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'de [u8], CodecError> {
+        if self.input.len() < len {
+            return Err(CodecError::UnexpectedEof);
+        }
+
+        let (bytes, rest) = self.input.split_at(len);
+        self.input = rest;
+        return Ok(bytes);
+    }
+
+    fn read_tag(&mut self) -> Result<u8, CodecError> {
+        return Ok(self.read_bytes(1)?[0]);
+    }
+
+    fn read_varint(&mut self) -> Result<u64, CodecError> {
+        let mut value: u64 = 0;
+        let mut shift = 0;
+
+        loop {
+            let b = self.read_tag()?;
+            if shift < 64 {
+                value |= ((b & 0x7F) as u64) << shift;
+            }
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_str(&mut self) -> Result<&'de str, CodecError> {
+        let len = self.read_varint()? as usize;
+        return std::str::from_utf8(self.read_bytes(len)?).map_err(|_| CodecError::InvalidUtf8);
+    }
+
+    fn expect_end(&mut self) -> Result<(), CodecError> {
+        return match self.read_tag()? {
+            TAG_END => { Ok(()) },
+            tag => { Err(CodecError::InvalidTag(tag)) }
+        };
+    }
+
+    // skip over the next value without decoding it
+    fn skip_value(&mut self) -> Result<(), CodecError> {
+        return self.skip_nested(MAX_SKIP_DEPTH);
+    }
+
+    // skip over the next value, failing if it nests more than depth values inside of it
+    fn skip_nested(&mut self, depth: usize) -> Result<(), CodecError> {
+        if depth == 0 {
+            return Err(CodecError::TooDeep);
+        }
+
+        match self.read_tag()? {
+            TAG_UNIT | TAG_NONE | TAG_FALSE | TAG_TRUE => {
+            },
+            TAG_SOME => {
+                self.skip_nested(depth - 1)?;
+            },
+            TAG_UINT | TAG_INT => {
+                self.read_varint()?;
+            },
+            TAG_F32 => {
+                self.read_bytes(4)?;
+            },
+            TAG_F64 => {
+                self.read_bytes(8)?;
+            },
+            TAG_STR | TAG_BYTES => {
+                let len = self.read_varint()? as usize;
+                self.read_bytes(len)?;
+            },
+            TAG_SEQ | TAG_MAP => {
+                while self.peek_tag()? != TAG_END {
+                    self.skip_nested(depth - 1)?;
+                }
+                self.expect_end()?;
+            },
+            TAG_ENUM => {
+                let len = self.read_varint()? as usize;
+                self.read_bytes(len)?;
+                self.skip_nested(depth - 1)?;
+            },
+            tag => {
+                return Err(CodecError::InvalidTag(tag));
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = CodecError;
+
+    fn is_human_readable(&self) -> bool {
+        return false;
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, CodecError> where V : Visitor<'de> {
+        return match self.read_tag()? {
+            TAG_UNIT => { visitor.visit_unit() },
+            TAG_NONE => { visitor.visit_none() },
+            TAG_SOME => { visitor.visit_some(self) },
+            TAG_FALSE => { visitor.visit_bool(false) },
+            TAG_TRUE => { visitor.visit_bool(true) },
+            TAG_UINT => { visitor.visit_u64(self.read_varint()?) },
+            TAG_INT => {
+                let v = self.read_varint()?;
+                visitor.visit_i64(((v >> 1) as i64) ^ -((v & 1) as i64))
+            },
+            TAG_F32 => { visitor.visit_f32(f32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap())) },
+            TAG_F64 => { visitor.visit_f64(f64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap())) },
+            TAG_STR => { visitor.visit_borrowed_str(self.read_str()?) },
+            TAG_BYTES => {
+                let len = self.read_varint()? as usize;
+                visitor.visit_borrowed_bytes(self.read_bytes(len)?)
+            },
+            TAG_SEQ => {
+                let value = visitor.visit_seq(Compound { de: self })?;
+                self.expect_end()?;
+                Ok(value)
+            },
+            TAG_MAP => {
+                let value = visitor.visit_map(Compound { de: self })?;
+                self.expect_end()?;
+                Ok(value)
+            },
+            TAG_ENUM => {
+                let variant = self.read_str()?;
+                visitor.visit_enum(Enum { de: self, variant: variant })
+            },
+            tag => { Err(CodecError::InvalidTag(tag)) }
+        };
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, CodecError> where V : Visitor<'de> {
+        return match self.peek_tag()? {
+            TAG_NONE => {
+                self.read_tag()?;
+                visitor.visit_none()
+            },
+            TAG_SOME => {
+                self.read_tag()?;
+                visitor.visit_some(self)
+            },
+            // a plain value, such as a field which has since been made optional
+            _ => { visitor.visit_some(self) }
+        };
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, CodecError> where V : Visitor<'de> {
+        return visitor.visit_newtype_struct(self);
+    }
+
+    fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, CodecError> where V : Visitor<'de> {
+        return match self.read_tag()? {
+            TAG_ENUM => {
+                let variant = self.read_str()?;
+                visitor.visit_enum(Enum { de: self, variant: variant })
+            },
+            tag => { Err(CodecError::InvalidTag(tag)) }
+        };
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, CodecError> where V : Visitor<'de> {
+        self.skip_value()?;
+        return visitor.visit_unit();
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+// elements of a sequence or entries of a map, up to the end tag
+struct Compound<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for Compound<'a, 'de> {
+    type Error = CodecError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, CodecError> where T : DeserializeSeed<'de> {
+        if self.de.peek_tag()? == TAG_END {
+            return Ok(None);
+        }
+        return seed.deserialize(&mut *self.de).map(Some);
+    }
+}
+
+impl<'de, 'a> MapAccess<'de> for Compound<'a, 'de> {
+    type Error = CodecError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, CodecError> where K : DeserializeSeed<'de> {
+        if self.de.peek_tag()? == TAG_END {
+            return Ok(None);
+        }
+        return seed.deserialize(&mut *self.de).map(Some);
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, CodecError> where V : DeserializeSeed<'de> {
+        return seed.deserialize(&mut *self.de);
+    }
+}
+
+struct Enum<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    variant: &'de str,
+}
+
+impl<'de, 'a> EnumAccess<'de> for Enum<'a, 'de> {
+    type Error = CodecError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), CodecError> where V : DeserializeSeed<'de> {
+        let variant = seed.deserialize(de::value::BorrowedStrDeserializer::<CodecError>::new(self.variant))?;
+        return Ok((variant, self));
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for Enum<'a, 'de> {
+    type Error = CodecError;
+
+    fn unit_variant(self) -> Result<(), CodecError> {
+        return self.de.skip_value();
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, CodecError> where T : DeserializeSeed<'de> {
+        return seed.deserialize(self.de);
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, CodecError> where V : Visitor<'de> {
+        return de::Deserializer::deserialize_any(self.de, visitor);
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, CodecError> where V : Visitor<'de> {
+        return de::Deserializer::deserialize_any(self.de, visitor);
+    }
+}
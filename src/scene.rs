@@ -0,0 +1,446 @@
+use std::sync::Arc;
+
+use field_offset::offset_of;
+
+use crate::{math::{Matrix4x4, Quaternion, Vector2, Vector3, Vector4}, vdp::{self, BlendEquation, BlendFactor, Color32, Rectangle, Texture, Vertex}};
+
+#[cfg(feature = "mesh")]
+use crate::{math::bounds::Frustum, mesh::DBMesh};
+
+/// The translation, rotation & scale of a scene node relative to its parent
+#[derive(Clone, Copy)]
+pub struct Transform {
+    pub position: Vector3,
+    pub rotation: Quaternion,
+    pub scale: Vector3,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { position: Vector3::zero(), rotation: Quaternion::identity(), scale: Vector3::new(1.0, 1.0, 1.0) };
+
+    pub const fn new(position: Vector3, rotation: Quaternion, scale: Vector3) -> Transform {
+        return Transform { position: position, rotation: rotation, scale: scale };
+    }
+
+    /// Construct a transform with the given position, no rotation, and unit scale
+    pub const fn from_position(position: Vector3) -> Transform {
+        return Transform { position: position, rotation: Quaternion::identity(), scale: Vector3::new(1.0, 1.0, 1.0) };
+    }
+
+    /// Compute the transform matrix (scale, then rotation, then translation)
+    pub fn to_matrix(&self) -> Matrix4x4 {
+        let mut mat = Matrix4x4::identity();
+        Matrix4x4::load_simd(&Matrix4x4::scale(self.scale));
+        Matrix4x4::mul_simd(&Matrix4x4::rotation(self.rotation));
+        Matrix4x4::mul_simd(&Matrix4x4::translation(self.position));
+        Matrix4x4::store_simd(&mut mat);
+        return mat;
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        return Transform::IDENTITY;
+    }
+}
+
+/// Something drawn at a scene node's world transform
+#[derive(Clone)]
+pub enum Renderable {
+    /// A single part of a mesh, drawn unskinned with the detail level selected by camera distance & skipped if outside the camera frustum
+    #[cfg(feature = "mesh")]
+    MeshPart { mesh: Arc<DBMesh>, part: usize },
+    /// A textured quad of the given size, centered on the node's origin in its local XY plane
+    Sprite { texture: Arc<Texture>, src: Rectangle, size: Vector2, color: Color32 },
+    /// A textured quad of the given size, centered on the node's origin & always facing the camera (ignores node rotation & scale)
+    Billboard { texture: Arc<Texture>, src: Rectangle, size: Vector2, color: Color32 },
+}
+
+/// Handle to a node in a scene <br/>
+/// Handles of removed nodes may be reused by nodes added later
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NodeId(usize);
+
+/// A single node in a scene
+pub struct SceneNode {
+    pub name: String,
+    pub transform: Transform,
+    pub renderable: Option<Renderable>,
+    /// Invisible nodes skip drawing both themselves and their children
+    pub visible: bool,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    world: Matrix4x4,
+}
+
+impl SceneNode {
+    /// Get the parent of this node, or None if it's a root node
+    pub fn parent(&self) -> Option<NodeId> {
+        return self.parent;
+    }
+
+    /// Get the children of this node, in draw order
+    pub fn children(&self) -> &[NodeId] {
+        return &self.children;
+    }
+
+    /// Get the world transform of this node as of the last call to update_transforms or draw
+    pub fn world_matrix(&self) -> Matrix4x4 {
+        return self.world;
+    }
+}
+
+/// The order in which a scene submits its renderables
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DrawOrder {
+    /// Draw renderables in traversal order
+    Traversal,
+    /// Draw opaque renderables first grouped by texture & cull state and front to back within each group, then blended renderables back to front <br/>
+    /// This cuts redundant state changes & overdraw, at the cost of ignoring traversal order
+    Sorted,
+}
+
+// a renderable queued for drawing along with its sort key
+#[derive(Clone, Copy)]
+struct DrawItem {
+    node: NodeId,
+    blend: bool,
+    texture: usize,
+    cull: bool,
+    distance: f32,
+}
+
+/// A retained hierarchy of nodes, each with a transform relative to its parent and an optional renderable <br/>
+/// Nodes are traversed depth first, in the order they were added to their parent
+pub struct Scene {
+    /// The order in which renderables are submitted by draw
+    pub draw_order: DrawOrder,
+    nodes: Vec<Option<SceneNode>>,
+    free: Vec<usize>,
+    roots: Vec<NodeId>,
+    draw_list: Vec<DrawItem>,
+}
+
+fn quad_vertices(corners: [Vector3;4], texture: &Texture, src: Rectangle, color: Color32) -> [Vertex;6] {
+    let uv_min = Vector2::new(src.x as f32 / texture.width as f32, src.y as f32 / texture.height as f32);
+    let uv_max = Vector2::new((src.x + src.width) as f32 / texture.width as f32, (src.y + src.height) as f32 / texture.height as f32);
+    let uv = [uv_min, Vector2::new(uv_max.x, uv_min.y), uv_max, Vector2::new(uv_min.x, uv_max.y)];
+    let col = Vector4::from(color);
+
+    return [0, 1, 2, 2, 3, 0].map(|i| {
+        return Vertex::new(corners[i].extend(1.0), col, Vector4::zero(), Vector4::new(uv[i].x, uv[i].y, 0.0, 0.0));
+    });
+}
+
+fn draw_quad(vertices: &mut [Vertex;6], texture: &Texture, transform: &Matrix4x4) {
+    Matrix4x4::load_simd(transform);
+    Matrix4x4::transform_vertex_simd(vertices.as_mut_slice(), offset_of!(Vertex => position));
+
+    vdp::set_culling(false);
+    vdp::bind_texture(Some(texture));
+    vdp::blend_equation(BlendEquation::Add);
+    vdp::blend_func(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha);
+    vdp::depth_write(false);
+    vdp::draw_geometry(vdp::Topology::TriangleList, vertices.as_slice());
+}
+
+// compute the sort key of a renderable drawn at the given world transform
+fn draw_item(node: NodeId, renderable: &Renderable, world: &Matrix4x4, camera_pos: Vector3) -> DrawItem {
+    let texture_key = |t: Option<&Arc<Texture>>| t.map_or(0, |x| Arc::as_ptr(x) as usize);
+
+    let (blend, texture, cull, world) = match renderable {
+        #[cfg(feature = "mesh")]
+        Renderable::MeshPart { mesh, part } => {
+            match mesh.mesh_parts.get(*part) {
+                Some(v) => { (v.material.blend_enable, texture_key(v.material.texture.as_ref()), v.material.enable_cull, v.transform * *world) },
+                None => { (false, 0, false, *world) }
+            }
+        },
+        Renderable::Sprite { texture, .. } | Renderable::Billboard { texture, .. } => {
+            (true, texture_key(Some(texture)), false, *world)
+        }
+    };
+
+    let origin = Vector3::new(world.m[3][0], world.m[3][1], world.m[3][2]);
+    return DrawItem {
+        node: node,
+        blend: blend,
+        texture: texture,
+        cull: cull,
+        distance: Vector3::distance(&origin, &camera_pos),
+    };
+}
+
+impl Scene {
+    /// Construct a new empty scene
+    pub fn new() -> Scene {
+        return Scene {
+            draw_order: DrawOrder::Traversal,
+            nodes: Vec::new(),
+            free: Vec::new(),
+            roots: Vec::new(),
+            draw_list: Vec::new(),
+        };
+    }
+
+    /// Add a new node to the scene as the last child of the given parent (or as a root node if None) <br/>
+    /// Returns None if the parent doesn't exist
+    pub fn add(&mut self, name: &str, transform: Transform, renderable: Option<Renderable>, parent: Option<NodeId>) -> Option<NodeId> {
+        if let Some(p) = parent {
+            self.node(p)?;
+        }
+
+        let node = SceneNode {
+            name: name.to_string(),
+            transform: transform,
+            renderable: renderable,
+            visible: true,
+            parent: parent,
+            children: Vec::new(),
+            world: Matrix4x4::identity(),
+        };
+
+        let id = match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = Some(node);
+                NodeId(idx)
+            },
+            None => {
+                self.nodes.push(Some(node));
+                NodeId(self.nodes.len() - 1)
+            }
+        };
+
+        self.child_list(parent).push(id);
+        return Some(id);
+    }
+
+    /// Remove the given node along with all of its children
+    pub fn remove(&mut self, id: NodeId) {
+        let parent = match self.node(id) {
+            Some(v) => { v.parent },
+            None => { return; }
+        };
+        self.child_list(parent).retain(|x| *x != id);
+
+        let mut pending = vec![id];
+        while let Some(NodeId(idx)) = pending.pop() {
+            if let Some(node) = self.nodes[idx].take() {
+                pending.extend(node.children);
+                self.free.push(idx);
+            }
+        }
+    }
+
+    /// Move the given node to be the last child of a new parent (or a root node if None), keeping its local transform <br/>
+    /// Returns false if either node doesn't exist, or if the new parent is the node itself or one of its descendants
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> bool {
+        let old_parent = match self.node(id) {
+            Some(v) => { v.parent },
+            None => { return false; }
+        };
+
+        // walk up from the new parent to make sure this wouldn't create a cycle
+        let mut ancestor = parent;
+        while let Some(a) = ancestor {
+            if a == id {
+                return false;
+            }
+            ancestor = match self.node(a) {
+                Some(v) => { v.parent },
+                None => { return false; }
+            };
+        }
+
+        self.child_list(old_parent).retain(|x| *x != id);
+        self.child_list(parent).push(id);
+        self.nodes[id.0].as_mut().unwrap().parent = parent;
+        return true;
+    }
+
+    /// Get the node with the given handle
+    pub fn node(&self, id: NodeId) -> Option<&SceneNode> {
+        return self.nodes.get(id.0)?.as_ref();
+    }
+
+    /// Get the node with the given handle
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut SceneNode> {
+        return self.nodes.get_mut(id.0)?.as_mut();
+    }
+
+    /// Find the first node with the given name
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        return self.nodes.iter().position(|x| x.as_ref().is_some_and(|n| n.name == name)).map(NodeId);
+    }
+
+    /// Get the root nodes of the scene, in draw order
+    pub fn roots(&self) -> &[NodeId] {
+        return &self.roots;
+    }
+
+    /// Get the number of nodes in the scene
+    pub fn len(&self) -> usize {
+        return self.nodes.len() - self.free.len();
+    }
+
+    /// Gets whether the scene contains no nodes
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    fn child_list(&mut self, parent: Option<NodeId>) -> &mut Vec<NodeId> {
+        return match parent {
+            Some(p) => { &mut self.nodes[p.0].as_mut().unwrap().children },
+            None => { &mut self.roots }
+        };
+    }
+
+    /// Visit every node depth first after computing its world transform, skipping invisible subtrees if visible_only is set
+    fn traverse<F>(&mut self, visible_only: bool, mut visit: F) where F : FnMut(NodeId, &SceneNode) {
+        let mut pending: Vec<(NodeId, Matrix4x4)> = self.roots.iter().rev().map(|x| (*x, Matrix4x4::identity())).collect();
+
+        while let Some((id, parent_world)) = pending.pop() {
+            let node = match self.nodes[id.0].as_mut() {
+                Some(v) => { v },
+                None => { continue; }
+            };
+
+            Matrix4x4::load_simd(&node.transform.to_matrix());
+            Matrix4x4::mul_simd(&parent_world);
+            Matrix4x4::store_simd(&mut node.world);
+
+            if visible_only && !node.visible {
+                continue;
+            }
+
+            visit(id, node);
+
+            // pushed in reverse so that children are visited in order
+            pending.extend(node.children.iter().rev().map(|x| (*x, node.world)));
+        }
+    }
+
+    /// Recompute the world transform of every node
+    pub fn update_transforms(&mut self) {
+        self.traverse(false, |_, _| {});
+    }
+
+    /// Recompute the world transform of every visible node & draw each renderable with the given camera, in the scene's draw order
+    pub fn draw(&mut self, view: &Matrix4x4, projection: &Matrix4x4) {
+        let mut view_proj = Matrix4x4::identity();
+        Matrix4x4::load_simd(view);
+        Matrix4x4::mul_simd(projection);
+        Matrix4x4::store_simd(&mut view_proj);
+
+        // camera axes & position in world space, from the inverse of the view matrix's rotation
+        let camera_right = Vector3::new(view.m[0][0], view.m[1][0], view.m[2][0]);
+        let camera_up = Vector3::new(view.m[0][1], view.m[1][1], view.m[2][1]);
+
+        let camera_pos = {
+            let camera_back = Vector3::new(view.m[0][2], view.m[1][2], view.m[2][2]);
+            let t = Vector3::new(view.m[3][0], view.m[3][1], view.m[3][2]);
+            (camera_right * -t.x) + (camera_up * -t.y) + (camera_back * -t.z)
+        };
+
+        #[cfg(feature = "mesh")]
+        let frustum = Frustum::from_matrix(&view_proj);
+        #[cfg(feature = "mesh")]
+        let mut culled = 0;
+
+        // queue up renderables first so they can be reordered before drawing
+        let sorted = self.draw_order == DrawOrder::Sorted;
+        let mut draw_list = std::mem::take(&mut self.draw_list);
+
+        self.traverse(true, |id, node| {
+            if let Some(renderable) = &node.renderable {
+                if sorted {
+                    draw_list.push(draw_item(id, renderable, &node.world, camera_pos));
+                } else {
+                    draw_list.push(DrawItem { node: id, blend: false, texture: 0, cull: false, distance: 0.0 });
+                }
+            }
+        });
+
+        if sorted {
+            draw_list.sort_by(|a, b| {
+                return a.blend.cmp(&b.blend).then_with(|| {
+                    if a.blend {
+                        // blended draws must stay back to front to composite correctly
+                        b.distance.total_cmp(&a.distance)
+                    } else {
+                        a.texture.cmp(&b.texture)
+                            .then(a.cull.cmp(&b.cull))
+                            .then(a.distance.total_cmp(&b.distance))
+                    }
+                });
+            });
+        }
+
+        for item in &draw_list {
+            let node = self.nodes[item.node.0].as_ref().unwrap();
+            let renderable = match &node.renderable {
+                Some(v) => { v },
+                None => { continue; }
+            };
+
+            match renderable {
+                #[cfg(feature = "mesh")]
+                Renderable::MeshPart { mesh, part } => {
+                    let mesh_part = match mesh.mesh_parts.get(*part) {
+                        Some(v) => { v },
+                        None => { continue; }
+                    };
+
+                    let mut world = Matrix4x4::identity();
+                    Matrix4x4::load_simd(&mesh_part.transform);
+                    Matrix4x4::mul_simd(&node.world);
+                    Matrix4x4::store_simd(&mut world);
+
+                    if !frustum.intersects_aabb(&mesh_part.bounds.transformed(&world)) {
+                        culled += 1;
+                        continue;
+                    }
+
+                    let mut mvp = Matrix4x4::identity();
+                    Matrix4x4::load_simd(&node.world);
+                    Matrix4x4::mul_simd(&view_proj);
+                    Matrix4x4::store_simd(&mut mvp);
+
+                    let origin = Vector3::new(world.m[3][0], world.m[3][1], world.m[3][2]);
+                    mesh_part.draw_lod(Vector3::distance(&origin, &camera_pos), &mvp, &[]);
+                },
+                Renderable::Sprite { texture, src, size, color } => {
+                    let (hw, hh) = (size.x * 0.5, size.y * 0.5);
+                    let corners = [Vector3::new(-hw, hh, 0.0), Vector3::new(hw, hh, 0.0), Vector3::new(hw, -hh, 0.0), Vector3::new(-hw, -hh, 0.0)];
+
+                    let mut mvp = Matrix4x4::identity();
+                    Matrix4x4::load_simd(&node.world);
+                    Matrix4x4::mul_simd(&view_proj);
+                    Matrix4x4::store_simd(&mut mvp);
+
+                    draw_quad(&mut quad_vertices(corners, texture, *src, *color), texture, &mvp);
+                },
+                Renderable::Billboard { texture, src, size, color } => {
+                    let origin = Vector3::new(node.world.m[3][0], node.world.m[3][1], node.world.m[3][2]);
+                    let right = camera_right * (size.x * 0.5);
+                    let up = camera_up * (size.y * 0.5);
+                    let corners = [origin - right + up, origin + right + up, origin + right - up, origin - right - up];
+
+                    draw_quad(&mut quad_vertices(corners, texture, *src, *color), texture, &view_proj);
+                }
+            };
+        }
+
+        draw_list.clear();
+        self.draw_list = draw_list;
+
+        #[cfg(feature = "mesh")]
+        vdp::record_culled(culled);
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        return Scene::new();
+    }
+}
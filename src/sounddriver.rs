@@ -1,8 +1,8 @@
-use std::{convert::TryInto, mem::{size_of, transmute}, cell::RefCell, sync::{Weak, RwLock}, sync::Arc, io::{Read, Seek}};
+use std::{cell::Cell, convert::TryInto, f32::consts::{FRAC_PI_4, TAU}, mem::size_of, sync::{Weak, RwLock}, sync::Arc, io::{Read, Seek}};
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
-use crate::{audio::{VOICE_COUNT, AudioSample, get_voice_state, queue_stop_voice, get_time, queue_start_voice, queue_set_voice_param_f, AudioVoiceParam, queue_set_voice_param_i}, math::{Vector3, Quaternion}, io::FileStream};
+use crate::{audio::{VOICE_COUNT, AudioSample, get_voice_state, queue_stop_voice, get_time, queue_start_voice, queue_set_voice_param_f, AudioVoiceParam, queue_set_voice_param_i}, math::{Vector3, Quaternion, Lerp}, io::BufferedStream};
 
 #[derive(Clone, Copy)]
 pub enum AttenuationType {
@@ -12,15 +12,137 @@ pub enum AttenuationType {
     ExponentialDistance,
 }
 
+/// Mixer bus which a sound emitter is routed through
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundBus {
+    Sfx,
+    Music,
+    Voice,
+    Ui,
+}
+
+/// How an emitter's pan value is converted into voice volume & pan
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PanLaw {
+    /// Pan is passed straight to the hardware, which keeps the louder side at full volume (centered sounds are louder than hard-panned ones)
+    Linear,
+    /// Left & right gains follow a sine/cosine curve so total power stays constant across the stereo field
+    ConstantPower,
+}
+
+/// Total number of mixer buses
+pub const BUS_COUNT: usize = 4;
+
+/// Gameplay-defined category a sound emitter belongs to (for example world, dialogue, or menu sounds), with independent volume & mute <br/>
+/// Category ids must be less than CATEGORY_COUNT
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SoundCategory(u8);
+
+impl SoundCategory {
+    /// The category sounds belong to unless otherwise specified
+    pub const DEFAULT: SoundCategory = SoundCategory(0);
+
+    /// Construct a category with the given id (returns None if the id is not less than CATEGORY_COUNT)
+    pub const fn new(id: u8) -> Option<SoundCategory> {
+        if id as usize >= CATEGORY_COUNT {
+            return None;
+        }
+        return Some(SoundCategory(id));
+    }
+
+    /// Get the id of this category
+    pub fn id(&self) -> u8 {
+        return self.0;
+    }
+}
+
+/// Total number of sound categories
+pub const CATEGORY_COUNT: usize = 16;
+
+/// Playback state of a sound emitter
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EmitterState {
+    /// Waiting for its scheduled start time
+    Scheduled,
+    /// Playing on a hardware voice
+    Playing,
+    /// Playing, but not audible because there are more important emitters than hardware voices
+    Virtual,
+    /// Paused until resumed
+    Paused,
+    /// Finished playing or stopped
+    Stopped,
+}
+
+/// Ducks (temporarily lowers the volume of) one bus while any emitter on another bus is playing, for example lowering music under dialogue
+#[derive(Clone, Copy, Debug)]
+pub struct DuckingRule {
+    /// The bus whose playing emitters trigger ducking
+    pub trigger: SoundBus,
+    /// The bus which is ducked
+    pub target: SoundBus,
+    /// How far the target bus is lowered in decibels
+    pub attenuation_db: f32,
+    /// Time in seconds to fully duck the target bus once triggered
+    pub attack: f32,
+    /// Time in seconds to return the target bus to full volume once no trigger emitters are playing
+    pub release: f32,
+}
+
+struct DuckingState {
+    rule: DuckingRule,
+    level: f32,
+}
+
+/// Selects which emitters driver-wide controls such as SoundDriver::stop_all apply to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EmitterFilter {
+    All,
+    Bus(SoundBus),
+    Category(SoundCategory),
+}
+
+impl EmitterFilter {
+    fn matches(&self, emitter: &SoundEmitter) -> bool {
+        return match self {
+            EmitterFilter::All => { true },
+            EmitterFilter::Bus(bus) => { emitter.bus == *bus },
+            EmitterFilter::Category(category) => { emitter.category == *category },
+        };
+    }
+}
+
+#[derive(Clone, Copy)]
+struct SoundBusState {
+    volume: f32,
+    muted: bool,
+}
+
+impl SoundBusState {
+    fn gain(&self) -> f32 {
+        return if self.muted { 0.0 } else { self.volume };
+    }
+}
+
 #[derive(Clone, Copy)]
 struct SoundVoice {
     slot: i32,
-    priority: u8,
-    _is_playing: bool,
+    is_playing: bool,
     id: u32,
-    play_time: f64,
+    start_time: f64,
+    reserved: bool,
+    acquired: bool,
 }
 
+/// Amount of time in seconds before a scheduled emitter's start time that it is assigned a hardware voice & queued
+pub const SCHEDULE_LOOKAHEAD: f64 = 0.1;
+
+/// Maximum delay in seconds before a virtualized loop is resumed in sync with its next loop iteration (longer waits restart the loop immediately)
+pub const MAX_RESYNC_DELAY: f64 = 0.5;
+
+/// Playback speed multiplier a resumed emitter's hardware voice silently fast forwards at to reach the position it was paused at
+pub const SEEK_SPEED: f64 = 64.0;
+
 pub struct SoundEmitter {
     pub is_valid: bool,
     pub priority: u8,
@@ -32,12 +154,137 @@ pub struct SoundEmitter {
     pub atten_max_dist: f32,
     pub atten_rolloff: f32,
     pub position: Vector3,
+    /// Velocity in units per second, used for Doppler calculations
+    pub velocity: Vector3,
+    /// Orientation of the emitter, used for cone attenuation (the cone points along the emitter's local -Z axis)
+    pub orientation: Quaternion,
+    /// Full angle in radians of the cone inside which the emitter plays at full volume (TAU for an omnidirectional emitter)
+    pub cone_inner_angle: f32,
+    /// Full angle in radians of the cone outside which the emitter plays at cone_outer_gain
+    pub cone_outer_angle: f32,
+    /// Gain applied to the emitter outside of the outer cone
+    pub cone_outer_gain: f32,
     pub volume: f32,
     pub pitch: f32,
     pub pan: f32,
+    pub bus: SoundBus,
+    pub category: SoundCategory,
+    /// Duration in seconds the hardware voice fades in over when the emitter starts playing
+    pub fade_in: f32,
+    /// Loop start position in sample frames
+    pub loop_start: i32,
+    /// Loop end position in sample frames (0 loops the entire sample)
+    pub loop_end: i32,
     sample: Arc<AudioSample>,
     id: u32,
     voice: Option<i32>,
+    paused: bool,
+    fade_from: f32,
+    fade_target: f32,
+    fade_start: f64,
+    fade_duration: f32,
+    fade_stop: Option<f32>,
+    // playback position in frames, tracked even while the emitter has no hardware voice
+    play_position: f64,
+    // non-looping emitters only get a chance to start once, after which they play out virtually if they lose their voice
+    started: bool,
+    start_time: f64,
+    // time the current hardware voice was queued to start at, which may be up to SCHEDULE_LOOKAHEAD in the future
+    voice_start: f64,
+    // position in frames the next hardware voice fast forwards to before becoming audible, set when resuming a paused emitter
+    seek: f64,
+    // time the current hardware voice finishes fast forwarding, from which its params are queued
+    seek_end: f64,
+    on_complete: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl SoundEmitter {
+    /// Gets whether this emitter is currently paused
+    pub fn is_paused(&self) -> bool {
+        return self.paused;
+    }
+
+    /// Gets whether this emitter is virtualized (still playing, but not audible because there are more important emitters than hardware voices)
+    pub fn is_virtual(&self) -> bool {
+        return self.is_valid && !self.paused && self.voice.is_none() && !self.is_scheduled();
+    }
+
+    /// Get the current playback state of this emitter
+    pub fn state(&self) -> EmitterState {
+        if !self.is_valid {
+            return EmitterState::Stopped;
+        } else if self.paused {
+            return EmitterState::Paused;
+        } else if self.is_scheduled() {
+            return EmitterState::Scheduled;
+        } else if self.voice.is_some() {
+            return EmitterState::Playing;
+        }
+
+        return EmitterState::Virtual;
+    }
+
+    /// Set a callback invoked from SoundDriver::update once this emitter finishes playing or is stopped
+    pub fn set_on_complete<F>(&mut self, callback: F) where F : FnOnce() + Send + Sync + 'static {
+        self.on_complete = Some(Box::new(callback));
+    }
+
+    /// Gets whether this emitter is waiting for its scheduled start time
+    pub fn is_scheduled(&self) -> bool {
+        let t = get_time();
+        return (!self.started && self.start_time > t) || (self.voice.is_some() && self.voice_start > t);
+    }
+
+    /// Smoothly ramp the volume of this emitter to the given value over the given duration in seconds
+    pub fn fade_to(&mut self, volume: f32, duration: f32) {
+        self.fade_from = self.volume;
+        self.fade_target = volume;
+        self.fade_start = get_time();
+        self.fade_duration = duration;
+
+        if duration <= 0.0 {
+            self.volume = volume;
+        }
+    }
+
+    /// Gets whether this emitter is currently ramping its volume
+    pub fn is_fading(&self) -> bool {
+        return self.fade_duration > 0.0;
+    }
+
+    /// Stop this emitter, fading out the hardware voice over the given duration in seconds (takes effect on the next SoundDriver update)
+    pub fn stop_with_fade(&mut self, duration: f32) {
+        self.fade_stop = Some(duration);
+    }
+
+    // unpause, letting the emitter get a new voice which seeks to the position it was paused at
+    fn resume(&mut self) {
+        self.paused = false;
+        self.started = false;
+        self.seek = self.play_position.max(0.0);
+    }
+
+    // get the loop region in frames, resolving a loop end of 0 to the end of the sample
+    fn loop_region(&self) -> (f64, f64) {
+        let length = self.sample.length as f64;
+        let end = if self.loop_end > 0 { (self.loop_end as f64).min(length) } else { length };
+        let start = (self.loop_start as f64).clamp(0.0, end);
+        return (start, end);
+    }
+
+    fn update_fade(&mut self, t: f64) {
+        if self.fade_duration <= 0.0 {
+            return;
+        }
+
+        let elapsed = (t - self.fade_start) as f32;
+        if elapsed >= self.fade_duration {
+            self.volume = self.fade_target;
+            self.fade_duration = 0.0;
+        } else {
+            self.volume = f32::lerp(self.fade_from, self.fade_target, elapsed / self.fade_duration);
+        }
+    }
 }
 
 struct WavHeader {
@@ -47,7 +294,7 @@ struct WavHeader {
 }
 
 impl WavHeader {
-    pub fn read(fs: &mut FileStream) -> WavHeader {
+    pub fn read<R>(fs: &mut R) -> WavHeader where R : Read {
         let mut riff: [u8;4] = [0;4];
         fs.read_exact(&mut riff).expect("Failed reading header");
         let overall_size = fs.read_u32::<LittleEndian>().expect("Failed reading header");
@@ -70,7 +317,7 @@ struct WavHeaderFormat {
 }
 
 impl WavHeaderFormat {
-    pub fn read(fs: &mut FileStream) -> WavHeaderFormat {
+    pub fn read<R>(fs: &mut R) -> WavHeaderFormat where R : Read {
         let mut fmt_chunk_marker: [u8;4] = [0;4];
         fs.read_exact(&mut fmt_chunk_marker).expect("Failed reading header");
         let length_of_fmt = fs.read_u32::<LittleEndian>().expect("Failed reading header");
@@ -100,25 +347,332 @@ struct WavChunkHeader {
 }
 
 impl WavChunkHeader {
-    pub fn read(fs: &mut FileStream) -> WavChunkHeader {
+    /// Read the next chunk header (returns None at the end of the file)
+    pub fn read<R>(fs: &mut R) -> Option<WavChunkHeader> where R : Read {
         let mut id: [u8;4] = [0;4];
-        fs.read_exact(&mut id).expect("Failed reading header");
-        let chunk_size = fs.read_u32::<LittleEndian>().expect("Failed reading header");
+        fs.read_exact(&mut id).ok()?;
+        let chunk_size = fs.read_u32::<LittleEndian>().ok()?;
 
-        return WavChunkHeader {
+        return Some(WavChunkHeader {
             id: id,
             chunk_size: chunk_size
+        });
+    }
+}
+
+/// A source of samples which the SoundDriver can play, either a single sample or a SoundContainer
+pub trait SoundSource {
+    /// Pick the sample to play, along with scales applied to the volume & pitch it is played at (None if there is nothing to play)
+    fn select(&self) -> Option<(Arc<AudioSample>, f32, f32)>;
+}
+
+impl SoundSource for Arc<AudioSample> {
+    fn select(&self) -> Option<(Arc<AudioSample>, f32, f32)> {
+        return Some((self.clone(), 1.0, 1.0));
+    }
+}
+
+/// How a SoundContainer picks which sample to play next
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContainerMode {
+    /// Cycle through samples in the order they were added
+    RoundRobin,
+    /// Pick a random sample, weighted by each sample's weight
+    WeightedRandom,
+}
+
+/// A set of sample variations (for example footsteps or impacts) played with randomized volume & pitch <br/>
+/// Can be passed to SoundDriver::play in place of a single sample
+pub struct SoundContainer {
+    entries: Vec<(Arc<AudioSample>, f32)>,
+    pub mode: ContainerMode,
+    pub volume_min: f32,
+    pub volume_max: f32,
+    pub pitch_min: f32,
+    pub pitch_max: f32,
+    next: Cell<usize>,
+    rng: Cell<u32>,
+}
+
+impl SoundContainer {
+    /// Construct a new empty container which picks samples using the given mode, with random choices seeded from the given seed
+    pub fn new(mode: ContainerMode, seed: u32) -> SoundContainer {
+        return SoundContainer {
+            entries: Vec::new(),
+            mode: mode,
+            volume_min: 1.0,
+            volume_max: 1.0,
+            pitch_min: 1.0,
+            pitch_max: 1.0,
+            next: Cell::new(0),
+            rng: Cell::new(if seed == 0 { 1 } else { seed }),
+        };
+    }
+
+    /// Add a sample with the given weight (only used in WeightedRandom mode)
+    pub fn add(&mut self, sample: &Arc<AudioSample>, weight: f32) {
+        self.entries.push((sample.clone(), weight.max(0.0)));
+    }
+
+    /// Set the range that each played sample's volume is randomly scaled within
+    pub fn set_volume_range(&mut self, min: f32, max: f32) {
+        self.volume_min = min;
+        self.volume_max = max;
+    }
+
+    /// Set the range that each played sample's pitch is randomly scaled within
+    pub fn set_pitch_range(&mut self, min: f32, max: f32) {
+        self.pitch_min = min;
+        self.pitch_max = max;
+    }
+
+    /// Get the number of samples in the container
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    /// Gets whether the container has no samples
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    // xorshift generator returning a value in the range [0, 1)
+    fn random(&self) -> f32 {
+        let mut state = self.rng.get();
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        self.rng.set(state);
+        return (state >> 8) as f32 / (1 << 24) as f32;
+    }
+}
+
+impl SoundSource for SoundContainer {
+    fn select(&self) -> Option<(Arc<AudioSample>, f32, f32)> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let idx = match self.mode {
+            ContainerMode::RoundRobin => {
+                let idx = self.next.get() % self.entries.len();
+                self.next.set(idx + 1);
+                idx
+            }
+            ContainerMode::WeightedRandom => {
+                let total: f32 = self.entries.iter().map(|x| x.1).sum();
+                let mut pick = self.random() * total;
+                let mut idx = self.entries.len() - 1;
+                for (i, (_, weight)) in self.entries.iter().enumerate() {
+                    if pick < *weight {
+                        idx = i;
+                        break;
+                    }
+                    pick -= weight;
+                }
+                idx
+            }
         };
+
+        let volume = f32::lerp(self.volume_min, self.volume_max, self.random());
+        let pitch = f32::lerp(self.pitch_min, self.pitch_max, self.random());
+
+        return Some((self.entries[idx].0.clone(), volume, pitch));
     }
 }
 
+/// Parameters used to start playing a sound, built up with chained setters
+#[derive(Clone, Copy)]
+pub struct PlayParams {
+    pub priority: u8,
+    pub looping: bool,
+    pub reverb: bool,
+    pub volume: f32,
+    pub pitch: f32,
+    pub pan: f32,
+    pub bus: SoundBus,
+    pub category: SoundCategory,
+    pub fade_in: f32,
+    pub loop_start: i32,
+    pub loop_end: i32,
+    pub is_3d: bool,
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub atten_type: AttenuationType,
+    pub atten_min_dist: f32,
+    pub atten_max_dist: f32,
+    pub atten_rolloff: f32,
+    pub orientation: Quaternion,
+    pub cone_inner_angle: f32,
+    pub cone_outer_angle: f32,
+    pub cone_outer_gain: f32,
+}
+
+impl PlayParams {
+    /// Construct default parameters for a non-looping 2D sound at full volume
+    pub fn new() -> PlayParams {
+        return PlayParams {
+            priority: 128,
+            looping: false,
+            reverb: false,
+            volume: 1.0,
+            pitch: 1.0,
+            pan: 0.0,
+            bus: SoundBus::Sfx,
+            category: SoundCategory::DEFAULT,
+            fade_in: 0.0,
+            loop_start: 0,
+            loop_end: 0,
+            is_3d: false,
+            position: Vector3::zero(),
+            velocity: Vector3::zero(),
+            atten_type: AttenuationType::None,
+            atten_min_dist: 0.0,
+            atten_max_dist: 0.0,
+            atten_rolloff: 0.0,
+            orientation: Quaternion::identity(),
+            cone_inner_angle: TAU,
+            cone_outer_angle: TAU,
+            cone_outer_gain: 0.0,
+        };
+    }
+
+    /// Set the voice priority (lower values are more important, 0 .. 255)
+    pub fn priority(mut self, priority: u8) -> PlayParams {
+        self.priority = priority;
+        return self;
+    }
+
+    /// Set whether the sound loops
+    pub fn looping(mut self, looping: bool) -> PlayParams {
+        self.looping = looping;
+        return self;
+    }
+
+    /// Set whether the sound is routed through the reverb unit
+    pub fn reverb(mut self, reverb: bool) -> PlayParams {
+        self.reverb = reverb;
+        return self;
+    }
+
+    /// Set the volume of the sound
+    pub fn volume(mut self, volume: f32) -> PlayParams {
+        self.volume = volume;
+        return self;
+    }
+
+    /// Set the pitch of the sound
+    pub fn pitch(mut self, pitch: f32) -> PlayParams {
+        self.pitch = pitch;
+        return self;
+    }
+
+    /// Set the pan of the sound (ignored for 3D sounds)
+    pub fn pan(mut self, pan: f32) -> PlayParams {
+        self.pan = pan;
+        return self;
+    }
+
+    /// Set the mixer bus the sound is routed through
+    pub fn bus(mut self, bus: SoundBus) -> PlayParams {
+        self.bus = bus;
+        return self;
+    }
+
+    /// Set the category the sound belongs to
+    pub fn category(mut self, category: SoundCategory) -> PlayParams {
+        self.category = category;
+        return self;
+    }
+
+    /// Set the duration in seconds the sound fades in over when it starts
+    pub fn fade_in(mut self, duration: f32) -> PlayParams {
+        self.fade_in = duration;
+        return self;
+    }
+
+    /// Set the loop region in sample frames (a loop end of 0 loops the entire sample), for example from a wav file's smpl chunk
+    pub fn loop_region(mut self, loop_start: i32, loop_end: i32) -> PlayParams {
+        self.loop_start = loop_start;
+        self.loop_end = loop_end;
+        return self;
+    }
+
+    /// Make the sound 3D, positioned in the world & attenuated by distance from the listener
+    pub fn spatial(mut self, position: Vector3, atten_type: AttenuationType, atten_min_dist: f32, atten_max_dist: f32, atten_rolloff: f32) -> PlayParams {
+        self.is_3d = true;
+        self.position = position;
+        self.atten_type = atten_type;
+        self.atten_min_dist = atten_min_dist;
+        self.atten_max_dist = atten_max_dist;
+        self.atten_rolloff = atten_rolloff;
+        return self;
+    }
+
+    /// Set the velocity of the sound in units per second, used for Doppler calculations
+    pub fn velocity(mut self, velocity: Vector3) -> PlayParams {
+        self.velocity = velocity;
+        return self;
+    }
+
+    /// Make the sound directional, with full angles of the inner & outer cones in radians
+    pub fn cone(mut self, orientation: Quaternion, inner_angle: f32, outer_angle: f32, outer_gain: f32) -> PlayParams {
+        self.orientation = orientation;
+        self.cone_inner_angle = inner_angle;
+        self.cone_outer_angle = outer_angle;
+        self.cone_outer_gain = outer_gain;
+        return self;
+    }
+}
+
+impl Default for PlayParams {
+    fn default() -> Self {
+        return PlayParams::new();
+    }
+}
+
+/// Default speed of sound in units per second used for Doppler calculations
+pub const DEFAULT_SPEED_OF_SOUND: f32 = 343.0;
+
+/// Maximum number of listeners (for example, one per split-screen viewport)
+pub const MAX_LISTENERS: usize = 4;
+
+/// A point in the world that 3D sounds are heard from
+#[derive(Clone, Copy)]
+pub struct Listener {
+    pub position: Vector3,
+    pub orientation: Quaternion,
+    /// Velocity in units per second, used for Doppler calculations
+    pub velocity: Vector3,
+}
+
+impl Listener {
+    /// Construct a new stationary listener
+    pub fn new(position: Vector3, orientation: Quaternion) -> Listener {
+        return Listener { position: position, orientation: orientation, velocity: Vector3::zero() };
+    }
+}
+
+// listener & mixer state used to compute voice parameters
+struct MixState {
+    listeners: [Listener;MAX_LISTENERS],
+    listener_count: usize,
+    doppler_factor: f32,
+    speed_of_sound: f32,
+    buses: [SoundBusState;BUS_COUNT],
+    categories: [SoundBusState;CATEGORY_COUNT],
+    ducking: [f32;BUS_COUNT],
+    pan_law: PanLaw,
+}
+
 pub struct SoundDriver {
     max_voices: usize,
     voices: [SoundVoice;VOICE_COUNT],
     emitters: Vec<Arc<RwLock<SoundEmitter>>>,
-    listener_position: Vector3,
-    listener_orientation: Quaternion,
     search_offset: usize,
+    last_update: f64,
+    mix: MixState,
+    ducking_rules: Vec<DuckingState>,
 }
 
 impl SoundDriver {
@@ -131,16 +685,27 @@ impl SoundDriver {
             voices: [
                 SoundVoice {
                     slot: 0,
-                    priority: 255,
-                    _is_playing: false,
+                    is_playing: false,
                     id: 0,
-                    play_time: 0.0
+                    start_time: 0.0,
+                    reserved: false,
+                    acquired: false,
                 }; VOICE_COUNT
             ],
             emitters: Vec::new(),
-            listener_position: Vector3::zero(),
-            listener_orientation: Quaternion::identity(),
             search_offset: 0,
+            last_update: get_time(),
+            mix: MixState {
+                listeners: [Listener::new(Vector3::zero(), Quaternion::identity()); MAX_LISTENERS],
+                listener_count: 1,
+                doppler_factor: 1.0,
+                speed_of_sound: DEFAULT_SPEED_OF_SOUND,
+                buses: [SoundBusState { volume: 1.0, muted: false }; BUS_COUNT],
+                categories: [SoundBusState { volume: 1.0, muted: false }; CATEGORY_COUNT],
+                ducking: [1.0; BUS_COUNT],
+                pan_law: PanLaw::Linear,
+            },
+            ducking_rules: Vec::new(),
         };
 
         for i in 0..VOICE_COUNT {
@@ -150,69 +715,159 @@ impl SoundDriver {
         return driver;
     }
 
-    fn allocate_voice(voices: &mut [SoundVoice], search_offset: &mut usize, max_voice: usize, priority: u8) -> Option<usize> {
+    fn allocate_voice(voices: &[SoundVoice], search_offset: &mut usize, max_voice: usize) -> Option<usize> {
         // a little silly but:
-        // voice stealing scheme can sometimes steal voices too early because we have to schedule playback in advance
-        // a simple round-robin search offset helps alleviate this
+        // voices which were just released may still be fading out, so prefer voices which haven't been used recently
+        // a simple round-robin search offset helps with this
 
+        let t = get_time();
         let mut ret: Option<usize> = None;
 
         for i in 0..max_voice {
             let idx = (i + *search_offset) % max_voice;
             let voice = &voices[idx];
 
-            if !voice._is_playing && !get_voice_state(voice.slot) {
+            // voices with a start queued in the future will also have a stop queued at that time, so they can't be reused until then
+            if !voice.reserved && !voice.is_playing && voice.start_time < t && !get_voice_state(voice.slot) {
                 ret = Some(idx);
                 break;
-            } else {
-                match ret {
-                    Some(r) => {
-                        let rv = &voices[r];
-                        if voice.play_time < rv.play_time && voice.priority >= priority {
-                            ret = Some(idx);
-                        }
-                    },
-                    None => {
-                        ret = Some(idx);
-                    }
-                };
             }
         }
 
         *search_offset = (*search_offset + 1) % max_voice;
 
-        match ret {
-            Some(r) => {
-                let rv = &mut voices[r];
-                rv.priority = priority;
-            },
-            None => {
+        return ret;
+    }
+   
+    fn start_voice(mix: &MixState, voices: &mut [SoundVoice], idx: usize, emitter: &mut SoundEmitter) {
+        let t = get_time();
+        let rate = emitter.sample.samplerate as f64 * emitter.pitch.max(0.0) as f64;
+        let mut start_time = t;
+        let mut seek_end = 0.0;
+
+        if emitter.looping && emitter.started {
+            // hardware voices always start from the beginning of the sample,
+            // so virtualized loops are resumed on their next loop iteration to stay in sync
+            let (loop_start, loop_end) = emitter.loop_region();
+            let loop_length = loop_end - loop_start;
+
+            if rate > 0.0 && loop_length > 0.0 {
+                // frames until the virtual position next reaches the loop start, minus the frames a new voice takes to get there
+                let until_loop = if emitter.play_position < loop_start { loop_start - emitter.play_position } else { loop_end - emitter.play_position };
+                let delay = (until_loop - loop_start).rem_euclid(loop_length) / rate;
+                if delay <= MAX_RESYNC_DELAY {
+                    start_time = t + delay;
+                    emitter.play_position = -delay * rate;
+                } else {
+                    emitter.play_position = 0.0;
+                }
+            }
+        } else {
+            // scheduled emitters are queued to start at exactly their requested time
+            start_time = t.max(emitter.start_time);
+            emitter.play_position = -(start_time - t) * rate;
+
+            // resumed emitters fast forward silently to where they were paused, and their params are only applied once they get there
+            if emitter.seek > 0.0 && emitter.sample.samplerate > 0 {
+                seek_end = start_time + (emitter.seek / (emitter.sample.samplerate as f64 * SEEK_SPEED));
+                emitter.play_position = emitter.seek - ((seek_end - t) * rate);
+
+                let slot = voices[idx].slot;
+                queue_set_voice_param_i(slot, AudioVoiceParam::SampleData, emitter.sample.handle, t);
+                queue_set_voice_param_i(slot, AudioVoiceParam::Samplerate, (emitter.sample.samplerate as f64 * SEEK_SPEED) as i32, t);
+                queue_set_voice_param_i(slot, AudioVoiceParam::LoopEnabled, if emitter.looping { 1 } else { 0 }, t);
+                queue_set_voice_param_i(slot, AudioVoiceParam::LoopStart, emitter.loop_start, t);
+                queue_set_voice_param_i(slot, AudioVoiceParam::LoopEnd, emitter.loop_end, t);
+                queue_set_voice_param_f(slot, AudioVoiceParam::Volume, 0.0, t);
+                queue_set_voice_param_f(slot, AudioVoiceParam::Detune, 0.0, t);
+                queue_set_voice_param_f(slot, AudioVoiceParam::Pitch, 1.0, t);
+                queue_set_voice_param_f(slot, AudioVoiceParam::FadeInDuration, 0.0, t);
+                queue_set_voice_param_f(slot, AudioVoiceParam::FadeOutDuration, 0.0, t);
             }
         }
 
-        return ret;
+        voices[idx].is_playing = true;
+        voices[idx].id += 1;
+        // the voice can't be reused until its queued start & fast forward are done, since a stop queued at this time is the only way to cancel them
+        voices[idx].start_time = start_time.max(seek_end);
+        emitter.voice = Some(idx.try_into().unwrap());
+        emitter.id = voices[idx].id;
+        emitter.started = true;
+        emitter.voice_start = start_time;
+        emitter.seek = 0.0;
+        emitter.seek_end = seek_end;
+
+        SoundDriver::update_voice(mix, &voices[idx], emitter);
+        queue_start_voice(idx.try_into().unwrap(), start_time);
     }
-   
-    fn assign_hw_voice(listener_position: &Vector3, listener_orientation: &Quaternion, voices: &mut [SoundVoice], search_offset: &mut usize, max_voice: usize, emitter: &mut SoundEmitter) {
-        let voice = SoundDriver::allocate_voice(voices, search_offset, max_voice, emitter.priority);
 
-        if voice.is_some() {
-            let idx = voice.unwrap();
-            let t = get_time();
-            voices[idx].play_time = t;
-            voices[idx].id += 1;
-            emitter.voice = Some(idx.try_into().unwrap());
-            emitter.id = voices[idx].id;
+    // hand hardware voices to the most important & audible emitters, virtualizing the rest
+    fn assign_voices(&mut self) {
+        let schedule_time = get_time() + SCHEDULE_LOOKAHEAD;
+        let mut ranked: Vec<(usize, u8, f32)> = Vec::new();
+        for (i, emitter_rc) in self.emitters.iter().enumerate() {
+            let em = emitter_rc.read().unwrap();
+            if em.is_valid && !em.paused && (em.looping || em.voice.is_some() || !em.started) && (em.started || em.start_time <= schedule_time) {
+                let (gain, _, _) = SoundDriver::calc_params(&self.mix, &em);
+                ranked.push((i, em.priority, gain));
+            }
+        }
+
+        // lower priority values are more important, ties are broken by loudness
+        ranked.sort_by(|a, b| a.1.cmp(&b.1).then(b.2.total_cmp(&a.2)));
+        let available = self.voices[0..self.max_voices].iter().filter(|x| !x.reserved).count();
+        let audible = ranked.len().min(available);
+
+        // virtualize emitters which fell out of the audible set, handing their voices over to emitters which entered it
+        let mut stolen: Vec<usize> = Vec::new();
+        for (i, _, _) in &ranked[audible..] {
+            let mut em = self.emitters[*i].write().unwrap();
+            if let Some(v) = em.voice {
+                let idx = TryInto::<usize>::try_into(v).unwrap();
+                SoundDriver::release_voice(&mut self.voices, &mut em, 0.0);
+                if self.voices[idx].start_time < get_time() {
+                    stolen.push(idx);
+                }
+            }
+            em.started = true;
+        }
+
+        for (i, _, _) in &ranked[..audible] {
+            let mut em = self.emitters[*i].write().unwrap();
+            if em.voice.is_some() {
+                continue;
+            }
 
-            SoundDriver::update_voice(listener_position, listener_orientation, &voices[idx], emitter);
-            queue_start_voice(idx.try_into().unwrap(), t);
+            let voice = match stolen.pop() {
+                Some(v) => { Some(v) },
+                None => { SoundDriver::allocate_voice(&self.voices, &mut self.search_offset, self.max_voices) }
+            };
+
+            match voice {
+                Some(v) => {
+                    SoundDriver::start_voice(&self.mix, &mut self.voices, v, &mut em);
+                },
+                None => {
+                    em.started = true;
+                }
+            }
         }
     }
 
-    fn calc_3d(listener_position: &Vector3, listener_orientation: &Quaternion, position: &Vector3, atten_type: AttenuationType, atten_min_dist: f32, atten_max_dist: f32, atten_rolloff: f32) -> (f32, f32) {
+    fn calc_3d(mix: &MixState, emitter: &SoundEmitter) -> (f32, f32, f32) {
+        let position = &emitter.position;
+
+        // with multiple listeners, each emitter is spatialized relative to whichever listener is nearest
+        let listener = mix.listeners[0..mix.listener_count].iter()
+            .min_by(|a, b| Vector3::distance_sq(&a.position, position).total_cmp(&Vector3::distance_sq(&b.position, position)))
+            .unwrap();
+        let atten_min_dist = emitter.atten_min_dist;
+        let atten_max_dist = emitter.atten_max_dist;
+        let atten_rolloff = emitter.atten_rolloff;
+
         // calculate gain from distance
-        let dist = Vector3::distance(position, listener_position).clamp(atten_min_dist, atten_max_dist);
-        let gain = match atten_type {
+        let dist = Vector3::distance(position, &listener.position).clamp(atten_min_dist, atten_max_dist);
+        let gain = match emitter.atten_type {
             AttenuationType::Linear => {
                 1.0 - atten_rolloff * (dist - atten_min_dist) / (atten_max_dist - atten_min_dist)
             }
@@ -228,43 +883,96 @@ impl SoundDriver {
         };
 
         // calculate pan
-        let mut local_pos = *position - *listener_position;
-        let mut rot = *listener_orientation;
+        let mut local_pos = *position - listener.position;
+        let mut rot = listener.orientation;
         rot.invert();
         local_pos = rot * local_pos;
         local_pos.normalize();
 
         let pan = local_pos.x;
 
-        return (gain, pan);
+        let to_listener = listener.position - *position;
+        let to_listener_dist = to_listener.length();
+        let dir = if to_listener_dist > 0.0 { to_listener / to_listener_dist } else { Vector3::zero() };
+
+        // calculate cone attenuation from the angle between the emitter's facing direction and the listener
+        let mut cone_gain = 1.0;
+        if emitter.cone_inner_angle < TAU && to_listener_dist > 0.0 {
+            let forward = emitter.orientation * Vector3::new(0.0, 0.0, -1.0);
+            let angle = Vector3::dot(&forward, &dir).clamp(-1.0, 1.0).acos() * 2.0;
+
+            if angle >= emitter.cone_outer_angle {
+                cone_gain = emitter.cone_outer_gain;
+            } else if angle > emitter.cone_inner_angle {
+                let t = (angle - emitter.cone_inner_angle) / (emitter.cone_outer_angle - emitter.cone_inner_angle);
+                cone_gain = f32::lerp(1.0, emitter.cone_outer_gain, t);
+            }
+        }
+
+        // calculate doppler pitch factor from the velocities of the emitter & listener along the line between them
+        let mut doppler = 1.0;
+
+        if mix.doppler_factor > 0.0 && to_listener_dist > 0.0 {
+            let max_speed = mix.speed_of_sound / mix.doppler_factor;
+            let listener_speed = Vector3::dot(&dir, &listener.velocity).min(max_speed);
+            let emitter_speed = Vector3::dot(&dir, &emitter.velocity).min(max_speed);
+
+            let denom = mix.speed_of_sound - (mix.doppler_factor * emitter_speed);
+            if denom > 0.0 {
+                doppler = ((mix.speed_of_sound - (mix.doppler_factor * listener_speed)) / denom).max(0.0);
+            }
+        }
+
+        return (gain * cone_gain, pan, doppler);
     }
 
-    fn update_voice(listener_position: &Vector3, listener_orientation: &Quaternion, voice: &SoundVoice, emitter: &mut SoundEmitter) {
-        if emitter.id == voice.id {
-            let t = get_time();
-            let mut gain = emitter.volume;
-            let mut pan = emitter.pan;
+    fn calc_params(mix: &MixState, emitter: &SoundEmitter) -> (f32, f32, f32) {
+        let mut gain = emitter.volume * mix.buses[emitter.bus as usize].gain() * mix.ducking[emitter.bus as usize] * mix.categories[emitter.category.0 as usize].gain();
+        let mut pan = emitter.pan;
+        let mut pitch = emitter.pitch;
+
+        if emitter.is_3d {
+            let (gain3d, pan3d, doppler) = SoundDriver::calc_3d(mix, emitter);
 
-            if emitter.is_3d {
-                let (gain3d, pan3d) = SoundDriver::calc_3d(listener_position, listener_orientation, &emitter.position, 
-                    emitter.atten_type, emitter.atten_min_dist, emitter.atten_max_dist, emitter.atten_rolloff);
+            gain *= gain3d;
+            pan = pan3d;
+            pitch *= doppler;
+        }
 
-                gain *= gain3d;
-                pan = pan3d;
+        if mix.pan_law == PanLaw::ConstantPower {
+            // the hardware keeps the louder side at full volume & scales the other side down linearly,
+            // so scale overall volume to the louder side's constant power gain & pan so the quieter side ends up at its own gain
+            let angle = (pan.clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+            let (right, left) = angle.sin_cos();
+            if pan >= 0.0 {
+                gain *= right;
+                pan = 1.0 - (left / right);
+            } else {
+                gain *= left;
+                pan = (right / left) - 1.0;
             }
+        }
+
+        return (gain, pan, pitch);
+    }
+
+    fn update_voice(mix: &MixState, voice: &SoundVoice, emitter: &mut SoundEmitter) {
+        if emitter.id == voice.id {
+            let t = get_time().max(emitter.seek_end);
+            let (gain, pan, pitch) = SoundDriver::calc_params(mix, emitter);
 
             let voice_slot = TryInto::<i32>::try_into(voice.slot).unwrap();
             queue_set_voice_param_i(voice_slot, AudioVoiceParam::SampleData, emitter.sample.handle, t);
             queue_set_voice_param_i(voice_slot, AudioVoiceParam::Samplerate, emitter.sample.samplerate, t);
             queue_set_voice_param_i(voice_slot, AudioVoiceParam::LoopEnabled, if emitter.looping { 1 } else { 0 }, t);
-            queue_set_voice_param_i(voice_slot, AudioVoiceParam::LoopStart, 0, t);
-            queue_set_voice_param_i(voice_slot, AudioVoiceParam::LoopEnd, 0, t);
+            queue_set_voice_param_i(voice_slot, AudioVoiceParam::LoopStart, emitter.loop_start, t);
+            queue_set_voice_param_i(voice_slot, AudioVoiceParam::LoopEnd, emitter.loop_end, t);
             queue_set_voice_param_i(voice_slot, AudioVoiceParam::Reverb, if emitter.reverb { 1 } else { 0 }, t);
             queue_set_voice_param_f(voice_slot, AudioVoiceParam::Volume, gain, t);
             queue_set_voice_param_f(voice_slot, AudioVoiceParam::Detune, 0.0, t);
-            queue_set_voice_param_f(voice_slot, AudioVoiceParam::Pitch, emitter.pitch, t);
+            queue_set_voice_param_f(voice_slot, AudioVoiceParam::Pitch, pitch, t);
             queue_set_voice_param_f(voice_slot, AudioVoiceParam::Pan, pan, t);
-            queue_set_voice_param_f(voice_slot, AudioVoiceParam::FadeInDuration, 0.0, t);
+            queue_set_voice_param_f(voice_slot, AudioVoiceParam::FadeInDuration, emitter.fade_in, t);
             queue_set_voice_param_f(voice_slot, AudioVoiceParam::FadeOutDuration, 0.0, t);
         } else {
             // something may have stolen this emitter's voice
@@ -274,131 +982,491 @@ impl SoundDriver {
 
     /// Update internal sound logic
     pub fn update(&mut self) {
+        let t = get_time();
+        let delta = (t - self.last_update).max(0.0);
+        self.last_update = t;
+
         let emitters = self.emitters.as_mut_slice();
         for emitter_rc in emitters {
-            let voice = {
-                emitter_rc.read().unwrap().voice
-            };
-            {
-                let mut emref = emitter_rc.write().unwrap();
-                match voice {
-                    Some(v) => {
-                        SoundDriver::update_voice(&self.listener_position, &self.listener_orientation, &self.voices[TryInto::<usize>::try_into(v).unwrap()], &mut emref);
-                    },
-                    None => {
-                        if emref.looping {
-                            SoundDriver::assign_hw_voice(&self.listener_position, &self.listener_orientation, &mut self.voices, &mut self.search_offset, self.max_voices, &mut emref);
-                        }
-                    }
+            let mut emref = emitter_rc.write().unwrap();
+
+            // emitters stopped with a fade hand their voice's fade out duration to the hardware and then stop
+            if let Some(duration) = emref.fade_stop.take() {
+                SoundDriver::release_voice(&mut self.voices, &mut emref, duration);
+                emref.is_valid = false;
+                continue;
+            }
+
+            // paused emitters keep their state but don't hold a voice until resumed
+            if emref.paused {
+                continue;
+            }
+
+            emref.update_fade(t);
+            emref.play_position += delta * emref.sample.samplerate as f64 * emref.pitch as f64;
+
+            if emref.looping {
+                let (loop_start, loop_end) = emref.loop_region();
+                if emref.play_position >= loop_end && loop_end > loop_start {
+                    emref.play_position = loop_start + (emref.play_position - loop_start).rem_euclid(loop_end - loop_start);
                 }
             }
-            {
-                let mut emref = emitter_rc.write().unwrap();
-                // for non-looping sounds: if the voice stops playing, or the sound's voice has been stolen, just stop emitter and remove from list
-                if !emref.looping && (!voice.is_some() || !get_voice_state(voice.unwrap().try_into().unwrap())) {
-                    emref.is_valid = false;
+
+            match emref.voice {
+                Some(v) => {
+                    let idx = TryInto::<usize>::try_into(v).unwrap();
+                    SoundDriver::update_voice(&self.mix, &self.voices[idx], &mut emref);
+
+                    // for non-looping sounds: if the voice stops playing, just stop emitter and remove from list
+                    // (voices queued to start now or in the future may not be playing yet, so they can't have finished)
+                    if !emref.looping && emref.voice.is_some() && emref.voice_start < t && !get_voice_state(v) {
+                        SoundDriver::release_voice(&mut self.voices, &mut emref, 0.0);
+                        emref.is_valid = false;
+                    }
+                },
+                None => {
+                    // virtualized non-looping sounds keep playing silently until they would have finished
+                    if !emref.looping && emref.started && emref.play_position >= emref.sample.length as f64 {
+                        emref.is_valid = false;
+                    }
                 }
             }
         }
 
-        // remove any emitters which are no longer valid
+        // remove any emitters which are no longer valid, running their completion callbacks once the emitter list is no longer borrowed
+        let mut completed: Vec<Box<dyn FnOnce() + Send + Sync>> = Vec::new();
         self.emitters.retain(|x| {
-            x.read().unwrap().is_valid
+            let mut emref = x.write().unwrap();
+            if !emref.is_valid {
+                if let Some(callback) = emref.on_complete.take() {
+                    completed.push(callback);
+                }
+            }
+            emref.is_valid
         });
+
+        self.update_ducking(delta as f32);
+        self.assign_voices();
+
+        for callback in completed {
+            callback();
+        }
+    }
+
+    fn update_ducking(&mut self, delta: f32) {
+        let mut active = [false;BUS_COUNT];
+        for emitter_rc in &self.emitters {
+            let em = emitter_rc.read().unwrap();
+            if em.state() == EmitterState::Playing {
+                active[em.bus as usize] = true;
+            }
+        }
+
+        self.mix.ducking = [1.0;BUS_COUNT];
+        for state in &mut self.ducking_rules {
+            let rule = &state.rule;
+
+            // ramp linearly towards fully ducked while triggered, and back to zero once released
+            if active[rule.trigger as usize] {
+                state.level = if rule.attack > 0.0 { (state.level + (delta / rule.attack)).min(1.0) } else { 1.0 };
+            } else {
+                state.level = if rule.release > 0.0 { (state.level - (delta / rule.release)).max(0.0) } else { 0.0 };
+            }
+
+            self.mix.ducking[rule.target as usize] *= 10.0_f32.powf(-rule.attenuation_db * state.level / 20.0);
+        }
+    }
+
+    /// Add a rule which ducks one bus while emitters on another bus are playing
+    pub fn add_ducking_rule(&mut self, rule: DuckingRule) {
+        self.ducking_rules.push(DuckingState { rule: rule, level: 0.0 });
+    }
+
+    /// Remove every ducking rule, immediately returning all buses to full volume
+    pub fn clear_ducking_rules(&mut self) {
+        self.ducking_rules.clear();
+        self.mix.ducking = [1.0;BUS_COUNT];
+    }
+
+    /// Get the gain currently applied to the given bus by ducking rules
+    pub fn bus_ducking(&self, bus: SoundBus) -> f32 {
+        return self.mix.ducking[bus as usize];
+    }
+
+    /// Reserve the given hardware voice slots so the driver never plays emitters on them (for example, for streaming music or video) <br/>
+    /// Emitters currently playing on those slots are virtualized
+    pub fn reserve_voices(&mut self, slots: &[i32]) {
+        let t = get_time();
+        for slot in slots {
+            let voice = &mut self.voices[TryInto::<usize>::try_into(*slot).unwrap()];
+            if voice.reserved {
+                continue;
+            }
+
+            // invalidate the id so any emitter using this voice notices it was taken
+            if voice.is_playing {
+                voice.id += 1;
+                voice.is_playing = false;
+                queue_stop_voice(voice.slot, 0.0);
+                if voice.start_time > t {
+                    queue_stop_voice(voice.slot, voice.start_time);
+                }
+            }
+
+            voice.reserved = true;
+            voice.acquired = false;
+        }
+    }
+
+    /// Return previously reserved hardware voice slots to the driver
+    pub fn unreserve_voices(&mut self, slots: &[i32]) {
+        for slot in slots {
+            let voice = &mut self.voices[TryInto::<usize>::try_into(*slot).unwrap()];
+            voice.reserved = false;
+            voice.acquired = false;
+        }
+    }
+
+    /// Acquire the given number of reserved voice slots which aren't already in use, returning None if not enough are available
+    pub fn acquire_voices(&mut self, count: usize) -> Option<Vec<i32>> {
+        let slots: Vec<i32> = self.voices.iter().filter(|x| x.reserved && !x.acquired).take(count).map(|x| x.slot).collect();
+        if slots.len() < count {
+            return None;
+        }
+
+        for slot in &slots {
+            self.voices[TryInto::<usize>::try_into(*slot).unwrap()].acquired = true;
+        }
+
+        return Some(slots);
     }
 
-    /// Set the listener position & orientation
+    /// Release reserved voice slots previously acquired with acquire_voices, so they can be acquired again
+    pub fn release_voices(&mut self, slots: &[i32]) {
+        for slot in slots {
+            self.voices[TryInto::<usize>::try_into(*slot).unwrap()].acquired = false;
+        }
+    }
+
+    /// Set the listener position & orientation (the first listener when using multiple listeners)
     pub fn set_listener(&mut self, position: Vector3, orientation: Quaternion) {
-        self.listener_position = position;
-        self.listener_orientation = orientation;
+        self.mix.listeners[0].position = position;
+        self.mix.listeners[0].orientation = orientation;
     }
 
-    /// Start playing a sound effect and return a handle to it
-    pub fn play(&mut self, priority: u8, sample: &Arc<AudioSample>, looping: bool, reverb: bool, volume: f32, pitch: f32, pan: f32) -> Weak<RwLock<SoundEmitter>> {
-        let mut emitter = SoundEmitter {
-            is_valid: true,
-            priority: priority,
-            looping: looping,
-            reverb: reverb,
-            is_3d: false,
-            atten_type: AttenuationType::None,
-            atten_min_dist: 0.0,
-            atten_max_dist: 0.0,
-            atten_rolloff: 0.0,
-            position: Vector3::zero(),
-            volume: volume,
-            pitch: pitch,
-            pan: pan,
-            sample: sample.clone(),
-            id: 0,
-            voice: None
-        };
-        SoundDriver::assign_hw_voice(&self.listener_position, &self.listener_orientation, &mut self.voices, &mut self.search_offset, self.max_voices, &mut emitter);
-        
-        let rc = Arc::new(RwLock::new(emitter));
-        let wr = Arc::downgrade(&rc);
-        self.emitters.push(rc);
+    /// Set the listener velocity in units per second, used for Doppler calculations (the first listener when using multiple listeners)
+    pub fn set_listener_velocity(&mut self, velocity: Vector3) {
+        self.mix.listeners[0].velocity = velocity;
+    }
 
-        return wr;
+    /// Set multiple listeners for split-screen play (1 to MAX_LISTENERS). Each 3D emitter is heard from the nearest listener
+    pub fn set_listeners(&mut self, listeners: &[Listener]) {
+        assert!(!listeners.is_empty() && listeners.len() <= MAX_LISTENERS, "Listener count must be between 1 and {}", MAX_LISTENERS);
+        self.mix.listeners[0..listeners.len()].copy_from_slice(listeners);
+        self.mix.listener_count = listeners.len();
+    }
+
+    /// Get the currently active listeners
+    pub fn listeners(&self) -> &[Listener] {
+        return &self.mix.listeners[0..self.mix.listener_count];
+    }
+
+    /// Set the Doppler effect scale (0.0 disables Doppler) and the speed of sound in units per second
+    pub fn set_doppler(&mut self, doppler_factor: f32, speed_of_sound: f32) {
+        self.mix.doppler_factor = doppler_factor;
+        self.mix.speed_of_sound = speed_of_sound;
+    }
+
+    /// Set the pan law used to convert emitter pan values into voice volume & pan
+    pub fn set_pan_law(&mut self, pan_law: PanLaw) {
+        self.mix.pan_law = pan_law;
+    }
+
+    /// Get the pan law used to convert emitter pan values into voice volume & pan
+    pub fn pan_law(&self) -> PanLaw {
+        return self.mix.pan_law;
+    }
+
+    /// Set the volume of the given mixer bus
+    pub fn set_bus_volume(&mut self, bus: SoundBus, volume: f32) {
+        self.mix.buses[bus as usize].volume = volume;
+    }
+
+    /// Get the volume of the given mixer bus
+    pub fn bus_volume(&self, bus: SoundBus) -> f32 {
+        return self.mix.buses[bus as usize].volume;
+    }
+
+    /// Set whether the given mixer bus is muted
+    pub fn set_bus_muted(&mut self, bus: SoundBus, muted: bool) {
+        self.mix.buses[bus as usize].muted = muted;
+    }
+
+    /// Gets whether the given mixer bus is muted
+    pub fn is_bus_muted(&self, bus: SoundBus) -> bool {
+        return self.mix.buses[bus as usize].muted;
     }
 
-    /// Start playing a 3D sound effect and return a handle to it
-    pub fn play_3d(&mut self, priority: u8, sample: &Arc<AudioSample>, looping: bool, reverb: bool, volume: f32, pitch: f32,
-        position: Vector3, atten_type: AttenuationType, atten_min_dist: f32, atten_max_dist: f32, atten_rolloff: f32) -> Weak<RwLock<SoundEmitter>> {
-        let mut emitter = SoundEmitter {
+    /// Set the volume of the given sound category
+    pub fn set_category_volume(&mut self, category: SoundCategory, volume: f32) {
+        self.mix.categories[category.0 as usize].volume = volume;
+    }
+
+    /// Get the volume of the given sound category
+    pub fn category_volume(&self, category: SoundCategory) -> f32 {
+        return self.mix.categories[category.0 as usize].volume;
+    }
+
+    /// Set whether the given sound category is muted
+    pub fn set_category_muted(&mut self, category: SoundCategory, muted: bool) {
+        self.mix.categories[category.0 as usize].muted = muted;
+    }
+
+    /// Gets whether the given sound category is muted
+    pub fn is_category_muted(&self, category: SoundCategory) -> bool {
+        return self.mix.categories[category.0 as usize].muted;
+    }
+
+    /// Start playing a sound effect (a single sample or a SoundContainer) with the given parameters and return a handle to it
+    pub fn play<S>(&mut self, source: &S, params: &PlayParams) -> Weak<RwLock<SoundEmitter>> where S : SoundSource + ?Sized {
+        return self.play_at(source, params, get_time());
+    }
+
+    /// Schedule a sound effect to start playing at the given audio time (see audio::get_time) and return a handle to it <br/>
+    /// The emitter isn't assigned a hardware voice until shortly before its start time, at which point its start is queued sample-accurately
+    pub fn play_at<S>(&mut self, source: &S, params: &PlayParams, time: f64) -> Weak<RwLock<SoundEmitter>> where S : SoundSource + ?Sized {
+        let (sample, volume_scale, pitch_scale) = match source.select() {
+            Some(v) => { v },
+            None => { return Weak::new(); }
+        };
+
+        let now = get_time();
+        let pitch = params.pitch * pitch_scale;
+        let emitter = SoundEmitter {
             is_valid: true,
-            priority: priority,
-            looping: looping,
-            reverb: reverb,
-            is_3d: true,
-            atten_type: atten_type,
-            atten_min_dist: atten_min_dist,
-            atten_max_dist: atten_max_dist,
-            atten_rolloff: atten_rolloff,
-            position: position,
-            volume: volume,
+            priority: params.priority,
+            looping: params.looping,
+            reverb: params.reverb,
+            is_3d: params.is_3d,
+            atten_type: params.atten_type,
+            atten_min_dist: params.atten_min_dist,
+            atten_max_dist: params.atten_max_dist,
+            atten_rolloff: params.atten_rolloff,
+            position: params.position,
+            velocity: params.velocity,
+            orientation: params.orientation,
+            cone_inner_angle: params.cone_inner_angle,
+            cone_outer_angle: params.cone_outer_angle,
+            cone_outer_gain: params.cone_outer_gain,
+            volume: params.volume * volume_scale,
             pitch: pitch,
-            pan: 0.0,
+            pan: params.pan,
+            bus: params.bus,
+            category: params.category,
+            fade_in: params.fade_in,
+            loop_start: params.loop_start,
+            loop_end: params.loop_end,
             sample: sample.clone(),
             id: 0,
-            voice: None
+            voice: None,
+            paused: false,
+            fade_from: 0.0,
+            fade_target: 0.0,
+            fade_start: 0.0,
+            fade_duration: 0.0,
+            fade_stop: None,
+            play_position: -(time - now).max(0.0) * sample.samplerate as f64 * pitch as f64,
+            started: false,
+            start_time: time,
+            voice_start: 0.0,
+            seek: 0.0,
+            seek_end: 0.0,
+            on_complete: None,
         };
-        SoundDriver::assign_hw_voice(&self.listener_position, &self.listener_orientation, &mut self.voices, &mut self.search_offset, self.max_voices, &mut emitter);
         
         let rc = Arc::new(RwLock::new(emitter));
         let wr = Arc::downgrade(&rc);
         self.emitters.push(rc);
+        self.assign_voices();
 
         return wr;
     }
 
+    fn release_voice(voices: &mut [SoundVoice], emitter: &mut SoundEmitter, fade_out: f32) {
+        if emitter.voice.is_some() {
+            let voiceid = TryInto::<usize>::try_into(emitter.voice.unwrap()).unwrap();
+            let voice: &mut SoundVoice = &mut voices[voiceid];
+            if voice.id == emitter.id {
+                voice.is_playing = false;
+                if fade_out > 0.0 {
+                    let t = get_time();
+                    queue_set_voice_param_f(voice.slot, AudioVoiceParam::FadeOutDuration, fade_out, t);
+                    queue_stop_voice(voice.slot, t);
+                } else {
+                    queue_stop_voice(voice.slot.try_into().unwrap(), 0.0);
+                }
+
+                // queued starts can't be cancelled, so stop the voice again right as it starts
+                if voice.start_time > get_time() {
+                    queue_stop_voice(voice.slot, voice.start_time);
+                }
+            }
+        }
+
+        emitter.voice = None;
+    }
+
     /// Stop the playing emitter
-    pub fn stop(&mut self, emitter_ref: Weak<RefCell<SoundEmitter>>) {
+    pub fn stop(&mut self, emitter_ref: &Weak<RwLock<SoundEmitter>>) {
         let rc = emitter_ref.upgrade();
         if !rc.is_some() {
             return;
         }
 
         let em = rc.unwrap();
-        let mut emitter = em.borrow_mut();
+        let mut emitter = em.write().unwrap();
 
         if !emitter.is_valid { return; }
 
-        if emitter.voice.is_some() {
-            let voiceid = TryInto::<usize>::try_into(emitter.voice.unwrap()).unwrap();
-            let voice: &mut SoundVoice = &mut self.voices[voiceid];
-            if voice.id == emitter.id {
-                voice.priority = 255;
-                queue_stop_voice(voice.slot.try_into().unwrap(), 0.0);
+        SoundDriver::release_voice(&mut self.voices, &mut emitter, 0.0);
+        emitter.is_valid = false;
+    }
+
+    /// Pause the playing emitter, releasing its hardware voice until it is resumed (its playback position is kept)
+    pub fn pause(&mut self, emitter_ref: &Weak<RwLock<SoundEmitter>>) {
+        let rc = emitter_ref.upgrade();
+        if !rc.is_some() {
+            return;
+        }
+
+        let em = rc.unwrap();
+        let mut emitter = em.write().unwrap();
+
+        if !emitter.is_valid || emitter.paused { return; }
+
+        SoundDriver::release_voice(&mut self.voices, &mut emitter, 0.0);
+        emitter.paused = true;
+    }
+
+    /// Resume a paused emitter from where it was paused <br/>
+    /// Note that hardware voices cannot be started partway through a sample, so the voice fast forwards silently at SEEK_SPEED to reach that position,
+    /// delaying the sound by a fraction of the time it had already played
+    pub fn resume(&mut self, emitter_ref: &Weak<RwLock<SoundEmitter>>) {
+        let rc = emitter_ref.upgrade();
+        if !rc.is_some() {
+            return;
+        }
+
+        let em = rc.unwrap();
+        let mut emitter = em.write().unwrap();
+
+        if !emitter.is_valid || !emitter.paused { return; }
+
+        emitter.resume();
+        drop(emitter);
+
+        self.assign_voices();
+    }
+
+    /// Stop every active emitter matching the given filter
+    pub fn stop_all(&mut self, filter: EmitterFilter) {
+        for emitter_rc in &self.emitters {
+            let mut emitter = emitter_rc.write().unwrap();
+            if emitter.is_valid && filter.matches(&emitter) {
+                SoundDriver::release_voice(&mut self.voices, &mut emitter, 0.0);
+                emitter.is_valid = false;
+            }
+        }
+    }
+
+    /// Get the playback state of the given emitter (Stopped if the emitter no longer exists)
+    pub fn emitter_state(emitter_ref: &Weak<RwLock<SoundEmitter>>) -> EmitterState {
+        return match emitter_ref.upgrade() {
+            Some(em) => { em.read().unwrap().state() },
+            None => { EmitterState::Stopped }
+        };
+    }
+
+    /// Pause every active emitter matching the given filter
+    pub fn pause_all(&mut self, filter: EmitterFilter) {
+        for emitter_rc in &self.emitters {
+            let mut emitter = emitter_rc.write().unwrap();
+            if emitter.is_valid && !emitter.paused && filter.matches(&emitter) {
+                SoundDriver::release_voice(&mut self.voices, &mut emitter, 0.0);
+                emitter.paused = true;
             }
         }
+    }
 
-        emitter.is_valid = false;
+    /// Resume every paused emitter matching the given filter (see resume)
+    pub fn resume_all(&mut self, filter: EmitterFilter) {
+        for emitter_rc in &self.emitters {
+            let mut emitter = emitter_rc.write().unwrap();
+            if emitter.is_valid && emitter.paused && filter.matches(&emitter) {
+                emitter.resume();
+            }
+        }
+
+        self.assign_voices();
+    }
+}
+
+/// A loaded wav file, along with any loop points read from its smpl chunk
+pub struct LoadedWav {
+    pub sample: AudioSample,
+    /// Loop start position in sample frames (0 if the file has no loop points)
+    pub loop_start: i32,
+    /// Loop end position in sample frames (0 if the file has no loop points, which loops the entire sample)
+    pub loop_end: i32,
+}
+
+struct WavSampleLoop {
+    start: u32,
+    end: u32,
+}
+
+impl WavSampleLoop {
+    /// Read the first loop from a smpl chunk (returns None if the chunk contains no loops)
+    pub fn read<R>(fs: &mut R) -> Result<Option<WavSampleLoop>,()> where R : Read {
+        // skip manufacturer, product, sample period, MIDI unity note, MIDI pitch fraction, SMPTE format, and SMPTE offset
+        let mut skip: [u8;28] = [0;28];
+        if fs.read_exact(&mut skip).is_err() {
+            return Err(());
+        }
+
+        let num_loops = match fs.read_u32::<LittleEndian>() {
+            Ok(v) => { v },
+            Err(_) => { return Err(()); }
+        };
+
+        // skip sampler data size
+        if fs.read_u32::<LittleEndian>().is_err() {
+            return Err(());
+        }
+
+        if num_loops == 0 {
+            return Ok(None);
+        }
+
+        // first loop: cue point id, type, start, end, fraction, play count
+        let mut fields: [u32;6] = [0;6];
+        for f in &mut fields {
+            *f = match fs.read_u32::<LittleEndian>() {
+                Ok(v) => { v },
+                Err(_) => { return Err(()); }
+            };
+        }
+
+        return Ok(Some(WavSampleLoop { start: fields[2], end: fields[3] }));
     }
 }
 
-/// Load a wav file, returning an audio sample handle (supported encodings are unsigned 8-bit, signed 16-bit, and IMA ADPCM)
-pub fn load_wav(file: &mut FileStream) -> Result<AudioSample,()> {
+/// Load a wav file from any stream (such as a FileStream or MemoryStream), returning an audio sample handle and loop points (supported encodings are unsigned 8-bit, signed 16-bit, and IMA ADPCM)
+pub fn load_wav<R>(file: &mut R) -> Result<LoadedWav,()> where R : Read + Seek {
+    // headers are read a few bytes at a time, so buffer reads to avoid a filesystem call per field
+    let mut reader = BufferedStream::new(file);
+    let file = &mut reader;
+
     let header = WavHeader::read(file);
 
     // check riff string
@@ -447,75 +1515,78 @@ pub fn load_wav(file: &mut FileStream) -> Result<AudioSample,()> {
         Err(_) => { return Err(()); }
     }
 
-    let mut data_found = false;
-    let mut chunk_header: WavChunkHeader = WavChunkHeader { id: [0;4], chunk_size: 0 };
-
-    while !file.end_of_file() {
-        chunk_header = WavChunkHeader::read(file);
+    // the smpl chunk may come before or after the data chunk, so walk every chunk & remember where the data is
+    let mut data_chunk: Option<(u64, usize)> = None;
+    let mut sample_loop: Option<WavSampleLoop> = None;
 
-        let chunk_id = match std::str::from_utf8(&chunk_header.id) {
+    while let Some(chunk_header) = WavChunkHeader::read(file) {
+        let chunk_start = match file.stream_position() {
             Ok(v) => { v },
             Err(_) => { return Err(()); }
         };
 
-        if chunk_id == "data" {
-            data_found = true;
-            break;
-        } else {
-            // skip chunk data
-            if file.seek(std::io::SeekFrom::Current(chunk_header.chunk_size.try_into().unwrap())).is_err() {
-                return Err(());
-            }
+        if &chunk_header.id == b"data" {
+            data_chunk = Some((chunk_start, chunk_header.chunk_size.try_into().unwrap()));
+        } else if &chunk_header.id == b"smpl" {
+            sample_loop = WavSampleLoop::read(file)?;
         }
+
+        // skip to next chunk (chunks are padded to an even number of bytes)
+        let next_chunk = chunk_start + chunk_header.chunk_size as u64 + (chunk_header.chunk_size & 1) as u64;
+        if file.seek(std::io::SeekFrom::Start(next_chunk)).is_err() {
+            return Err(());
+        }
+    }
+
+    let (data_start, data_size) = match data_chunk {
+        Some(v) => { v },
+        None => { return Err(()); }
+    };
+
+    if file.seek(std::io::SeekFrom::Start(data_start)).is_err() {
+        return Err(());
     }
 
-    if !data_found {
+    let mut data: Vec<u8> = vec![0;data_size];
+    if file.read_exact(data.as_mut_slice()).is_err() {
         return Err(());
     }
 
-    if fmt_header.format_type == 1 && fmt_header.bits_per_sample == 8 {
-        // unsigned 8-bit PCM
-        let mut pcm8: Vec<u8> = vec![0;chunk_header.chunk_size.try_into().unwrap()];
-        match file.read(pcm8.as_mut_slice()) {
-            Ok(_) => {},
-            Err(_) => { return Err(()); }
-        };
+    let samplerate: i32 = fmt_header.samplerate.try_into().map_err(|_| ())?;
 
+    let sample_handle = if fmt_header.format_type == 1 && fmt_header.bits_per_sample == 8 {
+        // unsigned 8-bit PCM
         // convert from unsigned 0 .. 255 to signed -128 .. 127
-        for i in 0..pcm8.len() {
-            pcm8[i] = pcm8[i].wrapping_sub(128);
-        }
-
-        let sample_handle = unsafe { AudioSample::create_s8(transmute(pcm8.as_slice()), 
-            fmt_header.samplerate.try_into().unwrap())? };
-
-        return Ok(sample_handle);
+        let pcm8: Vec<i8> = data.iter().map(|x| x.wrapping_sub(128) as i8).collect();
+        AudioSample::create_s8(pcm8.as_slice(), samplerate)?
     } else if fmt_header.format_type == 1 && fmt_header.bits_per_sample == 16 {
         // signed 16-bit PCM
-        let mut pcm16: Vec<u8> = vec![0, chunk_header.chunk_size.try_into().unwrap()];
-        match file.read(pcm16.as_mut_slice()) {
-            Ok(_) => {},
-            Err(_) => { return Err(()); }
-        };
-
-        let sample_handle = unsafe { AudioSample::create_s16(transmute(pcm16.as_slice()), 
-            fmt_header.samplerate.try_into().unwrap())? };
-        
-        return Ok(sample_handle);
+        let pcm16: Vec<i16> = data.chunks_exact(2).map(|x| i16::from_le_bytes([x[0], x[1]])).collect();
+        AudioSample::create_s16(pcm16.as_slice(), samplerate)?
     } else if fmt_header.format_type == 0x11 {
         // IMA ADPCM
-        let mut adpcm: Vec<u8> = vec![0, chunk_header.chunk_size.try_into().unwrap()];
-        match file.read(adpcm.as_mut_slice()) {
-            Ok(_) => {},
-            Err(_) => { return Err(()); }
-        }
-
-        let sample_handle = AudioSample::create_adpcm(adpcm.as_slice(), 
-            fmt_header.block_align.try_into().unwrap(), 
-            fmt_header.samplerate.try_into().unwrap())?;
+        AudioSample::create_adpcm(data.as_slice(), fmt_header.block_align.into(), samplerate)?
+    } else {
+        return Err(());
+    };
 
-        return Ok(sample_handle);
-    }
+    // smpl loop end is inclusive, voice loop end is exclusive
+    // loop points come straight from the file, so reject any which don't fit inside the sample
+    let (loop_start, loop_end) = match sample_loop {
+        Some(l) => {
+            let start: i32 = l.start.try_into().map_err(|_| ())?;
+            let end: i32 = l.end.checked_add(1).ok_or(())?.try_into().map_err(|_| ())?;
+            if start >= end || end > sample_handle.length {
+                return Err(());
+            }
+            (start, end)
+        }
+        None => { (0, 0) }
+    };
 
-    return Err(());
-}
\ No newline at end of file
+    return Ok(LoadedWav {
+        sample: sample_handle,
+        loop_start: loop_start,
+        loop_end: loop_end,
+    });
+}
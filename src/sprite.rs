@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use crate::{math::{Vector2, Vector4}, vdp::{self, Color32, PackedVertex, PooledBuffer, Rectangle, SCREEN_HEIGHT, SCREEN_WIDTH, Texture}};
+
+pub mod atlas;
+pub mod flare;
+
+use atlas::SpriteAtlas;
+
+fn to_clip(p: Vector2) -> Vector4 {
+    return Vector4::new((p.x / SCREEN_WIDTH as f32) * 2.0 - 1.0, 1.0 - (p.y / SCREEN_HEIGHT as f32) * 2.0, 0.0, 1.0);
+}
+
+struct SpriteRun {
+    texture: Arc<Texture>,
+    vertices: PooledBuffer<PackedVertex>,
+}
+
+/// Immediate-mode batch of textured quads in screen pixel coordinates (origin at the top left) <br/>
+/// Consecutive sprites sharing a texture are merged into a single draw call
+pub struct SpriteBatch {
+    runs: Vec<SpriteRun>,
+}
+
+impl SpriteBatch {
+    /// Construct a new empty batch
+    pub fn new() -> SpriteBatch {
+        return SpriteBatch {
+            runs: Vec::new(),
+        };
+    }
+
+    /// Add a quad with the given corner positions (in pixels) & texture coordinates to the batch, in clockwise order starting from the top left
+    pub fn draw_quad(&mut self, texture: &Arc<Texture>, positions: [Vector2;4], texcoords: [Vector2;4], color: Color32) {
+        let run = match self.runs.last_mut() {
+            Some(v) if Arc::ptr_eq(&v.texture, texture) => { v },
+            _ => {
+                self.runs.push(SpriteRun { texture: texture.clone(), vertices: vdp::take_packed_vertex_buffer(6) });
+                self.runs.last_mut().unwrap()
+            }
+        };
+
+        let ocolor = Color32::new(0, 0, 0, 0);
+        for i in [0, 1, 2, 2, 3, 0] {
+            run.vertices.push(PackedVertex::new(to_clip(positions[i]), texcoords[i], color, ocolor));
+        }
+    }
+
+    /// Add the given region of a texture (in pixels) to the batch, drawn at its original size with its top left corner at the given position
+    pub fn draw(&mut self, texture: &Arc<Texture>, src: Rectangle, position: Vector2, color: Color32) {
+        self.draw_scaled(texture, src, position, Vector2::new(src.width as f32, src.height as f32), color);
+    }
+
+    /// Add the given region of a texture (in pixels) to the batch, stretched to the given size with its top left corner at the given position
+    pub fn draw_scaled(&mut self, texture: &Arc<Texture>, src: Rectangle, position: Vector2, size: Vector2, color: Color32) {
+        let uv_min = Vector2::new(src.x as f32 / texture.width as f32, src.y as f32 / texture.height as f32);
+        let uv_max = Vector2::new((src.x + src.width) as f32 / texture.width as f32, (src.y + src.height) as f32 / texture.height as f32);
+
+        self.draw_quad(texture,
+            [position, position + Vector2::new(size.x, 0.0), position + size, position + Vector2::new(0.0, size.y)],
+            [uv_min, Vector2::new(uv_max.x, uv_min.y), uv_max, Vector2::new(uv_min.x, uv_max.y)],
+            color);
+    }
+
+    /// Add the named sprite from an atlas to the batch, with its pivot at the given position, scaled & rotated clockwise (in radians) around the pivot <br/>
+    /// Returns false if the atlas doesn't contain a sprite with the given name
+    pub fn draw_sprite(&mut self, atlas: &SpriteAtlas, name: &str, position: Vector2, scale: Vector2, rotation: f32, color: Color32) -> bool {
+        let frame = match atlas.frame(name) {
+            Some(v) => { v },
+            None => { return false; }
+        };
+
+        // corners of the trimmed region, relative to the pivot of the untrimmed sprite
+        let origin = frame.trim_offset - (frame.pivot * frame.source_size);
+        let size = Vector2::new(frame.rect.width as f32, frame.rect.height as f32);
+        let corners = [
+            origin,
+            origin + Vector2::new(size.x, 0.0),
+            origin + size,
+            origin + Vector2::new(0.0, size.y),
+        ];
+
+        let (sin, cos) = rotation.sin_cos();
+        let positions = corners.map(|c| {
+            let c = c * scale;
+            return position + Vector2::new(c.x * cos - c.y * sin, c.x * sin + c.y * cos);
+        });
+
+        let (uv_min, uv_max) = atlas.uv_rect(frame);
+        self.draw_quad(atlas.texture(), positions, [uv_min, Vector2::new(uv_max.x, uv_min.y), uv_max, Vector2::new(uv_min.x, uv_max.y)], color);
+
+        return true;
+    }
+
+    /// Gets whether the batch contains no sprites
+    pub fn is_empty(&self) -> bool {
+        return self.runs.is_empty();
+    }
+
+    /// Remove all sprites from the batch
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
+
+    /// Draw the batch and then clear it <br/>
+    /// This disables depth testing & culling and enables alpha blending. The last sprite's texture is left bound
+    pub fn flush(&mut self) {
+        if self.runs.is_empty() {
+            return;
+        }
+
+        vdp::depth_write(false);
+        vdp::depth_func(vdp::Compare::Always);
+        vdp::blend_equation(vdp::BlendEquation::Add);
+        vdp::blend_func(vdp::BlendFactor::SrcAlpha, vdp::BlendFactor::OneMinusSrcAlpha);
+        vdp::set_culling(false);
+
+        for run in &self.runs {
+            vdp::bind_texture(Some(&run.texture));
+            vdp::draw_geometry_packed(vdp::Topology::TriangleList, &run.vertices);
+        }
+
+        self.runs.clear();
+    }
+}
+
+impl Default for SpriteBatch {
+    fn default() -> Self {
+        return SpriteBatch::new();
+    }
+}
@@ -0,0 +1,241 @@
+use std::{collections::HashMap, io::Read, sync::Arc};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{math::Vector2, vdp::{Rectangle, Texture}};
+
+const DBSA_VER: u32 = 1;
+
+/// Enumeration of errors which can result from loading a sprite atlas
+#[derive(Debug)]
+pub enum AtlasError {
+    ParseError,
+    VersionError,
+    /// The atlas uses a feature which isn't supported (such as rotated frames)
+    Unsupported,
+    #[cfg(feature = "atlas_json")]
+    JsonError(serde_json::Error),
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for AtlasError {
+    fn from(value: std::io::Error) -> Self {
+        return AtlasError::IOError(value);
+    }
+}
+
+#[cfg(feature = "atlas_json")]
+impl From<serde_json::Error> for AtlasError {
+    fn from(value: serde_json::Error) -> Self {
+        return AtlasError::JsonError(value);
+    }
+}
+
+/// Describes a single sprite packed into an atlas texture
+#[derive(Clone, Copy)]
+pub struct SpriteFrame {
+    /// Region of the atlas texture containing the (trimmed) sprite, in pixels
+    pub rect: Rectangle,
+    /// Size of the original untrimmed sprite in pixels
+    pub source_size: Vector2,
+    /// Offset of the trimmed region within the original sprite in pixels
+    pub trim_offset: Vector2,
+    /// Pivot point relative to the original sprite size (0,0 is the top left, 1,1 is the bottom right)
+    pub pivot: Vector2,
+}
+
+/// A texture containing many sprites, along with the name & region of each one <br/>
+/// The binary DBSA format starts with a "DBSA" identifier, u32 version, and u32 frame count. Each frame contains a null-terminated 32-byte name,
+/// u16 x, y, width & height of the region in the texture, i16 trim offset x & y, u16 untrimmed width & height, and f32 pivot x & y
+pub struct SpriteAtlas {
+    texture: Arc<Texture>,
+    frames: HashMap<String, SpriteFrame>,
+}
+
+fn str_from_null_terminated(s: &[u8]) -> Result<String,AtlasError> {
+    let len = s.iter().position(|x| *x == 0).unwrap_or(s.len());
+    return match std::str::from_utf8(&s[..len]) {
+        Ok(v) => { Ok(v.to_string()) },
+        Err(_) => { Err(AtlasError::ParseError) }
+    };
+}
+
+impl SpriteAtlas {
+    /// Construct an atlas for the given texture from a list of named frames
+    pub fn from_frames(texture: Arc<Texture>, frames: HashMap<String, SpriteFrame>) -> SpriteAtlas {
+        return SpriteAtlas { texture: texture, frames: frames };
+    }
+
+    /// Load a DBSA atlas description from the given stream, paired with the given texture
+    pub fn new<R>(reader: &mut R, texture: Arc<Texture>) -> Result<SpriteAtlas,AtlasError> where R : Read {
+        let mut id: [u8;4] = [0;4];
+        reader.read_exact(&mut id)?;
+
+        if &id != b"DBSA" {
+            return Err(AtlasError::ParseError);
+        }
+
+        let ver = reader.read_u32::<LittleEndian>()?;
+        if ver != DBSA_VER {
+            return Err(AtlasError::VersionError);
+        }
+
+        let frame_count = reader.read_u32::<LittleEndian>()?;
+        let mut frames: HashMap<String, SpriteFrame> = HashMap::new();
+
+        for _ in 0..frame_count {
+            let mut name: [u8;32] = [0;32];
+            reader.read_exact(&mut name)?;
+
+            let x = reader.read_u16::<LittleEndian>()? as i32;
+            let y = reader.read_u16::<LittleEndian>()? as i32;
+            let width = reader.read_u16::<LittleEndian>()? as i32;
+            let height = reader.read_u16::<LittleEndian>()? as i32;
+            let trim_x = reader.read_i16::<LittleEndian>()? as f32;
+            let trim_y = reader.read_i16::<LittleEndian>()? as f32;
+            let source_width = reader.read_u16::<LittleEndian>()? as f32;
+            let source_height = reader.read_u16::<LittleEndian>()? as f32;
+            let pivot_x = reader.read_f32::<LittleEndian>()?;
+            let pivot_y = reader.read_f32::<LittleEndian>()?;
+
+            frames.insert(str_from_null_terminated(&name)?, SpriteFrame {
+                rect: Rectangle::new(x, y, width, height),
+                source_size: Vector2::new(source_width, source_height),
+                trim_offset: Vector2::new(trim_x, trim_y),
+                pivot: Vector2::new(pivot_x, pivot_y),
+            });
+        }
+
+        return Ok(SpriteAtlas { texture: texture, frames: frames });
+    }
+
+    /// Load an atlas description in the JSON format written by TexturePacker & Aseprite (either the hash or array variant), paired with the given texture
+    #[cfg(feature = "atlas_json")]
+    pub fn from_json<R>(reader: &mut R, texture: Arc<Texture>) -> Result<SpriteAtlas,AtlasError> where R : Read {
+        let doc: json::Document = serde_json::from_reader(reader)?;
+
+        let entries: Vec<(String, json::Frame)> = match doc.frames {
+            json::Frames::Hash(v) => { v.into_iter().collect() },
+            json::Frames::Array(v) => { v.into_iter().map(|x| (x.filename.clone(), x.frame)).collect() }
+        };
+
+        let mut frames: HashMap<String, SpriteFrame> = HashMap::new();
+        for (name, f) in entries {
+            if f.rotated {
+                return Err(AtlasError::Unsupported);
+            }
+
+            let source_size = match f.source_size {
+                Some(v) => { Vector2::new(v.w as f32, v.h as f32) },
+                None => { Vector2::new(f.frame.w as f32, f.frame.h as f32) }
+            };
+            let trim_offset = match f.sprite_source_size {
+                Some(v) => { Vector2::new(v.x as f32, v.y as f32) },
+                None => { Vector2::zero() }
+            };
+            let pivot = match f.pivot {
+                Some(v) => { Vector2::new(v.x, v.y) },
+                None => { Vector2::zero() }
+            };
+
+            frames.insert(name, SpriteFrame {
+                rect: Rectangle::new(f.frame.x, f.frame.y, f.frame.w, f.frame.h),
+                source_size: source_size,
+                trim_offset: trim_offset,
+                pivot: pivot,
+            });
+        }
+
+        return Ok(SpriteAtlas { texture: texture, frames: frames });
+    }
+
+    /// Get the atlas texture
+    pub fn texture(&self) -> &Arc<Texture> {
+        return &self.texture;
+    }
+
+    /// Get the frame with the given name
+    pub fn frame(&self, name: &str) -> Option<&SpriteFrame> {
+        return self.frames.get(name);
+    }
+
+    /// Get an iterator over the names of every frame in the atlas
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        return self.frames.keys().map(|x| x.as_str());
+    }
+
+    /// Get the number of frames in the atlas
+    pub fn len(&self) -> usize {
+        return self.frames.len();
+    }
+
+    /// Gets whether the atlas contains no frames
+    pub fn is_empty(&self) -> bool {
+        return self.frames.is_empty();
+    }
+
+    /// Get the minimum & maximum texture coordinates of the given frame
+    pub fn uv_rect(&self, frame: &SpriteFrame) -> (Vector2, Vector2) {
+        let size = Vector2::new(self.texture.width as f32, self.texture.height as f32);
+        let min = Vector2::new(frame.rect.x as f32, frame.rect.y as f32);
+        let max = Vector2::new((frame.rect.x + frame.rect.width) as f32, (frame.rect.y + frame.rect.height) as f32);
+        return (min / size, max / size);
+    }
+}
+
+#[cfg(feature = "atlas_json")]
+mod json {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    pub struct Document {
+        pub frames: Frames,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub enum Frames {
+        Hash(HashMap<String, Frame>),
+        Array(Vec<NamedFrame>),
+    }
+
+    #[derive(Deserialize)]
+    pub struct NamedFrame {
+        pub filename: String,
+        #[serde(flatten)]
+        pub frame: Frame,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Frame {
+        pub frame: Rect,
+        #[serde(default)]
+        pub rotated: bool,
+        pub sprite_source_size: Option<Rect>,
+        pub source_size: Option<Size>,
+        pub pivot: Option<Point>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Rect {
+        pub x: i32,
+        pub y: i32,
+        pub w: i32,
+        pub h: i32,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Size {
+        pub w: i32,
+        pub h: i32,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Point {
+        pub x: f32,
+        pub y: f32,
+    }
+}
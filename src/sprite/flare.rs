@@ -0,0 +1,130 @@
+use std::sync::{Arc, atomic::{AtomicU32, Ordering}};
+
+use crate::{math::{Matrix4x4, Vector2, Vector3}, vdp::{self, Color32, Compare, Rectangle, SCREEN_HEIGHT, SCREEN_WIDTH, Texture}};
+
+use super::SpriteBatch;
+
+// the VDP only supports a single depth query at a time, so flares take turns owning it (0 = free)
+static QUERY_OWNER: AtomicU32 = AtomicU32::new(0);
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// A flare sprite which fades in & out based on how much of a point (such as the sun) is visible, tested against the depth buffer <br/>
+/// Multiple flares can be active at once - they share the VDP's single depth query, so each may only be tested every few frames
+pub struct LensFlare {
+    pub texture: Arc<Texture>,
+    /// Region of the texture (in pixels) to draw
+    pub src: Rectangle,
+    /// Size (in pixels) of the flare at full visibility
+    pub size: Vector2,
+    pub color: Color32,
+    /// Half size (in pixels) of the square region around the point which is tested for visibility
+    pub query_radius: i32,
+    /// Time in seconds taken to fade between fully hidden & fully visible
+    pub fade_time: f32,
+    id: u32,
+    query_area: i32,
+    target: f32,
+    visibility: f32,
+    screen_pos: Option<Vector2>,
+}
+
+impl LensFlare {
+    /// Construct a new flare, drawing the given region of a texture
+    pub fn new(texture: Arc<Texture>, src: Rectangle) -> LensFlare {
+        return LensFlare {
+            texture: texture,
+            src: src,
+            size: Vector2::new(src.width as f32, src.height as f32),
+            color: Color32::new(255, 255, 255, 255),
+            query_radius: 2,
+            fade_time: 0.1,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            query_area: 0,
+            target: 0.0,
+            visibility: 0.0,
+            screen_pos: None,
+        };
+    }
+
+    /// Test the visibility of the given world space point & update the flare's fade <br/>
+    /// This must be called after the scene has been drawn (so the depth buffer is filled) but before anything which shouldn't occlude the flare. For a directional light such as the sun, pass a point far along the light direction from the camera
+    pub fn update(&mut self, point: Vector3, view_proj: &Matrix4x4, delta: f32) {
+        // collect the result of this flare's last query, releasing it for other flares
+        let collected = QUERY_OWNER.load(Ordering::Relaxed) == self.id;
+        if collected {
+            let passed = vdp::get_depth_query_result();
+            self.target = if self.query_area > 0 { (passed as f32 / self.query_area as f32).clamp(0.0, 1.0) } else { 0.0 };
+            QUERY_OWNER.store(0, Ordering::Relaxed);
+        }
+
+        let clip = *view_proj * point.extend(1.0);
+        self.screen_pos = None;
+
+        if clip.w > 0.0 {
+            let depth = clip.z / clip.w;
+            let pos = Vector2::new(((clip.x / clip.w) * 0.5 + 0.5) * SCREEN_WIDTH as f32, (0.5 - (clip.y / clip.w) * 0.5) * SCREEN_HEIGHT as f32);
+
+            if (0.0..=1.0).contains(&depth) && pos.x >= 0.0 && pos.y >= 0.0 && pos.x < SCREEN_WIDTH as f32 && pos.y < SCREEN_HEIGHT as f32 {
+                self.screen_pos = Some(pos);
+
+                // submit a new query if no other flare is waiting on one
+                // (a flare which just collected its result waits a turn, so that the next flare to update gets the query)
+                if !collected && QUERY_OWNER.compare_exchange(0, self.id, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+                    let rect = self.query_rect(pos);
+                    self.query_area = rect.width * rect.height;
+                    vdp::submit_depth_query(depth, Compare::LessOrEqual, rect);
+                }
+            }
+        }
+
+        // points off screen or behind the camera are hidden
+        if self.screen_pos.is_none() {
+            self.target = 0.0;
+        }
+
+        let step = if self.fade_time > 0.0 { delta / self.fade_time } else { 1.0 };
+        if self.visibility < self.target {
+            self.visibility = (self.visibility + step).min(self.target);
+        } else {
+            self.visibility = (self.visibility - step).max(self.target);
+        }
+    }
+
+    /// Get how visible the flare currently is, from 0.0 (hidden) to 1.0 (fully visible)
+    pub fn visibility(&self) -> f32 {
+        return self.visibility;
+    }
+
+    /// Get the screen position (in pixels) of the point at the last update, or None if it was off screen
+    pub fn screen_position(&self) -> Option<Vector2> {
+        return self.screen_pos;
+    }
+
+    /// Add the flare to the given batch, centered on the point & faded by its visibility
+    pub fn draw(&self, batch: &mut SpriteBatch) {
+        let pos = match self.screen_pos {
+            Some(v) if self.visibility > 0.0 => { v },
+            _ => { return; }
+        };
+
+        let color = Color32::new(self.color.r, self.color.g, self.color.b, (self.color.a as f32 * self.visibility).round() as u8);
+        batch.draw_scaled(&self.texture, self.src, pos - (self.size * 0.5), self.size, color);
+    }
+
+    // square region around the point, clipped to the screen
+    fn query_rect(&self, pos: Vector2) -> Rectangle {
+        let r = self.query_radius.max(0);
+        let x0 = (pos.x as i32 - r).max(0);
+        let y0 = (pos.y as i32 - r).max(0);
+        let x1 = (pos.x as i32 + r + 1).min(SCREEN_WIDTH);
+        let y1 = (pos.y as i32 + r + 1).min(SCREEN_HEIGHT);
+        return Rectangle::new(x0, y0, x1 - x0, y1 - y0);
+    }
+}
+
+impl Drop for LensFlare {
+    fn drop(&mut self) {
+        // don't leave the query owned by a flare which will never collect it
+        let _ = QUERY_OWNER.compare_exchange(self.id, 0, Ordering::Relaxed, Ordering::Relaxed);
+    }
+}
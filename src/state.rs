@@ -0,0 +1,231 @@
+use crate::{clock::Game, db::debug_draw::DebugDraw, gamepad::{GamepadSlot, InputManager}, vdp::{Color32, SCREEN_HEIGHT, SCREEN_WIDTH}};
+
+/// A boxed game state, as stored in a StateStack
+pub type BoxedState = Box<dyn GameState + Send>;
+
+/// A change to the state stack requested by the active state
+pub enum Transition {
+    None,
+    /// Push a new state on top of the active state (such as a pause menu over gameplay)
+    Push(BoxedState),
+    /// Remove the active state, returning to the state below it
+    Pop,
+    /// Replace the active state with a new state
+    Replace(BoxedState),
+    /// Fade to the stack's fade color over the given number of seconds, replace the active state, then fade back in over the same duration
+    FadeTo(BoxedState, f32),
+}
+
+/// A screen or mode of a game (title screen, gameplay, pause menu, etc), managed by a StateStack <br/>
+/// States request changes to the stack by returning a Transition, as the stack can't be modified while it is calling into one of its states
+pub trait GameState {
+    /// Called when this state is added to the stack
+    fn enter(&mut self) {
+    }
+
+    /// Called when this state is removed from the stack
+    fn exit(&mut self) {
+    }
+
+    /// Called when another state is pushed on top of this one
+    fn pause(&mut self) {
+    }
+
+    /// Called when this state becomes the active state again after the state above it was popped
+    fn resume(&mut self) {
+    }
+
+    /// Respond to input (only called on the active state, once per update step before update)
+    fn handle_input(&mut self, _input: &InputManager) -> Transition {
+        return Transition::None;
+    }
+
+    /// Advance the state by the given fixed time step in seconds (only called on the active state)
+    fn update(&mut self, delta: f32) -> Transition;
+
+    /// Draw the state, given the interpolation alpha between the last two updates
+    fn draw(&mut self, alpha: f32);
+
+    /// Gets whether the states below this one should still be drawn underneath it (such as for a pause menu)
+    fn is_overlay(&self) -> bool {
+        return false;
+    }
+}
+
+struct Fade {
+    target: Option<BoxedState>,
+    duration: f32,
+    time: f32,
+}
+
+/// A stack of game states, where the top state is active <br/>
+/// The stack implements Game, so it can be driven from the vsync handler with clock::run_game. Input from the stack's gamepad is polled once per update step
+pub struct StateStack {
+    /// Color faded to during FadeTo transitions (alpha is ignored)
+    pub fade_color: Color32,
+    states: Vec<BoxedState>,
+    input: InputManager,
+    fade: Option<Fade>,
+    batch: DebugDraw,
+}
+
+impl StateStack {
+    /// Construct a new stack with the given initial state, reading input from the given gamepad slot
+    pub fn new(initial: BoxedState, slot: GamepadSlot) -> StateStack {
+        let mut stack = StateStack {
+            fade_color: Color32::new(0, 0, 0, 255),
+            states: Vec::new(),
+            input: InputManager::new(slot),
+            fade: None,
+            batch: DebugDraw::new(),
+        };
+        stack.push(initial);
+        return stack;
+    }
+
+    /// Push a new state on top of the active state
+    pub fn push(&mut self, mut state: BoxedState) {
+        if let Some(top) = self.states.last_mut() {
+            top.pause();
+        }
+        state.enter();
+        self.states.push(state);
+    }
+
+    /// Remove the active state, resuming the state below it
+    pub fn pop(&mut self) {
+        if let Some(mut state) = self.states.pop() {
+            state.exit();
+        }
+        if let Some(top) = self.states.last_mut() {
+            top.resume();
+        }
+    }
+
+    /// Replace the active state with a new state
+    pub fn replace(&mut self, mut state: BoxedState) {
+        if let Some(mut old) = self.states.pop() {
+            old.exit();
+        }
+        state.enter();
+        self.states.push(state);
+    }
+
+    /// Apply the given transition (fades are ignored if a fade is already in progress)
+    pub fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => {
+            },
+            Transition::Push(state) => {
+                self.push(state);
+            },
+            Transition::Pop => {
+                self.pop();
+            },
+            Transition::Replace(state) => {
+                self.replace(state);
+            },
+            Transition::FadeTo(state, duration) => {
+                if self.fade.is_none() {
+                    self.fade = Some(Fade { target: Some(state), duration: duration.max(0.0), time: 0.0 });
+                }
+            }
+        };
+    }
+
+    /// Get the number of states on the stack
+    pub fn len(&self) -> usize {
+        return self.states.len();
+    }
+
+    /// Gets whether the stack contains no states (the game has nothing left to run)
+    pub fn is_empty(&self) -> bool {
+        return self.states.is_empty();
+    }
+
+    /// Gets whether a fade transition is in progress
+    pub fn is_fading(&self) -> bool {
+        return self.fade.is_some();
+    }
+
+    /// Get the input manager used to poll input for the active state
+    pub fn input(&self) -> &InputManager {
+        return &self.input;
+    }
+
+    /// Get the input manager used to poll input for the active state
+    pub fn input_mut(&mut self) -> &mut InputManager {
+        return &mut self.input;
+    }
+
+    // advance the current fade, swapping states once the screen is fully covered
+    fn update_fade(&mut self, delta: f32) {
+        let fade = match self.fade.as_mut() {
+            Some(v) => { v },
+            None => { return; }
+        };
+
+        fade.time += delta;
+
+        if fade.time >= fade.duration {
+            if let Some(target) = fade.target.take() {
+                self.replace(target);
+            }
+        }
+
+        if self.fade.as_ref().is_some_and(|x| x.time >= x.duration * 2.0) {
+            self.fade = None;
+        }
+    }
+
+    fn fade_opacity(&self) -> f32 {
+        return match &self.fade {
+            Some(fade) if fade.duration > 0.0 => {
+                1.0 - ((fade.time - fade.duration).abs() / fade.duration).min(1.0)
+            },
+            Some(_) => { 1.0 },
+            None => { 0.0 }
+        };
+    }
+}
+
+impl Game for StateStack {
+    fn update(&mut self, delta: f32) {
+        self.input.update();
+
+        // the active state is frozen while fading out, so it can't request another transition
+        let fading_out = self.fade.as_ref().is_some_and(|x| x.target.is_some());
+        self.update_fade(delta);
+
+        if fading_out {
+            return;
+        }
+
+        let transition = match self.states.last_mut() {
+            Some(top) => { top.handle_input(&self.input) },
+            None => { return; }
+        };
+        self.apply(transition);
+
+        let transition = match self.states.last_mut() {
+            Some(top) => { top.update(delta) },
+            None => { return; }
+        };
+        self.apply(transition);
+    }
+
+    fn render(&mut self, alpha: f32) {
+        // draw from the topmost state which covers the whole screen
+        let base = self.states.iter().rposition(|x| !x.is_overlay()).unwrap_or(0);
+        for state in self.states.iter_mut().skip(base) {
+            state.draw(alpha);
+        }
+
+        let opacity = self.fade_opacity();
+        if opacity > 0.0 {
+            let c = self.fade_color;
+            self.batch.rect(0.0, 0.0, SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32, Color32::new(c.r, c.g, c.b, (opacity * 255.0).round() as u8));
+            self.batch.flush();
+        }
+    }
+}
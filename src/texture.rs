@@ -0,0 +1,164 @@
+use std::{collections::HashMap, io::{Read, Seek}, sync::Mutex};
+
+use crate::{io::{BufferedStream, FileMode, FileStream, IOError}, vdp::{Texture, TextureError, TextureFormat}};
+
+#[cfg(feature = "tga")]
+pub mod tga;
+#[cfg(feature = "png")]
+pub mod png;
+#[cfg(feature = "ktx")]
+pub mod ktx;
+#[cfg(feature = "qoi")]
+pub mod qoi;
+#[cfg(feature = "dds")]
+pub mod dds;
+
+/// Enumeration of errors which can result from loading an image
+#[derive(Debug)]
+pub enum ImageError {
+    ParseError,
+    /// The image uses a feature or pixel format which isn't supported
+    Unsupported,
+    /// No loader is registered for the file's extension
+    UnknownFormat,
+    TextureError(TextureError),
+    IOError(std::io::Error),
+}
+
+impl From<IOError> for ImageError {
+    fn from(value: IOError) -> Self {
+        return ImageError::IOError(value.into());
+    }
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(value: std::io::Error) -> Self {
+        return ImageError::IOError(value);
+    }
+}
+
+impl From<TextureError> for ImageError {
+    fn from(value: TextureError) -> Self {
+        return ImageError::TextureError(value);
+    }
+}
+
+/// A decoded image in 8-bit RGBA format, with rows stored top to bottom
+pub struct Image {
+    pub width: i32,
+    pub height: i32,
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    /// Upload this image to a new RGBA8888 texture, optionally generating mipmaps <br/>
+    /// Width & height must be powers of two
+    pub fn to_texture(&self, mipmap: bool) -> Result<Texture,ImageError> {
+        let texture = Texture::new(self.width, self.height, mipmap, TextureFormat::RGBA8888)?;
+        texture.set_texture_data(0, &self.data);
+
+        if mipmap {
+            let mut level = 1;
+            let mut w = self.width as usize;
+            let mut h = self.height as usize;
+            let mut prev = self.data.clone();
+
+            // each level is a 2x2 box filter of the previous level
+            while w > 1 || h > 1 {
+                let nw = (w / 2).max(1);
+                let nh = (h / 2).max(1);
+                let mut next = vec![0;nw * nh * 4];
+
+                for y in 0..nh {
+                    for x in 0..nw {
+                        let x0 = (x * 2).min(w - 1);
+                        let x1 = (x * 2 + 1).min(w - 1);
+                        let y0 = (y * 2).min(h - 1);
+                        let y1 = (y * 2 + 1).min(h - 1);
+                        for c in 0..4 {
+                            let sum = prev[(y0 * w + x0) * 4 + c] as u32 + prev[(y0 * w + x1) * 4 + c] as u32
+                                + prev[(y1 * w + x0) * 4 + c] as u32 + prev[(y1 * w + x1) * 4 + c] as u32;
+                            next[(y * nw + x) * 4 + c] = ((sum + 2) / 4) as u8;
+                        }
+                    }
+                }
+
+                texture.set_texture_data(level, &next);
+                prev = next;
+                w = nw;
+                h = nh;
+                level += 1;
+            }
+        }
+
+        return Ok(texture);
+    }
+}
+
+/// A seekable stream which a texture loader reads from
+pub trait TextureSource : Read + Seek {}
+
+impl<T> TextureSource for T where T : Read + Seek {}
+
+/// A function which loads a texture from a stream, optionally generating mipmaps
+pub type TextureLoader = fn(&mut dyn TextureSource, bool) -> Result<Texture,ImageError>;
+
+static LOADERS: Mutex<Option<HashMap<String, TextureLoader>>> = Mutex::new(None);
+
+fn with_loaders<T, F>(f: F) -> T where F : FnOnce(&mut HashMap<String, TextureLoader>) -> T {
+    let mut loaders = LOADERS.lock().unwrap();
+    let loaders = loaders.get_or_insert_with(|| {
+        let builtin: &[(&str, TextureLoader)] = &[
+            #[cfg(feature = "tga")]
+            ("tga", |r, mipmap| tga::load(r, mipmap)),
+            #[cfg(feature = "png")]
+            ("png", |r, mipmap| png::load(r, mipmap)),
+            #[cfg(feature = "ktx")]
+            ("ktx", |r, mipmap| ktx::load(r, mipmap)),
+            #[cfg(feature = "qoi")]
+            ("qoi", |r, mipmap| qoi::load(r, mipmap)),
+            #[cfg(feature = "dds")]
+            ("dds", |r, mipmap| dds::load(r, mipmap)),
+        ];
+        builtin.iter().map(|(ext, loader)| (ext.to_string(), *loader)).collect()
+    });
+    return f(loaders);
+}
+
+/// Register a loader for files with the given extension (case insensitive, without the leading dot), replacing any existing loader for it <br/>
+/// Built-in loaders are registered for each enabled image format feature
+pub fn register_loader(extension: &str, loader: TextureLoader) {
+    with_loaders(|x| x.insert(extension.to_ascii_lowercase(), loader));
+}
+
+/// Remove the loader for the given extension
+pub fn unregister_loader(extension: &str) {
+    with_loaders(|x| x.remove(&extension.to_ascii_lowercase()));
+}
+
+/// Check whether a loader is registered for the given extension
+pub fn has_loader(extension: &str) -> bool {
+    return with_loaders(|x| x.contains_key(&extension.to_ascii_lowercase()));
+}
+
+/// Load a texture from the given path without mipmaps, using the loader registered for its extension
+pub fn load(path: &str) -> Result<Texture,ImageError> {
+    return load_with_mipmaps(path, false);
+}
+
+/// Load a texture from the given path, using the loader registered for its extension & optionally generating mipmaps
+pub fn load_with_mipmaps(path: &str, mipmap: bool) -> Result<Texture,ImageError> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let extension = match name.rsplit_once('.') {
+        Some((_, ext)) => { ext.to_ascii_lowercase() },
+        None => { return Err(ImageError::UnknownFormat); }
+    };
+
+    let loader = match with_loaders(|x| x.get(&extension).copied()) {
+        Some(v) => { v },
+        None => { return Err(ImageError::UnknownFormat); }
+    };
+
+    let mut file = BufferedStream::new(FileStream::open(path, FileMode::Read)?);
+    return loader(&mut file, mipmap);
+}
@@ -0,0 +1,139 @@
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{texture::ImageError, vdp::{Texture, TextureFormat, budget::texture_size}};
+
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+
+// read a channel out of a pixel using its bit mask, scaled to 8 bits
+fn channel(px: u32, mask: u32, default: u8) -> u8 {
+    if mask == 0 {
+        return default;
+    }
+
+    let max = mask >> mask.trailing_zeros();
+    let v = (px & mask) >> mask.trailing_zeros();
+    return ((v as u64 * 255) / max as u64) as u8;
+}
+
+/// Load a DDS texture straight into a new texture <br/>
+/// DXT1 & DXT3 data is uploaded as-is. Uncompressed 24 & 32-bit images are converted to RGBA8888, 16-bit images without alpha to RGB565, and 16-bit images with alpha to RGBA4444.
+/// Mip levels stored in the file are uploaded as-is, so the mipmap flag is ignored. Cubemap, volume & DX10 textures are not supported
+pub fn load<R>(reader: &mut R, _mipmap: bool) -> Result<Texture,ImageError> where R : Read + ?Sized {
+    let mut magic: [u8;4] = [0;4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"DDS " {
+        return Err(ImageError::ParseError);
+    }
+
+    if reader.read_u32::<LittleEndian>()? != 124 {
+        return Err(ImageError::ParseError);
+    }
+
+    let flags = reader.read_u32::<LittleEndian>()?;
+    let height = reader.read_u32::<LittleEndian>()?;
+    let width = reader.read_u32::<LittleEndian>()?;
+    let _pitch = reader.read_u32::<LittleEndian>()?;
+    let _depth = reader.read_u32::<LittleEndian>()?;
+    let mip_count = reader.read_u32::<LittleEndian>()?;
+    std::io::copy(&mut reader.take(11 * 4), &mut std::io::sink())?;
+
+    // pixel format
+    if reader.read_u32::<LittleEndian>()? != 32 {
+        return Err(ImageError::ParseError);
+    }
+    let pf_flags = reader.read_u32::<LittleEndian>()?;
+    let mut fourcc: [u8;4] = [0;4];
+    reader.read_exact(&mut fourcc)?;
+    let bit_count = reader.read_u32::<LittleEndian>()?;
+    let masks = [reader.read_u32::<LittleEndian>()?, reader.read_u32::<LittleEndian>()?, reader.read_u32::<LittleEndian>()?, reader.read_u32::<LittleEndian>()?];
+
+    let _caps = reader.read_u32::<LittleEndian>()?;
+    let caps2 = reader.read_u32::<LittleEndian>()?;
+    std::io::copy(&mut reader.take(3 * 4), &mut std::io::sink())?;
+
+    if caps2 & (DDSCAPS2_CUBEMAP | DDSCAPS2_VOLUME) != 0 {
+        return Err(ImageError::Unsupported);
+    }
+
+    // source bytes per pixel (0 for block compressed data) & the format it's uploaded as
+    let (format, src_bpp) = if pf_flags & DDPF_FOURCC != 0 {
+        match &fourcc {
+            b"DXT1" => { (TextureFormat::DXT1, 0) },
+            b"DXT3" => { (TextureFormat::DXT3, 0) },
+            _ => { return Err(ImageError::Unsupported); }
+        }
+    } else if pf_flags & DDPF_RGB != 0 {
+        match bit_count {
+            24 | 32 => { (TextureFormat::RGBA8888, bit_count as usize / 8) },
+            16 if masks[3] == 0 => { (TextureFormat::RGB565, 2) },
+            16 => { (TextureFormat::RGBA4444, 2) },
+            _ => { return Err(ImageError::Unsupported); }
+        }
+    } else {
+        return Err(ImageError::Unsupported);
+    };
+
+    let width: i32 = width.try_into().map_err(|_| ImageError::ParseError)?;
+    let height: i32 = height.try_into().map_err(|_| ImageError::ParseError)?;
+    let levels = if flags & DDSD_MIPMAPCOUNT != 0 { mip_count.max(1) } else { 1 };
+
+    // a full mip chain ends at 1x1
+    if levels > 32 - (width.max(height) as u32).leading_zeros() {
+        return Err(ImageError::ParseError);
+    }
+
+    let texture = Texture::new(width, height, levels > 1, format)?;
+
+    let mut w = width as usize;
+    let mut h = height as usize;
+    for level in 0..levels {
+        let data = if src_bpp == 0 {
+            let mut data: Vec<u8> = vec![0;texture_size(w as i32, h as i32, false, format)];
+            reader.read_exact(&mut data)?;
+            data
+        } else {
+            let mut src: Vec<u8> = vec![0;w * h * src_bpp];
+            reader.read_exact(&mut src)?;
+
+            let mut data: Vec<u8> = Vec::with_capacity(texture_size(w as i32, h as i32, false, format));
+            for p in src.chunks_exact(src_bpp) {
+                let mut bytes: [u8;4] = [0;4];
+                bytes[..src_bpp].copy_from_slice(p);
+                let px = u32::from_le_bytes(bytes);
+
+                let r = channel(px, masks[0], 0);
+                let g = channel(px, masks[1], 0);
+                let b = channel(px, masks[2], 0);
+                let a = channel(px, masks[3], 255);
+
+                match format {
+                    TextureFormat::RGB565 => {
+                        let v = ((r as u16 >> 3) << 11) | ((g as u16 >> 2) << 5) | (b as u16 >> 3);
+                        data.extend_from_slice(&v.to_le_bytes());
+                    },
+                    TextureFormat::RGBA4444 => {
+                        let v = ((r as u16 >> 4) << 12) | ((g as u16 >> 4) << 8) | ((b as u16 >> 4) << 4) | (a as u16 >> 4);
+                        data.extend_from_slice(&v.to_le_bytes());
+                    },
+                    _ => {
+                        data.extend_from_slice(&[r, g, b, a]);
+                    }
+                };
+            }
+            data
+        };
+
+        texture.set_texture_data(level as i32, &data);
+
+        w = (w / 2).max(1);
+        h = (h / 2).max(1);
+    }
+
+    return Ok(texture);
+}
@@ -0,0 +1,113 @@
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{texture::ImageError, vdp::{Texture, TextureFormat, budget::texture_size}};
+
+const KTX_IDENTIFIER: [u8;12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+const KTX_ENDIANNESS: u32 = 0x04030201;
+
+const GL_RGB: u32 = 0x1907;
+const GL_RGBA: u32 = 0x1908;
+const GL_UNSIGNED_BYTE: u32 = 0x1401;
+const GL_UNSIGNED_SHORT_5_6_5: u32 = 0x8363;
+const GL_UNSIGNED_SHORT_4_4_4_4: u32 = 0x8033;
+const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: u32 = 0x83F0;
+const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: u32 = 0x83F1;
+const GL_COMPRESSED_RGBA_S3TC_DXT3_EXT: u32 = 0x83F2;
+
+/// Load a KTX (version 1) texture in RGBA8888, RGB565, RGBA4444, DXT1 or DXT3 format straight into a new texture of the same format <br/>
+/// Mip levels stored in the file are uploaded as-is, so the mipmap flag is ignored (compressed data can't be filtered into new levels). Array, cubemap & 3D textures are not supported
+pub fn load<R>(reader: &mut R, _mipmap: bool) -> Result<Texture,ImageError> where R : Read + ?Sized {
+    let mut id: [u8;12] = [0;12];
+    reader.read_exact(&mut id)?;
+    if id != KTX_IDENTIFIER {
+        return Err(ImageError::ParseError);
+    }
+
+    // files written with the opposite byte order are rare enough to not be worth supporting
+    if reader.read_u32::<LittleEndian>()? != KTX_ENDIANNESS {
+        return Err(ImageError::Unsupported);
+    }
+
+    let gl_type = reader.read_u32::<LittleEndian>()?;
+    let _gl_type_size = reader.read_u32::<LittleEndian>()?;
+    let gl_format = reader.read_u32::<LittleEndian>()?;
+    let gl_internal_format = reader.read_u32::<LittleEndian>()?;
+    let _gl_base_internal_format = reader.read_u32::<LittleEndian>()?;
+    let width = reader.read_u32::<LittleEndian>()?;
+    let height = reader.read_u32::<LittleEndian>()?;
+    let depth = reader.read_u32::<LittleEndian>()?;
+    let array_elements = reader.read_u32::<LittleEndian>()?;
+    let faces = reader.read_u32::<LittleEndian>()?;
+    let levels = reader.read_u32::<LittleEndian>()?.max(1);
+    let kv_size = reader.read_u32::<LittleEndian>()?;
+
+    if depth > 1 || array_elements > 0 || faces != 1 {
+        return Err(ImageError::Unsupported);
+    }
+
+    let (format, bytes_per_pixel) = if gl_type == GL_UNSIGNED_BYTE && gl_format == GL_RGBA {
+        (TextureFormat::RGBA8888, 4)
+    } else if gl_type == GL_UNSIGNED_SHORT_5_6_5 && gl_format == GL_RGB {
+        (TextureFormat::RGB565, 2)
+    } else if gl_type == GL_UNSIGNED_SHORT_4_4_4_4 && gl_format == GL_RGBA {
+        (TextureFormat::RGBA4444, 2)
+    } else if gl_internal_format == GL_COMPRESSED_RGB_S3TC_DXT1_EXT || gl_internal_format == GL_COMPRESSED_RGBA_S3TC_DXT1_EXT {
+        (TextureFormat::DXT1, 0)
+    } else if gl_internal_format == GL_COMPRESSED_RGBA_S3TC_DXT3_EXT {
+        (TextureFormat::DXT3, 0)
+    } else {
+        return Err(ImageError::Unsupported);
+    };
+
+    let width: i32 = width.try_into().map_err(|_| ImageError::ParseError)?;
+    let height: i32 = height.max(1).try_into().map_err(|_| ImageError::ParseError)?;
+
+    // a full mip chain ends at 1x1
+    if levels > 32 - (width.max(height) as u32).leading_zeros() {
+        return Err(ImageError::ParseError);
+    }
+
+    // skip key/value metadata
+    std::io::copy(&mut reader.take(kv_size as u64), &mut std::io::sink())?;
+
+    let texture = Texture::new(width, height, levels > 1, format)?;
+
+    let mut w = width as usize;
+    let mut h = height as usize;
+    for level in 0..levels {
+        let image_size = reader.read_u32::<LittleEndian>()? as usize;
+
+        // uncompressed rows are padded to a multiple of 4 bytes, which the VDP doesn't expect
+        let expected = texture_size(w as i32, h as i32, false, format);
+        let row_size = w * bytes_per_pixel;
+        let padded_row = row_size.next_multiple_of(4);
+
+        let data = if bytes_per_pixel > 0 && image_size == padded_row * h && padded_row != row_size {
+            let mut data: Vec<u8> = vec![0;expected];
+            let mut row: Vec<u8> = vec![0;padded_row];
+            for y in 0..h {
+                reader.read_exact(&mut row)?;
+                data[y * row_size..(y + 1) * row_size].copy_from_slice(&row[..row_size]);
+            }
+            data
+        } else if image_size == expected {
+            let mut data: Vec<u8> = vec![0;expected];
+            reader.read_exact(&mut data)?;
+            data
+        } else {
+            return Err(ImageError::ParseError);
+        };
+
+        // each level is padded to a multiple of 4 bytes
+        std::io::copy(&mut reader.take((image_size.next_multiple_of(4) - image_size) as u64), &mut std::io::sink())?;
+
+        texture.set_texture_data(level as i32, &data);
+
+        w = (w / 2).max(1);
+        h = (h / 2).max(1);
+    }
+
+    return Ok(texture);
+}
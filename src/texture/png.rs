@@ -0,0 +1,46 @@
+use std::io::Read;
+
+use ::png::{ColorType, Decoder, Transformations};
+
+use crate::{texture::{Image, ImageError}, vdp::Texture};
+
+impl From<::png::DecodingError> for ImageError {
+    fn from(value: ::png::DecodingError) -> Self {
+        return match value {
+            ::png::DecodingError::IoError(e) => { ImageError::IOError(e) },
+            ::png::DecodingError::Format(_) => { ImageError::ParseError },
+            _ => { ImageError::Unsupported }
+        };
+    }
+}
+
+/// Decode a PNG image of any color type & bit depth (16-bit channels are reduced to 8 bits, and palettes are expanded)
+pub fn decode<R>(reader: R) -> Result<Image,ImageError> where R : Read {
+    let mut decoder = Decoder::new(reader);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info()?;
+    let mut buf: Vec<u8> = vec![0;reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    buf.truncate(info.buffer_size());
+
+    let count = (info.width * info.height) as usize;
+    let data: Vec<u8> = match info.color_type {
+        ColorType::Rgba => { buf },
+        ColorType::Rgb => { buf.chunks_exact(3).flat_map(|x| [x[0], x[1], x[2], 255]).collect() },
+        ColorType::GrayscaleAlpha => { buf.chunks_exact(2).flat_map(|x| [x[0], x[0], x[0], x[1]]).collect() },
+        ColorType::Grayscale => { buf.iter().flat_map(|x| [*x, *x, *x, 255]).collect() },
+        ColorType::Indexed => { return Err(ImageError::Unsupported); }
+    };
+
+    if data.len() != count * 4 {
+        return Err(ImageError::ParseError);
+    }
+
+    return Ok(Image { width: info.width as i32, height: info.height as i32, data: data });
+}
+
+/// Load a PNG image into a new RGBA8888 texture
+pub fn load<R>(reader: R, mipmap: bool) -> Result<Texture,ImageError> where R : Read {
+    return decode(reader)?.to_texture(mipmap);
+}
@@ -0,0 +1,88 @@
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::{texture::{Image, ImageError}, vdp::Texture};
+
+const QOI_OP_INDEX: u8 = 0x00;
+const QOI_OP_DIFF: u8 = 0x40;
+const QOI_OP_LUMA: u8 = 0x80;
+const QOI_OP_RGB: u8 = 0xFE;
+const QOI_OP_RGBA: u8 = 0xFF;
+const QOI_MASK: u8 = 0xC0;
+
+/// Largest number of pixels allowed by the QOI specification
+const QOI_PIXELS_MAX: usize = 400_000_000;
+
+fn hash(px: [u8;4]) -> usize {
+    return (px[0] as usize * 3 + px[1] as usize * 5 + px[2] as usize * 7 + px[3] as usize * 11) % 64;
+}
+
+/// Decode a QOI image (RGB or RGBA)
+pub fn decode<R>(reader: &mut R) -> Result<Image,ImageError> where R : Read + ?Sized {
+    let mut magic: [u8;4] = [0;4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"qoif" {
+        return Err(ImageError::ParseError);
+    }
+
+    let width = reader.read_u32::<BigEndian>()? as usize;
+    let height = reader.read_u32::<BigEndian>()? as usize;
+    let _channels = reader.read_u8()?;
+    let _colorspace = reader.read_u8()?;
+
+    let count = match width.checked_mul(height) {
+        Some(v) if v <= QOI_PIXELS_MAX && width <= i32::MAX as usize && height <= i32::MAX as usize => { v },
+        _ => { return Err(ImageError::ParseError); }
+    };
+
+    // the size comes from the header, so only reserve a bounded amount up front in case the file is truncated
+    let mut data: Vec<u8> = Vec::with_capacity(count.min(1 << 20) * 4);
+    let mut index: [[u8;4];64] = [[0;4];64];
+    let mut px: [u8;4] = [0, 0, 0, 255];
+
+    while data.len() < count * 4 {
+        let op = reader.read_u8()?;
+        let mut run = 1;
+
+        if op == QOI_OP_RGB {
+            reader.read_exact(&mut px[..3])?;
+        } else if op == QOI_OP_RGBA {
+            reader.read_exact(&mut px)?;
+        } else {
+            match op & QOI_MASK {
+                QOI_OP_INDEX => {
+                    px = index[op as usize];
+                },
+                QOI_OP_DIFF => {
+                    px[0] = px[0].wrapping_add((op >> 4) & 3).wrapping_sub(2);
+                    px[1] = px[1].wrapping_add((op >> 2) & 3).wrapping_sub(2);
+                    px[2] = px[2].wrapping_add(op & 3).wrapping_sub(2);
+                },
+                QOI_OP_LUMA => {
+                    let next = reader.read_u8()?;
+                    let dg = (op & 0x3F).wrapping_sub(32);
+                    px[0] = px[0].wrapping_add(dg.wrapping_sub(8).wrapping_add(next >> 4));
+                    px[1] = px[1].wrapping_add(dg);
+                    px[2] = px[2].wrapping_add(dg.wrapping_sub(8).wrapping_add(next & 0x0F));
+                },
+                _ => {
+                    // QOI_OP_RUN
+                    run = (op & 0x3F) as usize + 1;
+                }
+            };
+        }
+
+        index[hash(px)] = px;
+        for _ in 0..run.min(count - data.len() / 4) {
+            data.extend_from_slice(&px);
+        }
+    }
+
+    return Ok(Image { width: width as i32, height: height as i32, data: data });
+}
+
+/// Load a QOI image into a new RGBA8888 texture
+pub fn load<R>(reader: &mut R, mipmap: bool) -> Result<Texture,ImageError> where R : Read + ?Sized {
+    return decode(reader)?.to_texture(mipmap);
+}
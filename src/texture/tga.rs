@@ -0,0 +1,108 @@
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{texture::{Image, ImageError}, vdp::Texture};
+
+const TGA_TRUECOLOR: u8 = 2;
+const TGA_GRAYSCALE: u8 = 3;
+const TGA_TRUECOLOR_RLE: u8 = 10;
+const TGA_GRAYSCALE_RLE: u8 = 11;
+
+fn read_pixel<R>(reader: &mut R, bytes: usize) -> Result<[u8;4],ImageError> where R : Read + ?Sized {
+    let mut px: [u8;4] = [0;4];
+    reader.read_exact(&mut px[..bytes])?;
+
+    // stored as BGR(A) or grayscale
+    return Ok(match bytes {
+        1 => { [px[0], px[0], px[0], 255] },
+        3 => { [px[2], px[1], px[0], 255] },
+        4 => { [px[2], px[1], px[0], px[3]] },
+        _ => { return Err(ImageError::Unsupported); }
+    });
+}
+
+/// Decode an uncompressed or RLE compressed truecolor (24 or 32 bit) or grayscale (8 bit) TGA image
+pub fn decode<R>(reader: &mut R) -> Result<Image,ImageError> where R : Read + ?Sized {
+    let id_len = reader.read_u8()?;
+    let colormap_type = reader.read_u8()?;
+    let image_type = reader.read_u8()?;
+
+    // color map specification
+    let _colormap_start = reader.read_u16::<LittleEndian>()?;
+    let colormap_len = reader.read_u16::<LittleEndian>()?;
+    let colormap_bits = reader.read_u8()?;
+
+    // image specification
+    let _x_origin = reader.read_u16::<LittleEndian>()?;
+    let _y_origin = reader.read_u16::<LittleEndian>()?;
+    let width = reader.read_u16::<LittleEndian>()? as usize;
+    let height = reader.read_u16::<LittleEndian>()? as usize;
+    let bpp = reader.read_u8()?;
+    let descriptor = reader.read_u8()?;
+
+    let (rle, gray) = match image_type {
+        TGA_TRUECOLOR => { (false, false) },
+        TGA_GRAYSCALE => { (false, true) },
+        TGA_TRUECOLOR_RLE => { (true, false) },
+        TGA_GRAYSCALE_RLE => { (true, true) },
+        _ => { return Err(ImageError::Unsupported); }
+    };
+
+    let bytes = match (gray, bpp) {
+        (false, 24) => { 3 },
+        (false, 32) => { 4 },
+        (true, 8) => { 1 },
+        _ => { return Err(ImageError::Unsupported); }
+    };
+
+    // skip image id & any (unused) color map
+    let skip = id_len as u64 + if colormap_type != 0 { colormap_len as u64 * (colormap_bits as u64).div_ceil(8) } else { 0 };
+    std::io::copy(&mut reader.take(skip), &mut std::io::sink())?;
+
+    let count = width * height;
+    let size = count.checked_mul(4).ok_or(ImageError::ParseError)?;
+
+    // rle data can claim far more pixels than the file holds, so only reserve up front for a modest image
+    let mut pixels: Vec<[u8;4]> = Vec::with_capacity(count.min(1 << 20));
+
+    if rle {
+        while pixels.len() < count {
+            let header = reader.read_u8()?;
+            let run = ((header & 0x7F) as usize + 1).min(count - pixels.len());
+            if header & 0x80 != 0 {
+                let px = read_pixel(reader, bytes)?;
+                pixels.extend(std::iter::repeat_n(px, run));
+            } else {
+                for _ in 0..run {
+                    pixels.push(read_pixel(reader, bytes)?);
+                }
+            }
+        }
+    } else {
+        for _ in 0..count {
+            pixels.push(read_pixel(reader, bytes)?);
+        }
+    }
+
+    // rows are stored bottom to top unless bit 5 of the descriptor is set, and left to right unless bit 4 is set
+    let flip_y = descriptor & 0x20 == 0;
+    let flip_x = descriptor & 0x10 != 0;
+
+    let mut data: Vec<u8> = vec![0;size];
+    for y in 0..height {
+        let src_y = if flip_y { height - 1 - y } else { y };
+        for x in 0..width {
+            let src_x = if flip_x { width - 1 - x } else { x };
+            let dst = (y * width + x) * 4;
+            data[dst..dst + 4].copy_from_slice(&pixels[src_y * width + src_x]);
+        }
+    }
+
+    return Ok(Image { width: width as i32, height: height as i32, data: data });
+}
+
+/// Load a TGA image into a new RGBA8888 texture
+pub fn load<R>(reader: &mut R, mipmap: bool) -> Result<Texture,ImageError> where R : Read + ?Sized {
+    return decode(reader)?.to_texture(mipmap);
+}
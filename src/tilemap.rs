@@ -0,0 +1,314 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{math::Vector2, sprite::SpriteBatch, vdp::{Color32, Rectangle, SCREEN_HEIGHT, SCREEN_WIDTH, Texture}};
+
+#[cfg(feature = "tiled")]
+pub mod tiled;
+
+/// Tile flag set when a tile is flipped horizontally
+pub const TILE_FLIP_HORIZONTAL: u32 = 0x80000000;
+
+/// Tile flag set when a tile is flipped vertically
+pub const TILE_FLIP_VERTICAL: u32 = 0x40000000;
+
+/// Tile flag set when a tile is flipped diagonally (applied before the horizontal & vertical flips)
+pub const TILE_FLIP_DIAGONAL: u32 = 0x20000000;
+
+/// Mask of the global tile id within a tile, without flip flags
+pub const TILE_ID_MASK: u32 = 0x0FFFFFFF;
+
+/// A custom property value attached to a map, layer, object, or tile
+#[derive(Clone, PartialEq, Debug)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    String(String),
+}
+
+/// A set of named custom properties
+pub type Properties = HashMap<String, PropertyValue>;
+
+/// The shape of a map object
+#[derive(Clone)]
+pub enum ObjectShape {
+    Rectangle,
+    Ellipse,
+    Point,
+    /// A closed polygon, with points relative to the object's position
+    Polygon(Vec<Vector2>),
+    /// An open line strip, with points relative to the object's position
+    Polyline(Vec<Vector2>),
+    /// A tile object, which displays the given tile (including flip flags)
+    Tile(u32),
+}
+
+/// An object placed in a map (such as a spawn point, trigger, or collision shape)
+#[derive(Clone)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    pub class: String,
+    /// Position in pixels (the top left corner, except for tile objects which are positioned by their bottom left corner)
+    pub position: Vector2,
+    /// Size in pixels
+    pub size: Vector2,
+    /// Clockwise rotation in degrees around the object's position
+    pub rotation: f32,
+    pub visible: bool,
+    pub shape: ObjectShape,
+    pub properties: Properties,
+}
+
+impl MapObject {
+    /// Get the bounding rectangle of this object, ignoring rotation
+    pub fn bounds(&self) -> (Vector2, Vector2) {
+        return match &self.shape {
+            ObjectShape::Polygon(points) | ObjectShape::Polyline(points) => {
+                let mut min = self.position;
+                let mut max = self.position;
+                for p in points {
+                    min = Vector2::new(min.x.min(self.position.x + p.x), min.y.min(self.position.y + p.y));
+                    max = Vector2::new(max.x.max(self.position.x + p.x), max.y.max(self.position.y + p.y));
+                }
+                (min, max)
+            },
+            ObjectShape::Tile(_) => {
+                (self.position - Vector2::new(0.0, self.size.y), self.position + Vector2::new(self.size.x, 0.0))
+            },
+            _ => {
+                (self.position, self.position + self.size)
+            }
+        };
+    }
+}
+
+/// A grid of tiles
+pub struct TileLayer {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    /// Row-major tiles, each either 0 (empty) or a global tile id combined with flip flags
+    pub tiles: Vec<u32>,
+    pub visible: bool,
+    pub opacity: f32,
+    /// Offset of the layer in pixels
+    pub offset: Vector2,
+    pub properties: Properties,
+}
+
+impl TileLayer {
+    /// Construct a new empty tile layer (panics if width * height tiles can't be addressed)
+    pub fn new(name: &str, width: u32, height: u32) -> TileLayer {
+        let count = (width as usize).checked_mul(height as usize).expect("Tile layer is too large");
+        return TileLayer {
+            name: name.to_string(),
+            width: width,
+            height: height,
+            tiles: vec![0;count],
+            visible: true,
+            opacity: 1.0,
+            offset: Vector2::zero(),
+            properties: Properties::new(),
+        };
+    }
+
+    /// Get the tile at the given coordinates (0 if empty or out of bounds)
+    pub fn get(&self, x: i32, y: i32) -> u32 {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return 0;
+        }
+        return self.tiles[(y as usize * self.width as usize) + x as usize];
+    }
+
+    /// Set the tile at the given coordinates (ignored if out of bounds)
+    pub fn set(&mut self, x: i32, y: i32, tile: u32) {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return;
+        }
+        self.tiles[(y as usize * self.width as usize) + x as usize] = tile;
+    }
+}
+
+/// A layer of freely placed objects
+pub struct ObjectLayer {
+    pub name: String,
+    pub objects: Vec<MapObject>,
+    pub visible: bool,
+    pub offset: Vector2,
+    pub properties: Properties,
+}
+
+impl ObjectLayer {
+    /// Get the first object with the given name
+    pub fn object(&self, name: &str) -> Option<&MapObject> {
+        return self.objects.iter().find(|x| x.name == name);
+    }
+
+    /// Get an iterator over every object with the given class
+    pub fn objects_of_class<'a>(&'a self, class: &'a str) -> impl Iterator<Item = &'a MapObject> {
+        return self.objects.iter().filter(move |x| x.class == class);
+    }
+}
+
+/// A set of equally sized tiles stored in a single texture
+pub struct Tileset {
+    pub name: String,
+    /// Global id of the first tile in this set
+    pub first_gid: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub tile_count: u32,
+    pub columns: u32,
+    /// Space in pixels around the edge of the image
+    pub margin: u32,
+    /// Space in pixels between tiles
+    pub spacing: u32,
+    /// Path of the tileset image, relative to the map
+    pub image: String,
+    pub texture: Option<Arc<Texture>>,
+    /// Custom properties of individual tiles, by local tile id
+    pub tile_properties: HashMap<u32, Properties>,
+    /// Collision shapes of individual tiles relative to the tile's top left corner, by local tile id
+    pub tile_collision: HashMap<u32, Vec<MapObject>>,
+}
+
+impl Tileset {
+    /// Get the region of the tileset image containing the given local tile id
+    pub fn tile_rect(&self, local_id: u32) -> Rectangle {
+        let columns = self.columns.max(1);
+        let col = local_id % columns;
+        let row = local_id / columns;
+        return Rectangle::new(
+            (self.margin + col * (self.tile_width + self.spacing)) as i32,
+            (self.margin + row * (self.tile_height + self.spacing)) as i32,
+            self.tile_width as i32,
+            self.tile_height as i32);
+    }
+}
+
+/// An orthogonal tile map made up of tile layers, object layers, and the tilesets they reference
+pub struct TileMap {
+    /// Width of the map in tiles
+    pub width: u32,
+    /// Height of the map in tiles
+    pub height: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Tilesets, sorted by first global id
+    pub tilesets: Vec<Tileset>,
+    pub layers: Vec<TileLayer>,
+    pub object_layers: Vec<ObjectLayer>,
+    pub properties: Properties,
+}
+
+impl TileMap {
+    /// Get the tile layer with the given name
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        return self.layers.iter().find(|x| x.name == name);
+    }
+
+    /// Get the tile layer with the given name
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        return self.layers.iter_mut().find(|x| x.name == name);
+    }
+
+    /// Get the object layer with the given name
+    pub fn object_layer(&self, name: &str) -> Option<&ObjectLayer> {
+        return self.object_layers.iter().find(|x| x.name == name);
+    }
+
+    /// Get the tileset containing the given tile along with the tile's local id within that set
+    pub fn tileset(&self, tile: u32) -> Option<(&Tileset, u32)> {
+        let gid = tile & TILE_ID_MASK;
+        if gid == 0 {
+            return None;
+        }
+
+        let idx = self.tilesets.partition_point(|x| x.first_gid <= gid);
+        let tileset = self.tilesets.get(idx.checked_sub(1)?)?;
+        return Some((tileset, gid - tileset.first_gid));
+    }
+
+    /// Get the custom properties of the given tile
+    pub fn tile_properties(&self, tile: u32) -> Option<&Properties> {
+        let (tileset, local_id) = self.tileset(tile)?;
+        return tileset.tile_properties.get(&local_id);
+    }
+
+    /// Get the collision shapes of the given tile, relative to the tile's top left corner
+    pub fn tile_collision(&self, tile: u32) -> Option<&[MapObject]> {
+        let (tileset, local_id) = self.tileset(tile)?;
+        return tileset.tile_collision.get(&local_id).map(|x| x.as_slice());
+    }
+
+    /// Convert a position in pixels into tile coordinates
+    pub fn world_to_tile(&self, position: Vector2) -> (i32, i32) {
+        return ((position.x / self.tile_width as f32).floor() as i32, (position.y / self.tile_height as f32).floor() as i32);
+    }
+
+    /// Convert tile coordinates into the position in pixels of the tile's top left corner
+    pub fn tile_to_world(&self, x: i32, y: i32) -> Vector2 {
+        return Vector2::new((x * self.tile_width as i32) as f32, (y * self.tile_height as i32) as f32);
+    }
+
+    /// Add the visible tiles of the given layer to a sprite batch, with the given camera position (in pixels) at the top left of the screen <br/>
+    /// Tiles whose tileset has no texture are skipped
+    pub fn draw_layer(&self, batch: &mut SpriteBatch, layer: &TileLayer, camera: Vector2, color: Color32) {
+        if !layer.visible {
+            return;
+        }
+
+        let color = Color32::new(color.r, color.g, color.b, (color.a as f32 * layer.opacity.clamp(0.0, 1.0)).round() as u8);
+        let origin = layer.offset - camera;
+
+        // tiles from tilesets taller than the map's tiles can extend above their cell, so include an extra row below the screen
+        let (min_x, min_y) = self.world_to_tile(-origin);
+        let (max_x, max_y) = self.world_to_tile(-origin + Vector2::new(SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32));
+
+        for y in min_y.max(0)..=(max_y + 1).min(layer.height as i32 - 1) {
+            for x in min_x.max(0)..=max_x.min(layer.width as i32 - 1) {
+                let tile = layer.get(x, y);
+                let (tileset, local_id) = match self.tileset(tile) {
+                    Some(v) => { v },
+                    None => { continue; }
+                };
+
+                let texture = match &tileset.texture {
+                    Some(v) => { v },
+                    None => { continue; }
+                };
+
+                // tiles are aligned to the bottom left of their cell
+                let pos = origin + Vector2::new((x * self.tile_width as i32) as f32, ((y + 1) * self.tile_height as i32 - tileset.tile_height as i32) as f32);
+                let size = Vector2::new(tileset.tile_width as f32, tileset.tile_height as f32);
+
+                let src = tileset.tile_rect(local_id);
+                let uv_min = Vector2::new(src.x as f32 / texture.width as f32, src.y as f32 / texture.height as f32);
+                let uv_max = Vector2::new((src.x + src.width) as f32 / texture.width as f32, (src.y + src.height) as f32 / texture.height as f32);
+                let mut uv = [uv_min, Vector2::new(uv_max.x, uv_min.y), uv_max, Vector2::new(uv_min.x, uv_max.y)];
+
+                if tile & TILE_FLIP_DIAGONAL != 0 {
+                    uv.swap(1, 3);
+                }
+                if tile & TILE_FLIP_HORIZONTAL != 0 {
+                    uv.swap(0, 1);
+                    uv.swap(2, 3);
+                }
+                if tile & TILE_FLIP_VERTICAL != 0 {
+                    uv.swap(0, 3);
+                    uv.swap(1, 2);
+                }
+
+                batch.draw_quad(texture, [pos, pos + Vector2::new(size.x, 0.0), pos + size, pos + Vector2::new(0.0, size.y)], uv, color);
+            }
+        }
+    }
+
+    /// Add every visible tile layer to a sprite batch in order, with the given camera position (in pixels) at the top left of the screen
+    pub fn draw(&self, batch: &mut SpriteBatch, camera: Vector2, color: Color32) {
+        for layer in &self.layers {
+            self.draw_layer(batch, layer, camera, color);
+        }
+    }
+}
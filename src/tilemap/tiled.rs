@@ -0,0 +1,717 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use roxmltree::Node;
+use serde::Deserialize;
+
+use crate::{io::{self, IOError}, log_debug, math::Vector2, tilemap::{MapObject, ObjectLayer, ObjectShape, Properties, PropertyValue, TileLayer, TileMap, Tileset}, vdp::Texture};
+
+/// Enumeration of errors which can result from importing a Tiled map
+#[derive(Debug)]
+pub enum TiledError {
+    ParseError,
+    /// The map uses a feature which isn't supported (such as non-orthogonal orientation, infinite maps, or compressed layer data)
+    Unsupported,
+    XmlError(roxmltree::Error),
+    JsonError(serde_json::Error),
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for TiledError {
+    fn from(value: std::io::Error) -> Self {
+        return TiledError::IOError(value);
+    }
+}
+
+impl From<IOError> for TiledError {
+    fn from(value: IOError) -> Self {
+        return TiledError::IOError(value.into());
+    }
+}
+
+impl From<roxmltree::Error> for TiledError {
+    fn from(value: roxmltree::Error) -> Self {
+        return TiledError::XmlError(value);
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(value: serde_json::Error) -> Self {
+        return TiledError::JsonError(value);
+    }
+}
+
+/// Get the directory part of the given path, including the trailing slash
+fn parent_dir(path: &str) -> &str {
+    return match path.rfind('/') {
+        Some(i) => { &path[..i + 1] },
+        None => { "" }
+    };
+}
+
+/// Resolve a path relative to the given directory, collapsing any "." and ".." components
+fn join_path(dir: &str, path: &str) -> String {
+    if path.starts_with('/') || path.contains(':') {
+        return path.to_string();
+    }
+
+    let mut parts: Vec<&str> = dir.split('/').filter(|x| !x.is_empty()).collect();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {},
+            ".." if parts.last().is_some_and(|x| *x != "..") => { parts.pop(); },
+            _ => { parts.push(part); }
+        };
+    }
+
+    let joined = parts.join("/");
+    return if dir.starts_with('/') { format!("/{}", joined) } else { joined };
+}
+
+fn is_json_path(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    return lower.ends_with(".json") || lower.ends_with(".tmj") || lower.ends_with(".tsj");
+}
+
+/// Decode the tiles of a tile layer from CSV or base64 (uncompressed) data
+fn decode_tiles(encoding: Option<&str>, compression: Option<&str>, text: &str) -> Result<Vec<u32>,TiledError> {
+    if compression.is_some_and(|x| !x.is_empty()) {
+        return Err(TiledError::Unsupported);
+    }
+
+    return match encoding {
+        Some("csv") => {
+            text.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()).map(|x| {
+                return x.parse::<u32>().map_err(|_| TiledError::ParseError);
+            }).collect()
+        },
+        Some("base64") => {
+            let data = io::decode_base64(text.trim()).ok_or(TiledError::ParseError)?;
+            Ok(data.chunks_exact(4).map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]])).collect())
+        },
+        _ => {
+            Err(TiledError::Unsupported)
+        }
+    };
+}
+
+struct Importer<SL, TL> {
+    tileset_load_fn: SL,
+    tex_load_fn: TL,
+    map: TileMap,
+}
+
+impl<SL, TL> Importer<SL, TL>
+    where SL : FnMut(&str) -> Result<Vec<u8>,TiledError>,
+    TL : FnMut(&str) -> Result<Arc<Texture>,()>
+{
+    fn new(tileset_load_fn: SL, tex_load_fn: TL) -> Importer<SL, TL> {
+        return Importer {
+            tileset_load_fn: tileset_load_fn,
+            tex_load_fn: tex_load_fn,
+            map: TileMap {
+                width: 0,
+                height: 0,
+                tile_width: 0,
+                tile_height: 0,
+                tilesets: Vec::new(),
+                layers: Vec::new(),
+                object_layers: Vec::new(),
+                properties: Properties::new(),
+            },
+        };
+    }
+
+    fn add_tileset(&mut self, mut tileset: Tileset, dir: &str) {
+        if !tileset.image.is_empty() {
+            tileset.image = join_path(dir, tileset.image.as_str());
+            tileset.texture = (self.tex_load_fn)(tileset.image.as_str()).ok();
+        }
+        log_debug!("Parsed tileset (name: {}, first gid: {}, tile count: {})", tileset.name, tileset.first_gid, tileset.tile_count);
+        self.map.tilesets.push(tileset);
+    }
+
+    /// Load an external tileset referenced by the map
+    fn load_tileset(&mut self, source: &str, first_gid: u32) -> Result<(),TiledError> {
+        let data = (self.tileset_load_fn)(source)?;
+        let tileset = if is_json_path(source) {
+            json::tileset(&serde_json::from_slice(&data)?, first_gid)?
+        } else {
+            let text = std::str::from_utf8(&data).map_err(|_| TiledError::ParseError)?;
+            let doc = roxmltree::Document::parse(text)?;
+            tmx::tileset(doc.root_element(), first_gid)?
+        };
+
+        // tileset images are relative to the tileset file
+        self.add_tileset(tileset, parent_dir(source));
+        return Ok(());
+    }
+
+    fn finish(mut self) -> TileMap {
+        self.map.tilesets.sort_by_key(|x| x.first_gid);
+        log_debug!("Parsed tile map ({}x{} tiles, {} tile layers, {} object layers)", self.map.width, self.map.height, self.map.layers.len(), self.map.object_layers.len());
+        return self.map;
+    }
+}
+
+/// Accumulated state of the group layers containing a layer
+#[derive(Clone, Copy)]
+struct GroupState {
+    offset: Vector2,
+    opacity: f32,
+    visible: bool,
+}
+
+const ROOT_GROUP: GroupState = GroupState { offset: Vector2::zero(), opacity: 1.0, visible: true };
+
+mod tmx {
+    use super::*;
+
+    fn attr<T>(node: Node, name: &str) -> Result<Option<T>,TiledError> where T : FromStr {
+        return match node.attribute(name) {
+            Some(v) => { v.parse::<T>().map(Some).map_err(|_| TiledError::ParseError) },
+            None => { Ok(None) }
+        };
+    }
+
+    fn attr_or<T>(node: Node, name: &str, default: T) -> Result<T,TiledError> where T : FromStr {
+        return Ok(attr(node, name)?.unwrap_or(default));
+    }
+
+    fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+        return node.children().find(|x| x.has_tag_name(name));
+    }
+
+    pub fn properties(node: Node) -> Result<Properties,TiledError> {
+        let mut properties = Properties::new();
+        let list = match child(node, "properties") {
+            Some(v) => { v },
+            None => { return Ok(properties); }
+        };
+
+        for property in list.children().filter(|x| x.has_tag_name("property")) {
+            let name = property.attribute("name").ok_or(TiledError::ParseError)?;
+
+            // multi-line strings are stored as the element's text
+            let value = property.attribute("value").or(property.text()).unwrap_or("");
+            let value = match property.attribute("type").unwrap_or("string") {
+                "bool" => { PropertyValue::Bool(value == "true") },
+                "int" | "object" => { PropertyValue::Int(value.parse().map_err(|_| TiledError::ParseError)?) },
+                "float" => { PropertyValue::Float(value.parse().map_err(|_| TiledError::ParseError)?) },
+                _ => { PropertyValue::String(value.to_string()) }
+            };
+
+            properties.insert(name.to_string(), value);
+        }
+
+        return Ok(properties);
+    }
+
+    fn points(node: Node) -> Result<Vec<Vector2>,TiledError> {
+        let text = node.attribute("points").unwrap_or("");
+        return text.split_whitespace().map(|p| {
+            return match p.split_once(',') {
+                Some((x, y)) => {
+                    Ok(Vector2::new(x.parse().map_err(|_| TiledError::ParseError)?, y.parse().map_err(|_| TiledError::ParseError)?))
+                },
+                None => { Err(TiledError::ParseError) }
+            };
+        }).collect();
+    }
+
+    pub fn object(node: Node) -> Result<MapObject,TiledError> {
+        let shape = if let Some(gid) = attr::<u32>(node, "gid")? {
+            ObjectShape::Tile(gid)
+        } else if child(node, "ellipse").is_some() {
+            ObjectShape::Ellipse
+        } else if child(node, "point").is_some() {
+            ObjectShape::Point
+        } else if let Some(v) = child(node, "polygon") {
+            ObjectShape::Polygon(points(v)?)
+        } else if let Some(v) = child(node, "polyline") {
+            ObjectShape::Polyline(points(v)?)
+        } else {
+            ObjectShape::Rectangle
+        };
+
+        return Ok(MapObject {
+            id: attr_or(node, "id", 0)?,
+            name: node.attribute("name").unwrap_or("").to_string(),
+            class: node.attribute("class").or(node.attribute("type")).unwrap_or("").to_string(),
+            position: Vector2::new(attr_or(node, "x", 0.0)?, attr_or(node, "y", 0.0)?),
+            size: Vector2::new(attr_or(node, "width", 0.0)?, attr_or(node, "height", 0.0)?),
+            rotation: attr_or(node, "rotation", 0.0)?,
+            visible: attr_or(node, "visible", 1)? != 0,
+            shape: shape,
+            properties: properties(node)?,
+        });
+    }
+
+    pub fn tileset(node: Node, first_gid: u32) -> Result<Tileset,TiledError> {
+        let image = match child(node, "image") {
+            Some(v) => { v.attribute("source").unwrap_or("").to_string() },
+            None => { return Err(TiledError::Unsupported); }
+        };
+
+        let mut tileset = Tileset {
+            name: node.attribute("name").unwrap_or("").to_string(),
+            first_gid: first_gid,
+            tile_width: attr_or(node, "tilewidth", 0)?,
+            tile_height: attr_or(node, "tileheight", 0)?,
+            tile_count: attr_or(node, "tilecount", 0)?,
+            columns: attr_or(node, "columns", 0)?,
+            margin: attr_or(node, "margin", 0)?,
+            spacing: attr_or(node, "spacing", 0)?,
+            image: image,
+            texture: None,
+            tile_properties: HashMap::new(),
+            tile_collision: HashMap::new(),
+        };
+
+        for tile in node.children().filter(|x| x.has_tag_name("tile")) {
+            let id: u32 = attr(tile, "id")?.ok_or(TiledError::ParseError)?;
+
+            let props = properties(tile)?;
+            if !props.is_empty() {
+                tileset.tile_properties.insert(id, props);
+            }
+
+            if let Some(group) = child(tile, "objectgroup") {
+                let shapes = group.children().filter(|x| x.has_tag_name("object")).map(object).collect::<Result<Vec<MapObject>,TiledError>>()?;
+                tileset.tile_collision.insert(id, shapes);
+            }
+        }
+
+        return Ok(tileset);
+    }
+
+    fn layers<SL, TL>(importer: &mut Importer<SL, TL>, node: Node, group: GroupState) -> Result<(),TiledError>
+        where SL : FnMut(&str) -> Result<Vec<u8>,TiledError>,
+        TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        for layer in node.children().filter(|x| x.is_element()) {
+            let state = GroupState {
+                offset: group.offset + Vector2::new(attr_or(layer, "offsetx", 0.0)?, attr_or(layer, "offsety", 0.0)?),
+                opacity: group.opacity * attr_or(layer, "opacity", 1.0)?,
+                visible: group.visible && attr_or(layer, "visible", 1)? != 0,
+            };
+            let name = layer.attribute("name").unwrap_or("");
+
+            match layer.tag_name().name() {
+                "layer" => {
+                    let width: u32 = attr_or(layer, "width", 0)?;
+                    let height: u32 = attr_or(layer, "height", 0)?;
+
+                    let data = child(layer, "data").ok_or(TiledError::ParseError)?;
+                    if child(data, "chunk").is_some() {
+                        return Err(TiledError::Unsupported);
+                    }
+
+                    let tiles: Vec<u32> = match data.attribute("encoding") {
+                        Some(encoding) => { decode_tiles(Some(encoding), data.attribute("compression"), data.text().unwrap_or(""))? },
+                        None => {
+                            data.children().filter(|x| x.has_tag_name("tile")).map(|x| attr_or(x, "gid", 0)).collect::<Result<Vec<u32>,TiledError>>()?
+                        }
+                    };
+
+                    // the layer is built from the decoded tiles, so its size in the file never decides how much is allocated
+                    if Some(tiles.len()) != (width as usize).checked_mul(height as usize) {
+                        return Err(TiledError::ParseError);
+                    }
+
+                    importer.map.layers.push(TileLayer {
+                        name: name.to_string(),
+                        width: width,
+                        height: height,
+                        tiles: tiles,
+                        visible: state.visible,
+                        opacity: state.opacity,
+                        offset: state.offset,
+                        properties: properties(layer)?,
+                    });
+                },
+                "objectgroup" => {
+                    importer.map.object_layers.push(ObjectLayer {
+                        name: name.to_string(),
+                        objects: layer.children().filter(|x| x.has_tag_name("object")).map(object).collect::<Result<Vec<MapObject>,TiledError>>()?,
+                        visible: state.visible,
+                        offset: state.offset,
+                        properties: properties(layer)?,
+                    });
+                },
+                "group" => {
+                    layers(importer, layer, state)?;
+                },
+                _ => {
+                    // image layers, properties, etc
+                }
+            };
+        }
+
+        return Ok(());
+    }
+
+    pub fn map<SL, TL>(importer: &mut Importer<SL, TL>, text: &str) -> Result<(),TiledError>
+        where SL : FnMut(&str) -> Result<Vec<u8>,TiledError>,
+        TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        let doc = roxmltree::Document::parse(text)?;
+        let root = doc.root_element();
+        if !root.has_tag_name("map") {
+            return Err(TiledError::ParseError);
+        }
+
+        if root.attribute("orientation").unwrap_or("orthogonal") != "orthogonal" || attr_or(root, "infinite", 0)? != 0 {
+            return Err(TiledError::Unsupported);
+        }
+
+        importer.map.width = attr_or(root, "width", 0)?;
+        importer.map.height = attr_or(root, "height", 0)?;
+        importer.map.tile_width = attr_or(root, "tilewidth", 0)?;
+        importer.map.tile_height = attr_or(root, "tileheight", 0)?;
+        importer.map.properties = properties(root)?;
+
+        for node in root.children().filter(|x| x.has_tag_name("tileset")) {
+            let first_gid = attr_or(node, "firstgid", 1)?;
+            match node.attribute("source") {
+                Some(source) => {
+                    importer.load_tileset(source, first_gid)?;
+                },
+                None => {
+                    let tileset = tileset(node, first_gid)?;
+                    importer.add_tileset(tileset, "");
+                }
+            };
+        }
+
+        return layers(importer, root, ROOT_GROUP);
+    }
+}
+
+mod json {
+    use super::*;
+
+    fn default_true() -> bool {
+        return true;
+    }
+
+    fn default_opacity() -> f32 {
+        return 1.0;
+    }
+
+    #[derive(Deserialize)]
+    pub struct Property {
+        name: String,
+        #[serde(rename = "type", default)]
+        kind: String,
+        value: serde_json::Value,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Point {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    pub struct Object {
+        id: u32,
+        name: String,
+        #[serde(rename = "type")]
+        kind: String,
+        class: String,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        rotation: f32,
+        #[serde(default = "default_true")]
+        visible: bool,
+        gid: Option<u32>,
+        ellipse: bool,
+        point: bool,
+        polygon: Option<Vec<Point>>,
+        polyline: Option<Vec<Point>>,
+        properties: Vec<Property>,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    pub struct Layer {
+        #[serde(rename = "type")]
+        kind: String,
+        name: String,
+        #[serde(default = "default_true")]
+        visible: bool,
+        #[serde(default = "default_opacity")]
+        opacity: f32,
+        offsetx: f32,
+        offsety: f32,
+        width: u32,
+        height: u32,
+        data: Option<serde_json::Value>,
+        chunks: Option<serde_json::Value>,
+        encoding: Option<String>,
+        compression: Option<String>,
+        objects: Vec<Object>,
+        layers: Vec<Layer>,
+        properties: Vec<Property>,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    pub struct Tile {
+        id: u32,
+        properties: Vec<Property>,
+        objectgroup: Option<Layer>,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    pub struct TilesetDef {
+        firstgid: u32,
+        source: Option<String>,
+        name: String,
+        tilewidth: u32,
+        tileheight: u32,
+        tilecount: u32,
+        columns: u32,
+        margin: u32,
+        spacing: u32,
+        image: Option<String>,
+        tiles: Vec<Tile>,
+    }
+
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    pub struct Map {
+        width: u32,
+        height: u32,
+        tilewidth: u32,
+        tileheight: u32,
+        orientation: String,
+        infinite: bool,
+        layers: Vec<Layer>,
+        tilesets: Vec<TilesetDef>,
+        properties: Vec<Property>,
+    }
+
+    fn properties(list: &[Property]) -> Result<Properties,TiledError> {
+        let mut properties = Properties::new();
+        for property in list {
+            let value = match (property.kind.as_str(), &property.value) {
+                ("bool", serde_json::Value::Bool(v)) => { PropertyValue::Bool(*v) },
+                ("int" | "object", v) => { PropertyValue::Int(v.as_i64().ok_or(TiledError::ParseError)?) },
+                ("float", v) => { PropertyValue::Float(v.as_f64().ok_or(TiledError::ParseError)? as f32) },
+                (_, serde_json::Value::String(v)) => { PropertyValue::String(v.clone()) },
+                (_, v) => { PropertyValue::String(v.to_string()) }
+            };
+            properties.insert(property.name.clone(), value);
+        }
+        return Ok(properties);
+    }
+
+    fn points(list: &[Point]) -> Vec<Vector2> {
+        return list.iter().map(|p| Vector2::new(p.x, p.y)).collect();
+    }
+
+    fn object(obj: &Object) -> Result<MapObject,TiledError> {
+        let shape = if let Some(gid) = obj.gid {
+            ObjectShape::Tile(gid)
+        } else if obj.ellipse {
+            ObjectShape::Ellipse
+        } else if obj.point {
+            ObjectShape::Point
+        } else if let Some(v) = &obj.polygon {
+            ObjectShape::Polygon(points(v))
+        } else if let Some(v) = &obj.polyline {
+            ObjectShape::Polyline(points(v))
+        } else {
+            ObjectShape::Rectangle
+        };
+
+        return Ok(MapObject {
+            id: obj.id,
+            name: obj.name.clone(),
+            class: if obj.class.is_empty() { obj.kind.clone() } else { obj.class.clone() },
+            position: Vector2::new(obj.x, obj.y),
+            size: Vector2::new(obj.width, obj.height),
+            rotation: obj.rotation,
+            visible: obj.visible,
+            shape: shape,
+            properties: properties(&obj.properties)?,
+        });
+    }
+
+    pub fn tileset(def: &TilesetDef, first_gid: u32) -> Result<Tileset,TiledError> {
+        let image = match &def.image {
+            Some(v) => { v.clone() },
+            None => { return Err(TiledError::Unsupported); }
+        };
+
+        let mut tileset = Tileset {
+            name: def.name.clone(),
+            first_gid: first_gid,
+            tile_width: def.tilewidth,
+            tile_height: def.tileheight,
+            tile_count: def.tilecount,
+            columns: def.columns,
+            margin: def.margin,
+            spacing: def.spacing,
+            image: image,
+            texture: None,
+            tile_properties: HashMap::new(),
+            tile_collision: HashMap::new(),
+        };
+
+        for tile in &def.tiles {
+            let props = properties(&tile.properties)?;
+            if !props.is_empty() {
+                tileset.tile_properties.insert(tile.id, props);
+            }
+
+            if let Some(group) = &tile.objectgroup {
+                tileset.tile_collision.insert(tile.id, group.objects.iter().map(object).collect::<Result<Vec<MapObject>,TiledError>>()?);
+            }
+        }
+
+        return Ok(tileset);
+    }
+
+    fn layers<SL, TL>(importer: &mut Importer<SL, TL>, list: &[Layer], group: GroupState) -> Result<(),TiledError>
+        where SL : FnMut(&str) -> Result<Vec<u8>,TiledError>,
+        TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        for layer in list {
+            let state = GroupState {
+                offset: group.offset + Vector2::new(layer.offsetx, layer.offsety),
+                opacity: group.opacity * layer.opacity,
+                visible: group.visible && layer.visible,
+            };
+
+            match layer.kind.as_str() {
+                "tilelayer" => {
+                    if layer.chunks.is_some() {
+                        return Err(TiledError::Unsupported);
+                    }
+
+                    let tiles: Vec<u32> = match &layer.data {
+                        Some(serde_json::Value::Array(v)) => {
+                            v.iter().map(|x| x.as_u64().map(|x| x as u32).ok_or(TiledError::ParseError)).collect::<Result<Vec<u32>,TiledError>>()?
+                        },
+                        Some(serde_json::Value::String(v)) => {
+                            decode_tiles(layer.encoding.as_deref(), layer.compression.as_deref(), v.as_str())?
+                        },
+                        _ => { return Err(TiledError::ParseError); }
+                    };
+
+                    if Some(tiles.len()) != (layer.width as usize).checked_mul(layer.height as usize) {
+                        return Err(TiledError::ParseError);
+                    }
+
+                    importer.map.layers.push(TileLayer {
+                        name: layer.name.clone(),
+                        width: layer.width,
+                        height: layer.height,
+                        tiles: tiles,
+                        visible: state.visible,
+                        opacity: state.opacity,
+                        offset: state.offset,
+                        properties: properties(&layer.properties)?,
+                    });
+                },
+                "objectgroup" => {
+                    importer.map.object_layers.push(ObjectLayer {
+                        name: layer.name.clone(),
+                        objects: layer.objects.iter().map(object).collect::<Result<Vec<MapObject>,TiledError>>()?,
+                        visible: state.visible,
+                        offset: state.offset,
+                        properties: properties(&layer.properties)?,
+                    });
+                },
+                "group" => {
+                    layers(importer, &layer.layers, state)?;
+                },
+                _ => {
+                    // image layers
+                }
+            };
+        }
+
+        return Ok(());
+    }
+
+    pub fn map<SL, TL>(importer: &mut Importer<SL, TL>, data: &[u8]) -> Result<(),TiledError>
+        where SL : FnMut(&str) -> Result<Vec<u8>,TiledError>,
+        TL : FnMut(&str) -> Result<Arc<Texture>,()>
+    {
+        let map: Map = serde_json::from_slice(data)?;
+
+        if !(map.orientation.is_empty() || map.orientation == "orthogonal") || map.infinite {
+            return Err(TiledError::Unsupported);
+        }
+
+        importer.map.width = map.width;
+        importer.map.height = map.height;
+        importer.map.tile_width = map.tilewidth;
+        importer.map.tile_height = map.tileheight;
+        importer.map.properties = properties(&map.properties)?;
+
+        for def in &map.tilesets {
+            match &def.source {
+                Some(source) => {
+                    importer.load_tileset(source.as_str(), def.firstgid)?;
+                },
+                None => {
+                    let tileset = tileset(def, def.firstgid)?;
+                    importer.add_tileset(tileset, "");
+                }
+            };
+        }
+
+        return layers(importer, &map.layers, ROOT_GROUP);
+    }
+}
+
+/// Import a Tiled map in TMX (XML) format <br/>
+/// tileset_load_fn is called with the path of each external tileset (relative to the map) to load its contents, and tex_load_fn is called with the path of each tileset image (also relative to the map)
+pub fn from_tmx<SL,TL>(text: &str, tileset_load_fn: SL, tex_load_fn: TL) -> Result<TileMap,TiledError>
+    where SL : FnMut(&str) -> Result<Vec<u8>,TiledError>,
+    TL : FnMut(&str) -> Result<Arc<Texture>,()>
+{
+    let mut importer = Importer::new(tileset_load_fn, tex_load_fn);
+    tmx::map(&mut importer, text)?;
+    return Ok(importer.finish());
+}
+
+/// Import a Tiled map in JSON format <br/>
+/// tileset_load_fn is called with the path of each external tileset (relative to the map) to load its contents, and tex_load_fn is called with the path of each tileset image (also relative to the map)
+pub fn from_json<SL,TL>(data: &[u8], tileset_load_fn: SL, tex_load_fn: TL) -> Result<TileMap,TiledError>
+    where SL : FnMut(&str) -> Result<Vec<u8>,TiledError>,
+    TL : FnMut(&str) -> Result<Arc<Texture>,()>
+{
+    let mut importer = Importer::new(tileset_load_fn, tex_load_fn);
+    json::map(&mut importer, data)?;
+    return Ok(importer.finish());
+}
+
+/// Import a Tiled map from the given path (JSON if the extension is .tmj or .json, otherwise TMX), loading external tilesets relative to the map <br/>
+/// tex_load_fn is called with the full path of each tileset image
+pub fn load<TL>(path: &str, mut tex_load_fn: TL) -> Result<TileMap,TiledError>
+    where TL : FnMut(&str) -> Result<Arc<Texture>,()>
+{
+    let dir = parent_dir(path);
+    let data = io::read_entire_file(path)?;
+
+    let tileset_load_fn = |source: &str| -> Result<Vec<u8>,TiledError> {
+        return Ok(io::read_entire_file(join_path(dir, source).as_str())?);
+    };
+    let tex_load_fn = |image: &str| tex_load_fn(join_path(dir, image).as_str());
+
+    if is_json_path(path) {
+        return from_json(&data, tileset_load_fn, tex_load_fn);
+    }
+
+    let text = std::str::from_utf8(&data).map_err(|_| TiledError::ParseError)?;
+    return from_tmx(text, tileset_load_fn, tex_load_fn);
+}
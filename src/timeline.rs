@@ -0,0 +1,233 @@
+use std::sync::{Arc, RwLock, Weak};
+
+use crate::{anim::{AnimationMixer, DBAnimationClip}, audio::AudioSample, camera::CameraRail, db::debug_draw::DebugDraw, sounddriver::{PlayParams, SoundDriver, SoundEmitter}, ui::SAFE_AREA, vdp::Color32};
+
+/// Starts an animation clip on one of the actors passed to Timeline::update
+#[derive(Clone)]
+pub struct AnimationCue {
+    pub time: f32,
+    /// Index of the actor's mixer in the slice passed to Timeline::update
+    pub actor: usize,
+    pub clip: Arc<DBAnimationClip>,
+    /// Duration in seconds to crossfade from the actor's previous clips (0.0 to switch immediately)
+    pub crossfade: f32,
+}
+
+/// Plays a sound or music track through the sound driver
+#[derive(Clone)]
+pub struct SoundCue {
+    pub time: f32,
+    pub sample: Arc<AudioSample>,
+    pub params: PlayParams,
+    /// Whether the sound is stopped if the timeline is skipped (music which should carry on after the cutscene can leave this unset)
+    pub stop_on_skip: bool,
+}
+
+/// A line of subtitle text shown between two times
+#[derive(Clone)]
+pub struct Subtitle {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// Sequences a cutscene's camera rail, actor animations, sound & music cues, subtitles, and game-defined events against a single clock <br/>
+/// Cues fire as the clock passes over them, and skipping jumps to the end while leaving everything in its final state
+pub struct Timeline {
+    /// Camera rail advanced alongside the timeline (its events are reported along with the timeline's own events)
+    pub camera: Option<CameraRail>,
+    animations: Vec<AnimationCue>,
+    sounds: Vec<SoundCue>,
+    subtitles: Vec<Subtitle>,
+    events: Vec<(f32, u32)>,
+    fired: Vec<u32>,
+    playing_sounds: Vec<Weak<RwLock<SoundEmitter>>>,
+    time: f32,
+    started: bool,
+    skipped: bool,
+}
+
+// cues are kept ordered by time, with cues at the same time firing in the order they were added
+fn insert_sorted<T, F>(list: &mut Vec<T>, item: T, time: F) where F : Fn(&T) -> f32 {
+    let t = time(&item);
+    let idx = list.partition_point(|x| time(x) <= t);
+    list.insert(idx, item);
+}
+
+impl Timeline {
+    /// Construct a new empty timeline
+    pub fn new() -> Timeline {
+        return Timeline {
+            camera: None,
+            animations: Vec::new(),
+            sounds: Vec::new(),
+            subtitles: Vec::new(),
+            events: Vec::new(),
+            fired: Vec::new(),
+            playing_sounds: Vec::new(),
+            time: 0.0,
+            started: false,
+            skipped: false,
+        };
+    }
+
+    /// Start the given clip on an actor at the given time, crossfading from its previous clips over the given duration
+    pub fn add_animation(&mut self, time: f32, actor: usize, clip: Arc<DBAnimationClip>, crossfade: f32) {
+        insert_sorted(&mut self.animations, AnimationCue { time: time, actor: actor, clip: clip, crossfade: crossfade }, |x| x.time);
+    }
+
+    /// Play the given sample at the given time. Music can be cued by routing it through the music bus in the play parameters
+    pub fn add_sound(&mut self, time: f32, sample: Arc<AudioSample>, params: PlayParams, stop_on_skip: bool) {
+        insert_sorted(&mut self.sounds, SoundCue { time: time, sample: sample, params: params, stop_on_skip: stop_on_skip }, |x| x.time);
+    }
+
+    /// Show a line of subtitle text between the given times
+    pub fn add_subtitle(&mut self, start: f32, end: f32, text: &str) {
+        insert_sorted(&mut self.subtitles, Subtitle { start: start, end: end, text: text.to_string() }, |x| x.start);
+    }
+
+    /// Add a game-defined event which is reported by update when the clock passes the given time
+    pub fn add_event(&mut self, time: f32, id: u32) {
+        insert_sorted(&mut self.events, (time, id), |x| x.0);
+    }
+
+    /// Get the length of the timeline in seconds (the time of its last cue)
+    pub fn duration(&self) -> f32 {
+        let mut duration = match &self.camera {
+            Some(v) => { v.duration() },
+            None => { 0.0 }
+        };
+
+        duration = self.animations.iter().map(|x| x.time).fold(duration, f32::max);
+        duration = self.sounds.iter().map(|x| x.time).fold(duration, f32::max);
+        duration = self.subtitles.iter().map(|x| x.end).fold(duration, f32::max);
+        duration = self.events.iter().map(|x| x.0).fold(duration, f32::max);
+        return duration;
+    }
+
+    /// Get the current time of the timeline's clock in seconds
+    pub fn time(&self) -> f32 {
+        return self.time;
+    }
+
+    /// Gets whether the clock has reached the end of the timeline
+    pub fn is_finished(&self) -> bool {
+        return self.time >= self.duration();
+    }
+
+    /// Gets whether the timeline was ended early with skip
+    pub fn is_skipped(&self) -> bool {
+        return self.skipped;
+    }
+
+    /// Rewind the timeline to the start so it can be played again
+    pub fn reset(&mut self) {
+        self.time = 0.0;
+        self.started = false;
+        self.skipped = false;
+        self.playing_sounds.clear();
+        if let Some(camera) = &mut self.camera {
+            camera.rewind();
+        }
+    }
+
+    /// Advance the clock by the given delta time in seconds, firing any cues passed over <br/>
+    /// Animation cues are applied to the mixer at their actor index in the given slice, and sound cues are played through the given driver. Returns the IDs of events passed over in order
+    pub fn update(&mut self, delta: f32, sound: &mut SoundDriver, actors: &mut [AnimationMixer]) -> &[u32] {
+        self.fired.clear();
+
+        let duration = self.duration();
+        let from = self.time;
+        let to = (from + delta.max(0.0)).min(duration);
+
+        // cues exactly at the start only fire on the first update
+        let inclusive = !self.started;
+        self.started = true;
+        let passed = |t: f32| (t > from || (inclusive && t >= from)) && t <= to;
+
+        if let Some(camera) = &mut self.camera {
+            self.fired.extend_from_slice(camera.update(to - from));
+        }
+
+        for cue in self.animations.iter().filter(|x| passed(x.time)) {
+            if let Some(mixer) = actors.get_mut(cue.actor) {
+                mixer.crossfade(cue.clip.clone(), cue.crossfade);
+            }
+        }
+
+        for cue in self.sounds.iter().filter(|x| passed(x.time)) {
+            let emitter = sound.play(&cue.sample, &cue.params);
+            if cue.stop_on_skip {
+                self.playing_sounds.push(emitter);
+            }
+        }
+
+        self.fired.extend(self.events.iter().filter(|x| passed(x.0)).map(|x| x.1));
+        self.playing_sounds.retain(|x| x.strong_count() > 0);
+
+        self.time = to;
+        return &self.fired;
+    }
+
+    /// Skip to the end of the timeline <br/>
+    /// Sounds marked stop_on_skip are stopped & sound cues which haven't played yet are dropped, while each actor switches straight to the last clip cued for it and the camera moves to the end of its rail.
+    /// Returns the IDs of every event which hadn't fired yet, in order, so game logic tied to them still runs
+    pub fn skip(&mut self, sound: &mut SoundDriver, actors: &mut [AnimationMixer]) -> &[u32] {
+        self.fired.clear();
+
+        let from = self.time;
+        let inclusive = !self.started;
+        let pending = |t: f32| t > from || (inclusive && t >= from);
+
+        for emitter in &self.playing_sounds {
+            sound.stop(emitter);
+        }
+        self.playing_sounds.clear();
+
+        for (i, mixer) in actors.iter_mut().enumerate() {
+            if let Some(cue) = self.animations.iter().rev().find(|x| x.actor == i && pending(x.time)) {
+                mixer.play(cue.clip.clone());
+            }
+        }
+
+        if let Some(camera) = &mut self.camera {
+            let end = camera.duration();
+            self.fired.extend(camera.events().iter().filter(|x| x.time > camera.time() || (inclusive && x.time >= camera.time())).map(|x| x.id));
+            camera.seek(end);
+        }
+
+        self.fired.extend(self.events.iter().filter(|x| pending(x.0)).map(|x| x.1));
+
+        self.time = self.duration();
+        self.started = true;
+        self.skipped = true;
+        return &self.fired;
+    }
+
+    /// Get the text of the subtitle showing at the current time (the most recently started if several overlap)
+    pub fn subtitle(&self) -> Option<&str> {
+        return self.subtitles.iter().rev().find(|x| self.time >= x.start && self.time < x.end).map(|x| x.text.as_str());
+    }
+
+    /// Draw the current subtitle centered at the bottom of the screen's safe area, over a translucent backing rectangle
+    pub fn draw_subtitle(&self, draw: &mut DebugDraw, scale: f32, color: Color32) {
+        let text = match self.subtitle() {
+            Some(v) => { v },
+            None => { return; }
+        };
+
+        let size = DebugDraw::measure_text(text, scale);
+        let padding = 2.0 * scale;
+        let x = SAFE_AREA.x + ((SAFE_AREA.width - size.x) * 0.5);
+        let y = SAFE_AREA.y + SAFE_AREA.height - size.y;
+
+        draw.rect(x - padding, y - padding, size.x + (padding * 2.0), size.y + (padding * 2.0), Color32::new(0, 0, 0, 160));
+        draw.text(text, x, y, scale, color);
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        return Timeline::new();
+    }
+}
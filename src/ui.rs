@@ -0,0 +1,510 @@
+use std::sync::Arc;
+
+use crate::{db::debug_draw::DebugDraw, math::Vector2, sprite::{SpriteBatch, atlas::SpriteAtlas}, vdp::{Color32, Rectangle, SCREEN_HEIGHT, SCREEN_WIDTH, Texture}};
+
+/// A rectangle in screen pixels (origin at the top left)
+#[derive(Clone, Copy)]
+pub struct UiRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl UiRect {
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> UiRect {
+        return UiRect { x: x, y: y, width: width, height: height };
+    }
+
+    pub fn position(&self) -> Vector2 {
+        return Vector2::new(self.x, self.y);
+    }
+
+    pub fn size(&self) -> Vector2 {
+        return Vector2::new(self.width, self.height);
+    }
+
+    pub fn contains_point(&self, point: Vector2) -> bool {
+        return point.x >= self.x && point.y >= self.y && point.x < self.x + self.width && point.y < self.y + self.height;
+    }
+}
+
+/// The whole screen
+pub const SCREEN_RECT: UiRect = UiRect::new(0.0, 0.0, SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32);
+
+/// The region of the screen which is guaranteed to be visible on every display (the screen inset by 5% on each side)
+pub const SAFE_AREA: UiRect = UiRect::new(SCREEN_WIDTH as f32 * 0.05, SCREEN_HEIGHT as f32 * 0.05, SCREEN_WIDTH as f32 * 0.9, SCREEN_HEIGHT as f32 * 0.9);
+
+/// Where an element is attached within its parent, as fractions of the parent's size (0.0 = left/top, 1.0 = right/bottom) <br/>
+/// On each axis where min & max are equal the element keeps its size and is aligned to that point. Where they differ, the element stretches between them
+#[derive(Clone, Copy)]
+pub struct Anchor {
+    pub min: Vector2,
+    pub max: Vector2,
+}
+
+impl Anchor {
+    pub const TOP_LEFT: Anchor = Anchor::point(0.0, 0.0);
+    pub const TOP: Anchor = Anchor::point(0.5, 0.0);
+    pub const TOP_RIGHT: Anchor = Anchor::point(1.0, 0.0);
+    pub const LEFT: Anchor = Anchor::point(0.0, 0.5);
+    pub const CENTER: Anchor = Anchor::point(0.5, 0.5);
+    pub const RIGHT: Anchor = Anchor::point(1.0, 0.5);
+    pub const BOTTOM_LEFT: Anchor = Anchor::point(0.0, 1.0);
+    pub const BOTTOM: Anchor = Anchor::point(0.5, 1.0);
+    pub const BOTTOM_RIGHT: Anchor = Anchor::point(1.0, 1.0);
+    /// Stretch to fill the parent on both axes
+    pub const FILL: Anchor = Anchor::new(Vector2::new(0.0, 0.0), Vector2::new(1.0, 1.0));
+    /// Stretch across the top of the parent
+    pub const TOP_WIDE: Anchor = Anchor::new(Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0));
+    /// Stretch across the bottom of the parent
+    pub const BOTTOM_WIDE: Anchor = Anchor::new(Vector2::new(0.0, 1.0), Vector2::new(1.0, 1.0));
+
+    pub const fn new(min: Vector2, max: Vector2) -> Anchor {
+        return Anchor { min: min, max: max };
+    }
+
+    /// Construct an anchor which aligns the element to a single point of its parent
+    pub const fn point(x: f32, y: f32) -> Anchor {
+        return Anchor { min: Vector2::new(x, y), max: Vector2::new(x, y) };
+    }
+}
+
+/// Distances in pixels between an element & the edges it is anchored to
+#[derive(Clone, Copy, Default)]
+pub struct Margin {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Margin {
+    pub const ZERO: Margin = Margin::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(left: f32, top: f32, right: f32, bottom: f32) -> Margin {
+        return Margin { left: left, top: top, right: right, bottom: bottom };
+    }
+
+    /// Construct a margin with the same distance on every side
+    pub const fn all(value: f32) -> Margin {
+        return Margin { left: value, top: value, right: value, bottom: value };
+    }
+}
+
+/// How an element is sized & positioned relative to its parent (or to the slot given to it by its parent's container)
+#[derive(Clone, Copy)]
+pub struct Layout {
+    pub anchor: Anchor,
+    pub margin: Margin,
+    /// Size in pixels on axes which aren't stretched. A zero size uses the widget's natural size (such as the size of a label's text)
+    pub size: Vector2,
+}
+
+impl Layout {
+    /// Fill the parent, inset by the given margin
+    pub const fn fill(margin: Margin) -> Layout {
+        return Layout { anchor: Anchor::FILL, margin: margin, size: Vector2::zero() };
+    }
+
+    /// Align an element of the given size to a point of its parent, pushed inwards from the anchored edges by the given margin
+    pub const fn anchored(anchor: Anchor, margin: Margin, size: Vector2) -> Layout {
+        return Layout { anchor: anchor, margin: margin, size: size };
+    }
+
+    // resolve one axis of the layout within the given span of the parent
+    fn resolve_axis(parent_pos: f32, parent_size: f32, anchor_min: f32, anchor_max: f32, margin_min: f32, margin_max: f32, size: f32) -> (f32, f32) {
+        if anchor_min == anchor_max {
+            let pos = parent_pos + (parent_size - size) * anchor_min + margin_min * (1.0 - anchor_min) - margin_max * anchor_min;
+            return (pos, size);
+        }
+
+        let min = parent_pos + parent_size * anchor_min + margin_min;
+        let max = parent_pos + parent_size * anchor_max - margin_max;
+        return (min, (max - min).max(0.0));
+    }
+
+    /// Compute the rectangle covered by an element with this layout & the given size within a parent rectangle
+    pub fn resolve(&self, parent: &UiRect, size: Vector2) -> UiRect {
+        let (x, width) = Layout::resolve_axis(parent.x, parent.width, self.anchor.min.x, self.anchor.max.x, self.margin.left, self.margin.right, size.x);
+        let (y, height) = Layout::resolve_axis(parent.y, parent.height, self.anchor.min.y, self.anchor.max.y, self.margin.top, self.margin.bottom, size.y);
+        return UiRect::new(x, y, width, height);
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        return Layout::fill(Margin::ZERO);
+    }
+}
+
+/// Direction children are placed in by a stack container
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// How an element arranges its children
+#[derive(Clone, Copy)]
+pub enum Container {
+    /// Each child is laid out relative to the whole element
+    None,
+    /// Children are placed one after another, each in a slot as long as its size plus margins & as wide as the element
+    Stack { direction: Direction, spacing: f32 },
+    /// Children are placed left to right, top to bottom in equally sized cells
+    Grid { columns: u32, cell_size: Vector2, spacing: Vector2 },
+}
+
+/// What is drawn within an element's rectangle
+#[derive(Clone)]
+pub enum Widget {
+    None,
+    /// A solid color rectangle
+    Panel { color: Color32 },
+    /// Text in the debug font, aligned within the rectangle (0.0 = left/top, 1.0 = right/bottom)
+    Label { text: String, scale: f32, color: Color32, align: Vector2 },
+    /// A region of a texture stretched to fill the rectangle
+    Image { texture: Arc<Texture>, src: Rectangle, color: Color32 },
+    /// A named sprite from an atlas stretched to fill the rectangle
+    Sprite { atlas: Arc<SpriteAtlas>, name: String, color: Color32 },
+}
+
+impl Widget {
+    /// Get the natural size of the widget, used on axes where its layout has no size
+    pub fn natural_size(&self) -> Vector2 {
+        return match self {
+            Widget::Label { text, scale, .. } => { DebugDraw::measure_text(text, *scale) },
+            Widget::Image { src, .. } => { Vector2::new(src.width as f32, src.height as f32) },
+            Widget::Sprite { atlas, name, .. } => { atlas.frame(name).map_or(Vector2::zero(), |x| x.source_size) },
+            _ => { Vector2::zero() }
+        };
+    }
+}
+
+/// Handle to an element in a UI <br/>
+/// Handles of removed elements may be reused by elements added later
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct UiId(usize);
+
+/// A single element in a UI
+pub struct UiElement {
+    pub name: String,
+    pub layout: Layout,
+    pub container: Container,
+    pub widget: Widget,
+    /// Invisible elements skip drawing both themselves and their children, but still take up space in containers
+    pub visible: bool,
+    parent: Option<UiId>,
+    children: Vec<UiId>,
+    rect: UiRect,
+}
+
+impl UiElement {
+    /// Get the parent of this element, or None if it's a root element
+    pub fn parent(&self) -> Option<UiId> {
+        return self.parent;
+    }
+
+    /// Get the children of this element, in draw order
+    pub fn children(&self) -> &[UiId] {
+        return &self.children;
+    }
+
+    /// Get the rectangle covered by this element as of the last layout
+    pub fn rect(&self) -> UiRect {
+        return self.rect;
+    }
+
+    // size used by the layout, falling back to the widget's natural size on axes without one
+    fn size(&self) -> Vector2 {
+        let natural = self.widget.natural_size();
+        return Vector2::new(
+            if self.layout.size.x > 0.0 { self.layout.size.x } else { natural.x },
+            if self.layout.size.y > 0.0 { self.layout.size.y } else { natural.y });
+    }
+}
+
+/// A retained hierarchy of UI elements laid out relative to a bounding rectangle (the safe area by default) <br/>
+/// Layout is recomputed automatically before drawing whenever an element or the bounds have changed
+pub struct Ui {
+    elements: Vec<Option<UiElement>>,
+    free: Vec<usize>,
+    roots: Vec<UiId>,
+    bounds: UiRect,
+    dirty: bool,
+    shapes: DebugDraw,
+    sprites: SpriteBatch,
+}
+
+impl Ui {
+    /// Construct a new empty UI laid out within the safe area
+    pub fn new() -> Ui {
+        return Ui {
+            elements: Vec::new(),
+            free: Vec::new(),
+            roots: Vec::new(),
+            bounds: SAFE_AREA,
+            dirty: true,
+            shapes: DebugDraw::new(),
+            sprites: SpriteBatch::new(),
+        };
+    }
+
+    /// Get the rectangle root elements are laid out within
+    pub fn bounds(&self) -> UiRect {
+        return self.bounds;
+    }
+
+    /// Set the rectangle root elements are laid out within (such as SCREEN_RECT for full screen backgrounds)
+    pub fn set_bounds(&mut self, bounds: UiRect) {
+        self.bounds = bounds;
+        self.dirty = true;
+    }
+
+    /// Add a new element as the last child of the given parent (or as a root element if None) <br/>
+    /// Returns None if the parent doesn't exist
+    pub fn add(&mut self, name: &str, layout: Layout, widget: Widget, parent: Option<UiId>) -> Option<UiId> {
+        if let Some(p) = parent {
+            self.element(p)?;
+        }
+
+        let element = UiElement {
+            name: name.to_string(),
+            layout: layout,
+            container: Container::None,
+            widget: widget,
+            visible: true,
+            parent: parent,
+            children: Vec::new(),
+            rect: UiRect::new(0.0, 0.0, 0.0, 0.0),
+        };
+
+        let id = match self.free.pop() {
+            Some(idx) => {
+                self.elements[idx] = Some(element);
+                UiId(idx)
+            },
+            None => {
+                self.elements.push(Some(element));
+                UiId(self.elements.len() - 1)
+            }
+        };
+
+        self.child_list(parent).push(id);
+        self.dirty = true;
+        return Some(id);
+    }
+
+    /// Remove the given element along with all of its children
+    pub fn remove(&mut self, id: UiId) {
+        let parent = match self.element(id) {
+            Some(v) => { v.parent },
+            None => { return; }
+        };
+        self.child_list(parent).retain(|x| *x != id);
+
+        let mut pending = vec![id];
+        while let Some(UiId(idx)) = pending.pop() {
+            if let Some(element) = self.elements[idx].take() {
+                pending.extend(element.children);
+                self.free.push(idx);
+            }
+        }
+
+        self.dirty = true;
+    }
+
+    /// Get the element with the given handle
+    pub fn element(&self, id: UiId) -> Option<&UiElement> {
+        return self.elements.get(id.0)?.as_ref();
+    }
+
+    /// Get the element with the given handle (marks the layout as needing to be recomputed)
+    pub fn element_mut(&mut self, id: UiId) -> Option<&mut UiElement> {
+        self.dirty = true;
+        return self.elements.get_mut(id.0)?.as_mut();
+    }
+
+    /// Find the first element with the given name
+    pub fn find(&self, name: &str) -> Option<UiId> {
+        return self.elements.iter().position(|x| x.as_ref().is_some_and(|n| n.name == name)).map(UiId);
+    }
+
+    /// Get the root elements of the UI, in draw order
+    pub fn roots(&self) -> &[UiId] {
+        return &self.roots;
+    }
+
+    /// Get the number of elements in the UI
+    pub fn len(&self) -> usize {
+        return self.elements.len() - self.free.len();
+    }
+
+    /// Gets whether the UI contains no elements
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// Get the rectangle covered by the given element, recomputing the layout first if needed
+    pub fn rect(&mut self, id: UiId) -> Option<UiRect> {
+        self.layout();
+        return self.element(id).map(|x| x.rect);
+    }
+
+    /// Find the topmost visible element containing the given point in screen pixels, recomputing the layout first if needed
+    pub fn hit_test(&mut self, point: Vector2) -> Option<UiId> {
+        self.layout();
+
+        let mut result = None;
+        self.traverse(|id, element| {
+            if element.rect.contains_point(point) {
+                result = Some(id);
+            }
+        });
+
+        return result;
+    }
+
+    fn child_list(&mut self, parent: Option<UiId>) -> &mut Vec<UiId> {
+        return match parent {
+            Some(p) => { &mut self.elements[p.0].as_mut().unwrap().children },
+            None => { &mut self.roots }
+        };
+    }
+
+    /// Recompute the rectangle of every element if anything has changed since the last layout
+    pub fn layout(&mut self) {
+        if !self.dirty {
+            return;
+        }
+
+        let mut pending: Vec<(UiId, UiRect)> = self.roots.iter().rev().map(|x| (*x, self.bounds)).collect();
+
+        while let Some((id, slot)) = pending.pop() {
+            let element = match self.elements[id.0].as_mut() {
+                Some(v) => { v },
+                None => { continue; }
+            };
+
+            element.rect = element.layout.resolve(&slot, element.size());
+
+            let rect = element.rect;
+            let container = element.container;
+            let children = element.children.clone();
+
+            // assign each child the slot it is laid out within
+            let mut slots = Vec::with_capacity(children.len());
+            let mut cursor = 0.0;
+            for (i, child) in children.iter().enumerate() {
+                let slot = match container {
+                    Container::None => { rect },
+                    Container::Stack { direction, spacing } => {
+                        let (size, margin) = match self.element(*child) {
+                            Some(v) => { (v.size(), v.layout.margin) },
+                            None => { continue; }
+                        };
+
+                        match direction {
+                            Direction::Horizontal => {
+                                let length = size.x + margin.left + margin.right;
+                                cursor += length + spacing;
+                                UiRect::new(rect.x + cursor - length - spacing, rect.y, length, rect.height)
+                            },
+                            Direction::Vertical => {
+                                let length = size.y + margin.top + margin.bottom;
+                                cursor += length + spacing;
+                                UiRect::new(rect.x, rect.y + cursor - length - spacing, rect.width, length)
+                            }
+                        }
+                    },
+                    Container::Grid { columns, cell_size, spacing } => {
+                        let columns = columns.max(1) as usize;
+                        let col = (i % columns) as f32;
+                        let row = (i / columns) as f32;
+                        UiRect::new(rect.x + col * (cell_size.x + spacing.x), rect.y + row * (cell_size.y + spacing.y), cell_size.x, cell_size.y)
+                    }
+                };
+
+                slots.push((*child, slot));
+            }
+
+            // pushed in reverse so that children are laid out in order
+            pending.extend(slots.into_iter().rev());
+        }
+
+        self.dirty = false;
+    }
+
+    /// Visit every visible element depth first
+    fn traverse<F>(&self, mut visit: F) where F : FnMut(UiId, &UiElement) {
+        let mut pending: Vec<UiId> = self.roots.iter().rev().copied().collect();
+
+        while let Some(id) = pending.pop() {
+            let element = match self.element(id) {
+                Some(v) => { v },
+                None => { continue; }
+            };
+
+            if !element.visible {
+                continue;
+            }
+
+            visit(id, element);
+
+            // pushed in reverse so that children are visited in order
+            pending.extend(element.children.iter().rev());
+        }
+    }
+
+    /// Recompute the layout if needed & draw every visible element in order, with children drawn over their parents
+    pub fn draw(&mut self) {
+        self.layout();
+
+        let mut shapes = std::mem::take(&mut self.shapes);
+        let mut sprites = std::mem::take(&mut self.sprites);
+
+        self.traverse(|_, element| {
+            let rect = element.rect;
+
+            // flush the other batch when switching between shapes & sprites, so that elements stay in draw order
+            match &element.widget {
+                Widget::None => {
+                },
+                Widget::Panel { color } => {
+                    sprites.flush();
+                    shapes.rect(rect.x, rect.y, rect.width, rect.height, *color);
+                },
+                Widget::Label { text, scale, color, align } => {
+                    sprites.flush();
+                    let size = DebugDraw::measure_text(text, *scale);
+                    let pos = rect.position() + (rect.size() - size) * *align;
+                    shapes.text(text, pos.x.round(), pos.y.round(), *scale, *color);
+                },
+                Widget::Image { texture, src, color } => {
+                    shapes.flush();
+                    sprites.draw_scaled(texture, *src, rect.position(), rect.size(), *color);
+                },
+                Widget::Sprite { atlas, name, color } => {
+                    shapes.flush();
+                    if let Some(frame) = atlas.frame(name) {
+                        let scale = rect.size() / frame.source_size;
+                        sprites.draw_sprite(atlas, name, rect.position() + frame.pivot * rect.size(), scale, 0.0, *color);
+                    }
+                }
+            };
+        });
+
+        shapes.flush();
+        sprites.flush();
+
+        self.shapes = shapes;
+        self.sprites = sprites;
+    }
+}
+
+impl Default for Ui {
+    fn default() -> Self {
+        return Ui::new();
+    }
+}
@@ -1,13 +1,41 @@
 use std::convert::TryInto;
 use std::fmt::Debug;
+use std::ops;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 use crate::db_internal::{vdp_clearColor, vdp_setVsyncHandler, vdp_clearDepth, vdp_depthWrite, vdp_depthFunc, vdp_blendEquation, vdp_blendFunc, vdp_setWinding, vdp_setCulling, vdp_drawGeometry, vdp_allocTexture, vdp_releaseTexture, vdp_getUsage, vdp_setTextureData, vdp_copyFbToTexture, vdp_setSampleParams, vdp_bindTexture, vdp_viewport, vdp_submitDepthQuery, vdp_getDepthQueryResult, vdp_drawGeometryPacked, vdp_setTextureDataRegion, vdp_setTextureDataYUV};
-use crate::math::{Vector4, Vector2};
+use crate::math::{Vector4, Vector2, Lerp};
+
+pub mod budget;
 
 static mut VSYNC_HANDLER: Option<fn()> = Option::None;
 
+static DRAW_CALLS: AtomicU32 = AtomicU32::new(0);
+static DRAW_VERTICES: AtomicU32 = AtomicU32::new(0);
+static DRAW_CULLED: AtomicU32 = AtomicU32::new(0);
+
+static VERTEX_POOL: Mutex<BufferPool<Vertex>> = Mutex::new(BufferPool::new());
+static PACKED_VERTEX_POOL: Mutex<BufferPool<PackedVertex>> = Mutex::new(BufferPool::new());
+
+/// Width of the screen in pixels
+pub const SCREEN_WIDTH: i32 = 640;
+
+/// Height of the screen in pixels
+pub const SCREEN_HEIGHT: i32 = 480;
+
+/// Number of draw calls & vertices submitted since the stats were last reset
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DrawStats {
+    pub draw_calls: u32,
+    pub vertices: u32,
+    /// Number of objects skipped by frustum culling (see record_culled)
+    pub culled: u32,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color32 {
     pub r: u8,
     pub g: u8,
@@ -19,6 +47,181 @@ impl Color32 {
     pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Color32 {
         return Color32 { r: r, g: g, b: b, a: a };
     }
+
+    /// Construct a color from a packed 0xRRGGBBAA value
+    pub const fn from_hex(hex: u32) -> Color32 {
+        return Color32 {
+            r: (hex >> 24) as u8,
+            g: (hex >> 16) as u8,
+            b: (hex >> 8) as u8,
+            a: hex as u8,
+        };
+    }
+
+    /// Convert this color to a packed 0xRRGGBBAA value
+    pub const fn to_hex(self) -> u32 {
+        return ((self.r as u32) << 24) | ((self.g as u32) << 16) | ((self.b as u32) << 8) | (self.a as u32);
+    }
+
+    /// Construct a color from hue (in degrees), saturation, and value (both 0.0 .. 1.0), plus alpha
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: u8) -> Color32 {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let s = saturation.clamp(0.0, 1.0);
+        let v = value.clamp(0.0, 1.0);
+
+        let c = v * s;
+        let x = c * (1.0 - ((h % 2.0) - 1.0).abs());
+        let m = v - c;
+
+        let (r, g, b) = match h as i32 {
+            0 => { (c, x, 0.0) }
+            1 => { (x, c, 0.0) }
+            2 => { (0.0, c, x) }
+            3 => { (0.0, x, c) }
+            4 => { (x, 0.0, c) }
+            _ => { (c, 0.0, x) }
+        };
+
+        return Color32 {
+            r: ((r + m) * 255.0).round() as u8,
+            g: ((g + m) * 255.0).round() as u8,
+            b: ((b + m) * 255.0).round() as u8,
+            a: alpha,
+        };
+    }
+
+    /// Convert this color to hue (in degrees), saturation, and value (both 0.0 .. 1.0)
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * (((b - r) / delta) + 2.0)
+        } else {
+            60.0 * (((r - g) / delta) + 4.0)
+        };
+
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+        return (hue, saturation, max);
+    }
+
+    /// Linearly interpolate between two colors (t is clamped to 0.0 .. 1.0)
+    pub fn lerp(a: Color32, b: Color32, t: f32) -> Color32 {
+        return Color32::lerp_unclamped(a, b, t.clamp(0.0, 1.0));
+    }
+
+    // interpolate without clamping t, so components extrapolate until they saturate at 0 or 255
+    fn lerp_unclamped(a: Color32, b: Color32, t: f32) -> Color32 {
+        let mix = |x: u8, y: u8| -> u8 {
+            return (x as f32 + ((y as f32 - x as f32) * t)).round() as u8;
+        };
+
+        return Color32 {
+            r: mix(a.r, b.r),
+            g: mix(a.g, b.g),
+            b: mix(a.b, b.b),
+            a: mix(a.a, b.a),
+        };
+    }
+
+    /// Multiply two colors together component-wise (as if each component were normalized to 0.0 .. 1.0)
+    pub const fn multiply(self, other: Color32) -> Color32 {
+        return Color32 {
+            r: ((self.r as u32 * other.r as u32 + 127) / 255) as u8,
+            g: ((self.g as u32 * other.g as u32 + 127) / 255) as u8,
+            b: ((self.b as u32 * other.b as u32 + 127) / 255) as u8,
+            a: ((self.a as u32 * other.a as u32 + 127) / 255) as u8,
+        };
+    }
+
+    /// Produce a copy of this color with RGB premultiplied by alpha
+    pub const fn premultiplied(self) -> Color32 {
+        return Color32 {
+            r: ((self.r as u32 * self.a as u32 + 127) / 255) as u8,
+            g: ((self.g as u32 * self.a as u32 + 127) / 255) as u8,
+            b: ((self.b as u32 * self.a as u32 + 127) / 255) as u8,
+            a: self.a,
+        };
+    }
+
+    /// Produce a copy of this premultiplied color with alpha divided back out of RGB
+    pub const fn unpremultiplied(self) -> Color32 {
+        if self.a == 0 {
+            return Color32 { r: 0, g: 0, b: 0, a: 0 };
+        }
+
+        let a = self.a as u32;
+        return Color32 {
+            r: min_u32((self.r as u32 * 255 + a / 2) / a, 255) as u8,
+            g: min_u32((self.g as u32 * 255 + a / 2) / a, 255) as u8,
+            b: min_u32((self.b as u32 * 255 + a / 2) / a, 255) as u8,
+            a: self.a,
+        };
+    }
+}
+
+const fn min_u32(a: u32, b: u32) -> u32 {
+    return if a < b { a } else { b };
+}
+
+impl Lerp<Color32> for Color32 {
+    fn lerp(lhs: Color32, rhs: Color32, time: f32) -> Color32 {
+        return Color32::lerp_unclamped(lhs, rhs, time);
+    }
+}
+
+impl ops::Mul<Color32> for Color32 {
+    type Output = Color32;
+
+    fn mul(self, rhs: Color32) -> Color32 {
+        return self.multiply(rhs);
+    }
+}
+
+impl From<[u8;4]> for Color32 {
+    fn from(v: [u8;4]) -> Color32 {
+        return Color32 { r: v[0], g: v[1], b: v[2], a: v[3] };
+    }
+}
+
+impl From<Color32> for [u8;4] {
+    fn from(c: Color32) -> [u8;4] {
+        return [c.r, c.g, c.b, c.a];
+    }
+}
+
+impl From<Vector4> for Color32 {
+    /// Convert a normalized (0.0 .. 1.0) RGBA vector into a color, clamping out of range components
+    fn from(v: Vector4) -> Color32 {
+        return Color32 {
+            r: (v.x.clamp(0.0, 1.0) * 255.0).round() as u8,
+            g: (v.y.clamp(0.0, 1.0) * 255.0).round() as u8,
+            b: (v.z.clamp(0.0, 1.0) * 255.0).round() as u8,
+            a: (v.w.clamp(0.0, 1.0) * 255.0).round() as u8,
+        };
+    }
+}
+
+impl From<Color32> for Vector4 {
+    /// Convert a color into a normalized (0.0 .. 1.0) RGBA vector
+    fn from(c: Color32) -> Vector4 {
+        return Vector4 {
+            x: c.r as f32 / 255.0,
+            y: c.g as f32 / 255.0,
+            z: c.b as f32 / 255.0,
+            w: c.a as f32 / 255.0,
+        };
+    }
 }
 
 #[repr(C)]
@@ -82,18 +285,25 @@ pub struct Texture {
 }
 
 impl Texture {
+    /// Allocate a new texture <br/>
+    /// If there isn't enough texture memory, textures registered with budget::register_evictable are released to make room before AllocationFailed is returned
     pub fn new(width: i32, height: i32, mipmap: bool, format: TextureFormat) -> Result<Texture,TextureError> {
         // dimensions must be power of two (unless this is a YUV420 image)
         if format != TextureFormat::YUV420 && ((width & (width - 1)) != 0 || (height & (height - 1)) != 0) {
             return Result::Err(TextureError::DimensionsInvalid);
         }
 
-        // allocate and check to see if allocation failed
-        let handle = unsafe { vdp_allocTexture(mipmap, format, width, height) };
-        if handle == -1 {
-            return Result::Err(TextureError::AllocationFailed);
+        // allocate and check to see if allocation failed, releasing evictable textures to make room until it succeeds
+        let mut handle = unsafe { vdp_allocTexture(mipmap, format, width, height) };
+        while handle == -1 {
+            if !budget::evict_one() {
+                return Result::Err(TextureError::AllocationFailed);
+            }
+            handle = unsafe { vdp_allocTexture(mipmap, format, width, height) };
         }
 
+        budget::record_alloc(handle, budget::texture_size(width, height, mipmap, format));
+
         return Result::Ok(Texture {
             format: format,
             mipmap: mipmap,
@@ -145,6 +355,7 @@ impl Texture {
 impl Drop for Texture {
     fn drop(&mut self) {
         unsafe { vdp_releaseTexture(self.handle) };
+        budget::record_free(self.handle);
     }
 }
 
@@ -228,6 +439,12 @@ pub enum TextureWrap {
 }
 
 unsafe extern "C" fn real_vsync_handler() {
+    reset_vertex_pools();
+
+    if budget::should_reload() {
+        budget::reload_evicted();
+    }
+
     if VSYNC_HANDLER.is_some() {
         VSYNC_HANDLER.unwrap()();
     }
@@ -275,14 +492,134 @@ pub fn set_culling(enabled: bool) {
 
 /// Submit a buffer of geometry to draw
 pub fn draw_geometry(topology: Topology, vertex_data: &[Vertex]) {
+    record_draw(vertex_data.len());
     unsafe { vdp_drawGeometry(topology, 0, vertex_data.len().try_into().unwrap(), vertex_data.as_ptr()) };
 }
 
 /// Submit a buffer of geometry to draw
 pub fn draw_geometry_packed(topology: Topology, vertex_data: &[PackedVertex]) {
+    record_draw(vertex_data.len());
     unsafe { vdp_drawGeometryPacked(topology, 0, vertex_data.len().try_into().unwrap(), vertex_data.as_ptr()) };
 }
 
+fn record_draw(vertices: usize) {
+    DRAW_CALLS.fetch_add(1, Ordering::Relaxed);
+    DRAW_VERTICES.fetch_add(vertices as u32, Ordering::Relaxed);
+}
+
+/// Get the number of draw calls, vertices & culled objects since the last call to reset_draw_stats
+pub fn get_draw_stats() -> DrawStats {
+    return DrawStats {
+        draw_calls: DRAW_CALLS.load(Ordering::Relaxed),
+        vertices: DRAW_VERTICES.load(Ordering::Relaxed),
+        culled: DRAW_CULLED.load(Ordering::Relaxed),
+    };
+}
+
+/// Record that the given number of objects were skipped by culling, to be reported in the draw stats
+pub fn record_culled(count: u32) {
+    DRAW_CULLED.fetch_add(count, Ordering::Relaxed);
+}
+
+/// Reset the draw call, vertex & culled object counters (usually once per frame)
+pub fn reset_draw_stats() {
+    DRAW_CALLS.store(0, Ordering::Relaxed);
+    DRAW_VERTICES.store(0, Ordering::Relaxed);
+    DRAW_CULLED.store(0, Ordering::Relaxed);
+}
+
+// free vertex buffers kept for reuse, along with how many are handed out & the most handed out at once since the last vsync
+struct BufferPool<T> {
+    free: Vec<Vec<T>>,
+    in_use: usize,
+    peak: usize,
+}
+
+impl<T> BufferPool<T> {
+    const fn new() -> BufferPool<T> {
+        return BufferPool { free: Vec::new(), in_use: 0, peak: 0 };
+    }
+
+    fn take(&mut self, capacity: usize) -> Vec<T> {
+        self.in_use += 1;
+        self.peak = self.peak.max(self.in_use);
+
+        // prefer the smallest free buffer which is already big enough, so large buffers stay available for large requests
+        let idx = self.free.iter().enumerate()
+            .filter(|(_, x)| x.capacity() >= capacity)
+            .min_by_key(|(_, x)| x.capacity())
+            .map(|(i, _)| i);
+
+        let mut buffer = match idx {
+            Some(i) => { self.free.swap_remove(i) },
+            None => { self.free.pop().unwrap_or_default() }
+        };
+        buffer.reserve(capacity);
+        return buffer;
+    }
+
+    fn release(&mut self, mut buffer: Vec<T>) {
+        buffer.clear();
+        self.in_use = self.in_use.saturating_sub(1);
+        self.free.push(buffer);
+    }
+
+    // drop buffers which weren't needed last frame so a one-off spike doesn't keep its memory forever
+    fn reset(&mut self) {
+        let keep = self.peak.saturating_sub(self.in_use);
+        if self.free.len() > keep {
+            self.free.sort_unstable_by_key(|x| std::cmp::Reverse(x.capacity()));
+            self.free.truncate(keep);
+        }
+        self.peak = self.in_use;
+    }
+}
+
+/// A vertex buffer borrowed from the per-frame buffer pool, which derefs to a Vec <br/>
+/// When dropped, the buffer is cleared & returned to the pool with its capacity intact so that drawing code can build vertices each frame without allocating
+pub struct PooledBuffer<T> where T : 'static {
+    buffer: Vec<T>,
+    pool: &'static Mutex<BufferPool<T>>,
+}
+
+impl<T> ops::Deref for PooledBuffer<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        return &self.buffer;
+    }
+}
+
+impl<T> ops::DerefMut for PooledBuffer<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        return &mut self.buffer;
+    }
+}
+
+impl<T> Drop for PooledBuffer<T> {
+    fn drop(&mut self) {
+        let buffer = std::mem::take(&mut self.buffer);
+        self.pool.lock().unwrap().release(buffer);
+    }
+}
+
+/// Borrow an empty vertex buffer with room for at least the given number of vertices from the per-frame pool
+pub fn take_vertex_buffer(capacity: usize) -> PooledBuffer<Vertex> {
+    return PooledBuffer { buffer: VERTEX_POOL.lock().unwrap().take(capacity), pool: &VERTEX_POOL };
+}
+
+/// Borrow an empty packed vertex buffer with room for at least the given number of vertices from the per-frame pool
+pub fn take_packed_vertex_buffer(capacity: usize) -> PooledBuffer<PackedVertex> {
+    return PooledBuffer { buffer: PACKED_VERTEX_POOL.lock().unwrap().take(capacity), pool: &PACKED_VERTEX_POOL };
+}
+
+/// Release pooled vertex buffers which went unused during the last frame <br/>
+/// This is called automatically at the start of each vsync once a handler has been installed with set_vsync_handler
+pub fn reset_vertex_pools() {
+    VERTEX_POOL.lock().unwrap().reset();
+    PACKED_VERTEX_POOL.lock().unwrap().reset();
+}
+
 /// Get total texture memory usage in bytes
 pub fn get_usage() -> i32 {
     unsafe { return vdp_getUsage() };
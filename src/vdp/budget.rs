@@ -0,0 +1,272 @@
+use std::sync::Mutex;
+
+use crate::{budget::{self, BudgetTracker}, db::log, vdp::{Texture, TextureFormat, get_usage}};
+
+type ReleaseFn = Box<dyn FnMut() + Send>;
+type ReloadFn = Box<dyn FnMut() -> bool + Send>;
+
+struct EvictableRecord {
+    id: u32,
+    label: String,
+    priority: i32,
+    bytes: usize,
+    evicted: bool,
+    // callbacks are taken out of the record while running so they can allocate & release textures themselves
+    release: Option<ReleaseFn>,
+    reload: Option<ReloadFn>,
+}
+
+struct EvictionState {
+    evictable: Vec<EvictableRecord>,
+    next_id: u32,
+    reloading: bool,
+    // set while an evictable texture's release callback runs, so the textures it drops aren't counted as freed
+    evicting: bool,
+    freed: bool,
+}
+
+static TRACKER: Mutex<BudgetTracker> = Mutex::new(BudgetTracker::new());
+
+static STATE: Mutex<EvictionState> = Mutex::new(EvictionState {
+    evictable: Vec::new(),
+    next_id: 0,
+    reloading: false,
+    evicting: false,
+    freed: false,
+});
+
+/// Identifies a texture registered with register_evictable
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EvictableId(u32);
+
+/// Summary of texture memory usage
+#[derive(Clone, Copy, Debug)]
+pub struct BudgetReport {
+    /// Estimated bytes of texture data allocated through Texture
+    pub tracked_bytes: usize,
+    /// Bytes of texture memory in use as reported by the VDP
+    pub hardware_bytes: usize,
+    /// Number of live textures
+    pub texture_count: usize,
+    /// Number of evictable textures which are currently released
+    pub evicted_count: usize,
+    /// The configured budget in bytes, if any
+    pub budget: Option<usize>,
+}
+
+/// Estimate the number of bytes of texture memory used by a texture with the given size & format
+pub fn texture_size(width: i32, height: i32, mipmap: bool, format: TextureFormat) -> usize {
+    let mut w = width.max(1) as usize;
+    let mut h = height.max(1) as usize;
+    let mut bytes = 0;
+
+    loop {
+        bytes += match format {
+            TextureFormat::RGB565 | TextureFormat::RGBA4444 => { w * h * 2 },
+            TextureFormat::RGBA8888 => { w * h * 4 },
+            // block compressed formats store each 4x4 block in 8 or 16 bytes
+            TextureFormat::DXT1 => { w.div_ceil(4) * h.div_ceil(4) * 8 },
+            TextureFormat::DXT3 => { w.div_ceil(4) * h.div_ceil(4) * 16 },
+            TextureFormat::YUV420 => { (w * h) + (w.div_ceil(2) * h.div_ceil(2) * 2) },
+        };
+
+        if !mipmap || (w == 1 && h == 1) {
+            break;
+        }
+
+        w = (w / 2).max(1);
+        h = (h / 2).max(1);
+    }
+
+    return bytes;
+}
+
+/// Set the texture memory budget in bytes (or None to disable budget checks) <br/>
+/// When an allocation exceeds the budget, a warning and the largest textures are logged. If panic_in_debug is set, debug builds will also panic
+pub fn set_budget(budget: Option<usize>, panic_in_debug: bool) {
+    TRACKER.lock().unwrap().set_budget(budget, panic_in_debug);
+}
+
+/// Attach a debug label to the given texture, used when reporting usage
+pub fn set_label(texture: &Texture, label: &str) {
+    TRACKER.lock().unwrap().set_label(texture.handle, label);
+}
+
+/// Run the given function, labelling every texture allocated inside of it with the given debug label (for example, a whole image load)
+pub fn with_label<T, F>(label: &str, f: F) -> T where F : FnOnce() -> T {
+    return budget::with_label(&TRACKER, label, f);
+}
+
+/// Register a low priority texture which can be released automatically when a texture allocation fails <br/>
+/// release should drop every reference to the texture (for example, by removing it from a cache), and reload should load it again, returning whether it succeeded.
+/// When an allocation fails, evictable textures are released in order of lowest priority first until it succeeds. Released textures are reloaded by reload_evicted
+pub fn register_evictable<R, L>(texture: &Texture, priority: i32, release: R, reload: L) -> EvictableId
+    where R : FnMut() + Send + 'static, L : FnMut() -> bool + Send + 'static
+{
+    let label = TRACKER.lock().unwrap().label(texture.handle);
+
+    let mut state = STATE.lock().unwrap();
+    let id = state.next_id;
+    state.next_id += 1;
+
+    state.evictable.push(EvictableRecord {
+        id: id,
+        label: label,
+        priority: priority,
+        bytes: texture_size(texture.width, texture.height, texture.mipmap, texture.format),
+        evicted: false,
+        release: Some(Box::new(release)),
+        reload: Some(Box::new(reload)),
+    });
+
+    return EvictableId(id);
+}
+
+/// Stop tracking the given evictable texture. Its callbacks will not be called again
+pub fn unregister_evictable(id: EvictableId) {
+    let mut state = STATE.lock().unwrap();
+    state.evictable.retain(|x| x.id != id.0);
+}
+
+/// Gets whether the given evictable texture is currently released
+pub fn is_evicted(id: EvictableId) -> bool {
+    let state = STATE.lock().unwrap();
+    return state.evictable.iter().any(|x| x.id == id.0 && x.evicted);
+}
+
+/// Try to reload every released evictable texture, highest priority first, returning the number reloaded <br/>
+/// Textures which would push usage over the budget are left released. This is called automatically at the start of each vsync after any texture has been released (other than by eviction),
+/// once a handler has been installed with set_vsync_handler
+pub fn reload_evicted() -> usize {
+    let mut pending: Vec<(u32, i32, usize)> = {
+        let mut state = STATE.lock().unwrap();
+        state.freed = false;
+        state.evictable.iter().filter(|x| x.evicted).map(|x| (x.id, x.priority, x.bytes)).collect()
+    };
+    pending.sort_by_key(|x| std::cmp::Reverse(x.1));
+
+    let mut count = 0;
+    for (id, _, bytes) in pending {
+        let reload = {
+            let budget = TRACKER.lock().unwrap().budget();
+            if let Some(budget) = budget {
+                if get_usage().max(0) as usize + bytes > budget {
+                    continue;
+                }
+            }
+
+            let mut state = STATE.lock().unwrap();
+
+            // don't evict other textures to make room for a reload
+            state.reloading = true;
+            match state.evictable.iter_mut().find(|x| x.id == id) {
+                Some(v) => { v.reload.take() },
+                None => { None }
+            }
+        };
+
+        let mut reload = match reload {
+            Some(v) => { v },
+            None => {
+                STATE.lock().unwrap().reloading = false;
+                continue;
+            }
+        };
+
+        let success = reload();
+
+        let mut state = STATE.lock().unwrap();
+        state.reloading = false;
+        if let Some(record) = state.evictable.iter_mut().find(|x| x.id == id) {
+            record.reload = Some(reload);
+            if success {
+                record.evicted = false;
+                count += 1;
+            }
+        }
+    }
+
+    return count;
+}
+
+/// Get a summary of current texture memory usage
+pub fn report() -> BudgetReport {
+    let tracker = TRACKER.lock().unwrap();
+    let state = STATE.lock().unwrap();
+    return BudgetReport {
+        tracked_bytes: tracker.tracked_bytes(),
+        hardware_bytes: get_usage().max(0) as usize,
+        texture_count: tracker.count(),
+        evicted_count: state.evictable.iter().filter(|x| x.evicted).count(),
+        budget: tracker.budget(),
+    };
+}
+
+/// Get the labels & estimated sizes in bytes of the largest live textures, largest first
+pub fn largest(count: usize) -> Vec<(String, usize)> {
+    return TRACKER.lock().unwrap().largest(count);
+}
+
+/// Log current usage along with the given number of largest textures
+pub fn dump(count: usize) {
+    let r = report();
+    match r.budget {
+        Some(budget) => {
+            log(format!("Texture memory: {} bytes in use ({} tracked across {} textures, {} evicted), budget {} bytes", r.hardware_bytes, r.tracked_bytes, r.texture_count, r.evicted_count, budget).as_str());
+        }
+        None => {
+            log(format!("Texture memory: {} bytes in use ({} tracked across {} textures, {} evicted)", r.hardware_bytes, r.tracked_bytes, r.texture_count, r.evicted_count).as_str());
+        }
+    }
+
+    budget::log_largest(largest(count));
+}
+
+// release the lowest priority resident evictable texture, returning false if there was nothing left to release
+pub(crate) fn evict_one() -> bool {
+    let (id, label, mut release, was_evicting) = {
+        let mut state = STATE.lock().unwrap();
+        if state.reloading {
+            return false;
+        }
+
+        let record = match state.evictable.iter_mut().filter(|x| !x.evicted && x.release.is_some()).min_by_key(|x| x.priority) {
+            Some(v) => { v },
+            None => { return false; }
+        };
+
+        record.evicted = true;
+        let (id, label, release) = (record.id, record.label.clone(), record.release.take().unwrap());
+        (id, label, release, std::mem::replace(&mut state.evicting, true))
+    };
+
+    crate::log_warn!("texture allocation failed, releasing evictable texture {}", if label.is_empty() { "<unlabeled>" } else { label.as_str() });
+    release();
+
+    let mut state = STATE.lock().unwrap();
+    state.evicting = was_evicting;
+    if let Some(record) = state.evictable.iter_mut().find(|x| x.id == id) {
+        record.release = Some(release);
+    }
+
+    return true;
+}
+
+pub(crate) fn record_alloc(handle: i32, bytes: usize) {
+    budget::record_alloc(&TRACKER, handle, bytes, get_usage().max(0) as usize, "texture", dump);
+}
+
+pub(crate) fn record_free(handle: i32) {
+    TRACKER.lock().unwrap().record_free(handle);
+
+    let mut state = STATE.lock().unwrap();
+    if !state.evicting {
+        state.freed = true;
+    }
+}
+
+// whether any texture has been released since evicted textures were last reloaded
+pub(crate) fn should_reload() -> bool {
+    let state = STATE.lock().unwrap();
+    return state.freed && state.evictable.iter().any(|x| x.evicted);
+}